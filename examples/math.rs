@@ -38,7 +38,9 @@ fn main() {
     pdf_doc.set_font_size(10);
 
     let math_font_family = pdf_doc.add_font_family(math_font_family);
-    pdf_doc.enable_math(math_font_data, math_font_family);
+    pdf_doc
+        .enable_math(math_font_data, math_font_family)
+        .expect("Failed to load math font");
 
     pdf_doc.push(elements::Text::new("Math with Rex and genpdf"));
     pdf_doc.push(elements::Math::new(_MATH_EXAMPLE_SHORT).aligned(genpdf::Alignment::Center));
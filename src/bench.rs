@@ -0,0 +1,317 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Synthetic documents for benchmarks.
+//!
+//! The [`Workload`][] struct generates documents with a configurable amount of text, tables,
+//! code blocks and formulas.  They can be used to measure the rendering performance of `genpdf`
+//! in your environment, or to track performance regressions in your application with a
+//! benchmark harness like [criterion][].  The generated content is deterministic:  a workload
+//! always produces the same document for the same configuration and seed.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use genpdf::bench;
+//! let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+//!     .expect("Failed to load font family");
+//! let workload = bench::Workload::new(20)
+//!     .with_table_rows(10)
+//!     .with_code_lines(15);
+//! let mut buf = Vec::new();
+//! workload
+//!     .document(font_family)
+//!     .render(&mut buf)
+//!     .expect("Failed to render document");
+//! ```
+//!
+//! [`Workload`]: struct.Workload.html
+//! [criterion]: https://docs.rs/criterion
+
+use crate::elements;
+use crate::fonts;
+use crate::style::Style;
+use crate::{Document, Element as _};
+
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "ex",
+    "ea",
+    "commodo",
+    "consequat",
+];
+
+#[cfg(feature = "math")]
+const FORMULAS: &[&str] = &[
+    r"\sum_{k=1}^n k = \frac{n(n+1)}{2}",
+    r"\int_0^\infty e^{-x^2} dx = \frac{\sqrt{\pi}}{2}",
+    r"x = \frac{-b \pm \sqrt{b^2 - 4ac}}{2a}",
+    r"e^{i\pi} + 1 = 0",
+];
+
+/// A generator for synthetic documents, see the [module documentation](index.html).
+///
+/// A workload consists of a number of pages.  Every page starts with a heading, followed by a
+/// number of paragraphs with random words and, optionally, a table, a code block and formulas.
+/// Each page starts on a new page of the document, but if the content of a page does not fit on
+/// a single page of the document, it is continued on the next page.
+///
+/// Per default, a page contains five paragraphs and no tables, code blocks or formulas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Workload {
+    pages: usize,
+    paragraphs: usize,
+    table_rows: usize,
+    code_lines: usize,
+    formulas: usize,
+    seed: u64,
+}
+
+impl Workload {
+    /// Creates a new workload with the given number of pages.
+    pub fn new(pages: usize) -> Workload {
+        Workload {
+            pages,
+            paragraphs: 5,
+            table_rows: 0,
+            code_lines: 0,
+            formulas: 0,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of paragraphs per page.
+    pub fn set_paragraphs(&mut self, paragraphs: usize) {
+        self.paragraphs = paragraphs;
+    }
+
+    /// Sets the number of paragraphs per page and returns the workload.
+    pub fn with_paragraphs(mut self, paragraphs: usize) -> Self {
+        self.set_paragraphs(paragraphs);
+        self
+    }
+
+    /// Sets the number of rows of the table on every page.
+    ///
+    /// If the number of rows is zero, no tables are generated.
+    pub fn set_table_rows(&mut self, table_rows: usize) {
+        self.table_rows = table_rows;
+    }
+
+    /// Sets the number of rows of the table on every page and returns the workload.
+    pub fn with_table_rows(mut self, table_rows: usize) -> Self {
+        self.set_table_rows(table_rows);
+        self
+    }
+
+    /// Sets the number of lines of the code block on every page.
+    ///
+    /// If the number of lines is zero, no code blocks are generated.  If the
+    /// `code-syntax-highlighting` feature is enabled, the code is highlighted as Rust code, so
+    /// the document must be configured with a syntax highlighter.
+    pub fn set_code_lines(&mut self, code_lines: usize) {
+        self.code_lines = code_lines;
+    }
+
+    /// Sets the number of lines of the code block on every page and returns the workload.
+    pub fn with_code_lines(mut self, code_lines: usize) -> Self {
+        self.set_code_lines(code_lines);
+        self
+    }
+
+    /// Sets the number of formulas on every page.
+    ///
+    /// *Only available if the `math` feature is enabled.*
+    ///
+    /// If the number of formulas is larger than zero, math rendering must be enabled for the
+    /// document, see [`Document::enable_math`][].
+    ///
+    /// [`Document::enable_math`]: ../struct.Document.html#method.enable_math
+    #[cfg(feature = "math")]
+    pub fn set_formulas(&mut self, formulas: usize) {
+        self.formulas = formulas;
+    }
+
+    /// Sets the number of formulas on every page and returns the workload.
+    ///
+    /// *Only available if the `math` feature is enabled.*
+    #[cfg(feature = "math")]
+    pub fn with_formulas(mut self, formulas: usize) -> Self {
+        self.set_formulas(formulas);
+        self
+    }
+
+    /// Sets the seed for the random text of the workload.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Sets the seed for the random text of the workload and returns the workload.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    /// Generates the content of this workload.
+    pub fn elements(&self) -> elements::LinearLayout {
+        let mut rng = Rng(self.seed);
+        let mut layout = elements::LinearLayout::vertical();
+        for page in 0..self.pages {
+            if page > 0 {
+                layout.push(elements::PageBreak::new());
+            }
+            layout.push(
+                elements::Paragraph::new(format!("Page {}: {}", page + 1, rng.words(3)))
+                    .styled(Style::new().bold().with_font_size(16))
+                    .padded((0, 0, 3, 0)),
+            );
+            for _ in 0..self.paragraphs {
+                let words = 40 + rng.next(40);
+                layout.push(elements::Paragraph::new(rng.words(words)).padded((0, 0, 2, 0)));
+            }
+            if self.table_rows > 0 {
+                layout.push(self.table(&mut rng).padded((0, 0, 2, 0)));
+            }
+            if self.code_lines > 0 {
+                layout.push(self.code_block(&mut rng).padded((0, 0, 2, 0)));
+            }
+            #[cfg(feature = "math")]
+            for i in 0..self.formulas {
+                let formula = FORMULAS[(page + i) % FORMULAS.len()];
+                layout.push(elements::Math::new(formula).expect("Invalid formula"));
+            }
+        }
+        layout
+    }
+
+    /// Creates a new document with the given default font family and the content of this
+    /// workload.
+    pub fn document(&self, font_family: fonts::FontFamily<fonts::FontData>) -> Document {
+        let mut doc = Document::new(font_family);
+        doc.set_title("genpdf benchmark");
+        doc.push(self.elements());
+        doc
+    }
+
+    fn table(&self, rng: &mut Rng) -> elements::TableLayout {
+        let mut table = elements::TableLayout::new(vec![1, 3, 1]);
+        table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+        for row in 0..self.table_rows {
+            let words = 3 + rng.next(5);
+            table
+                .row()
+                .element(elements::Paragraph::new((row + 1).to_string()))
+                .element(elements::Paragraph::new(rng.words(words)))
+                .element(elements::Paragraph::new(format!(
+                    "{}.{:02}",
+                    rng.next(1000),
+                    rng.next(100)
+                )))
+                .push()
+                .expect("Invalid table row");
+        }
+        table
+    }
+
+    fn code_block(&self, rng: &mut Rng) -> elements::CodeBlock {
+        let mut code = String::from("fn main() {\n");
+        for _ in 2..self.code_lines {
+            code.push_str(&format!(
+                "    let {} = {}; // {}\n",
+                rng.word(),
+                rng.next(1000),
+                rng.words(4)
+            ));
+        }
+        code.push('}');
+        let style = Style::new().with_font_size(9);
+        #[cfg(not(feature = "code-syntax-highlighting"))]
+        return elements::CodeBlock::new(&code, style);
+        #[cfg(feature = "code-syntax-highlighting")]
+        return elements::CodeBlock::new(&code, "rs", None, style);
+    }
+}
+
+impl Default for Workload {
+    fn default() -> Workload {
+        Workload::new(1)
+    }
+}
+
+/// A simple linear congruential generator for the random content of a workload.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((self.0 >> 33) % n as u64) as usize
+    }
+
+    fn word(&mut self) -> &'static str {
+        WORDS[self.next(WORDS.len())]
+    }
+
+    fn words(&mut self, n: usize) -> String {
+        let mut s = String::new();
+        for i in 0..n {
+            if i > 0 {
+                s.push(' ');
+            }
+            s.push_str(self.word());
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Workload;
+    use crate::Element as _;
+
+    #[test]
+    fn test_workload_deterministic() {
+        let workload = Workload::new(3).with_table_rows(4).with_code_lines(5);
+        assert_eq!(
+            workload.elements().dump_tree(),
+            workload.elements().dump_tree()
+        );
+        assert_ne!(
+            workload.elements().dump_tree(),
+            workload.clone().with_seed(1).elements().dump_tree()
+        );
+        // 3 headings, 15 paragraphs, 3 tables, 3 code blocks and 2 page breaks
+        assert_eq!(workload.elements().dump_tree().children().len(), 26);
+    }
+}
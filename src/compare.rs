@@ -0,0 +1,439 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Visual comparison of two revisions of a text.
+//!
+//! The [`Comparison`][] element compares two versions of a plain text word by word and renders
+//! the differences:  removed words are struck through in red, added words are underlined in
+//! green.  Lines are separated by line breaks (`'\n'`), paragraphs by blank lines.  Changes in
+//! the whitespace between two words are ignored.
+//!
+//! If you only need the differences, you can use the [`diff`][] function.
+//!
+//! # Example
+//!
+//! ```
+//! use genpdf::compare;
+//! let old = "The quick brown fox jumps over the lazy dog.";
+//! let new = "The quick red fox jumps over the dog.";
+//! let comparison = compare::Comparison::new(old, new);
+//! ```
+//!
+//! [`Comparison`]: struct.Comparison.html
+//! [`diff`]: fn.diff.html
+
+use crate::elements;
+use crate::error::Error;
+use crate::render;
+use crate::style::{Color, Style};
+use crate::{Context, Element, RenderResult};
+
+/// A change between two revisions of a text, see [`diff`][].
+///
+/// [`diff`]: fn.diff.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change<'s> {
+    /// Text that is contained in both revisions.  The slice is taken from the new revision.
+    Unchanged(&'s str),
+    /// Text that is only contained in the old revision.
+    Removed(&'s str),
+    /// Text that is only contained in the new revision.
+    Added(&'s str),
+}
+
+/// Compares the two given revisions of a text word by word and returns the changes.
+///
+/// Consecutive words with the same kind of change are combined.  Every word includes the spaces
+/// after it, but changes in whitespace are not reported.  The comparison needs memory that is
+/// proportional to the product of the number of changed words in both revisions.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::compare::{diff, Change};
+/// assert_eq!(
+///     diff("a b c", "a d c"),
+///     vec![
+///         Change::Unchanged("a "),
+///         Change::Removed("b "),
+///         Change::Added("d "),
+///         Change::Unchanged("c"),
+///     ]
+/// );
+/// ```
+pub fn diff<'s>(old: &'s str, new: &'s str) -> Vec<Change<'s>> {
+    let mut changes: Vec<(Operation, Token)> = Vec::new();
+    for (operation, token) in diff_tokens(old, new) {
+        match changes.last_mut() {
+            Some((last_operation, last))
+                if *last_operation == operation && last.end == token.start =>
+            {
+                last.end = token.end;
+            }
+            _ => changes.push((operation, token)),
+        }
+    }
+    changes
+        .into_iter()
+        .map(|(operation, token)| {
+            operation.change(&operation.source(old, new)[token.start..token.end])
+        })
+        .collect()
+}
+
+/// An element that renders the differences between two revisions of a text.
+///
+/// See the [module documentation](index.html) for details.  Per default, removed text is struck
+/// through and colored red and added text is underlined and colored green.  You can change these
+/// styles with the [`set_removed_style`][] and [`set_added_style`][] methods.  Unchanged text
+/// uses the style of the element.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{compare, style};
+/// let comparison = compare::Comparison::new("Total: 10 EUR", "Total: 12 EUR")
+///     .with_added_style(style::Style::new().bold().with_underline());
+/// ```
+///
+/// [`set_removed_style`]: #method.set_removed_style
+/// [`set_added_style`]: #method.set_added_style
+pub struct Comparison {
+    old: String,
+    new: String,
+    removed_style: Style,
+    added_style: Style,
+    layout: Option<elements::LinearLayout>,
+}
+
+impl Comparison {
+    /// Creates a new comparison between the given old and new revisions of a text.
+    pub fn new(old: impl Into<String>, new: impl Into<String>) -> Comparison {
+        Comparison {
+            old: old.into(),
+            new: new.into(),
+            removed_style: Style::new()
+                .with_color(Color::Rgb(200, 0, 0))
+                .with_strikethrough(),
+            added_style: Style::new()
+                .with_color(Color::Rgb(0, 128, 0))
+                .with_underline(),
+            layout: None,
+        }
+    }
+
+    /// Sets the style for removed text.
+    pub fn set_removed_style(&mut self, style: impl Into<Style>) {
+        self.removed_style = style.into();
+    }
+
+    /// Sets the style for removed text and returns the comparison.
+    pub fn with_removed_style(mut self, style: impl Into<Style>) -> Self {
+        self.set_removed_style(style);
+        self
+    }
+
+    /// Sets the style for added text.
+    pub fn set_added_style(&mut self, style: impl Into<Style>) {
+        self.added_style = style.into();
+    }
+
+    /// Sets the style for added text and returns the comparison.
+    pub fn with_added_style(mut self, style: impl Into<Style>) -> Self {
+        self.set_added_style(style);
+        self
+    }
+}
+
+impl Element for Comparison {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let (old, new) = (&self.old, &self.new);
+        let (removed_style, added_style) = (self.removed_style, self.added_style);
+        self.layout
+            .get_or_insert_with(|| layout(old, new, removed_style, added_style))
+            .render(context, area, style)
+    }
+}
+
+/// Creates a layout with one paragraph per paragraph of the compared text.
+fn layout(
+    old: &str,
+    new: &str,
+    removed_style: Style,
+    added_style: Style,
+) -> elements::LinearLayout {
+    let mut builder = LayoutBuilder::new();
+    for (operation, token) in diff_tokens(old, new) {
+        let s = &operation.source(old, new)[token.start..token.end];
+        let style = match operation {
+            Operation::Unchanged => Style::new(),
+            Operation::Removed => removed_style,
+            Operation::Added => added_style,
+        };
+        match (token.kind, operation) {
+            (TokenKind::Word, _) => builder.push(s, style),
+            // Removed breaks are replaced with a space so that the removed words stay separated.
+            (_, Operation::Removed) => builder.push(" ", style),
+            (TokenKind::LineBreak, _) => builder.line_break(),
+            (TokenKind::ParagraphBreak, _) => builder.paragraph_break(),
+        }
+    }
+    builder.finish()
+}
+
+struct LayoutBuilder {
+    layout: elements::LinearLayout,
+    paragraph: elements::Paragraph,
+    has_content: bool,
+    text: String,
+    style: Style,
+}
+
+impl LayoutBuilder {
+    fn new() -> LayoutBuilder {
+        LayoutBuilder {
+            layout: elements::LinearLayout::vertical(),
+            paragraph: elements::Paragraph::default(),
+            has_content: false,
+            text: String::new(),
+            style: Style::new(),
+        }
+    }
+
+    fn push(&mut self, s: &str, style: Style) {
+        if style != self.style {
+            self.flush();
+            self.style = style;
+        }
+        self.text.push_str(s);
+    }
+
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            self.paragraph
+                .push_styled(self.text.split_off(0), self.style);
+            self.has_content = true;
+        }
+    }
+
+    fn line_break(&mut self) {
+        self.flush();
+        self.paragraph.push_line_break();
+        self.has_content = true;
+    }
+
+    fn paragraph_break(&mut self) {
+        self.flush();
+        if self.has_content {
+            let paragraph = std::mem::take(&mut self.paragraph);
+            self.layout.push(paragraph);
+            self.layout.push(elements::Break::new(1));
+            self.has_content = false;
+        }
+    }
+
+    fn finish(mut self) -> elements::LinearLayout {
+        self.flush();
+        if self.has_content {
+            self.layout.push(self.paragraph);
+        }
+        self.layout
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operation {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+impl Operation {
+    /// Returns the revision that contains the tokens for this operation.
+    fn source<'s>(&self, old: &'s str, new: &'s str) -> &'s str {
+        match self {
+            Operation::Removed => old,
+            Operation::Unchanged | Operation::Added => new,
+        }
+    }
+
+    fn change<'s>(&self, s: &'s str) -> Change<'s> {
+        match self {
+            Operation::Unchanged => Change::Unchanged(s),
+            Operation::Removed => Change::Removed(s),
+            Operation::Added => Change::Added(s),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TokenKind {
+    /// A word including the spaces after it.
+    Word,
+    /// Whitespace with a single line break.
+    LineBreak,
+    /// Whitespace with more than one line break.
+    ParagraphBreak,
+}
+
+/// A token of a text, given by its byte range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    /// Returns the part of the token that is compared:  the word without trailing spaces.
+    fn key<'s>(&self, s: &'s str) -> (TokenKind, &'s str) {
+        match self.kind {
+            TokenKind::Word => (self.kind, s[self.start..self.end].trim_end()),
+            _ => (self.kind, ""),
+        }
+    }
+}
+
+/// Splits the given text into words and line and paragraph breaks.  Leading spaces are ignored.
+fn tokenize(s: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while start < s.len() {
+        let rest = &s[start..];
+        let whitespace = rest.len() - rest.trim_start().len();
+        let end = if whitespace > 0 {
+            let kind = match rest[..whitespace].matches('\n').count() {
+                0 => None,
+                1 => Some(TokenKind::LineBreak),
+                _ => Some(TokenKind::ParagraphBreak),
+            };
+            if let Some(kind) = kind {
+                tokens.push(Token {
+                    kind,
+                    start,
+                    end: start + whitespace,
+                });
+            }
+            start + whitespace
+        } else {
+            let word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let spaces = rest[word..]
+                .find(|c: char| c == '\n' || !c.is_whitespace())
+                .unwrap_or(rest.len() - word);
+            let end = start + word + spaces;
+            tokens.push(Token {
+                kind: TokenKind::Word,
+                start,
+                end,
+            });
+            end
+        };
+        start = end;
+    }
+    tokens
+}
+
+/// Calculates the changes between the tokens of the given texts using the longest common
+/// subsequence.
+fn diff_tokens(old: &str, new: &str) -> Vec<(Operation, Token)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let is_equal = |i: usize, j: usize| old_tokens[i].key(old) == new_tokens[j].key(new);
+
+    // The common prefix and suffix don't have to be part of the LCS table.
+    let mut prefix = 0;
+    while prefix < old_tokens.len() && prefix < new_tokens.len() && is_equal(prefix, prefix) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < old_tokens.len() - prefix
+        && suffix < new_tokens.len() - prefix
+        && is_equal(old_tokens.len() - suffix - 1, new_tokens.len() - suffix - 1)
+    {
+        suffix += 1;
+    }
+    let (m, n) = (old_tokens.len() - suffix, new_tokens.len() - suffix);
+
+    // lcs[lcs_idx(i, j)] is the length of the LCS of old_tokens[i..m] and new_tokens[j..n].
+    let width = n - prefix + 1;
+    let lcs_idx = |i: usize, j: usize| (i - prefix) * width + (j - prefix);
+    let mut lcs = vec![0; (m - prefix + 1) * width];
+    for i in (prefix..m).rev() {
+        for j in (prefix..n).rev() {
+            lcs[lcs_idx(i, j)] = if is_equal(i, j) {
+                lcs[lcs_idx(i + 1, j + 1)] + 1
+            } else {
+                lcs[lcs_idx(i + 1, j)].max(lcs[lcs_idx(i, j + 1)])
+            };
+        }
+    }
+
+    let mut changes: Vec<_> = new_tokens[..prefix]
+        .iter()
+        .map(|token| (Operation::Unchanged, *token))
+        .collect();
+    let (mut i, mut j) = (prefix, prefix);
+    while i < m || j < n {
+        if i < m && j < n && is_equal(i, j) {
+            changes.push((Operation::Unchanged, new_tokens[j]));
+            i += 1;
+            j += 1;
+        } else if i < m && (j == n || lcs[lcs_idx(i + 1, j)] >= lcs[lcs_idx(i, j + 1)]) {
+            changes.push((Operation::Removed, old_tokens[i]));
+            i += 1;
+        } else {
+            changes.push((Operation::Added, new_tokens[j]));
+            j += 1;
+        }
+    }
+    changes.extend(
+        new_tokens[n..]
+            .iter()
+            .map(|token| (Operation::Unchanged, *token)),
+    );
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Change};
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("", ""), vec![]);
+        assert_eq!(diff("a b", "a b"), vec![Change::Unchanged("a b")]);
+        assert_eq!(
+            diff("a b c d", "a x c y d e"),
+            vec![
+                Change::Unchanged("a "),
+                Change::Removed("b "),
+                Change::Added("x "),
+                Change::Unchanged("c "),
+                Change::Added("y "),
+                Change::Unchanged("d "),
+                Change::Added("e"),
+            ]
+        );
+        assert_eq!(
+            diff("a  b\nc", "a b c"),
+            vec![
+                Change::Unchanged("a b "),
+                Change::Removed("\n"),
+                Change::Unchanged("c"),
+            ]
+        );
+        assert_eq!(
+            diff("a\n\nb", "a\n\nc"),
+            vec![
+                Change::Unchanged("a\n\n"),
+                Change::Removed("b"),
+                Change::Added("c"),
+            ]
+        );
+    }
+}
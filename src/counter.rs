@@ -0,0 +1,397 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Counters for the numbering of headings, figures, tables, listings and equations.
+//!
+//! A [`Counter`][] has a name, a current value and a [`NumberStyle`][].  It can be nested in a
+//! parent counter:  Whenever the parent is stepped, the child is reset, and the formatted number
+//! of the child is prefixed with the number of the parent.  For example, if the `figure` counter
+//! is nested in the `chapter` counter, the second figure of the third chapter is numbered `3.2`.
+//!
+//! The counters of a document are stored in a [`Counters`][] registry that can be accessed with
+//! [`Document::counters_mut`][].  As the elements of a document are rendered in the order in
+//! which they are added, the numbers are assigned while the document is built.  The registry
+//! contains the following counters per default:
+//! - `chapter`, `section` (nested in `chapter`) and `subsection` (nested in `section`) for
+//!   headings,
+//! - `figure`, `table`, `listing` and `equation`.
+//!
+//! # Example
+//!
+//! ```
+//! use genpdf::counter::{Counters, NumberStyle};
+//! use genpdf::elements;
+//!
+//! let mut counters = Counters::new();
+//! counters.set_parent("figure", "chapter");
+//!
+//! let number = counters.step("chapter");
+//! let heading = elements::Paragraph::new(format!("{} Introduction", number));
+//! let number = counters.step("figure");
+//! let caption = elements::Paragraph::new(format!("Figure {}: Overview", number));
+//! assert_eq!(number, "1.1");
+//!
+//! // Appendices are numbered with letters
+//! if let Some(chapter) = counters.get_mut("chapter") {
+//!     chapter.set_style(NumberStyle::UpperAlpha);
+//!     chapter.reset();
+//! }
+//! assert_eq!(counters.step("chapter"), "A");
+//! assert_eq!(counters.step("figure"), "A.1");
+//! ```
+//!
+//! [`Counter`]: struct.Counter.html
+//! [`Counters`]: struct.Counters.html
+//! [`NumberStyle`]: enum.NumberStyle.html
+//! [`Document::counters_mut`]: ../struct.Document.html#method.counters_mut
+
+/// The style of the numbers of a [`Counter`][].
+///
+/// Letters and roman numerals can only represent positive numbers, so the value zero is always
+/// formatted as an arabic number.
+///
+/// [`Counter`]: struct.Counter.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberStyle {
+    /// Arabic numbers (1, 2, 3, …).
+    Arabic,
+    /// Lowercase letters (a, b, …, z, aa, ab, …).
+    LowerAlpha,
+    /// Uppercase letters (A, B, …, Z, AA, AB, …).
+    UpperAlpha,
+    /// Lowercase roman numerals (i, ii, iii, …).
+    LowerRoman,
+    /// Uppercase roman numerals (I, II, III, …).
+    UpperRoman,
+}
+
+impl Default for NumberStyle {
+    fn default() -> NumberStyle {
+        NumberStyle::Arabic
+    }
+}
+
+impl NumberStyle {
+    /// Formats the given value with this style.
+    pub fn format(&self, value: usize) -> String {
+        if value == 0 {
+            return value.to_string();
+        }
+        match self {
+            NumberStyle::Arabic => value.to_string(),
+            NumberStyle::LowerAlpha => alpha(value),
+            NumberStyle::UpperAlpha => alpha(value).to_uppercase(),
+            NumberStyle::LowerRoman => roman(value),
+            NumberStyle::UpperRoman => roman(value).to_uppercase(),
+        }
+    }
+}
+
+/// A named counter, see the [module documentation](index.html).
+///
+/// # Example
+///
+/// ```
+/// use genpdf::counter::{Counter, NumberStyle};
+/// let counter = Counter::new("equation")
+///     .with_parent("chapter")
+///     .with_style(NumberStyle::LowerRoman);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counter {
+    name: String,
+    value: usize,
+    parent: Option<String>,
+    style: NumberStyle,
+    separator: String,
+}
+
+impl Counter {
+    /// Creates a new top-level counter with the given name, the value zero and arabic numbers.
+    pub fn new(name: impl Into<String>) -> Counter {
+        Counter {
+            name: name.into(),
+            value: 0,
+            parent: None,
+            style: NumberStyle::default(),
+            separator: ".".to_owned(),
+        }
+    }
+
+    /// Returns the name of this counter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the current value of this counter.
+    pub fn value(&self) -> usize {
+        self.value
+    }
+
+    /// Sets the current value of this counter.
+    pub fn set_value(&mut self, value: usize) {
+        self.value = value;
+    }
+
+    /// Increments the value of this counter.
+    ///
+    /// Note that this method does not reset the child counters.  Use [`Counters::step`][] for
+    /// that.
+    ///
+    /// [`Counters::step`]: struct.Counters.html#method.step
+    pub fn increment(&mut self) {
+        self.value += 1;
+    }
+
+    /// Resets the value of this counter to zero.
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+
+    /// Returns the name of the parent counter, if set.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Sets the name of the parent counter.
+    pub fn set_parent(&mut self, parent: impl Into<String>) {
+        self.parent = Some(parent.into());
+    }
+
+    /// Sets the name of the parent counter and returns the counter.
+    pub fn with_parent(mut self, parent: impl Into<String>) -> Self {
+        self.set_parent(parent);
+        self
+    }
+
+    /// Returns the number style of this counter.
+    pub fn style(&self) -> NumberStyle {
+        self.style
+    }
+
+    /// Sets the number style of this counter.
+    pub fn set_style(&mut self, style: NumberStyle) {
+        self.style = style;
+    }
+
+    /// Sets the number style of this counter and returns the counter.
+    pub fn with_style(mut self, style: NumberStyle) -> Self {
+        self.set_style(style);
+        self
+    }
+
+    /// Sets the separator between the number of the parent counter and the number of this
+    /// counter (defaults to `"."`).
+    pub fn set_separator(&mut self, separator: impl Into<String>) {
+        self.separator = separator.into();
+    }
+
+    /// Sets the separator between the number of the parent counter and the number of this
+    /// counter and returns the counter.
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.set_separator(separator);
+        self
+    }
+}
+
+/// A registry of named counters, see the [module documentation](index.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counters {
+    counters: Vec<Counter>,
+}
+
+impl Counters {
+    /// Creates a new registry with the default counters, see the [module
+    /// documentation](index.html).
+    pub fn new() -> Counters {
+        let mut counters = Counters::empty();
+        counters.add(Counter::new("chapter"));
+        counters.add(Counter::new("section").with_parent("chapter"));
+        counters.add(Counter::new("subsection").with_parent("section"));
+        for name in &["figure", "table", "listing", "equation"] {
+            counters.add(Counter::new(*name));
+        }
+        counters
+    }
+
+    /// Creates a new registry without counters.
+    pub fn empty() -> Counters {
+        Counters {
+            counters: Vec::new(),
+        }
+    }
+
+    /// Adds the given counter to this registry, replacing an existing counter with the same name.
+    pub fn add(&mut self, counter: Counter) {
+        if let Some(existing) = self.get_mut(&counter.name) {
+            *existing = counter;
+        } else {
+            self.counters.push(counter);
+        }
+    }
+
+    /// Returns the counter with the given name, if it exists.
+    pub fn get(&self, name: &str) -> Option<&Counter> {
+        self.counters.iter().find(|c| c.name == name)
+    }
+
+    /// Returns a mutable reference to the counter with the given name, if it exists.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Counter> {
+        self.counters.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Returns a mutable reference to the counter with the given name, adding a new top-level
+    /// counter if it does not exist.
+    fn get_or_add(&mut self, name: &str) -> &mut Counter {
+        let idx = match self.counters.iter().position(|c| c.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.counters.push(Counter::new(name));
+                self.counters.len() - 1
+            }
+        };
+        &mut self.counters[idx]
+    }
+
+    /// Sets the parent of the counter with the given name, adding the counter if it does not
+    /// exist.
+    pub fn set_parent(&mut self, name: &str, parent: impl Into<String>) {
+        self.get_or_add(name).set_parent(parent);
+    }
+
+    /// Increments the counter with the given name, resets its child counters and returns the
+    /// formatted number.
+    ///
+    /// If the counter does not exist, a new top-level counter is added.
+    pub fn step(&mut self, name: &str) -> String {
+        self.get_or_add(name).increment();
+        self.reset_children(name);
+        self.format(name).unwrap_or_default()
+    }
+
+    /// Resets the counter with the given name and its child counters.
+    pub fn reset(&mut self, name: &str) {
+        if let Some(counter) = self.get_mut(name) {
+            counter.reset();
+        }
+        self.reset_children(name);
+    }
+
+    fn reset_children(&mut self, name: &str) {
+        let mut parents = vec![name.to_owned()];
+        // Every counter is reset at most once, so cyclic parents cannot cause an endless loop.
+        let mut is_reset = vec![false; self.counters.len()];
+        while let Some(parent) = parents.pop() {
+            for (counter, is_reset) in self.counters.iter_mut().zip(is_reset.iter_mut()) {
+                if !*is_reset && counter.parent.as_deref() == Some(&parent) {
+                    counter.reset();
+                    *is_reset = true;
+                    parents.push(counter.name.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns the formatted number of the counter with the given name including the numbers of
+    /// its parents, or `None` if the counter does not exist.
+    pub fn format(&self, name: &str) -> Option<String> {
+        let mut counter = self.get(name)?;
+        let mut s = counter.style.format(counter.value);
+        // Limit the depth so that cyclic parents cannot cause an endless loop.
+        for _ in 0..self.counters.len() {
+            let parent = match counter.parent.as_deref().and_then(|p| self.get(p)) {
+                Some(parent) => parent,
+                None => break,
+            };
+            s = format!(
+                "{}{}{}",
+                parent.style.format(parent.value),
+                counter.separator,
+                s
+            );
+            counter = parent;
+        }
+        Some(s)
+    }
+}
+
+impl Default for Counters {
+    fn default() -> Counters {
+        Counters::new()
+    }
+}
+
+/// Formats the given positive value with letters (a, …, z, aa, ab, …).
+fn alpha(mut value: usize) -> String {
+    let mut s = Vec::new();
+    while value > 0 {
+        value -= 1;
+        s.push(b'a' + (value % 26) as u8);
+        value /= 26;
+    }
+    s.iter().rev().map(|&b| char::from(b)).collect()
+}
+
+/// Formats the given positive value as a lowercase roman numeral.
+fn roman(mut value: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut s = String::new();
+    for (n, numeral) in NUMERALS.iter() {
+        while value >= *n {
+            s.push_str(numeral);
+            value -= n;
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counter, Counters, NumberStyle};
+
+    #[test]
+    fn test_number_style() {
+        assert_eq!(NumberStyle::Arabic.format(12), "12");
+        assert_eq!(NumberStyle::LowerAlpha.format(1), "a");
+        assert_eq!(NumberStyle::UpperAlpha.format(28), "AB");
+        assert_eq!(NumberStyle::LowerRoman.format(1994), "mcmxciv");
+        assert_eq!(NumberStyle::UpperRoman.format(4), "IV");
+        assert_eq!(NumberStyle::UpperRoman.format(0), "0");
+    }
+
+    #[test]
+    fn test_counters() {
+        let mut counters = Counters::new();
+        counters.add(Counter::new("figure").with_parent("chapter"));
+        assert_eq!(counters.step("chapter"), "1");
+        assert_eq!(counters.step("section"), "1.1");
+        assert_eq!(counters.step("subsection"), "1.1.1");
+        assert_eq!(counters.step("figure"), "1.1");
+        assert_eq!(counters.step("figure"), "1.2");
+        assert_eq!(counters.step("table"), "1");
+        assert_eq!(counters.step("chapter"), "2");
+        assert_eq!(counters.format("subsection"), Some("2.0.0".to_owned()));
+        assert_eq!(counters.step("figure"), "2.1");
+        assert_eq!(counters.step("table"), "2");
+        assert_eq!(counters.step("custom"), "1");
+        assert_eq!(counters.format("unknown"), None);
+
+        counters.set_parent("chapter", "subsection");
+        counters.reset("chapter");
+        assert_eq!(counters.get("section").map(Counter::value), Some(0));
+    }
+}
@@ -0,0 +1,672 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Hybrid e-invoices according to ZUGFeRD 2 and Factur-X.
+//!
+//! A hybrid e-invoice is a PDF/A-3 document with an embedded XML invoice.  The [`EInvoice`][]
+//! helper takes a rendered PDF document and the invoice XML and writes a new PDF document that
+//! contains the XML as an associated file with the correct `AFRelationship` and the Factur-X XMP
+//! metadata for the selected [`Profile`][].
+//!
+//! The XMP metadata identifies the document as PDF/A-3b.  [`EInvoice::render`][] renders a
+//! [`Document`][] with the PDF/A-3 conformance, so prefer it over [`EInvoice::write`][], which
+//! only rejects documents whose metadata identifies them as another PDF/A part or as PDF/X.
+//! Apart from the conformance setting, the helper does not make the document PDF/A-3 compliant,
+//! so make sure to only use embedded fonts.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use genpdf::einvoice;
+//! let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+//!     .expect("Failed to load font family");
+//! let mut doc = genpdf::Document::new(font_family);
+//! doc.push(genpdf::elements::Paragraph::new("Invoice"));
+//!
+//! let xml = std::fs::read("factur-x.xml").expect("Failed to read invoice XML");
+//! let file = std::fs::File::create("invoice.pdf").expect("Failed to create file");
+//! einvoice::EInvoice::new(xml, einvoice::Profile::En16931)
+//!     .render(doc, file)
+//!     .expect("Failed to write e-invoice");
+//! ```
+//!
+//! [`Document`]: ../struct.Document.html
+//! [`EInvoice`]: struct.EInvoice.html
+//! [`EInvoice::render`]: struct.EInvoice.html#method.render
+//! [`EInvoice::write`]: struct.EInvoice.html#method.write
+//! [`Profile`]: enum.Profile.html
+
+use std::io;
+
+use lopdf::{dictionary, Object, Stream};
+
+use crate::error::{Error, ErrorKind};
+use crate::Document;
+
+const FACTURX_NAMESPACE: &str = "urn:factur-x:pdfa:CrossIndustryDocument:invoice:1p0#";
+
+/// The PDF/A identification properties that are replaced in the XMP metadata.
+const PDFAID_PROPERTIES: &[&str] = &[
+    "pdfaid:part",
+    "pdfaid:conformance",
+    "pdfaid:amd",
+    "pdfaid:rev",
+];
+
+const FACTURX_PROPERTIES: &[(&str, &str)] = &[
+    ("DocumentFileName", "The name of the embedded XML document"),
+    (
+        "DocumentType",
+        "The type of the hybrid document in capital letters, e.g. INVOICE",
+    ),
+    (
+        "Version",
+        "The actual version of the standard applying to the embedded XML document",
+    ),
+    (
+        "ConformanceLevel",
+        "The conformance level of the embedded XML document",
+    ),
+];
+
+/// A Factur-X or ZUGFeRD profile, i. e. the conformance level of the invoice XML.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Profile {
+    /// The MINIMUM profile.
+    Minimum,
+    /// The BASIC WL profile.
+    BasicWl,
+    /// The BASIC profile.
+    Basic,
+    /// The EN 16931 (COMFORT) profile.
+    En16931,
+    /// The EXTENDED profile.
+    Extended,
+    /// The XRECHNUNG profile.
+    XRechnung,
+}
+
+impl Profile {
+    /// Returns the conformance level for this profile as used in the XMP metadata.
+    pub fn conformance_level(&self) -> &'static str {
+        match self {
+            Profile::Minimum => "MINIMUM",
+            Profile::BasicWl => "BASIC WL",
+            Profile::Basic => "BASIC",
+            Profile::En16931 => "EN 16931",
+            Profile::Extended => "EXTENDED",
+            Profile::XRechnung => "XRECHNUNG",
+        }
+    }
+
+    /// Returns the default file name of the embedded XML file for this profile.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Profile::XRechnung => "xrechnung.xml",
+            _ => "factur-x.xml",
+        }
+    }
+
+    /// Returns the default relationship between the embedded XML file and the PDF document.
+    ///
+    /// The MINIMUM and BASIC WL profiles do not contain all information required for an invoice,
+    /// so the XML file is only marked as data.  For the other profiles, the XML file is an
+    /// alternative representation of the PDF document.
+    pub fn relationship(&self) -> Relationship {
+        match self {
+            Profile::Minimum | Profile::BasicWl => Relationship::Data,
+            _ => Relationship::Alternative,
+        }
+    }
+}
+
+/// The relationship between an associated file and the PDF document (`AFRelationship`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Relationship {
+    /// The file is the original source material for the document.
+    Source,
+    /// The file contains data that is used to derive the visual presentation of the document.
+    Data,
+    /// The file is an alternative representation of the document.
+    Alternative,
+}
+
+impl Relationship {
+    fn name(&self) -> &'static str {
+        match self {
+            Relationship::Source => "Source",
+            Relationship::Data => "Data",
+            Relationship::Alternative => "Alternative",
+        }
+    }
+}
+
+/// Combines a rendered PDF document and an invoice XML to a hybrid e-invoice.
+///
+/// See the [module documentation](index.html) for more information.
+#[derive(Clone, Debug)]
+pub struct EInvoice {
+    xml: Vec<u8>,
+    profile: Profile,
+    file_name: String,
+    relationship: Relationship,
+    version: String,
+}
+
+impl EInvoice {
+    /// Creates a new e-invoice helper for the given invoice XML and profile.
+    ///
+    /// The file name and the relationship are set to the defaults for the profile, and the
+    /// Factur-X version is set to 1.0.
+    pub fn new(xml: impl Into<Vec<u8>>, profile: Profile) -> EInvoice {
+        EInvoice {
+            xml: xml.into(),
+            profile,
+            file_name: profile.file_name().to_owned(),
+            relationship: profile.relationship(),
+            version: "1.0".to_owned(),
+        }
+    }
+
+    /// Sets the file name of the embedded XML file.
+    pub fn set_file_name(&mut self, file_name: impl Into<String>) {
+        self.file_name = file_name.into();
+    }
+
+    /// Sets the file name of the embedded XML file and returns the helper.
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.set_file_name(file_name);
+        self
+    }
+
+    /// Sets the relationship between the embedded XML file and the PDF document.
+    pub fn set_relationship(&mut self, relationship: Relationship) {
+        self.relationship = relationship;
+    }
+
+    /// Sets the relationship between the embedded XML file and the PDF document and returns the
+    /// helper.
+    pub fn with_relationship(mut self, relationship: Relationship) -> Self {
+        self.set_relationship(relationship);
+        self
+    }
+
+    /// Sets the Factur-X version that is written to the XMP metadata.
+    pub fn set_version(&mut self, version: impl Into<String>) {
+        self.version = version.into();
+    }
+
+    /// Sets the Factur-X version that is written to the XMP metadata and returns the helper.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.set_version(version);
+        self
+    }
+
+    /// Renders the given document with the PDF/A-3 conformance, embeds the invoice XML and writes
+    /// the result to the given writer.
+    ///
+    /// The conformance that has been set for the document is replaced.
+    pub fn render(&self, doc: Document, w: impl io::Write) -> Result<(), Error> {
+        let mut doc = doc;
+        doc.set_conformance(printpdf::PdfConformance::A3_2012_PDF_1_7);
+        let mut pdf = Vec::new();
+        doc.render(&mut pdf)?;
+        self.write(&pdf, w)
+    }
+
+    /// Embeds the invoice XML into the given rendered PDF document and writes the result to the
+    /// given writer.
+    ///
+    /// The PDF/A identification in the XMP metadata of the document is replaced with PDF/A-3b.
+    /// If the metadata identifies the document as another PDF/A part or as PDF/X, an error with
+    /// the [`ErrorKind::InvalidData`][] kind is returned.  Documents without metadata cannot be
+    /// checked, so make sure that they have been rendered with the PDF/A-3 conformance, or use
+    /// [`render`][] instead.
+    ///
+    /// [`ErrorKind::InvalidData`]: ../error/enum.ErrorKind.html#variant.InvalidData
+    /// [`render`]: #method.render
+    pub fn write(&self, pdf: &[u8], w: impl io::Write) -> Result<(), Error> {
+        let mut doc = lopdf::Document::load_mem(pdf).map_err(invalid_pdf)?;
+        let catalog_id = doc
+            .trailer
+            .get(b"Root")
+            .and_then(Object::as_reference)
+            .map_err(invalid_pdf)?;
+        let xmp = existing_xmp(&doc, catalog_id).map_err(invalid_pdf)?;
+        if let Some(xmp) = &xmp {
+            check_conformance(xmp)?;
+        }
+        self.embed(&mut doc, catalog_id, xmp).map_err(invalid_pdf)?;
+        let mut w = w;
+        doc.save_to(&mut w)
+            .map_err(|err| Error::new("Failed to write e-invoice", err))
+    }
+
+    fn embed(
+        &self,
+        doc: &mut lopdf::Document,
+        catalog_id: lopdf::ObjectId,
+        xmp: Option<String>,
+    ) -> Result<(), lopdf::Error> {
+        let file_name = Object::string_literal(self.file_name.as_str());
+        let mut params = dictionary! {
+            "Size" => self.xml.len() as i64,
+        };
+        if let Some(date) = info_date(doc) {
+            params.set("ModDate", date);
+        }
+        let file_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "EmbeddedFile",
+                "Subtype" => Object::Name(b"text/xml".to_vec()),
+                "Params" => params,
+            },
+            self.xml.clone(),
+        ));
+        let spec_id = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => file_name.clone(),
+            "UF" => file_name.clone(),
+            "Desc" => Object::string_literal("Factur-X/ZUGFeRD invoice"),
+            "AFRelationship" => self.relationship.name(),
+            "EF" => dictionary! {
+                "F" => file_id,
+                "UF" => file_id,
+            },
+        });
+
+        let xmp = self.xmp_metadata(xmp);
+        let metadata_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "Metadata",
+                "Subtype" => "XML",
+            },
+            xmp.into_bytes(),
+        ));
+
+        let embedded_files = dictionary! {
+            "Names" => vec![file_name, spec_id.into()],
+        };
+        let names_id = match doc.get_dictionary(catalog_id)?.get(b"Names") {
+            Ok(Object::Reference(id)) => Some(*id),
+            _ => None,
+        };
+        let catalog = if let Some(names_id) = names_id {
+            doc.get_object_mut(names_id)?
+                .as_dict_mut()?
+                .set("EmbeddedFiles", embedded_files);
+            doc.get_object_mut(catalog_id)?.as_dict_mut()?
+        } else {
+            let catalog = doc.get_object_mut(catalog_id)?.as_dict_mut()?;
+            match catalog.get_mut(b"Names") {
+                Ok(Object::Dictionary(names)) => names.set("EmbeddedFiles", embedded_files),
+                _ => catalog.set("Names", dictionary! { "EmbeddedFiles" => embedded_files }),
+            }
+            catalog
+        };
+        catalog.set("AF", vec![Object::Reference(spec_id)]);
+        catalog.set("Metadata", metadata_id);
+        Ok(())
+    }
+
+    /// Adds the Factur-X description to the given existing XMP metadata of the document or creates
+    /// new metadata if the document does not have any.
+    ///
+    /// Existing PDF/A identification properties are removed as they are replaced by the
+    /// description.
+    fn xmp_metadata(&self, xmp: Option<String>) -> String {
+        let description = self.xmp_description();
+        if let Some(mut xmp) = xmp {
+            for name in PDFAID_PROPERTIES {
+                remove_xmp_property(&mut xmp, name);
+            }
+            if let Some(idx) = xmp.rfind("</rdf:RDF>") {
+                xmp.insert_str(idx, &description);
+                return xmp;
+            }
+        }
+        format!(
+            "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+             <x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n\
+             <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n\
+             {}</rdf:RDF>\n\
+             </x:xmpmeta>\n\
+             <?xpacket end=\"w\"?>",
+            description
+        )
+    }
+
+    fn xmp_description(&self) -> String {
+        format!(
+            "<rdf:Description rdf:about=\"\"\n    \
+             xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\">\n  \
+             <pdfaid:part>3</pdfaid:part>\n  \
+             <pdfaid:conformance>B</pdfaid:conformance>\n\
+             </rdf:Description>\n\
+             <rdf:Description rdf:about=\"\"\n    \
+             xmlns:fx=\"{namespace}\">\n  \
+             <fx:DocumentType>INVOICE</fx:DocumentType>\n  \
+             <fx:DocumentFileName>{file_name}</fx:DocumentFileName>\n  \
+             <fx:Version>{version}</fx:Version>\n  \
+             <fx:ConformanceLevel>{conformance_level}</fx:ConformanceLevel>\n\
+             </rdf:Description>\n\
+             <rdf:Description rdf:about=\"\"\n    \
+             xmlns:pdfaExtension=\"http://www.aiim.org/pdfa/ns/extension/\"\n    \
+             xmlns:pdfaSchema=\"http://www.aiim.org/pdfa/ns/schema#\"\n    \
+             xmlns:pdfaProperty=\"http://www.aiim.org/pdfa/ns/property#\">\n  \
+             <pdfaExtension:schemas><rdf:Bag><rdf:li rdf:parseType=\"Resource\">\n    \
+             <pdfaSchema:schema>Factur-X PDFA Extension Schema</pdfaSchema:schema>\n    \
+             <pdfaSchema:namespaceURI>{namespace}</pdfaSchema:namespaceURI>\n    \
+             <pdfaSchema:prefix>fx</pdfaSchema:prefix>\n    \
+             <pdfaSchema:property><rdf:Seq>\n      \
+             {properties}\
+             </rdf:Seq></pdfaSchema:property>\n  \
+             </rdf:li></rdf:Bag></pdfaExtension:schemas>\n\
+             </rdf:Description>\n",
+            namespace = FACTURX_NAMESPACE,
+            file_name = escape_xml(&self.file_name),
+            version = escape_xml(&self.version),
+            conformance_level = self.profile.conformance_level(),
+            properties = FACTURX_PROPERTIES
+                .iter()
+                .map(|(name, description)| format!(
+                    "<rdf:li rdf:parseType=\"Resource\">\
+                     <pdfaProperty:name>{}</pdfaProperty:name>\
+                     <pdfaProperty:valueType>Text</pdfaProperty:valueType>\
+                     <pdfaProperty:category>external</pdfaProperty:category>\
+                     <pdfaProperty:description>{}</pdfaProperty:description>\
+                     </rdf:li>\n",
+                    name, description
+                ))
+                .collect::<String>(),
+        )
+    }
+}
+
+/// Returns the XMP metadata of the document with the given catalog, if any.
+fn existing_xmp(
+    doc: &lopdf::Document,
+    catalog_id: lopdf::ObjectId,
+) -> Result<Option<String>, lopdf::Error> {
+    let metadata_id = match doc.get_dictionary(catalog_id)?.get(b"Metadata") {
+        Ok(Object::Reference(id)) => *id,
+        _ => return Ok(None),
+    };
+    let stream = doc.get_object(metadata_id)?.as_stream()?;
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    Ok(Some(String::from_utf8_lossy(&content).into_owned()))
+}
+
+/// Checks that the given XMP metadata does not identify the document as another PDF/A part or as
+/// PDF/X.
+fn check_conformance(xmp: &str) -> Result<(), Error> {
+    let part = xmp_property(xmp, "pdfaid:part");
+    let pdfx_version = xmp_property(xmp, "pdfx:GTS_PDFXVersion")
+        .or_else(|| xmp_property(xmp, "pdfxid:GTS_PDFXVersion"))
+        .filter(|version| !version.is_empty());
+    if part.map(|part| part != "3").unwrap_or(false)
+        || pdfx_version
+            .map(|version| !version.starts_with("PDF/A-3"))
+            .unwrap_or(false)
+    {
+        let identification = part
+            .map(|part| format!("PDF/A-{}", part))
+            .or_else(|| pdfx_version.map(ToOwned::to_owned))
+            .unwrap_or_default();
+        return Err(Error::new(
+            format!(
+                "E-invoices must be PDF/A-3 documents, but the document is {}",
+                identification
+            ),
+            ErrorKind::InvalidData,
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the value of the given simple property of the XMP metadata that is either stored as
+/// an element or as an attribute.
+fn xmp_property<'a>(xmp: &'a str, name: &str) -> Option<&'a str> {
+    let start_tag = format!("<{}>", name);
+    if let Some(idx) = xmp.find(&start_tag) {
+        let value = &xmp[idx + start_tag.len()..];
+        let end = value.find('<')?;
+        return Some(value[..end].trim());
+    }
+    let attribute = format!("{}=\"", name);
+    let idx = xmp.find(&attribute)?;
+    let value = &xmp[idx + attribute.len()..];
+    let end = value.find('"')?;
+    Some(&value[..end])
+}
+
+/// Removes all occurrences of the given simple property from the XMP metadata.
+fn remove_xmp_property(xmp: &mut String, name: &str) {
+    let start_tag = format!("<{}>", name);
+    let end_tag = format!("</{}>", name);
+    while let Some(start) = xmp.find(&start_tag) {
+        match xmp[start..].find(&end_tag) {
+            Some(end) => xmp.replace_range(start..start + end + end_tag.len(), ""),
+            None => break,
+        }
+    }
+    let attribute = format!("{}=\"", name);
+    while let Some(start) = xmp.find(&attribute) {
+        let value_start = start + attribute.len();
+        match xmp[value_start..].find('"') {
+            Some(end) => xmp.replace_range(start..value_start + end + 1, ""),
+            None => break,
+        }
+    }
+}
+
+/// Returns the modification date (or the creation date) from the document information dictionary.
+fn info_date(doc: &lopdf::Document) -> Option<Object> {
+    let info_id = doc.trailer.get(b"Info").ok()?.as_reference().ok()?;
+    let info = doc.get_dictionary(info_id).ok()?;
+    info.get(b"ModDate")
+        .or_else(|_| info.get(b"CreationDate"))
+        .ok()
+        .cloned()
+}
+
+fn invalid_pdf(err: lopdf::Error) -> Error {
+    Error::new(
+        format!("Invalid PDF document for e-invoice: {}", err),
+        ErrorKind::InvalidData,
+    )
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use lopdf::{dictionary, Object};
+
+    use super::{EInvoice, Profile, Relationship};
+    use crate::error::ErrorKind;
+
+    fn minimal_pdf() -> Vec<u8> {
+        minimal_pdf_with_xmp(None)
+    }
+
+    fn minimal_pdf_with_xmp(xmp: Option<&str>) -> Vec<u8> {
+        let mut doc = lopdf::Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let mut catalog = dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        };
+        if let Some(xmp) = xmp {
+            let metadata_id = doc.add_object(lopdf::Stream::new(
+                dictionary! {
+                    "Type" => "Metadata",
+                    "Subtype" => "XML",
+                },
+                xmp.as_bytes().to_vec(),
+            ));
+            catalog.set("Metadata", metadata_id);
+        }
+        let catalog_id = doc.add_object(catalog);
+        doc.trailer.set("Root", catalog_id);
+        let mut pdf = Vec::new();
+        doc.save_to(&mut pdf).unwrap();
+        pdf
+    }
+
+    fn xmp(pdf: &[u8]) -> String {
+        let doc = lopdf::Document::load_mem(pdf).unwrap();
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let catalog = doc.get_dictionary(catalog_id).unwrap();
+        let metadata_id = catalog.get(b"Metadata").unwrap().as_reference().unwrap();
+        let metadata = doc
+            .get_object(metadata_id)
+            .and_then(Object::as_stream)
+            .unwrap();
+        String::from_utf8(metadata.content.clone()).unwrap()
+    }
+
+    fn xmp_with_description(description: &str) -> String {
+        format!(
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n\
+             <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n\
+             {}\n\
+             </rdf:RDF>\n\
+             </x:xmpmeta>",
+            description
+        )
+    }
+
+    #[test]
+    fn test_profile_defaults() {
+        assert_eq!(Profile::Minimum.relationship(), Relationship::Data);
+        assert_eq!(Profile::En16931.relationship(), Relationship::Alternative);
+        assert_eq!(Profile::XRechnung.file_name(), "xrechnung.xml");
+    }
+
+    #[test]
+    fn test_write() {
+        let xml = b"<rsm:CrossIndustryInvoice/>".to_vec();
+        let mut pdf = Vec::new();
+        EInvoice::new(xml.clone(), Profile::Basic)
+            .write(&minimal_pdf(), &mut pdf)
+            .unwrap();
+
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let catalog = doc.get_dictionary(catalog_id).unwrap();
+
+        let af = catalog.get(b"AF").unwrap().as_array().unwrap();
+        let spec = doc.get_dictionary(af[0].as_reference().unwrap()).unwrap();
+        assert_eq!(
+            spec.get(b"AFRelationship").unwrap().as_name().unwrap(),
+            b"Alternative"
+        );
+        let file_id = spec
+            .get(b"EF")
+            .and_then(Object::as_dict)
+            .and_then(|ef| ef.get(b"F"))
+            .and_then(Object::as_reference)
+            .unwrap();
+        let file = doc.get_object(file_id).and_then(Object::as_stream).unwrap();
+        assert_eq!(file.content, xml);
+
+        let metadata_id = catalog.get(b"Metadata").unwrap().as_reference().unwrap();
+        let metadata = doc
+            .get_object(metadata_id)
+            .and_then(Object::as_stream)
+            .unwrap();
+        let xmp = String::from_utf8(metadata.content.clone()).unwrap();
+        assert!(xmp.contains("<fx:ConformanceLevel>BASIC</fx:ConformanceLevel>"));
+        assert!(xmp.contains("<pdfaid:part>3</pdfaid:part>"));
+    }
+
+    #[test]
+    fn test_write_replaces_pdfa_identification() {
+        let descriptions = [
+            "<rdf:Description rdf:about=\"\" xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\">\
+             <pdfaid:part>3</pdfaid:part><pdfaid:conformance>A</pdfaid:conformance>\
+             </rdf:Description>",
+            "<rdf:Description rdf:about=\"\" xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\" \
+             pdfaid:part=\"3\" pdfaid:conformance=\"A\"/>",
+        ];
+        for description in &descriptions {
+            let pdf = minimal_pdf_with_xmp(Some(&xmp_with_description(description)));
+            let mut output = Vec::new();
+            EInvoice::new(b"<rsm:CrossIndustryInvoice/>".to_vec(), Profile::Basic)
+                .write(&pdf, &mut output)
+                .unwrap();
+
+            let xmp = xmp(&output);
+            assert_eq!(xmp.matches("pdfaid:part").count(), 2);
+            assert_eq!(xmp.matches("pdfaid:conformance").count(), 2);
+            assert!(xmp.contains("<pdfaid:part>3</pdfaid:part>"));
+            assert!(xmp.contains("<pdfaid:conformance>B</pdfaid:conformance>"));
+        }
+    }
+
+    #[test]
+    fn test_write_other_conformance() {
+        let descriptions = [
+            "<rdf:Description rdf:about=\"\" xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\" \
+             pdfaid:part=\"1\" pdfaid:conformance=\"B\"/>",
+            "<rdf:Description rdf:about=\"\" xmlns:pdfx=\"http://ns.adobe.com/pdfx/1.3/\">\
+             <pdfx:GTS_PDFXVersion>PDF/X-3:2002</pdfx:GTS_PDFXVersion></rdf:Description>",
+        ];
+        for description in &descriptions {
+            let pdf = minimal_pdf_with_xmp(Some(&xmp_with_description(description)));
+            let err = EInvoice::new(b"<rsm:CrossIndustryInvoice/>".to_vec(), Profile::Basic)
+                .write(&pdf, Vec::new())
+                .unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::InvalidData));
+        }
+
+        let description = "<rdf:Description rdf:about=\"\" \
+                           xmlns:pdfx=\"http://ns.adobe.com/pdfx/1.3/\">\
+                           <pdfx:GTS_PDFXVersion>PDF/A-3:2012</pdfx:GTS_PDFXVersion>\
+                           </rdf:Description>";
+        let pdf = minimal_pdf_with_xmp(Some(&xmp_with_description(description)));
+        EInvoice::new(b"<rsm:CrossIndustryInvoice/>".to_vec(), Profile::Basic)
+            .write(&pdf, Vec::new())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let mut doc = crate::Document::new(crate::fonts::test_font_family());
+        doc.set_conformance(printpdf::PdfConformance::X3_2002_PDF_1_3);
+        doc.push(crate::elements::Text::new("Invoice"));
+        let mut pdf = Vec::new();
+        EInvoice::new(b"<rsm:CrossIndustryInvoice/>".to_vec(), Profile::Basic)
+            .render(doc, &mut pdf)
+            .unwrap();
+        assert!(xmp(&pdf).contains("<pdfaid:part>3</pdfaid:part>"));
+    }
+}
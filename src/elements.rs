@@ -9,36 +9,77 @@
 //! It includes the following elements:
 //! - Containers:
 //!   - [`LinearLayout`][]: arranges its elements sequentially
+//!   - [`Columns`][]: flows its elements through multiple columns
 //!   - [`TableLayout`][]: arranges its elements in columns and rows
 //!   - [`OrderedList`][] and [`UnorderedList`][]: arrange their elements sequentially with bullet
 //!     points
+//!   - [`Float`][]: floats an element to the side of a paragraph that wraps around it
 //! - Text:
 //!   - [`Text`][]: a single line of text
 //!   - [`Paragraph`][]: a wrapped and aligned paragraph of text
+//!   - [`TocEntry`][]: a table of contents entry with a leader and a page number
+//!   - [`TableOfContents`][]: a table of contents generated from a document outline
 //! - Wrappers:
 //!   - [`FramedElement`][]: draws a frame around the wrapped element
 //!   - [`PaddedElement`][]: adds a padding to the wrapped element
 //!   - [`StyledElement`][]: sets a default style for the wrapped element and its children
+//!   - [`Offset`][]: shifts the wrapped element without changing its size in the layout
+//!   - [`ScaleToFit`][]: scales the wrapped element down so that it fits into the available area
+//!   - [`SubDocument`][]: an independently authored component with its own style, fonts and
+//!     decorator
+//!   - [`OutlineMarker`][]: adds the wrapped element to the document outline
+//!   - [`FixedHeight`][]: renders the wrapped element in a box with a fixed height
+//!   - [`FixedSize`][]: renders the wrapped element in a box with a fixed width and height
+//!   - [`MinHeight`][] and [`MaxHeight`][]: limit the height of the wrapped element
+//!   - [`KeepWithNext`][]: keeps the wrapped element on the same page as the next element
 //! - Other:
 //!   - [`Image`][]: an image (requires the `images` feature)
 //!   - [`Break`][]: adds forced line breaks as a spacer
+//!   - [`Glue`][]: adds vertical space that can stretch or shrink to fill the page
 //!   - [`PageBreak`][]: adds a forced page break
+//!   - [`PageBreakHint`][]: marks a preferred position for page breaks
+//!   - [`Placeholder`][]: a crossed-out box for missing content
+//!   - [`Canvas`][]: custom graphics drawn by a closure
+//!   - [`GlyphGrid`][]: a dense grid of glyphs with background colors
+//!   - [`Rectangle`][], [`Ellipse`][], [`Circle`][] and [`Polygon`][]: filled or outlined shapes
 //!
 //! You can create custom elements by implementing the [`Element`][] trait.
 //!
 //! [`Element`]: ../trait.Element.html
 //! [`LinearLayout`]: struct.LinearLayout.html
+//! [`Columns`]: struct.Columns.html
 //! [`TableLayout`]: struct.TableLayout.html
 //! [`OrderedList`]: struct.OrderedList.html
 //! [`UnorderedList`]: struct.UnorderedList.html
+//! [`Float`]: struct.Float.html
 //! [`Text`]: struct.Text.html
 //! [`Image`]: struct.Image.html
 //! [`Break`]: struct.Break.html
+//! [`Glue`]: struct.Glue.html
 //! [`PageBreak`]: struct.PageBreak.html
+//! [`PageBreakHint`]: struct.PageBreakHint.html
+//! [`Placeholder`]: struct.Placeholder.html
+//! [`Canvas`]: struct.Canvas.html
+//! [`GlyphGrid`]: struct.GlyphGrid.html
+//! [`Rectangle`]: struct.Rectangle.html
+//! [`Ellipse`]: struct.Ellipse.html
+//! [`Circle`]: struct.Circle.html
+//! [`Polygon`]: struct.Polygon.html
 //! [`Paragraph`]: struct.Paragraph.html
+//! [`TocEntry`]: struct.TocEntry.html
+//! [`TableOfContents`]: struct.TableOfContents.html
 //! [`FramedElement`]: struct.FramedElement.html
 //! [`PaddedElement`]: struct.PaddedElement.html
 //! [`StyledElement`]: struct.StyledElement.html
+//! [`Offset`]: struct.Offset.html
+//! [`ScaleToFit`]: struct.ScaleToFit.html
+//! [`SubDocument`]: struct.SubDocument.html
+//! [`OutlineMarker`]: struct.OutlineMarker.html
+//! [`FixedHeight`]: struct.FixedHeight.html
+//! [`FixedSize`]: struct.FixedSize.html
+//! [`MinHeight`]: struct.MinHeight.html
+//! [`MaxHeight`]: struct.MaxHeight.html
+//! [`KeepWithNext`]: struct.KeepWithNext.html
 
 #[cfg(feature = "images")]
 mod images;
@@ -46,26 +87,44 @@ mod images;
 #[cfg(feature = "math")]
 mod math;
 
+mod canvas;
 mod codeblock;
+mod shapes;
+mod terminal;
 
+use std::borrow;
+use std::cmp;
 use std::collections;
+use std::fmt;
 use std::iter;
 use std::mem;
+use std::rc;
 
-use crate::error::{Error, ErrorKind};
+use crate::error::{Error, ErrorKind, Warning, WarningKind};
 use crate::fonts;
+use crate::outline::{Outline, OutlineEntry, OutlineKind};
 use crate::render;
-use crate::style::{LineStyle, Style, StyledString};
+use crate::style::{Color, Fill, LineStyle, Style, StyledStr, StyledString, TextRenderingMode};
+use crate::tree::ElementTree;
 use crate::wrap;
-use crate::{Alignment, Context, Element, Margins, Mm, Position, RenderResult, Size};
+use crate::{
+    Alignment, AreaSnapshot, Context, Element, ElementErrorPolicy, Margins, Mm, PageDecorator,
+    Position, RenderResult, Size, StrictMode, VerticalAlignment,
+};
 
 #[cfg(feature = "images")]
-pub use images::Image;
+pub use images::{Image, ImageLimits};
 
 #[cfg(feature = "math")]
 pub use math::Math;
 
-pub use codeblock::CodeBlock;
+#[cfg(feature = "math")]
+pub use crate::math::MathStyle;
+
+pub use canvas::{Canvas, Painter};
+pub use codeblock::{CaptionPosition, CodeBlock};
+pub use shapes::{Circle, Ellipse, Polygon, Rectangle};
+pub use terminal::Terminal;
 
 /// Helper trait for creating boxed elements.
 pub trait IntoBoxedElement {
@@ -85,9 +144,74 @@ impl IntoBoxedElement for Box<dyn Element> {
     }
 }
 
+/// An element that can be cloned into a new boxed element.
+///
+/// This trait is automatically implemented for all elements that implement `Clone`.  It is used
+/// for elements that have to be rendered multiple times, for example the header rows of a
+/// [`TableLayout`][].
+///
+/// [`TableLayout`]: struct.TableLayout.html
+pub trait CloneableElement: Element {
+    /// Returns a boxed clone of this element.
+    fn clone_element(&self) -> Box<dyn Element>;
+}
+
+impl<E: Element + Clone + 'static> CloneableElement for E {
+    fn clone_element(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+}
+
+/// Measures an element by rendering a copy of it on a dry run area, see [`Element::measure`][].
+///
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+fn measure_copy<E: Element + Clone>(
+    element: &E,
+    context: &Context,
+    area: render::Area<'_>,
+    style: Style,
+) -> Option<RenderResult> {
+    element.clone().render(context, area.dry_run(), style).ok()
+}
+
+/// Handles an error returned by an element according to the [`ElementErrorPolicy`][] and renders
+/// a [`Placeholder`][] with the error message instead of the element.
+///
+/// If the policy is [`ElementErrorPolicy::Abort`][], the error is returned.  Errors are only
+/// collected if the area is not a dry run so that measuring an element does not report its
+/// errors twice.
+///
+/// [`ElementErrorPolicy`]: ../enum.ElementErrorPolicy.html
+/// [`ElementErrorPolicy::Abort`]: ../enum.ElementErrorPolicy.html#variant.Abort
+/// [`Placeholder`]: struct.Placeholder.html
+fn render_error_placeholder(
+    context: &Context,
+    area: render::Area<'_>,
+    style: Style,
+    err: Error,
+) -> Result<RenderResult, Error> {
+    let label = err.to_string();
+    if !area.is_dry_run() {
+        context.handle_element_error(err)?;
+    } else if context.element_error_policy() == ElementErrorPolicy::Abort {
+        return Err(err);
+    }
+    let size = Size::new(
+        area.size().width,
+        style.line_height(&context.font_cache) * 2.0,
+    );
+    let mut placeholder = Placeholder::new(size).with_label(label);
+    Ok(placeholder.render(context, area, style).unwrap_or_default())
+}
+
 /// Arranges a list of elements sequentially.
 ///
-/// Currently, elements can only be arranged vertically.
+/// Currently, elements can only be arranged vertically.  Optionally, a fixed gap can be inserted
+/// between consecutive elements, see [`set_spacing`][].  The gap is omitted at page breaks.
+///
+/// If the layout contains [`PageBreakHint`][] elements, it may break the page at a hint instead
+/// of splitting the elements after the hint.  If it contains [`Glue`][] elements, the space that
+/// is left empty on a page is distributed among the glue elements on that page.
 ///
 /// # Examples
 ///
@@ -107,9 +231,15 @@ impl IntoBoxedElement for Box<dyn Element> {
 ///     .element(elements::Paragraph::new("Test2"));
 /// ```
 ///
+/// [`set_spacing`]: #method.set_spacing
+/// [`PageBreakHint`]: struct.PageBreakHint.html
+/// [`Glue`]: struct.Glue.html
 pub struct LinearLayout {
     elements: Vec<Box<dyn Element>>,
     render_idx: usize,
+    spacing: Mm,
+    area_snapshots: Option<Vec<AreaSnapshot>>,
+    is_hint_taken: bool,
 }
 
 impl LinearLayout {
@@ -117,6 +247,9 @@ impl LinearLayout {
         LinearLayout {
             elements: Vec::new(),
             render_idx: 0,
+            spacing: Mm(0.0),
+            area_snapshots: None,
+            is_hint_taken: false,
         }
     }
 
@@ -136,23 +269,248 @@ impl LinearLayout {
         self
     }
 
+    /// Sets the gap between consecutive elements of this layout.
+    pub fn set_spacing(&mut self, spacing: impl Into<Mm>) {
+        self.spacing = spacing.into();
+    }
+
+    /// Sets the gap between consecutive elements of this layout and returns the layout.
+    pub fn with_spacing(mut self, spacing: impl Into<Mm>) -> Self {
+        self.set_spacing(spacing);
+        self
+    }
+
+    /// Adds the spacing after an element if there are more elements before the given end index.
+    /// If the spacing does not fit into the area, it is truncated so that the next element starts
+    /// on the next page.
+    fn add_spacing(&self, area: &mut render::Area<'_>, result: &mut RenderResult, end: usize) {
+        if self.render_idx < end && self.spacing > Mm(0.0) {
+            let spacing = self.spacing.min(area.size().height);
+            area.add_offset(Position::new(0, spacing));
+            result.size = result.size.stack_vertical(Size::new(0, spacing));
+        }
+    }
+
+    /// Enables or disables the recording of area snapshots for the elements of this layout.
+    pub(crate) fn set_record_area_snapshots(&mut self, record: bool) {
+        self.area_snapshots = if record { Some(Vec::new()) } else { None };
+    }
+
+    /// Returns the area snapshots that have been recorded since the last call of this method.
+    pub(crate) fn take_area_snapshots(&mut self) -> Vec<AreaSnapshot> {
+        self.area_snapshots
+            .as_mut()
+            .map(mem::take)
+            .unwrap_or_default()
+    }
+
+    fn record_area_snapshot(&mut self, available_height: Mm, height: Mm, has_more: bool) {
+        let element = self.render_idx;
+        if let Some(snapshots) = &mut self.area_snapshots {
+            snapshots.push(AreaSnapshot {
+                element,
+                page: 0,
+                available_height,
+                height,
+                has_more,
+            });
+        }
+    }
+
+    /// Returns true if the page should be broken at the page break hint with the given index and
+    /// penalty, see [`PageBreakHint`][].
+    ///
+    /// [`PageBreakHint`]: struct.PageBreakHint.html
+    fn is_break_at_hint(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+        start_height: Mm,
+        idx: usize,
+        penalty: u8,
+    ) -> bool {
+        // The hint is only used if the space that would be left empty is small enough
+        let max_empty_height = start_height * f64::from(100 - penalty.min(100)) / 100.0;
+        if area.size().height >= start_height || area.size().height > max_empty_height {
+            return false;
+        }
+
+        // Check whether the elements up to the next hint fit into the remaining area.  If we
+        // cannot measure an element, we ignore the hint.
+        let section = self.elements[idx + 1..]
+            .iter()
+            .take_while(|element| element.page_break_penalty().is_none());
+        for (i, element) in section.enumerate() {
+            if i > 0 {
+                area.add_offset(Position::new(0, self.spacing));
+            }
+            if area.size().height <= Mm(0.0) {
+                return true;
+            }
+            match element.measure(context, area.clone(), style) {
+                Some(result) if result.has_more => return true,
+                Some(result) => area.add_offset(Position::new(0, result.size.height)),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Calculates the adjustment of the glue elements that are rendered on the current page, see
+    /// [`Glue`][].
+    ///
+    /// A positive ratio means that the glue is stretched, a negative ratio means that it is
+    /// shrunk.  If the page ends with a split element or if an element cannot be measured, the
+    /// glue keeps its natural height.
+    ///
+    /// [`Glue`]: struct.Glue.html
+    fn glue_ratio(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+        end: usize,
+    ) -> GlueAdjustment {
+        // Keep a small margin so that rounding errors do not move the last element to the next page
+        const EPSILON: Mm = Mm(0.001);
+
+        let elements = &self.elements[self.render_idx..end];
+        if !elements.iter().any(|element| element.glue().is_some()) {
+            return GlueAdjustment::Ratio(0.0);
+        }
+
+        let available_height = area.size().height - EPSILON;
+        let mut height = Mm(0.0);
+        let mut stretch = Mm(0.0);
+        let mut shrink = Mm(0.0);
+        let mut fill_count = 0;
+        for (i, element) in elements.iter().enumerate() {
+            if i > 0 {
+                height += self.spacing;
+            }
+            if let Some(glue) = element.glue() {
+                height += glue.natural;
+                stretch += glue.stretch;
+                shrink += glue.shrink;
+                if glue.is_fill {
+                    fill_count += 1;
+                }
+                continue;
+            }
+
+            // Check whether the element fits if the glue is shrunk as much as possible
+            let min_height = height - shrink;
+            if min_height >= available_height {
+                break;
+            }
+            let mut element_area = area.clone();
+            element_area.add_offset(Position::new(0, min_height));
+            let result = match element.measure(context, element_area, style) {
+                Some(result) => result,
+                None => return GlueAdjustment::Ratio(0.0),
+            };
+            if result.has_more {
+                if result.size.height > Mm(0.0) {
+                    return GlueAdjustment::Ratio(0.0);
+                }
+                break;
+            }
+            height += result.size.height;
+        }
+
+        let ratio = if height <= available_height {
+            if fill_count > 0 {
+                return GlueAdjustment::Fill((available_height - height) / f64::from(fill_count));
+            } else if stretch > Mm(0.0) {
+                (available_height - height).0 / stretch.0
+            } else {
+                0.0
+            }
+        } else if shrink > Mm(0.0) {
+            -((height - available_height).0 / shrink.0).min(1.0)
+        } else {
+            0.0
+        };
+        GlueAdjustment::Ratio(ratio)
+    }
+
+    /// Renders the elements up to (excluding) the given end index.
     fn render_vertical(
         &mut self,
         context: &Context,
         mut area: render::Area<'_>,
         style: Style,
+        end: usize,
     ) -> Result<RenderResult, Error> {
         let mut result = RenderResult::default();
-        while area.size().height > Mm(0.0) && self.render_idx < self.elements.len() {
+        let start_height = area.size().height;
+        let glue_ratio = self.glue_ratio(context, area.clone(), style, end);
+        while area.size().height > Mm(0.0) && self.render_idx < end {
+            let available_height = area.size().height;
+            if let Some(penalty) = self.elements[self.render_idx].page_break_penalty() {
+                if !self.is_hint_taken
+                    && self.is_break_at_hint(
+                        context,
+                        area.clone(),
+                        style,
+                        start_height,
+                        self.render_idx,
+                        penalty,
+                    )
+                {
+                    // Continue with the hint on the next page
+                    self.is_hint_taken = true;
+                    if !area.is_dry_run() {
+                        context.request_page_break();
+                    }
+                    result.has_more = true;
+                    return Ok(result);
+                }
+                self.is_hint_taken = false;
+            }
+            if let Some(glue) = self.elements[self.render_idx].glue() {
+                let height = glue.height(glue_ratio).min(available_height);
+                area.add_offset(Position::new(0, height));
+                result.size = result.size.stack_vertical(Size::new(0, height));
+                self.record_area_snapshot(available_height, height, false);
+                self.render_idx += 1;
+                self.add_spacing(&mut area, &mut result, end);
+                continue;
+            }
             let element_result =
-                self.elements[self.render_idx].render(context, area.clone(), style)?;
+                match self.elements[self.render_idx].render(context, area.clone(), style) {
+                    Ok(element_result) => element_result,
+                    Err(err) => {
+                        // Depending on the error policy, replace the failing element with a
+                        // placeholder or abort
+                        let placeholder_result =
+                            render_error_placeholder(context, area.clone(), style, err)?;
+                        area.add_offset(Position::new(0, placeholder_result.size.height));
+                        result.size = result.size.stack_vertical(placeholder_result.size);
+                        self.record_area_snapshot(
+                            available_height,
+                            placeholder_result.size.height,
+                            false,
+                        );
+                        self.render_idx += 1;
+                        self.add_spacing(&mut area, &mut result, end);
+                        continue;
+                    }
+                };
             area.add_offset(Position::new(0, element_result.size.height));
             result.size = result.size.stack_vertical(element_result.size);
+            self.record_area_snapshot(
+                available_height,
+                element_result.size.height,
+                element_result.has_more,
+            );
             if element_result.has_more {
                 result.has_more = true;
                 return Ok(result);
             }
             self.render_idx += 1;
+            self.add_spacing(&mut area, &mut result, end);
         }
         result.has_more = self.render_idx < self.elements.len();
         Ok(result)
@@ -167,7 +525,57 @@ impl Element for LinearLayout {
         style: Style,
     ) -> Result<RenderResult, Error> {
         // TODO: add horizontal layout
-        self.render_vertical(context, area, style)
+        self.render_vertical(context, area, style, self.elements.len())
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        let mut result = RenderResult::default();
+        let start_height = area.size().height;
+        let glue_ratio = self.glue_ratio(context, area.clone(), style, self.elements.len());
+        for (i, element) in self.elements.iter().enumerate().skip(self.render_idx) {
+            if area.size().height <= Mm(0.0) {
+                result.has_more = true;
+                break;
+            }
+            if let Some(penalty) = element.page_break_penalty() {
+                if !(i == self.render_idx && self.is_hint_taken)
+                    && self.is_break_at_hint(context, area.clone(), style, start_height, i, penalty)
+                {
+                    result.has_more = true;
+                    break;
+                }
+            }
+            let element_result = match element.glue() {
+                Some(glue) => RenderResult {
+                    size: Size::new(0, glue.height(glue_ratio).min(area.size().height)),
+                    has_more: false,
+                },
+                None => element.measure(context, area.clone(), style)?,
+            };
+            area.add_offset(Position::new(0, element_result.size.height));
+            result.size = result.size.stack_vertical(element_result.size);
+            if element_result.has_more {
+                result.has_more = true;
+                break;
+            }
+            if i + 1 < self.elements.len() && self.spacing > Mm(0.0) {
+                let spacing = self.spacing.min(area.size().height);
+                area.add_offset(Position::new(0, spacing));
+                result.size = result.size.stack_vertical(Size::new(0, spacing));
+            }
+        }
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("LinearLayout")
+            .with_property("elements", self.elements.len())
+            .with_children(self.elements.iter().map(|e| e.dump_tree()))
     }
 }
 
@@ -178,6 +586,290 @@ impl<E: IntoBoxedElement> iter::Extend<E> for LinearLayout {
     }
 }
 
+/// Flows its elements through multiple columns of equal width.
+///
+/// The elements are arranged vertically like in a [`LinearLayout`][].  If the first column is
+/// full, the content continues in the next column, and only if the last column is full, it
+/// continues on the next page.  The width of the columns is derived from the width of the area
+/// that the element is rendered in, so it can also be used to pack dense content into a cell of
+/// a [`TableLayout`][].  The gap between the columns can be set with [`set_gutter`][] (default:
+/// 5 mm).
+///
+/// As a table cell provides the remaining height of the page, all elements of a short layout
+/// would be placed in the first column, and on the last page of a long layout, the last column
+/// would be shorter than the others.  If the layout is [balanced][], the elements are instead
+/// distributed so that the columns have roughly the same height, as long as all remaining
+/// elements fit into the columns and support [measuring][].  If possible, the elements are
+/// distributed without splitting them.  Otherwise, the height of the columns is reduced so that
+/// the elements are split evenly between the columns, for example a long [`Paragraph`][] with
+/// its [orphan and widow control][].
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let columns = elements::Columns::new(2)
+///     .with_gutter(3)
+///     .with_balanced(true)
+///     .element(elements::Paragraph::new("Weight: 1.2 kg"))
+///     .element(elements::Paragraph::new("Voltage: 230 V"))
+///     .element(elements::Paragraph::new("Power: 60 W"));
+/// let mut table = elements::TableLayout::new(vec![1, 2]);
+/// table
+///     .row()
+///     .element(elements::Paragraph::new("Specifications"))
+///     .element(columns)
+///     .push()
+///     .expect("Invalid table row");
+/// ```
+///
+/// [`LinearLayout`]: struct.LinearLayout.html
+/// [`TableLayout`]: struct.TableLayout.html
+/// [`set_gutter`]: #method.set_gutter
+/// [balanced]: #method.set_balanced
+/// [measuring]: ../trait.Element.html#method.measure
+/// [`Paragraph`]: struct.Paragraph.html
+/// [orphan and widow control]: struct.Paragraph.html#method.set_orphan_widow_control
+pub struct Columns {
+    layout: LinearLayout,
+    columns: usize,
+    gutter: Mm,
+    balanced: bool,
+}
+
+impl Columns {
+    /// Creates a new layout with the given number of columns.
+    ///
+    /// If the number of columns is zero, one column is used.
+    pub fn new(columns: usize) -> Columns {
+        Columns {
+            layout: LinearLayout::vertical(),
+            columns: columns.max(1),
+            gutter: Mm(5.0),
+            balanced: false,
+        }
+    }
+
+    /// Adds the given element to this layout.
+    pub fn push<E: IntoBoxedElement>(&mut self, element: E) {
+        self.layout.push(element);
+    }
+
+    /// Adds the given element to this layout and it returns the layout.
+    pub fn element<E: IntoBoxedElement>(mut self, element: E) -> Self {
+        self.push(element);
+        self
+    }
+
+    /// Sets the gap between two columns.
+    pub fn set_gutter(&mut self, gutter: impl Into<Mm>) {
+        self.gutter = gutter.into();
+    }
+
+    /// Sets the gap between two columns and returns the layout.
+    pub fn with_gutter(mut self, gutter: impl Into<Mm>) -> Self {
+        self.set_gutter(gutter);
+        self
+    }
+
+    /// Sets the gap between consecutive elements in a column.
+    pub fn set_spacing(&mut self, spacing: impl Into<Mm>) {
+        self.layout.set_spacing(spacing);
+    }
+
+    /// Sets the gap between consecutive elements in a column and returns the layout.
+    pub fn with_spacing(mut self, spacing: impl Into<Mm>) -> Self {
+        self.set_spacing(spacing);
+        self
+    }
+
+    /// Sets whether the elements should be distributed so that the columns have roughly the same
+    /// height.
+    pub fn set_balanced(&mut self, balanced: bool) {
+        self.balanced = balanced;
+    }
+
+    /// Sets whether the elements should be distributed so that the columns have roughly the same
+    /// height and returns the layout.
+    pub fn with_balanced(mut self, balanced: bool) -> Self {
+        self.set_balanced(balanced);
+        self
+    }
+
+    /// Returns the end index of the elements in each column so that the remaining elements are
+    /// distributed evenly and the height of the highest column, or `None` if they cannot be
+    /// measured or do not fit into the columns.
+    fn balanced_ends(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<(Vec<usize>, Mm)> {
+        // Tolerance for rounding errors when comparing sums of heights
+        const EPSILON: Mm = Mm(0.001);
+
+        let start = self.layout.render_idx;
+        let mut heights = Vec::new();
+        for element in &self.layout.elements[start..] {
+            let result = element.measure(context, area.clone(), style)?;
+            if result.has_more {
+                return None;
+            }
+            heights.push(result.size.height);
+        }
+        let spacing = self.layout.spacing;
+
+        // Distributes the elements greedily into columns with the given maximum height
+        let distribute = |max_height: Mm| -> Option<Vec<usize>> {
+            let mut ends = Vec::new();
+            let mut column_height: Option<Mm> = None;
+            for (i, height) in heights.iter().enumerate() {
+                if *height > max_height + EPSILON {
+                    return None;
+                }
+                column_height = match column_height {
+                    Some(h) if h + spacing + *height > max_height + EPSILON => {
+                        ends.push(start + i);
+                        Some(*height)
+                    }
+                    Some(h) => Some(h + spacing + *height),
+                    None => Some(*height),
+                };
+            }
+            ends.push(start + heights.len());
+            if ends.len() > self.columns {
+                None
+            } else {
+                ends.resize(self.columns, start + heights.len());
+                Some(ends)
+            }
+        };
+
+        // The optimal column height is the height of a sequence of consecutive elements
+        let mut candidates = Vec::new();
+        for i in 0..heights.len() {
+            let mut height = Mm(0.0);
+            for (j, element_height) in heights.iter().enumerate().skip(i) {
+                if j > i {
+                    height += spacing;
+                }
+                height += *element_height;
+                if height <= area.size().height {
+                    candidates.push(height);
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal));
+        let idx = candidates.partition_point(|height| distribute(*height).is_none());
+        let height = *candidates.get(idx)?;
+        distribute(height).map(|ends| (ends, height))
+    }
+
+    /// Returns the reduced height for all but the last column so that the remaining elements are
+    /// split evenly between the columns, or `None` if they cannot be measured or do not fit into
+    /// the columns.
+    fn balanced_height(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<Mm> {
+        let height = area.size().height;
+        let mut area = area.dry_run();
+        area.set_height(height * self.columns as f64);
+        let result = self.layout.measure(context, area, style)?;
+        if result.has_more {
+            return None;
+        }
+        // The columns are split at line boundaries, so we add one line of slack.
+        let balanced_height =
+            result.size.height / self.columns as f64 + style.line_height(&context.font_cache);
+        Some(balanced_height.min(height))
+    }
+
+    /// Returns the width of a single column in an area with the given width.
+    fn column_width(&self, width: Mm) -> Mm {
+        let gutters = self.gutter * (self.columns - 1) as f64;
+        (width - gutters) / self.columns as f64
+    }
+}
+
+impl Element for Columns {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let column_width = self.column_width(area.size().width);
+        let mut ends = None;
+        let mut column_height = None;
+        if self.balanced {
+            let mut column_area = area.clone();
+            column_area.set_width(column_width);
+            // Splitting elements is only necessary if the columns are not balanced otherwise.
+            let height = self.balanced_height(context, column_area.clone(), style);
+            match self.balanced_ends(context, column_area, style) {
+                Some((balanced_ends, max_height))
+                    if height.map(|height| max_height <= height).unwrap_or(true) =>
+                {
+                    ends = Some(balanced_ends);
+                }
+                _ => column_height = height,
+            }
+        }
+
+        let mut result = RenderResult::default();
+        for i in 0..self.columns {
+            let mut column_area = area.clone();
+            column_area.add_offset(Position::new((column_width + self.gutter) * i as f64, 0));
+            column_area.set_width(column_width);
+            // The last column uses the full height so that no content is moved to the next page.
+            if let Some(height) = column_height.filter(|_| i + 1 < self.columns) {
+                column_area.set_height(height);
+            }
+            let end = ends
+                .as_ref()
+                .map(|ends| ends[i])
+                .unwrap_or_else(|| self.layout.elements.len());
+            let column_result = self
+                .layout
+                .render_vertical(context, column_area, style, end)?;
+            result.size = Size::new(
+                (column_width + self.gutter) * i as f64 + column_width,
+                result.size.height.max(column_result.size.height),
+            );
+            result.has_more = column_result.has_more;
+            if !result.has_more {
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        let mut width = Mm(0.0);
+        for element in &self.layout.elements {
+            width = width.max(element.natural_width(context, style)?);
+        }
+        Some(width * self.columns as f64 + self.gutter * (self.columns - 1) as f64)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("Columns")
+            .with_property("columns", self.columns)
+            .with_property("gutter", self.gutter.0)
+            .with_property("balanced", self.balanced)
+            .with_children(self.layout.elements.iter().map(|e| e.dump_tree()))
+    }
+}
+
+impl<E: IntoBoxedElement> iter::Extend<E> for Columns {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.layout.extend(iter)
+    }
+}
+
 /// A single line of formatted text.
 ///
 /// This element renders a single styled string on a single line.  It does not wrap it if the
@@ -188,12 +880,37 @@ impl<E: IntoBoxedElement> iter::Extend<E> for LinearLayout {
 #[derive(Clone, Debug, Default)]
 pub struct Text {
     text: StyledString,
+    max_width: Option<Mm>,
 }
 
 impl Text {
     /// Creates a new instance with the given styled string.
     pub fn new(text: impl Into<StyledString>) -> Text {
-        Text { text: text.into() }
+        Text {
+            text: text.into(),
+            max_width: None,
+        }
+    }
+
+    /// Sets the maximum width of this text.
+    ///
+    /// If the string is wider than the maximum width (or the width of the available area), it is
+    /// truncated and an ellipsis (`…`) is appended.
+    pub fn set_max_width(&mut self, max_width: impl Into<Mm>) {
+        self.max_width = Some(max_width.into());
+    }
+
+    /// Sets the maximum width of this text and returns the text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let text = elements::Text::new("A very long product description").with_max_width(30);
+    /// ```
+    pub fn with_max_width(mut self, max_width: impl Into<Mm>) -> Self {
+        self.set_max_width(max_width);
+        self
     }
 }
 
@@ -206,146 +923,351 @@ impl Element for Text {
     ) -> Result<RenderResult, Error> {
         let mut result = RenderResult::default();
         style.merge(self.text.style);
-        if area.print_str(
-            &context.font_cache,
-            Position::default(),
-            style,
-            &self.text.s,
-        )? {
-            result.size = Size::new(
-                style.str_width(&context.font_cache, &self.text.s),
-                style.line_height(&context.font_cache),
-            );
+        let mut text = context.text_normalization().apply(&self.text.s);
+        if let Some(max_width) = self.max_width {
+            let max_width = max_width.min(area.size().width);
+            if let borrow::Cow::Owned(truncated) =
+                truncate_str(&context.font_cache, style, &text, max_width)
+            {
+                context.report_warning(&area, truncation_warning(&text, max_width))?;
+                text = truncated.into();
+            }
+        }
+        check_glyphs(context, &area, style, &text)?;
+        if area.print_str(&context.font_cache, Position::default(), style, &text)? {
+            let width = style.str_width(&context.font_cache, &text);
+            draw_text_decorations(&area, style, width, style.metrics(&context.font_cache));
+            result.size = Size::new(width, style.line_height(&context.font_cache));
         } else {
             result.has_more = true;
         }
         Ok(result)
     }
+
+    fn natural_width(&self, context: &Context, mut style: Style) -> Option<Mm> {
+        style.merge(self.text.style);
+        let text = context.text_normalization().apply(&self.text.s);
+        let width = style.str_width(&context.font_cache, &text);
+        Some(match self.max_width {
+            Some(max_width) => width.min(max_width),
+            None => width,
+        })
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("Text").with_text("text", &self.text.s)
+    }
 }
 
-/// A multi-line wrapped paragraph of formatted text.
-///
-/// If the text of this paragraph is longer than the page width, the paragraph is wrapped at word
-/// borders (and additionally at string borders if it contains multiple strings).  If a word in the
-/// paragraph is longer than the page width, the text is truncated.
-///
-/// Use the [`push`][], [`string`][], [`push_styled`][] and [`string_styled`][] methods to add
-/// strings to this paragraph.  Besides the styling of the text (see [`Style`][]), you can also set
-/// an [`Alignment`][] for the paragraph.
-///
-/// The line height and spacing are calculated based on the style of each string.
+/// Creates a warning for a string that has been truncated to the given width.
+fn truncation_warning(s: &str, width: Mm) -> Warning {
+    Warning::new(
+        format!(
+            "The string \"{}\" has been truncated to a width of {:.1} mm",
+            s, width.0
+        ),
+        WarningKind::TruncatedText,
+    )
+}
+
+/// Reports the first character of the given string that is not supported by the font of the
+/// given style, see [`WarningKind::MissingGlyph`][].
 ///
-/// # Examples
+/// Built-in fonts are not checked as unsupported characters already cause an error for them.
 ///
-/// With setters:
-/// ```
-/// use genpdf::{elements, style};
-/// let mut p = elements::Paragraph::default();
-/// p.push("This is an ");
-/// p.push_styled("important", style::Color::Rgb(255, 0, 0));
-/// p.push(" message!");
-/// p.set_alignment(genpdf::Alignment::Center);
-/// ```
+/// [`WarningKind::MissingGlyph`]: ../error/enum.WarningKind.html#variant.MissingGlyph
+fn check_glyphs(
+    context: &Context,
+    area: &render::Area<'_>,
+    style: Style,
+    s: &str,
+) -> Result<(), Error> {
+    if context.strict_mode() == StrictMode::Disabled || area.is_dry_run() {
+        return Ok(());
+    }
+    let font = style.font(&context.font_cache);
+    if font.is_builtin() {
+        return Ok(());
+    }
+    let glyph_ids = font.glyph_ids(&context.font_cache, s.chars());
+    let missing = s
+        .chars()
+        .zip(glyph_ids)
+        .find(|&(c, glyph_id)| glyph_id == 0 && !c.is_control() && c != '\u{ad}');
+    if let Some((c, _)) = missing {
+        let warning = Warning::new(
+            format!(
+                "The font does not contain a glyph for the character {:?} (U+{:04X}) in \"{}\"",
+                c, c as u32, s
+            ),
+            WarningKind::MissingGlyph,
+        );
+        context.report_warning(area, warning)?;
+    }
+    Ok(())
+}
+
+/// Truncates the given string so that it fits into the given width, appending an ellipsis if the
+/// string has been truncated.
+fn truncate_str<'s>(
+    font_cache: &fonts::FontCache,
+    style: Style,
+    s: &'s str,
+    width: Mm,
+) -> borrow::Cow<'s, str> {
+    const ELLIPSIS: &str = "…";
+    if style.str_width(font_cache, s) <= width {
+        return s.into();
+    }
+    let ellipsis_width = style.str_width(font_cache, ELLIPSIS);
+    let mut truncated = s;
+    while !truncated.is_empty() && style.str_width(font_cache, truncated) + ellipsis_width > width {
+        let n = truncated.char_indices().last().map(|(i, _)| i).unwrap_or(0);
+        truncated = &truncated[..n];
+    }
+    if ellipsis_width > width {
+        truncated.into()
+    } else {
+        format!("{}{}", truncated.trim_end(), ELLIPSIS).into()
+    }
+}
+
+/// Replaces the line breaks in the given paragraph text with the separators used for wrapping.
+///
+/// Forced line breaks become `'\n'`, and line breaks in the text become spaces unless
+/// `newline_breaks` is set.
+fn convert_line_breaks(s: &mut String, newline_breaks: bool) {
+    if !newline_breaks && s.contains('\n') {
+        *s = s.replace('\n', " ");
+    }
+    if s.contains(LINE_SEPARATOR) {
+        *s = s.replace(LINE_SEPARATOR, "\n");
+    }
+}
+
+/// Replaces the given icon placeholder with the character of the icon with the given name.
+fn resolve_icon(
+    s: &mut StyledString,
+    font_cache: &fonts::FontCache,
+    name: &str,
+) -> Result<(), Error> {
+    let (font_family, c) = font_cache
+        .get_icon(name)
+        .ok_or_else(|| Error::new(format!("Unknown icon: {}", name), ErrorKind::InvalidData))?;
+    s.s = c.to_string();
+    s.style.set_font_family(font_family);
+    Ok(())
+}
+
+/// The placeholder for an inline formula in a paragraph, see [`resolve_math`][].
+///
+/// [`resolve_math`]: fn.resolve_math.html
+const MATH_PLACEHOLDER: &str = "\u{a0}";
+
+/// The character used for forced line breaks in the text of a paragraph, see
+/// [`Paragraph::push_line_break`][].
+///
+/// [`Paragraph::push_line_break`]: struct.Paragraph.html#method.push_line_break
+const LINE_SEPARATOR: char = '\u{2028}';
+
+/// Replaces the given math placeholder with the rendered formula with the given source.
+///
+/// The placeholder is an invisible non-breaking space that has the width of the formula, so that
+/// it is wrapped and justified like a word.  The formula is drawn over it when the line is
+/// printed.
+#[cfg(feature = "math")]
+fn resolve_math(
+    s: &mut StyledString,
+    context: &Context,
+    source: &str,
+) -> Result<rc::Rc<crate::math::MathBlock>, Error> {
+    let block = math::render_inline_math(context, source, s.style)?;
+    let space_width = s.style.str_width(&context.font_cache, MATH_PLACEHOLDER);
+    s.s = MATH_PLACEHOLDER.to_owned();
+    s.style = s
+        .style
+        .with_letter_spacing(block.size.width - space_width)
+        .with_text_rendering_mode(TextRenderingMode::Invisible);
+    Ok(block)
+}
+
+/// Returns whether the given string is the placeholder for an inline formula.
+fn is_math_placeholder(s: &str, style: Style) -> bool {
+    cfg!(feature = "math")
+        && s == MATH_PLACEHOLDER
+        && style.text_rendering_mode() == TextRenderingMode::Invisible
+}
+
+/// Draws the strikethrough and underline of a string with the given style and width that has been
+/// printed at the origin of the given area in a line with the given metrics.
+fn draw_text_decorations(
+    area: &render::Area<'_>,
+    style: Style,
+    width: Mm,
+    metrics: fonts::Metrics,
+) {
+    if style.is_strikethrough() {
+        let mut line_style = LineStyle::default()
+            .with_thickness(0.3)
+            .with_opacity(style.opacity());
+        if let Some(color) = style.color() {
+            line_style.set_color(color);
+        }
+        let y = metrics.glyph_height / 2.0;
+        area.draw_line([Position::new(0, y), Position::new(width, y)], line_style);
+    }
+    if style.is_underline() {
+        let mut line_style = LineStyle::default()
+            .with_thickness(style.underline_thickness())
+            .with_opacity(style.opacity());
+        if let Some(color) = style.underline_color().or_else(|| style.color()) {
+            line_style.set_color(color);
+        }
+        // Per default, the underline is drawn slightly below the baseline, between the baseline
+        // and the descent of the font.
+        let offset = style
+            .underline_offset()
+            .unwrap_or((metrics.glyph_height - metrics.ascent) * 0.4);
+        let y = metrics.ascent + offset;
+        area.draw_line([Position::new(0, y), Position::new(width, y)], line_style);
+    }
+}
+
+/// An entry of a table of contents:  a text on the left, a page number on the right and a leader
+/// (per default dots) between them.
+///
+/// If the text does not fit into one line, it is wrapped, and the leader and the number are
+/// printed on the last line.  The leader characters are aligned to a grid, so the leaders of
+/// consecutive entries line up.  The entry is never split across pages.
+///
+/// The number is an arbitrary string, so it has to be set manually, for example from the
+/// [`Outline`][] of a previous rendering pass.  Per default, it is right-aligned, see
+/// [`PageNumberAlignment`][].  If a link target is set with [`set_link`][], the entry is a
+/// clickable link to that position.  [`TableOfContents`][] creates the entries for all headings of
+/// an outline.
+///
+/// # Example
 ///
-/// Chained:
 /// ```
 /// use genpdf::{elements, style};
-/// let p = elements::Paragraph::default()
-///     .string("This is an ")
-///     .styled_string("important", style::Color::Rgb(255, 0, 0))
-///     .string(" message!")
-///     .aligned(genpdf::Alignment::Center);
+/// let mut toc = elements::LinearLayout::vertical();
+/// toc.push(elements::TocEntry::new(style::StyledString::new("Introduction", style::Style::new().bold()), "1"));
+/// toc.push(elements::TocEntry::new("Motivation", "2").with_indent(5));
 /// ```
 ///
-/// [`Style`]: ../style/struct.Style.html
-/// [`Alignment`]: ../enum.Alignment.html
-/// [`Element::styled`]: ../trait.Element.html#method.styled
-/// [`push`]: #method.push
-/// [`push_styled`]: #method.push_styled
-/// [`string`]: #method.string
-/// [`string_styled`]: #method.string_styled
-#[derive(Clone, Debug, Default)]
-pub struct Paragraph {
-    text: Vec<StyledString>,
-    words: collections::VecDeque<StyledString>,
-    style_applied: bool,
-    alignment: Alignment,
+/// [`Outline`]: ../outline/struct.Outline.html
+/// [`PageNumberAlignment`]: enum.PageNumberAlignment.html
+/// [`set_link`]: #method.set_link
+/// [`TableOfContents`]: struct.TableOfContents.html
+#[derive(Clone, Debug)]
+pub struct TocEntry {
+    text: StyledString,
+    number: StyledString,
+    leader: char,
+    indent: Mm,
+    number_alignment: PageNumberAlignment,
+    link: Option<(usize, Position)>,
 }
 
-impl Paragraph {
-    /// Creates a new paragraph with the given content.
-    pub fn new(text: impl Into<StyledString>) -> Paragraph {
-        Paragraph {
-            text: vec![text.into()],
-            ..Default::default()
+impl TocEntry {
+    /// Creates a new entry with the given text and number.
+    pub fn new(text: impl Into<StyledString>, number: impl Into<StyledString>) -> TocEntry {
+        TocEntry {
+            text: text.into(),
+            number: number.into(),
+            leader: '.',
+            indent: Mm(0.0),
+            number_alignment: PageNumberAlignment::default(),
+            link: None,
         }
     }
 
-    /// Sets the alignment of this paragraph.
-    pub fn set_alignment(&mut self, alignment: Alignment) {
-        self.alignment = alignment;
+    /// Sets the character that is repeated between the text and the number.
+    ///
+    /// Per default, a dot is used.
+    pub fn set_leader(&mut self, leader: char) {
+        self.leader = leader;
     }
 
-    /// Sets the alignment of this paragraph and returns the paragraph.
-    pub fn aligned(mut self, alignment: Alignment) -> Self {
-        self.set_alignment(alignment);
+    /// Sets the character that is repeated between the text and the number and returns the
+    /// entry.
+    pub fn with_leader(mut self, leader: char) -> Self {
+        self.set_leader(leader);
         self
     }
 
-    /// Adds a string to the end of this paragraph.
-    pub fn push(&mut self, s: impl Into<StyledString>) {
-        self.text.push(s.into());
+    /// Sets the indentation of the text, for example to indicate the level of the entry.
+    pub fn set_indent(&mut self, indent: impl Into<Mm>) {
+        self.indent = indent.into();
     }
 
-    /// Adds a string to the end of this paragraph and returns the paragraph.
-    pub fn string(mut self, s: impl Into<StyledString>) -> Self {
-        self.push(s);
+    /// Sets the indentation of the text and returns the entry.
+    pub fn with_indent(mut self, indent: impl Into<Mm>) -> Self {
+        self.set_indent(indent);
         self
     }
 
-    /// Adds a string with the given style to the end of this paragraph.
-    pub fn push_styled(&mut self, s: impl Into<String>, style: impl Into<Style>) {
-        self.text.push(StyledString::new(s, style))
+    /// Sets the alignment of the number.
+    pub fn set_number_alignment(&mut self, alignment: PageNumberAlignment) {
+        self.number_alignment = alignment;
     }
 
-    /// Adds a string with the given style to the end of this paragraph and returns the paragraph.
-    pub fn styled_string(mut self, s: impl Into<String>, style: impl Into<Style>) -> Self {
-        self.push_styled(s, style);
+    /// Sets the alignment of the number and returns the entry.
+    pub fn with_number_alignment(mut self, alignment: PageNumberAlignment) -> Self {
+        self.set_number_alignment(alignment);
         self
     }
 
-    /// Adds a string to the end of this paragraph if the provided check function returns true. The
-    /// check callback is provided with the current Text to decide if the new string should be
-    /// added
-    pub fn push_if_text(
-        &mut self,
-        s: impl Into<StyledString>,
-        check: impl Fn(&[StyledString]) -> bool,
-    ) {
-        if check(&self.text) {
-            self.push(s);
-        }
+    /// Makes this entry a link to the given position on the page with the given number, starting
+    /// with 1.
+    ///
+    /// The position is measured from the upper left corner of the page, as for the entries of an
+    /// [`Outline`][].
+    ///
+    /// [`Outline`]: ../outline/struct.Outline.html
+    pub fn set_link(&mut self, page: usize, position: impl Into<Position>) {
+        self.link = Some((page, position.into()));
     }
 
-    fn get_offset(&self, width: Mm, max_width: Mm) -> Mm {
-        match self.alignment {
-            Alignment::Left | Alignment::Justified(_) => Mm::default(),
-            Alignment::Center => (max_width - width) / 2.0,
-            Alignment::Right => max_width - width,
-        }
+    /// Makes this entry a link to the given position and returns the entry.
+    ///
+    /// See [`set_link`][] for details.
+    ///
+    /// [`set_link`]: #method.set_link
+    pub fn with_link(mut self, page: usize, position: impl Into<Position>) -> Self {
+        self.set_link(page, position);
+        self
     }
+}
 
-    fn apply_style(&mut self, style: Style) {
-        if !self.style_applied {
-            for s in &mut self.text {
-                s.style = style.and(s.style);
-            }
-            self.style_applied = true;
-        }
+/// The alignment of the page number of a [`TocEntry`][].
+///
+/// [`TocEntry`]: struct.TocEntry.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageNumberAlignment {
+    /// The number is aligned to the right edge of the area and separated from the text by a
+    /// leader.
+    Right,
+    /// The number is printed directly after the text, separated by a space.
+    AfterText,
+}
+
+impl Default for PageNumberAlignment {
+    fn default() -> PageNumberAlignment {
+        PageNumberAlignment::Right
     }
 }
 
-impl Element for Paragraph {
+impl Element for TocEntry {
     fn render(
         &mut self,
         context: &Context,
@@ -353,437 +1275,3862 @@ impl Element for Paragraph {
         style: Style,
     ) -> Result<RenderResult, Error> {
         let mut result = RenderResult::default();
-
-        self.apply_style(style);
-
-        if self.words.is_empty() {
-            if self.text.is_empty() {
-                return Ok(result);
+        let font_cache = &context.font_cache;
+        let normalization = context.text_normalization();
+        let link_area = area.clone();
+
+        let number_style = style.and(self.number.style);
+        let number = normalization.apply(&self.number.s);
+        let number_width = number_style.str_width(font_cache, &number);
+        let leader = self.leader.to_string();
+        let leader_width = style.str_width(font_cache, &leader);
+        // Keep a gap of one leader character before and after the leader.
+        let text_width = area.size().width - self.indent - number_width - leader_width * 3.0;
+
+        let text = StyledString::new(
+            normalization.apply(&self.text.s),
+            style.and(self.text.style),
+        );
+        let words: Vec<_> = wrap::Words::new(iter::once(text)).collect();
+        let mut wrapper =
+            wrap::Wrapper::new(words.iter().map(StyledStr::from), context, text_width);
+        let mut lines: Vec<_> = wrapper.by_ref().map(|(line, _)| line).collect();
+        if let Some(overflow) = wrapper.overflow() {
+            return Err(overflow_error(overflow));
+        }
+        if lines.is_empty() {
+            lines.push(Vec::new());
+        }
+        for line in &mut lines {
+            if line.last().map(|s| s.s == "\n").unwrap_or(false) {
+                line.pop();
             }
-            self.words = wrap::Words::new(mem::take(&mut self.text)).collect();
         }
 
-        let words = self.words.iter().map(Into::into);
-        let mut rendered_len = 0;
-        let mut wrapper = wrap::Wrapper::new(words, context, area.size().width);
-
-        let mut curr_wrap = wrapper.next();
-        while let Some((line, delta)) = curr_wrap {
-            let next_wrap = wrapper.next();
+        let last_metrics = style
+            .metrics(font_cache)
+            .max(&number_style.metrics(font_cache));
+        let metrics: Vec<_> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let metrics = if i + 1 == lines.len() {
+                    last_metrics
+                } else {
+                    fonts::Metrics::default()
+                };
+                line.iter()
+                    .map(|s| s.style.metrics(font_cache))
+                    .fold(metrics, |max, m| max.max(&m))
+            })
+            .collect();
+        let height: Mm = metrics.iter().map(|m| m.line_height).sum();
+        if height > area.size().height {
+            result.has_more = true;
+            return Ok(result);
+        }
 
-            let width = line.iter().map(|s| s.width(&context.font_cache)).sum();
-            // Calculate the maximum line height
-            let metrics = line
-                .iter()
-                .map(|s| s.style.metrics(&context.font_cache))
-                .fold(fonts::Metrics::default(), |max, m| max.max(&m));
-            let position = Position::new(self.get_offset(width, area.size().width), 0);
+        for (i, (line, metrics)) in lines.iter().zip(&metrics).enumerate() {
+            let position = Position::new(self.indent, 0);
+            let mut section = match area.text_section(font_cache, position, *metrics) {
+                Some(section) => section,
+                None => {
+                    result.has_more = true;
+                    return Ok(result);
+                }
+            };
+            for s in line {
+                section.print_str(&s.s, s.style)?;
+            }
 
-            // Extra word spacing for justified text alignment, except on the last line
-            let extra_word_spacing = match self.alignment {
-                Alignment::Justified(trim_spaces) if next_wrap.is_some() => {
-                    let mut width = width;
-                    if let Some(word) = line.first() {
-                        let diff = word.width(&context.font_cache)
-                            - word
-                                .style
-                                .str_width(&context.font_cache, word.s.trim_start());
-                        width -= diff;
+            if i + 1 == lines.len() {
+                let text_width = match line.split_last() {
+                    Some((last, line)) => {
+                        line.iter().map(|s| s.width(font_cache)).sum::<Mm>()
+                            + last.width_trimmed_end(font_cache)
+                    }
+                    None => Mm(0.0),
+                };
+                let number_x = match self.number_alignment {
+                    PageNumberAlignment::Right => area.size().width - number_width,
+                    PageNumberAlignment::AfterText => {
+                        self.indent + text_width + style.char_width(font_cache, ' ')
                     }
-                    match (trim_spaces, line.last()) {
-                        (true, Some(word)) => {
-                            let diff = word.width(&context.font_cache)
-                                - word.style.str_width(&context.font_cache, word.s.trim_end());
-                            width -= diff;
+                };
+                if self.number_alignment == PageNumberAlignment::Right && leader_width > Mm(0.0) {
+                    let start = self.indent + text_width + leader_width;
+                    let start = leader_width * (start.0 / leader_width.0).ceil();
+                    let end = number_x - leader_width;
+                    if end > start {
+                        let count = ((end - start).0 / leader_width.0).floor() as usize;
+                        let position = Position::new(start, 0);
+                        if let Some(mut section) = area.text_section(font_cache, position, *metrics)
+                        {
+                            section.print_str(leader.repeat(count), style)?;
                         }
-                        _ => (),
                     }
-
-                    let leftover_space = area.size().width - width;
-                    (leftover_space / (line.len() - 1).max(1) as f64) / style.font_size() as f64
                 }
-                _ => Mm(0.0),
-            };
-
-            if let Some(mut section) = area.text_section(&context.font_cache, position, metrics) {
-                let mut strikethrough_area = area.clone();
-                strikethrough_area.add_offset(position);
-
-                for s in line {
-                    section.print_str_xoff(&s.s, s.style, extra_word_spacing)?;
-
-                    let width = s.width(&context.font_cache);
-                    if s.style.is_strikethrough() {
-                        strikethrough_area.draw_line(
-                            [
-                                Position::new(0, metrics.glyph_height / 2.0),
-                                Position::new(width, metrics.glyph_height / 2.0),
-                            ],
-                            LineStyle::default().with_thickness(0.3),
-                        );
-                    }
-                    strikethrough_area.add_offset(Position::new(width, 0));
-
-                    rendered_len += s.s.len();
+                let position = Position::new(number_x, 0);
+                if let Some(mut section) = area.text_section(font_cache, position, *metrics) {
+                    section.print_str(&number, number_style)?;
                 }
-                rendered_len -= delta;
-            } else {
-                result.has_more = true;
-                break;
             }
+
+            area.add_offset(Position::new(0, metrics.line_height));
             result.size = result
                 .size
-                .stack_vertical(Size::new(width, metrics.line_height));
-            area.add_offset(Position::new(0, metrics.line_height));
-
-            curr_wrap = next_wrap;
-        }
-
-        if wrapper.has_overflowed() {
-            return Err(Error::new(
-                "Page overflowed while trying to wrap a string",
-                ErrorKind::PageSizeExceeded,
-            ));
+                .stack_vertical(Size::new(area.size().width, metrics.line_height));
         }
-
-        // Remove the rendered data from self.words so that we don’t render it again on the next
-        // call to render.
-        while rendered_len > 0 && !self.words.is_empty() {
-            if self.words[0].s.len() <= rendered_len {
-                rendered_len -= self.words[0].s.len();
-                self.words.pop_front();
-            } else {
-                self.words[0].s.replace_range(..rendered_len, "");
-                rendered_len = 0;
-            }
+        if let Some((page, position)) = self.link {
+            link_area.add_link(Position::default(), result.size, page, position);
         }
-
         Ok(result)
     }
-}
 
-impl From<Vec<StyledString>> for Paragraph {
-    fn from(text: Vec<StyledString>) -> Paragraph {
-        Paragraph {
-            text,
-            ..Default::default()
-        }
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        let font_cache = &context.font_cache;
+        let normalization = context.text_normalization();
+        let text_style = style.and(self.text.style);
+        let number_style = style.and(self.number.style);
+        let separator_width = match self.number_alignment {
+            PageNumberAlignment::Right => style.char_width(font_cache, self.leader) * 3.0,
+            PageNumberAlignment::AfterText => style.char_width(font_cache, ' '),
+        };
+        Some(
+            self.indent
+                + text_style.str_width(font_cache, &normalization.apply(&self.text.s))
+                + separator_width
+                + number_style.str_width(font_cache, &normalization.apply(&self.number.s)),
+        )
     }
-}
 
-impl<T: Into<StyledString>> iter::Extend<T> for Paragraph {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for s in iter {
-            self.push(s);
-        }
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
     }
-}
 
-impl<T: Into<StyledString>> iter::FromIterator<T> for Paragraph {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut paragraph = Paragraph::default();
-        paragraph.extend(iter);
-        paragraph
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("TocEntry")
+            .with_text("text", &self.text.s)
+            .with_text("number", &self.number.s)
     }
 }
 
-/// A line break.
+/// A table of contents that lists the headings of a document [`Outline`][].
 ///
-/// This element inserts a given number of empty lines.
+/// As the page numbers are only known after rendering, the outline has to be taken from a previous
+/// rendering pass of the same document, see [`Document::render_with_outline`][].  If the table of
+/// contents itself changes the page numbers of the headings, a third pass may be necessary.
+///
+/// For every heading up to the maximum depth, a [`TocEntry`][] is created.  The indentation and the
+/// style of the entries can be configured per heading level, and the entries can be clickable
+/// links to the headings.
 ///
 /// # Example
 ///
+/// ```no_run
+/// use genpdf::{elements, outline, style, Element as _};
+///
+/// fn build(toc: elements::TableOfContents) -> genpdf::Document {
+///     let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+///         .expect("Failed to load font family");
+///     let mut doc = genpdf::Document::new(font_family);
+///     doc.push(toc);
+///     doc.push(elements::PageBreak::new());
+///     doc.push(elements::OutlineMarker::heading(
+///         elements::Paragraph::new("Introduction"),
+///         1,
+///         "Introduction",
+///     ));
+///     doc
+/// }
+///
+/// // The first pass uses an empty outline to determine the page numbers of the headings.
+/// let toc = elements::TableOfContents::new(&outline::Outline::new());
+/// let outline = build(toc)
+///     .render_with_outline(std::io::sink())
+///     .expect("Failed to render document");
+/// let toc = elements::TableOfContents::new(&outline)
+///     .with_max_depth(2)
+///     .with_level_style(1, style::Style::new().bold())
+///     .with_links(true);
+/// build(toc)
+///     .render_to_file("output.pdf")
+///     .expect("Failed to render document");
 /// ```
-/// // Draws 5 empty lines (calculating the line height using the current style)
-/// let b = genpdf::elements::Break::new(5);
-/// ```
-#[derive(Clone, Copy, Debug, Default)]
-pub struct Break {
-    lines: f64,
+///
+/// [`Outline`]: ../outline/struct.Outline.html
+/// [`Document::render_with_outline`]: ../struct.Document.html#method.render_with_outline
+/// [`TocEntry`]: struct.TocEntry.html
+pub struct TableOfContents {
+    headings: Vec<OutlineEntry>,
+    max_depth: Option<u8>,
+    indent: Mm,
+    level_indents: collections::HashMap<u8, Mm>,
+    level_styles: collections::HashMap<u8, Style>,
+    number_alignment: PageNumberAlignment,
+    leader: char,
+    links: bool,
+    layout: Option<LinearLayout>,
 }
 
-impl Break {
-    /// Creates a new break with the given number of lines.
-    pub fn new(lines: impl Into<f64>) -> Break {
-        Break {
-            lines: lines.into(),
+impl TableOfContents {
+    /// Creates a new table of contents for the headings of the given outline.
+    pub fn new(outline: &Outline) -> TableOfContents {
+        TableOfContents {
+            headings: outline.headings().cloned().collect(),
+            max_depth: None,
+            indent: Mm(5.0),
+            level_indents: collections::HashMap::new(),
+            level_styles: collections::HashMap::new(),
+            number_alignment: PageNumberAlignment::default(),
+            leader: '.',
+            links: false,
+            layout: None,
         }
     }
-}
 
-impl Element for Break {
-    fn render(
-        &mut self,
-        context: &Context,
-        area: render::Area<'_>,
-        style: Style,
-    ) -> Result<RenderResult, Error> {
-        let mut result = RenderResult::default();
-        if self.lines <= 0.0 {
-            return Ok(result);
-        }
-        let line_height = style.line_height(&context.font_cache);
-        let break_height = line_height * self.lines;
-        if break_height < area.size().height {
-            result.size.height = break_height;
-            self.lines = 0.0;
-        } else {
-            result.size.height = area.size().height;
-            self.lines -= result.size.height.0 / line_height.0;
-        }
-        Ok(result)
+    /// Sets the maximum level of the headings that are included in the table of contents.
+    ///
+    /// Per default, all headings are included.
+    pub fn set_max_depth(&mut self, max_depth: u8) {
+        self.max_depth = Some(max_depth);
     }
-}
 
-/// A page break.
-///
-/// This element inserts a page break.
-///
-/// # Example
-///
-/// ```
-/// let pb = genpdf::elements::PageBreak::new();
-/// ```
-#[derive(Clone, Copy, Debug, Default)]
-pub struct PageBreak {
-    cont: bool,
-}
+    /// Sets the maximum level of the included headings and returns the table of contents.
+    pub fn with_max_depth(mut self, max_depth: u8) -> Self {
+        self.set_max_depth(max_depth);
+        self
+    }
 
-impl PageBreak {
-    /// Creates a new page break.
-    pub fn new() -> PageBreak {
-        PageBreak::default()
+    /// Sets the indentation per heading level.
+    ///
+    /// Entries for headings of level *n* are indented by *n - 1* times this indentation, unless
+    /// an indentation has been set for the level with [`set_level_indent`][].  Per default, the
+    /// indentation is 5 mm.
+    ///
+    /// [`set_level_indent`]: #method.set_level_indent
+    pub fn set_indent(&mut self, indent: impl Into<Mm>) {
+        self.indent = indent.into();
     }
-}
 
-impl Element for PageBreak {
-    fn render(
-        &mut self,
-        _context: &Context,
-        _area: render::Area<'_>,
-        _style: Style,
-    ) -> Result<RenderResult, Error> {
-        if self.cont {
-            Ok(RenderResult::default())
-        } else {
-            // We don’t use (0,0) as the size as this might abort the render process if this is the
-            // first element on a new page, see the Rendering Process section of the crate
-            // documentation.
-            self.cont = true;
-            Ok(RenderResult {
-                size: Size::new(1, 0),
-                has_more: true,
-            })
-        }
+    /// Sets the indentation per heading level and returns the table of contents.
+    pub fn with_indent(mut self, indent: impl Into<Mm>) -> Self {
+        self.set_indent(indent);
+        self
     }
-}
 
-/// Adds a padding to the wrapped element.
-///
-/// # Examples
-///
-/// Direct usage:
-/// ```
-/// use genpdf::elements;
-/// let p = elements::PaddedElement::new(
-///     elements::Paragraph::new("text"),
-///     genpdf::Margins::trbl(5, 2, 5, 10),
-/// );
-/// ```
-///
-/// Using [`Element::padded`][]:
-/// ```
-/// use genpdf::{elements, Element as _};
-/// let p = elements::Paragraph::new("text")
-///     .padded(genpdf::Margins::trbl(5, 2, 5, 10));
-/// ```
-///
-/// [`Element::padded`]: ../trait.Element.html#method.padded
-#[derive(Clone, Debug, Default)]
-pub struct PaddedElement<E: Element> {
-    element: E,
-    padding: Margins,
-}
+    /// Sets the indentation of the entries for headings of the given level.
+    pub fn set_level_indent(&mut self, level: u8, indent: impl Into<Mm>) {
+        self.level_indents.insert(level, indent.into());
+    }
 
-impl<E: Element> PaddedElement<E> {
-    /// Creates a new padded element that wraps the given element with the given padding.
-    pub fn new(element: E, padding: impl Into<Margins>) -> PaddedElement<E> {
-        PaddedElement {
-            element,
-            padding: padding.into(),
+    /// Sets the indentation of the entries for headings of the given level and returns the table
+    /// of contents.
+    pub fn with_level_indent(mut self, level: u8, indent: impl Into<Mm>) -> Self {
+        self.set_level_indent(level, indent);
+        self
+    }
+
+    /// Sets the style of the entries for headings of the given level.
+    pub fn set_level_style(&mut self, level: u8, style: impl Into<Style>) {
+        self.level_styles.insert(level, style.into());
+    }
+
+    /// Sets the style of the entries for headings of the given level and returns the table of
+    /// contents.
+    pub fn with_level_style(mut self, level: u8, style: impl Into<Style>) -> Self {
+        self.set_level_style(level, style);
+        self
+    }
+
+    /// Sets the alignment of the page numbers.
+    pub fn set_number_alignment(&mut self, alignment: PageNumberAlignment) {
+        self.number_alignment = alignment;
+    }
+
+    /// Sets the alignment of the page numbers and returns the table of contents.
+    pub fn with_number_alignment(mut self, alignment: PageNumberAlignment) -> Self {
+        self.set_number_alignment(alignment);
+        self
+    }
+
+    /// Sets the character that is repeated between the headings and the page numbers.
+    pub fn set_leader(&mut self, leader: char) {
+        self.leader = leader;
+    }
+
+    /// Sets the character that is repeated between the headings and the page numbers and returns
+    /// the table of contents.
+    pub fn with_leader(mut self, leader: char) -> Self {
+        self.set_leader(leader);
+        self
+    }
+
+    /// Sets whether the entries are clickable links to the headings.
+    pub fn set_links(&mut self, links: bool) {
+        self.links = links;
+    }
+
+    /// Sets whether the entries are clickable links to the headings and returns the table of
+    /// contents.
+    pub fn with_links(mut self, links: bool) -> Self {
+        self.set_links(links);
+        self
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (u8, &OutlineEntry)> {
+        let max_depth = self.max_depth;
+        self.headings
+            .iter()
+            .filter_map(|heading| heading.level().map(|level| (level, heading)))
+            .filter(move |(level, _)| max_depth.map(|max| *level <= max).unwrap_or(true))
+    }
+
+    fn build_layout(&self) -> LinearLayout {
+        let mut layout = LinearLayout::vertical();
+        for (level, heading) in self.entries() {
+            let indent = self
+                .level_indents
+                .get(&level)
+                .copied()
+                .unwrap_or(self.indent * f64::from(level.saturating_sub(1)));
+            let mut entry = TocEntry::new(heading.title(), heading.page().to_string())
+                .with_indent(indent)
+                .with_leader(self.leader)
+                .with_number_alignment(self.number_alignment);
+            if self.links {
+                entry.set_link(heading.page(), heading.position());
+            }
+            let style = self.level_styles.get(&level).copied().unwrap_or_default();
+            layout.push(entry.styled(style));
         }
+        layout
     }
 }
 
-impl<E: Element> Element for PaddedElement<E> {
+impl Element for TableOfContents {
     fn render(
         &mut self,
         context: &Context,
-        mut area: render::Area<'_>,
+        area: render::Area<'_>,
         style: Style,
     ) -> Result<RenderResult, Error> {
-        area.add_margins(Margins {
-            bottom: Mm(0.0),
-            ..self.padding
-        });
-        let mut result = self.element.render(context, area, style)?;
-        result.size.width += self.padding.left + self.padding.right;
-        result.size.height += self.padding.top + self.padding.bottom;
-        Ok(result)
+        let layout = self.layout.take().unwrap_or_else(|| self.build_layout());
+        self.layout
+            .get_or_insert(layout)
+            .render(context, area, style)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("TableOfContents")
+            .with_property("entries", self.entries().count())
+            .with_children(self.entries().map(|(level, heading)| {
+                ElementTree::new("TocEntry")
+                    .with_property("level", level)
+                    .with_text("text", heading.title())
+            }))
     }
 }
 
-/// Adds a default style to the wrapped element and its children.
-///
-/// # Examples
-///
-/// Direct usage:
-/// ```
-/// use genpdf::{elements, style};
-/// let p = elements::StyledElement::new(
-///     elements::Paragraph::new("text"),
-///     style::Effect::Bold,
-/// );
-/// ```
+/// An inline token of a rich text, see [`Paragraph::from_inlines`][].
 ///
-/// Using [`Element::styled`][]:
-/// ```
-/// use genpdf::{elements, style, Element as _};
-/// let p = elements::Paragraph::new("text")
-///     .styled(style::Effect::Bold);
-/// ```
+/// This enum provides a stable intermediate representation for converters from other formats like
+/// Markdown or HTML.
 ///
-/// [`Element::styled`]: ../trait.Element.html#method.styled
-#[derive(Clone, Debug, Default)]
-pub struct StyledElement<E: Element> {
-    element: E,
-    style: Style,
+/// [`Paragraph::from_inlines`]: struct.Paragraph.html#method.from_inlines
+#[derive(Clone, Debug)]
+pub enum Inline {
+    /// Unstyled text that inherits the style of the paragraph.
+    Text(String),
+    /// Styled text.
+    Styled(StyledString),
+    /// A link with a text and a target URI.
+    ///
+    /// The target is kept so that converters do not lose it, but link annotations for URIs are
+    /// not supported yet, so only the text is rendered.
+    Link {
+        /// The text of the link.
+        text: StyledString,
+        /// The target URI of the link.
+        target: String,
+    },
+    /// A forced line break.
+    LineBreak,
+    /// A space that does not allow a line break.
+    NonBreakingSpace,
+    /// An inline math formula in LaTeX syntax.
+    ///
+    /// If the `math` feature is enabled, the formula is rendered as described for
+    /// [`Paragraph::push_math`][].  Otherwise, the formula source is rendered as text.
+    ///
+    /// [`Paragraph::push_math`]: struct.Paragraph.html#method.push_math
+    InlineMath(String),
 }
 
-impl<E: Element> StyledElement<E> {
-    /// Creates a new styled element that wraps the given element with the given style.
-    pub fn new(element: E, style: impl Into<Style>) -> StyledElement<E> {
-        StyledElement {
-            element,
-            style: style.into(),
-        }
+impl From<String> for Inline {
+    fn from(s: String) -> Inline {
+        Inline::Text(s)
     }
 }
 
-impl<E: Element> Element for StyledElement<E> {
-    fn render(
-        &mut self,
-        context: &Context,
-        area: render::Area<'_>,
-        mut style: Style,
-    ) -> Result<RenderResult, Error> {
-        style.merge(self.style);
-        self.element.render(context, area, style)
+impl<'a> From<&'a str> for Inline {
+    fn from(s: &'a str) -> Inline {
+        Inline::Text(s.to_owned())
     }
 }
 
-/// Adds a frame around the wrapped element.
+impl From<StyledString> for Inline {
+    fn from(s: StyledString) -> Inline {
+        Inline::Styled(s)
+    }
+}
+
+/// A multi-line wrapped paragraph of formatted text.
+///
+/// If the text of this paragraph is longer than the page width, the paragraph is wrapped at word
+/// borders (and additionally at string borders if it contains multiple strings).  If a word in the
+/// paragraph is longer than the page width, rendering fails with a [`PageSizeExceeded`][] error
+/// that describes the offending word.  Alternatively, you can set a minimum font size with
+/// [`set_min_font_size`][].  Then the font size of the paragraph is reduced until all words fit
+/// into a line or until the minimum font size is reached.
+///
+/// Use the [`push`][], [`string`][], [`push_styled`][] and [`string_styled`][] methods to add
+/// strings to this paragraph.  Besides the styling of the text (see [`Style`][]), you can also set
+/// an [`Alignment`][] for the paragraph.
+///
+/// The line height and spacing are calculated based on the style of each string.  Forced line
+/// breaks can be added with [`push_line_break`][].  Per default, line breaks (`'\n'`) in the text
+/// are treated as spaces; use [`set_newline_breaks`][] to start a new line at them instead.
+/// Icons from registered icon fonts can be added by name with [`push_icon`][].
+/// If the `math` feature is enabled, inline formulas can be added with [`push_math`][].
+///
+/// A paragraph can also be created from a sequence of [`Inline`][] tokens using the
+/// [`from_inlines`][] method.
+///
+/// Per default, a paragraph is split after any line if it does not fit on the current page.  Use
+/// [`set_orphan_widow_control`][] to keep a minimum number of lines together at page breaks.
 ///
 /// # Examples
 ///
-/// Direct usage:
+/// With setters:
 /// ```
-/// use genpdf::elements;
-/// let p = elements::FramedElement::new(
-///     elements::Paragraph::new("text"),
-/// );
+/// use genpdf::{elements, style};
+/// let mut p = elements::Paragraph::default();
+/// p.push("This is an ");
+/// p.push_styled("important", style::Color::Rgb(255, 0, 0));
+/// p.push(" message!");
+/// p.set_alignment(genpdf::Alignment::Center);
 /// ```
 ///
-/// Using [`Element::framed`][]:
+/// Chained:
 /// ```
-/// use genpdf::{elements, style, Element as _};
-/// let p = elements::Paragraph::new("text").framed(style::LineStyle::new());
+/// use genpdf::{elements, style};
+/// let p = elements::Paragraph::default()
+///     .string("This is an ")
+///     .styled_string("important", style::Color::Rgb(255, 0, 0))
+///     .string(" message!")
+///     .aligned(genpdf::Alignment::Center);
 /// ```
 ///
-/// [`Element::framed`]: ../trait.Element.html#method.framed
+/// [`Style`]: ../style/struct.Style.html
+/// [`Alignment`]: ../enum.Alignment.html
+/// [`Element::styled`]: ../trait.Element.html#method.styled
+/// [`Inline`]: enum.Inline.html
+/// [`from_inlines`]: #method.from_inlines
+/// [`PageSizeExceeded`]: ../error/enum.ErrorKind.html#variant.PageSizeExceeded
+/// [`push`]: #method.push
+/// [`push_icon`]: #method.push_icon
+/// [`push_line_break`]: #method.push_line_break
+/// [`push_math`]: #method.push_math
+/// [`push_styled`]: #method.push_styled
+/// [`set_min_font_size`]: #method.set_min_font_size
+/// [`set_newline_breaks`]: #method.set_newline_breaks
+/// [`set_orphan_widow_control`]: #method.set_orphan_widow_control
+/// [`string`]: #method.string
+/// [`string_styled`]: #method.string_styled
 #[derive(Clone, Debug, Default)]
-pub struct FramedElement<E: Element> {
-    element: E,
-    is_first: bool,
-    line_style: LineStyle,
+pub struct Paragraph {
+    text: Vec<StyledString>,
+    words: collections::VecDeque<StyledString>,
+    style_applied: bool,
+    alignment: Alignment,
+    max_word_spacing: Option<Mm>,
+    min_font_size: Option<u8>,
+    orphan_widow_control: Option<(usize, usize)>,
+    first_line_indent: Mm,
+    hanging_indent: Mm,
+    newline_breaks: bool,
+    #[cfg(feature = "hyphenation")]
+    hyphenation_language: Option<hyphenation::Language>,
+    word_style_cb: Option<WordStyleCallback>,
+    // The indices of the icon placeholders in text and the names of the icons
+    icons: Vec<(usize, String)>,
+    // The indices of the math placeholders in text and the sources of the formulas
+    #[cfg(feature = "math")]
+    math: Vec<(usize, String)>,
+    // The rendered formulas that have not been printed yet
+    #[cfg(feature = "math")]
+    math_blocks: collections::VecDeque<rc::Rc<crate::math::MathBlock>>,
+    rendered_lines: usize,
+    deferred: bool,
+    // The area next to a floating element that the text has to avoid, see Float
+    exclusion: Option<Exclusion>,
 }
 
-impl<E: Element> FramedElement<E> {
-    /// Creates a new framed element that wraps the given element.
-    pub fn new(element: E) -> FramedElement<E> {
-        FramedElement::with_line_style(element, LineStyle::new())
-    }
+/// An area at the top of a [`Paragraph`][] that is occupied by a floating element, see
+/// [`Float`][].
+///
+/// [`Paragraph`]: struct.Paragraph.html
+/// [`Float`]: struct.Float.html
+#[derive(Clone, Copy, Debug)]
+struct Exclusion {
+    side: FloatSide,
+    width: Mm,
+    height: Mm,
+}
 
-    /// Creates a new framed element that wraps the given element,
-    /// and with the given line style.
-    pub fn with_line_style(element: E, line_style: impl Into<LineStyle>) -> FramedElement<E> {
-        Self {
-            is_first: true,
-            element,
-            line_style: line_style.into(),
-        }
+/// A callback that restyles the words of a [`Paragraph`][].
+///
+/// [`Paragraph`]: struct.Paragraph.html
+#[derive(Clone)]
+struct WordStyleCallback(rc::Rc<WordStyleFn>);
+
+type WordStyleFn = dyn Fn(&str, Style) -> Style;
+
+impl fmt::Debug for WordStyleCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WordStyleCallback")
     }
 }
 
-impl<E: Element> Element for FramedElement<E> {
-    fn render(
-        &mut self,
-        context: &Context,
-        area: render::Area<'_>,
-        style: Style,
-    ) -> Result<RenderResult, Error> {
-        // For the element area calculations, we have to take into account the full line thickness.
-        // For the frame area, we only need half because we specify the center of the line.
-        let line_thickness = self.line_style.thickness();
-        let line_offset = line_thickness / 2.0;
-
-        // Calculate the areas in which to draw the element and the frame.
-        let mut element_area = area.clone();
-        let mut frame_area = area.clone();
-        element_area.add_margins(Margins::trbl(
-            0,
-            line_thickness,
-            line_thickness,
-            line_thickness,
-        ));
-        frame_area.add_margins(Margins::trbl(0, line_offset, 0, line_offset));
-        if self.is_first {
-            element_area.add_margins(Margins::trbl(line_thickness, 0, 0, 0));
-            frame_area.add_margins(Margins::trbl(line_offset, 0, 0, 0));
+impl Paragraph {
+    /// Creates a new paragraph with the given content.
+    pub fn new(text: impl Into<StyledString>) -> Paragraph {
+        Paragraph {
+            text: vec![text.into()],
+            ..Default::default()
         }
+    }
 
-        // Draw the element.
-        let mut result = self.element.render(context, element_area, style)?;
-        result.size.width = area.size().width;
-        if result.has_more {
-            frame_area.set_height(result.size.height + line_offset);
-        } else {
-            frame_area.set_height(result.size.height + line_thickness);
-        }
+    /// Creates a new paragraph from the given inline tokens.
+    ///
+    /// Consecutive strings with the same style are merged, and non-breaking spaces are appended
+    /// to the previous string so that the paragraph is not wrapped at them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements::{Inline, Paragraph};
+    /// use genpdf::style::{Style, StyledString};
+    /// let p = Paragraph::from_inlines(vec![
+    ///     Inline::Text("Page".to_owned()),
+    ///     Inline::NonBreakingSpace,
+    ///     Inline::Text("42".to_owned()),
+    ///     Inline::LineBreak,
+    ///     Inline::Styled(StyledString::new("Important", Style::new().bold())),
+    /// ]);
+    /// ```
+    pub fn from_inlines(inlines: impl IntoIterator<Item = Inline>) -> Paragraph {
+        let mut paragraph = Paragraph::default();
+        for inline in inlines {
+            paragraph.push_inline(inline);
+        }
+        paragraph
+    }
+
+    /// Adds an inline token to the end of this paragraph.
+    pub fn push_inline(&mut self, inline: impl Into<Inline>) {
+        let s = match inline.into() {
+            Inline::Text(s) => StyledString::from(s),
+            Inline::Styled(s) => s,
+            Inline::Link { text, .. } => text,
+            Inline::LineBreak => StyledString::from(LINE_SEPARATOR.to_string()),
+            Inline::NonBreakingSpace => match self.last_text_mut() {
+                Some(last) => {
+                    last.s.push('\u{a0}');
+                    return;
+                }
+                None => StyledString::from("\u{a0}"),
+            },
+            #[cfg(feature = "math")]
+            Inline::InlineMath(source) => {
+                self.push_math(source);
+                return;
+            }
+            #[cfg(not(feature = "math"))]
+            Inline::InlineMath(source) => StyledString::from(source),
+        };
+        match self.last_text_mut() {
+            Some(last) if last.style == s.style => last.s.push_str(&s.s),
+            _ => self.text.push(s),
+        }
+    }
+
+    /// Returns the last string of this paragraph unless it is an icon or a formula.
+    fn last_text_mut(&mut self) -> Option<&mut StyledString> {
+        let idx = self.text.len().checked_sub(1)?;
+        if self.icons.iter().any(|(i, _)| *i == idx) {
+            return None;
+        }
+        #[cfg(feature = "math")]
+        {
+            if self.math.iter().any(|(i, _)| *i == idx) {
+                return None;
+            }
+        }
+        self.text.last_mut()
+    }
+
+    /// Sets the alignment of this paragraph.
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment;
+    }
+
+    /// Sets the alignment of this paragraph and returns the paragraph.
+    pub fn aligned(mut self, alignment: Alignment) -> Self {
+        self.set_alignment(alignment);
+        self
+    }
+
+    /// Sets the maximum extra spacing between two words of a line for justified alignment.
+    ///
+    /// If a line of a justified paragraph would have to be stretched by more than this spacing
+    /// per word gap, for example because it only contains two words, it is aligned left instead.
+    /// Note that the first word that does not fit into a line is always hyphenated if possible,
+    /// so enabling hyphenation reduces the number of lines that exceed the threshold.  Per
+    /// default, the extra word spacing is not limited.
+    pub fn set_max_word_spacing(&mut self, max_word_spacing: impl Into<Mm>) {
+        self.max_word_spacing = Some(max_word_spacing.into());
+    }
+
+    /// Sets the maximum extra spacing between two words of a line for justified alignment and
+    /// returns the paragraph.
+    pub fn with_max_word_spacing(mut self, max_word_spacing: impl Into<Mm>) -> Self {
+        self.set_max_word_spacing(max_word_spacing);
+        self
+    }
+
+    /// Sets a callback that can change the style of the words of this paragraph.
+    ///
+    /// The callback is called once for every word when the paragraph is rendered for the first
+    /// time, with the text of the word (without surrounding whitespace) and its style.  The
+    /// returned style is used for the word, including the following whitespace.  This can be used
+    /// to highlight search terms or to mark words that should be redacted without splitting the
+    /// text into styled strings manually.  As the words are restyled before they are wrapped,
+    /// style changes that affect the width of a word, for example bold text, are taken into
+    /// account for the line breaks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::{elements, style};
+    /// let mut p = elements::Paragraph::new("Search results for rust and pdf");
+    /// p.set_word_style_cb(|word, style| {
+    ///     if word.eq_ignore_ascii_case("rust") {
+    ///         style.bold().with_color(style::Color::Rgb(200, 0, 0))
+    ///     } else {
+    ///         style
+    ///     }
+    /// });
+    /// ```
+    pub fn set_word_style_cb(&mut self, cb: impl Fn(&str, Style) -> Style + 'static) {
+        self.word_style_cb = Some(WordStyleCallback(rc::Rc::new(cb)));
+    }
+
+    /// Sets a callback that can change the style of the words of this paragraph and returns the
+    /// paragraph.
+    ///
+    /// See [`set_word_style_cb`][] for details.
+    ///
+    /// [`set_word_style_cb`]: #method.set_word_style_cb
+    pub fn with_word_style_cb(mut self, cb: impl Fn(&str, Style) -> Style + 'static) -> Self {
+        self.set_word_style_cb(cb);
+        self
+    }
+
+    /// Applies the word style callback, if set, to the given word.
+    fn style_word(&self, mut word: StyledString) -> StyledString {
+        if let Some(cb) = &self.word_style_cb {
+            if word.s != "\n" && !is_math_placeholder(&word.s, word.style) {
+                word.style = (cb.0)(word.s.trim(), word.style);
+            }
+        }
+        word
+    }
+
+    /// Sets the minimum font size for this paragraph.
+    ///
+    /// If a word of this paragraph does not fit into a line, the font size of the paragraph is
+    /// reduced by the smallest number of points so that all words fit, but not below the given
+    /// font size.  The font size is only determined when the paragraph is rendered for the first
+    /// time, using the width of the area on the first page.
+    pub fn set_min_font_size(&mut self, min_font_size: u8) {
+        self.min_font_size = Some(min_font_size);
+    }
+
+    /// Sets the minimum font size for this paragraph and returns the paragraph.
+    pub fn with_min_font_size(mut self, min_font_size: u8) -> Self {
+        self.set_min_font_size(min_font_size);
+        self
+    }
+
+    /// Sets whether line breaks (`'\n'`) in the text of this paragraph start a new line.
+    ///
+    /// Per default, line breaks in the text are treated as spaces.  Forced line breaks added with
+    /// [`push_line_break`][] always start a new line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let p = elements::Paragraph::new("Jane Doe\nMain Street 1").with_newline_breaks(true);
+    /// ```
+    ///
+    /// [`push_line_break`]: #method.push_line_break
+    pub fn set_newline_breaks(&mut self, newline_breaks: bool) {
+        self.newline_breaks = newline_breaks;
+    }
+
+    /// Sets whether line breaks in the text of this paragraph start a new line and returns the
+    /// paragraph.
+    pub fn with_newline_breaks(mut self, newline_breaks: bool) -> Self {
+        self.set_newline_breaks(newline_breaks);
+        self
+    }
+
+    /// Sets the indentation of the first line of this paragraph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut p = elements::Paragraph::new("Lorem ipsum dolor sit amet.");
+    /// p.set_first_line_indent(5);
+    /// ```
+    pub fn set_first_line_indent(&mut self, indent: impl Into<Mm>) {
+        self.first_line_indent = indent.into();
+    }
+
+    /// Sets the indentation of the first line of this paragraph and returns the paragraph.
+    pub fn with_first_line_indent(mut self, indent: impl Into<Mm>) -> Self {
+        self.set_first_line_indent(indent);
+        self
+    }
+
+    /// Sets the indentation of all lines of this paragraph except for the first line.
+    ///
+    /// Lines after a forced line break are indented too.  If the first line should not be
+    /// indented at all, the first line indent has to be zero, which is the default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut reference = elements::Paragraph::new(
+    ///     "Knuth, D. E. (1984). The TeXbook. Addison-Wesley.",
+    /// );
+    /// reference.set_hanging_indent(8);
+    /// ```
+    pub fn set_hanging_indent(&mut self, indent: impl Into<Mm>) {
+        self.hanging_indent = indent.into();
+    }
+
+    /// Sets the indentation of all lines of this paragraph except for the first line and returns
+    /// the paragraph.
+    pub fn with_hanging_indent(mut self, indent: impl Into<Mm>) -> Self {
+        self.set_hanging_indent(indent);
+        self
+    }
+
+    /// Sets the minimum number of lines at the start and at the end of this paragraph that are
+    /// kept together if the paragraph is split across pages.
+    ///
+    /// If fewer than `min_first_lines` lines of the paragraph fit on the current page (orphans),
+    /// the whole paragraph is moved to the next page.  If fewer than `min_last_lines` lines would
+    /// be carried over to the next page (widows), more lines are moved to the next page.  If the
+    /// lines do not fit on the next page either, the paragraph is split without these checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut p = elements::Paragraph::new("Lorem ipsum dolor sit amet.");
+    /// p.set_orphan_widow_control(2, 2);
+    /// ```
+    pub fn set_orphan_widow_control(&mut self, min_first_lines: usize, min_last_lines: usize) {
+        self.orphan_widow_control = Some((min_first_lines, min_last_lines));
+    }
+
+    /// Sets the minimum number of lines at the start and at the end of this paragraph that are
+    /// kept together if the paragraph is split across pages and returns the paragraph.
+    pub fn with_orphan_widow_control(
+        mut self,
+        min_first_lines: usize,
+        min_last_lines: usize,
+    ) -> Self {
+        self.set_orphan_widow_control(min_first_lines, min_last_lines);
+        self
+    }
+
+    /// Sets the language that is used to hyphenate this paragraph.
+    ///
+    /// The paragraph is hyphenated with the hyphenator that has been added for this language
+    /// with [`Document::add_hyphenator`][].  If this method is not called, the default
+    /// hyphenator set with [`Document::set_hyphenator`][] is used.
+    ///
+    /// *Only available if the `hyphenation` feature is enabled.*
+    ///
+    /// [`Document::add_hyphenator`]: ../struct.Document.html#method.add_hyphenator
+    /// [`Document::set_hyphenator`]: ../struct.Document.html#method.set_hyphenator
+    #[cfg(feature = "hyphenation")]
+    pub fn set_hyphenation_language(&mut self, language: hyphenation::Language) {
+        self.hyphenation_language = Some(language);
+    }
+
+    /// Sets the language that is used to hyphenate this paragraph and returns the paragraph.
+    ///
+    /// *Only available if the `hyphenation` feature is enabled.*
+    #[cfg(feature = "hyphenation")]
+    pub fn with_hyphenation_language(mut self, language: hyphenation::Language) -> Self {
+        self.set_hyphenation_language(language);
+        self
+    }
+
+    /// Adds a string to the end of this paragraph.
+    pub fn push(&mut self, s: impl Into<StyledString>) {
+        self.text.push(s.into());
+    }
+
+    /// Adds a string to the end of this paragraph and returns the paragraph.
+    pub fn string(mut self, s: impl Into<StyledString>) -> Self {
+        self.push(s);
+        self
+    }
+
+    /// Adds a string with the given style to the end of this paragraph.
+    pub fn push_styled(&mut self, s: impl Into<String>, style: impl Into<Style>) {
+        self.text.push(StyledString::new(s, style))
+    }
+
+    /// Adds a string with the given style to the end of this paragraph and returns the paragraph.
+    pub fn styled_string(mut self, s: impl Into<String>, style: impl Into<Style>) -> Self {
+        self.push_styled(s, style);
+        self
+    }
+
+    /// Adds the icon with the given name and style to the end of this paragraph.
+    ///
+    /// The icon is looked up by its name in the icon fonts that have been registered with
+    /// [`Document::add_icon_font`][] when the paragraph is rendered.  It is printed with the font
+    /// size and color of the given style.  If there is no icon with the given name, rendering
+    /// fails with an [`InvalidData`][] error.
+    ///
+    /// [`Document::add_icon_font`]: ../struct.Document.html#method.add_icon_font
+    /// [`InvalidData`]: ../error/enum.ErrorKind.html#variant.InvalidData
+    pub fn push_icon(&mut self, name: impl Into<String>, style: impl Into<Style>) {
+        self.icons.push((self.text.len(), name.into()));
+        self.text.push(StyledString::new(String::new(), style));
+    }
+
+    /// Adds the icon with the given name and style to the end of this paragraph and returns the
+    /// paragraph.
+    ///
+    /// See [`push_icon`][] for details.
+    ///
+    /// [`push_icon`]: #method.push_icon
+    pub fn icon(mut self, name: impl Into<String>, style: impl Into<Style>) -> Self {
+        self.push_icon(name, style);
+        self
+    }
+
+    /// Adds the given math formula in LaTeX syntax to the end of this paragraph.
+    ///
+    /// The formula is rendered in the text style with the math font that has been set with
+    /// [`Document::enable_math`][] when the paragraph is rendered.  It uses the font size and the
+    /// color of the paragraph style, is aligned to the baseline of the surrounding text and is
+    /// wrapped like a word.  Note that formulas that are higher than the text, for example
+    /// fractions, do not increase the line height.  If the formula cannot be parsed or if math
+    /// rendering has not been enabled, rendering fails with a [`Math`][] error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut p = elements::Paragraph::new("The angles ");
+    /// p.push_math(r"\alpha + \beta");
+    /// p.push(" add up to 90 degrees.");
+    /// ```
+    ///
+    /// [`Document::enable_math`]: ../struct.Document.html#method.enable_math
+    /// [`Math`]: ../error/enum.ErrorKind.html#variant.Math
+    #[cfg(feature = "math")]
+    pub fn push_math(&mut self, source: impl Into<String>) {
+        self.math.push((self.text.len(), source.into()));
+        self.text
+            .push(StyledString::new(String::new(), Style::new()));
+    }
+
+    /// Adds the given math formula in LaTeX syntax to the end of this paragraph and returns the
+    /// paragraph.
+    ///
+    /// See [`push_math`][] for details.
+    ///
+    /// [`push_math`]: #method.push_math
+    #[cfg(feature = "math")]
+    pub fn math(mut self, source: impl Into<String>) -> Self {
+        self.push_math(source);
+        self
+    }
+
+    /// Adds a forced line break to the end of this paragraph.
+    ///
+    /// The text after the line break starts on a new line but keeps the alignment and the line
+    /// spacing of the paragraph.  Unlike line breaks (`'\n'`) in the text, this line break does
+    /// not depend on the [`set_newline_breaks`][] setting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut address = elements::Paragraph::new("Jane Doe");
+    /// address.push_line_break();
+    /// address.push("Main Street 1");
+    /// address.push_line_break();
+    /// address.push("12345 Springfield");
+    /// ```
+    ///
+    /// [`set_newline_breaks`]: #method.set_newline_breaks
+    pub fn push_line_break(&mut self) {
+        self.push_inline(Inline::LineBreak);
+    }
+
+    /// Adds a forced line break to the end of this paragraph and returns the paragraph.
+    pub fn line_break(mut self) -> Self {
+        self.push_line_break();
+        self
+    }
+
+    /// Adds a string to the end of this paragraph if the provided check function returns true. The
+    /// check callback is provided with the current Text to decide if the new string should be
+    /// added
+    pub fn push_if_text(
+        &mut self,
+        s: impl Into<StyledString>,
+        check: impl Fn(&[StyledString]) -> bool,
+    ) {
+        if check(&self.text) {
+            self.push(s);
+        }
+    }
+
+    fn get_offset(&self, width: Mm, max_width: Mm) -> Mm {
+        match self.alignment {
+            Alignment::Left | Alignment::Justified(_) => Mm::default(),
+            Alignment::Center => (max_width - width) / 2.0,
+            Alignment::Right => max_width - width,
+        }
+    }
+
+    fn apply_style(&mut self, style: Style) {
+        if !self.style_applied {
+            for s in &mut self.text {
+                s.style = style.and(s.style);
+            }
+            self.style_applied = true;
+        }
+    }
+
+    /// Returns the indentation of the next line and of the following lines.
+    fn indents(&self) -> (Mm, Mm) {
+        if self.rendered_lines == 0 {
+            (self.first_line_indent, self.hanging_indent)
+        } else {
+            (self.hanging_indent, self.hanging_indent)
+        }
+    }
+
+    /// Creates a wrapper for the remaining words of this paragraph that reduces the line width by
+    /// the indentation and by the width of the exclusion, if set.
+    fn wrapper<'a>(
+        &'a self,
+        context: &'a Context,
+        width: Mm,
+    ) -> wrap::Wrapper<'a, 'a, impl Iterator<Item = StyledStr<'a>>> {
+        let excluded_lines = self.excluded_lines(context, width);
+        self.wrapper_with_exclusion(context, width, excluded_lines)
+    }
+
+    /// Creates a wrapper for the remaining words of this paragraph where the given number of lines
+    /// is reduced by the width of the exclusion.
+    fn wrapper_with_exclusion<'a>(
+        &'a self,
+        context: &'a Context,
+        width: Mm,
+        excluded_lines: usize,
+    ) -> wrap::Wrapper<'a, 'a, impl Iterator<Item = StyledStr<'a>>> {
+        let (first_indent, indent) = self.indents();
+        let words = self.words.iter().map(Into::into);
+        let mut wrapper = wrap::Wrapper::new(words, context, width - indent)
+            .with_first_line_width(width - first_indent);
+        if let Some(exclusion) = self.exclusion {
+            let line_widths = (0..excluded_lines)
+                .map(|i| {
+                    let indent = if i == 0 { first_indent } else { indent };
+                    width - indent - exclusion.width
+                })
+                .collect();
+            wrapper = wrapper.with_line_widths(line_widths);
+        }
+        #[cfg(feature = "hyphenation")]
+        let wrapper = wrapper.with_language(self.hyphenation_language);
+        wrapper
+    }
+
+    /// Returns the number of lines that start next to the exclusion and therefore have a reduced
+    /// width.
+    fn excluded_lines(&self, context: &Context, width: Mm) -> usize {
+        let exclusion = match self.exclusion {
+            Some(exclusion) => exclusion,
+            None => return 0,
+        };
+
+        // The line heights depend on the wrapping, so we increase the number of excluded lines
+        // until all lines next to the exclusion are reduced
+        let mut excluded_lines = 0;
+        loop {
+            let mut height = Mm(0.0);
+            let mut lines = 0;
+            for (line, _) in self.wrapper_with_exclusion(context, width, excluded_lines) {
+                if height >= exclusion.height {
+                    break;
+                }
+                let metrics = line
+                    .iter()
+                    .map(|s| s.style.metrics(&context.font_cache))
+                    .fold(fonts::Metrics::default(), |max, m| max.max(&m));
+                height += metrics.line_height;
+                lines += 1;
+            }
+            if lines <= excluded_lines {
+                return excluded_lines;
+            }
+            excluded_lines = lines;
+        }
+    }
+
+    /// Returns the maximum number of lines that can be rendered into the given area according to
+    /// the orphan and widow control, or `None` if all remaining lines fit or if the orphan and
+    /// widow control is disabled.
+    fn max_lines(&self, context: &Context, area: &render::Area<'_>) -> Option<usize> {
+        let (min_first_lines, min_last_lines) = self.orphan_widow_control?;
+
+        let mut height = Mm(0.0);
+        let mut total_lines: usize = 0;
+        let mut fitting_lines = None;
+        for (line, _) in self.wrapper(context, area.size().width) {
+            let metrics = line
+                .iter()
+                .map(|s| s.style.metrics(&context.font_cache))
+                .fold(fonts::Metrics::default(), |max, m| max.max(&m));
+            if fitting_lines.is_none() && height + metrics.glyph_height > area.size().height {
+                fitting_lines = Some(total_lines);
+            }
+            height += metrics.line_height;
+            total_lines += 1;
+        }
+
+        let fitting_lines = fitting_lines?;
+        let lines = fitting_lines.min(total_lines.saturating_sub(min_last_lines));
+        if self.rendered_lines == 0 && lines < min_first_lines {
+            Some(0)
+        } else {
+            Some(lines)
+        }
+    }
+
+    /// Draws the next inline formula at the given horizontal offset in the line with the given
+    /// metrics that starts at the top of the given area.
+    #[cfg(feature = "math")]
+    fn draw_math(
+        &mut self,
+        context: &Context,
+        area: &render::Area<'_>,
+        x: Mm,
+        metrics: fonts::Metrics,
+        style: Style,
+    ) -> Result<(), Error> {
+        let block = match self.math_blocks.pop_front() {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+        let style = style.with_text_rendering_mode(TextRenderingMode::Fill);
+        if let Some(math_renderer) = &context.math_renderer {
+            let font_size = f64::from(style.font_size());
+            let baseline = math::math_baseline(context, math_renderer, &block, font_size, style);
+            let origin = Position::new(x, metrics.ascent - baseline);
+            math::draw_math_block(context, area, math_renderer, &block, origin, style)?;
+        }
+        Ok(())
+    }
+
+    /// Reduces the font size of the words by the smallest amount so that they can be wrapped to
+    /// the given width, but not below the given minimum font size.
+    ///
+    /// The font sizes of all words are reduced by the same number of points so that their
+    /// relative sizes are kept.  The reduction is determined with a binary search.
+    fn shrink_to_fit(&mut self, context: &Context, width: Mm, min_font_size: u8) {
+        // The original font sizes of the words that can be shrunk.  The width of formulas is
+        // fixed once they have been rendered.
+        let font_sizes: Vec<_> = self
+            .words
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| !is_math_placeholder(&word.s, word.style))
+            .map(|(i, word)| (i, word.style.base_font_size()))
+            .filter(|(_, font_size)| *font_size > min_font_size)
+            .collect();
+        let max_reduction = font_sizes
+            .iter()
+            .map(|(_, font_size)| font_size - min_font_size)
+            .max()
+            .unwrap_or(0);
+
+        let has_overflowed = |paragraph: &mut Paragraph, reduction: u8| {
+            for (i, font_size) in &font_sizes {
+                let font_size = font_size.saturating_sub(reduction).max(min_font_size);
+                paragraph.words[*i].style.set_font_size(font_size);
+            }
+            let mut wrapper = paragraph.wrapper(context, width);
+            while wrapper.next().is_some() {}
+            wrapper.has_overflowed()
+        };
+
+        if max_reduction == 0 || !has_overflowed(self, 0) {
+            return;
+        }
+        // Invariant:  The words overflow with low, and they fit with high or high is the maximum
+        let (mut low, mut high) = (0, max_reduction);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if has_overflowed(self, mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        has_overflowed(self, high);
+    }
+}
+
+fn overflow_error(overflow: &wrap::Overflow) -> Error {
+    const MAX_LEN: usize = 40;
+    let mut text: String = overflow.text.chars().take(MAX_LEN).collect();
+    if overflow.text.chars().count() > MAX_LEN {
+        text.push('…');
+    }
+    Error::new(
+        format!(
+            "Page overflowed while trying to wrap the string \"{}\": it requires a width of \
+             {:.1} mm, but only {:.1} mm are available (font size {} pt).  Consider using a \
+             smaller font size, a wider area or setting a minimum font size for the paragraph.",
+            text,
+            overflow.width.0,
+            overflow.available_width.0,
+            overflow.style.font_size(),
+        ),
+        ErrorKind::PageSizeExceeded,
+    )
+}
+
+impl Element for Paragraph {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+
+        self.apply_style(style);
+
+        if self.words.is_empty() {
+            if self.text.is_empty() {
+                return Ok(result);
+            }
+            for (idx, name) in mem::take(&mut self.icons) {
+                resolve_icon(&mut self.text[idx], &context.font_cache, &name)?;
+            }
+            #[cfg(feature = "math")]
+            for (idx, source) in mem::take(&mut self.math) {
+                let block = resolve_math(&mut self.text[idx], context, &source)?;
+                self.math_blocks.push_back(block);
+            }
+            let normalization = context.text_normalization();
+            let newline_breaks = self.newline_breaks;
+            let text = mem::take(&mut self.text).into_iter().map(|mut s| {
+                if let borrow::Cow::Owned(normalized) = normalization.apply(&s.s) {
+                    s.s = normalized;
+                }
+                convert_line_breaks(&mut s.s, newline_breaks);
+                s
+            });
+            self.words = wrap::Words::new(text)
+                .map(|word| self.style_word(word))
+                .collect();
+            if let Some(min_font_size) = self.min_font_size {
+                self.shrink_to_fit(context, area.size().width, min_font_size);
+            }
+        }
+
+        let mut max_lines = self.max_lines(context, &area);
+        if max_lines == Some(0) {
+            if !self.deferred {
+                // Move the paragraph to the next page.  We request a page break so that the page
+                // does not count as empty if this is the first element on the page.
+                self.deferred = true;
+                if !area.is_dry_run() {
+                    context.request_page_break();
+                }
+                return Ok(RenderResult {
+                    size: Size::new(0, 0),
+                    has_more: true,
+                });
+            }
+            max_lines = None;
+        }
+        self.deferred = false;
+
+        let (first_indent, indent) = self.indents();
+        let excluded_lines = self.excluded_lines(context, area.size().width);
+        let mut rendered_len = 0;
+        let mut rendered_lines = 0;
+        let mut wrapper = self.wrapper_with_exclusion(context, area.size().width, excluded_lines);
+
+        let mut curr_wrap = wrapper.next();
+        while let Some((mut line, delta)) = curr_wrap {
+            if max_lines == Some(rendered_lines) {
+                result.has_more = true;
+                break;
+            }
+            let next_wrap = wrapper.next();
+
+            // Calculate the maximum line height (including forced line breaks so that empty lines
+            // have the correct height)
+            let metrics = line
+                .iter()
+                .map(|s| s.style.metrics(&context.font_cache))
+                .fold(fonts::Metrics::default(), |max, m| max.max(&m));
+            // Forced line breaks are not printed
+            let has_line_break = line.last().map(|s| s.s == "\n").unwrap_or(false);
+            if has_line_break {
+                line.pop();
+            }
+            let width = line.iter().map(|s| s.width(&context.font_cache)).sum();
+            let mut indent = if rendered_lines == 0 {
+                first_indent
+            } else {
+                indent
+            };
+            let mut line_width = area.size().width - indent;
+            if let Some(exclusion) = self.exclusion.filter(|_| rendered_lines < excluded_lines) {
+                line_width -= exclusion.width;
+                if exclusion.side == FloatSide::Left {
+                    indent += exclusion.width;
+                }
+            }
+            let position = Position::new(indent + self.get_offset(width, line_width), 0);
+
+            // Extra word spacing for justified text alignment, except on the last line and on lines
+            // ending with a forced line break
+            let extra_word_spacing = match self.alignment {
+                Alignment::Justified(trim_spaces) if next_wrap.is_some() && !has_line_break => {
+                    let mut width = width;
+                    if let Some(word) = line.first() {
+                        let diff = word.width(&context.font_cache)
+                            - word
+                                .style
+                                .str_width(&context.font_cache, wrap::trim_start(&word.s));
+                        width -= diff;
+                    }
+                    let trailing_space = match line.last() {
+                        Some(word) => {
+                            word.width(&context.font_cache)
+                                - word
+                                    .style
+                                    .str_width(&context.font_cache, wrap::trim_end(&word.s))
+                        }
+                        None => Mm(0.0),
+                    };
+                    if trim_spaces {
+                        width -= trailing_space;
+                    }
+
+                    let leftover_space = line_width - width;
+                    // Trailing spaces are invisible, so they do not count as overflow
+                    let overflow = width - line_width;
+                    let overflow = if trim_spaces {
+                        overflow
+                    } else {
+                        overflow - trailing_space
+                    };
+                    if overflow > Mm(0.0) {
+                        let text: String = line.iter().map(|s| s.s.as_ref()).collect();
+                        let warning = Warning::new(
+                            format!(
+                                "The justified line \"{}\" exceeds the available width by {:.1} mm",
+                                text.trim(),
+                                overflow.0
+                            ),
+                            WarningKind::OverfullLine,
+                        );
+                        context.report_warning(&area, warning)?;
+                    }
+                    let word_spacing = leftover_space / (line.len() - 1).max(1) as f64;
+                    match self.max_word_spacing {
+                        // Lines that would be stretched too much are aligned left
+                        Some(max_word_spacing) if word_spacing > max_word_spacing => Mm(0.0),
+                        _ => word_spacing / style.font_size() as f64,
+                    }
+                }
+                _ => Mm(0.0),
+            };
+
+            // The horizontal offsets and styles of the inline formulas in this line
+            let mut math_offsets = Vec::new();
+            if let Some(mut section) = area.text_section(&context.font_cache, position, metrics) {
+                let mut decoration_area = area.clone();
+                decoration_area.add_offset(position);
+
+                for s in line {
+                    check_glyphs(context, &area, s.style, &s.s)?;
+                    section.print_str_xoff(&s.s, s.style, extra_word_spacing)?;
+
+                    let width = s.width(&context.font_cache);
+                    if is_math_placeholder(&s.s, s.style) {
+                        math_offsets.push((position.x + section.cursor() - width, s.style));
+                    }
+                    draw_text_decorations(&decoration_area, s.style, width, metrics);
+                    decoration_area.add_offset(Position::new(width, 0));
+
+                    rendered_len += s.s.len();
+                }
+                rendered_len -= delta;
+                if has_line_break {
+                    rendered_len += 1;
+                }
+            } else {
+                result.has_more = true;
+                break;
+            }
+            #[cfg(feature = "math")]
+            for (x, style) in math_offsets {
+                self.draw_math(context, &area, x, metrics, style)?;
+            }
+            result.size = result
+                .size
+                .stack_vertical(Size::new(width, metrics.line_height));
+            area.add_offset(Position::new(0, metrics.line_height));
+            rendered_lines += 1;
+
+            curr_wrap = next_wrap;
+        }
+        if let Some(overflow) = wrapper.overflow() {
+            return Err(overflow_error(overflow));
+        }
+        drop(wrapper);
+        self.rendered_lines += rendered_lines;
+
+        // Remove the rendered data from self.words so that we don’t render it again on the next
+        // call to render.
+        while rendered_len > 0 && !self.words.is_empty() {
+            if self.words[0].s.len() <= rendered_len {
+                rendered_len -= self.words[0].s.len();
+                self.words.pop_front();
+            } else {
+                self.words[0].s.replace_range(..rendered_len, "");
+                rendered_len = 0;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        let normalization = context.text_normalization();
+        let mut text = self.text.clone();
+        for (idx, name) in &self.icons {
+            resolve_icon(&mut text[*idx], &context.font_cache, name).ok()?;
+        }
+        #[cfg(feature = "math")]
+        for (idx, source) in &self.math {
+            let s = &mut text[*idx];
+            s.style = style.and(s.style);
+            resolve_math(s, context, source).ok()?;
+        }
+        for s in &mut text {
+            convert_line_breaks(&mut s.s, self.newline_breaks);
+        }
+        let text = text
+            .iter()
+            .chain(self.words.iter())
+            .map(|s| StyledString::new(normalization.apply(&s.s), style.and(s.style)));
+        let (first_indent, indent) = self.indents();
+        let mut max_width = Mm(0.0);
+        let mut line_width = first_indent;
+        // If the paragraph has already been split into words, the callback has been applied.
+        let is_styled = !self.words.is_empty();
+        for word in wrap::Words::new(text) {
+            let word = if is_styled {
+                word
+            } else {
+                self.style_word(word)
+            };
+            if word.s == "\n" {
+                line_width = indent;
+            } else {
+                line_width += word.width(&context.font_cache);
+                max_width = max_width.max(line_width);
+            }
+        }
+        Some(max_width)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        let text: String = self
+            .text
+            .iter()
+            .chain(&self.words)
+            .map(|s| s.s.as_str())
+            .collect();
+        let mut tree = ElementTree::new("Paragraph").with_text("text", &text);
+        if self.alignment != Alignment::default() {
+            tree.push_property("alignment", format!("{:?}", self.alignment));
+        }
+        tree
+    }
+}
+
+impl From<Vec<StyledString>> for Paragraph {
+    fn from(text: Vec<StyledString>) -> Paragraph {
+        Paragraph {
+            text,
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Into<StyledString>> iter::Extend<T> for Paragraph {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for s in iter {
+            self.push(s);
+        }
+    }
+}
+
+impl<T: Into<StyledString>> iter::FromIterator<T> for Paragraph {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut paragraph = Paragraph::default();
+        paragraph.extend(iter);
+        paragraph
+    }
+}
+
+/// The side of a [`Float`][] that the floating element is placed on.
+///
+/// [`Float`]: struct.Float.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatSide {
+    /// The element is placed on the left side and the text flows on its right.
+    Left,
+    /// The element is placed on the right side and the text flows on its left.
+    Right,
+}
+
+impl Default for FloatSide {
+    fn default() -> FloatSide {
+        FloatSide::Left
+    }
+}
+
+/// Floats an element to the left or right side of a paragraph so that the text wraps around it.
+///
+/// The floating element, for example an [`Image`][], is rendered at the top of the area, and the
+/// lines of the [`Paragraph`][] that start next to it are shortened by the width of the element
+/// and the gap (see [`set_gap`][], default: 3 mm).  The following lines use the full width.  The
+/// floating element is rendered into an area with the width set with [`set_width`][] (default:
+/// half of the available width) and then takes the width that it actually used.
+///
+/// The floating element is not split.  If it does not fit on the current page, it is moved to the
+/// next page together with the paragraph.  If the paragraph is continued on the next page, the
+/// remaining lines use the full width.  If the floating element does not support measuring (see
+/// [`Element::measure`][]) and only a part of it fits on the page, rendering fails with a
+/// [`PageSizeExceeded`][] error.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let text = elements::Paragraph::new("Lorem ipsum dolor sit amet, consectetur adipiscing.");
+/// let float = elements::Float::new(elements::Placeholder::new((30, 20)), text)
+///     .with_side(elements::FloatSide::Right)
+///     .with_width(30);
+/// ```
+///
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+/// [`Image`]: struct.Image.html
+/// [`PageSizeExceeded`]: ../error/enum.ErrorKind.html#variant.PageSizeExceeded
+/// [`Paragraph`]: struct.Paragraph.html
+/// [`set_gap`]: #method.set_gap
+/// [`set_width`]: #method.set_width
+#[derive(Clone, Debug)]
+pub struct Float<E: Element> {
+    element: E,
+    paragraph: Paragraph,
+    side: FloatSide,
+    width: Option<Mm>,
+    gap: Mm,
+    is_float_rendered: bool,
+}
+
+impl<E: Element> Float<E> {
+    /// Creates a new float that places the given element next to the given paragraph.
+    pub fn new(element: E, paragraph: Paragraph) -> Float<E> {
+        Float {
+            element,
+            paragraph,
+            side: FloatSide::default(),
+            width: None,
+            gap: Mm(3.0),
+            is_float_rendered: false,
+        }
+    }
+
+    /// Sets the side that the floating element is placed on.
+    pub fn set_side(&mut self, side: FloatSide) {
+        self.side = side;
+    }
+
+    /// Sets the side that the floating element is placed on and returns the float.
+    pub fn with_side(mut self, side: FloatSide) -> Self {
+        self.set_side(side);
+        self
+    }
+
+    /// Sets the maximum width of the floating element.
+    pub fn set_width(&mut self, width: impl Into<Mm>) {
+        self.width = Some(width.into());
+    }
+
+    /// Sets the maximum width of the floating element and returns the float.
+    pub fn with_width(mut self, width: impl Into<Mm>) -> Self {
+        self.set_width(width);
+        self
+    }
+
+    /// Sets the gap between the floating element and the text.
+    pub fn set_gap(&mut self, gap: impl Into<Mm>) {
+        self.gap = gap.into();
+    }
+
+    /// Sets the gap between the floating element and the text and returns the float.
+    pub fn with_gap(mut self, gap: impl Into<Mm>) -> Self {
+        self.set_gap(gap);
+        self
+    }
+
+    /// Returns the area for the floating element.
+    fn float_area<'p>(&self, area: &render::Area<'p>) -> render::Area<'p> {
+        let mut float_area = area.clone();
+        let width = self.width.unwrap_or(area.size().width / 2.0);
+        float_area.set_width(width.min(area.size().width));
+        float_area
+    }
+
+    /// Returns the exclusion of the paragraph for a floating element with the given size.
+    fn exclusion(&self, size: Size) -> Exclusion {
+        Exclusion {
+            side: self.side,
+            width: size.width + self.gap,
+            height: size.height,
+        }
+    }
+}
+
+impl<E: Element> Element for Float<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        if self.is_float_rendered {
+            return self.paragraph.render(context, area, style);
+        }
+
+        let mut float_area = self.float_area(&area);
+        let mut float_width = float_area.size().width;
+        if let Some(result) = self.element.measure(context, float_area.clone(), style) {
+            if result.has_more {
+                return Ok(RenderResult {
+                    size: Size::new(0, 0),
+                    has_more: true,
+                });
+            }
+            float_width = result.size.width;
+        }
+        if self.side == FloatSide::Right {
+            float_area.add_offset(Position::new(area.size().width - float_width, 0));
+            float_area.set_width(float_width);
+        }
+        let float_result = self.element.render(context, float_area, style)?;
+        if float_result.has_more {
+            // The element could not be measured, so we only notice now that it does not fit.
+            if float_result.size == Size::new(0, 0) {
+                return Ok(RenderResult {
+                    size: Size::new(0, 0),
+                    has_more: true,
+                });
+            }
+            return Err(Error::new(
+                "The floating element does not fit into the available area and cannot be split",
+                ErrorKind::PageSizeExceeded,
+            ));
+        }
+        self.is_float_rendered = true;
+
+        let width = area.size().width;
+        self.paragraph.exclusion = Some(self.exclusion(float_result.size));
+        let result = self.paragraph.render(context, area, style);
+        self.paragraph.exclusion = None;
+        let mut result = result?;
+        result.size.width = width;
+        result.size.height = result.size.height.max(float_result.size.height);
+        Ok(result)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        if self.is_float_rendered {
+            return self.paragraph.measure(context, area, style);
+        }
+
+        let float_result = self
+            .element
+            .measure(context, self.float_area(&area), style)?;
+        if float_result.has_more {
+            return Some(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        let mut paragraph = self.paragraph.clone();
+        paragraph.exclusion = Some(self.exclusion(float_result.size));
+        let mut result = paragraph.measure(context, area.clone(), style)?;
+        result.size.width = area.size().width;
+        result.size.height = result.size.height.max(float_result.size.height);
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("Float")
+            .with_property("side", format!("{:?}", self.side))
+            .with_child(self.element.dump_tree())
+            .with_child(self.paragraph.dump_tree())
+    }
+}
+
+/// A line break.
+///
+/// This element inserts a given number of empty lines or, if created with [`with_height`][], a
+/// vertical space with a fixed height that does not depend on the font size.
+///
+/// # Examples
+///
+/// ```
+/// // Draws 5 empty lines (calculating the line height using the current style)
+/// let b = genpdf::elements::Break::new(5);
+/// ```
+///
+/// ```
+/// // Inserts 12.5 mm of vertical space
+/// let b = genpdf::elements::Break::with_height(12.5);
+/// ```
+///
+/// [`with_height`]: #method.with_height
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Break {
+    lines: f64,
+    height: Mm,
+}
+
+impl Break {
+    /// Creates a new break with the given number of lines.
+    pub fn new(lines: impl Into<f64>) -> Break {
+        Break {
+            lines: lines.into(),
+            height: Mm(0.0),
+        }
+    }
+
+    /// Creates a new break with the given height.
+    pub fn with_height(height: impl Into<Mm>) -> Break {
+        Break {
+            lines: 0.0,
+            height: height.into(),
+        }
+    }
+}
+
+impl Element for Break {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        let lines = self.lines.max(0.0);
+        let height = self.height.max(Mm(0.0));
+        if lines == 0.0 && height == Mm(0.0) {
+            return Ok(result);
+        }
+        let line_height = style.line_height(&context.font_cache);
+        let break_height = line_height * lines + height;
+        if break_height < area.size().height {
+            result.size.height = break_height;
+            self.lines = 0.0;
+            self.height = Mm(0.0);
+        } else {
+            result.size.height = area.size().height;
+            if height >= result.size.height {
+                self.height = height - result.size.height;
+            } else {
+                self.height = Mm(0.0);
+                self.lines = lines - (result.size.height - height).0 / line_height.0;
+            }
+        }
+        Ok(result)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+}
+
+/// Vertical space that can stretch or shrink.
+///
+/// Glue has a natural height and optional stretch and shrink components.  Outside of a
+/// [`LinearLayout`][], it just adds its natural height as a spacer.  Within a layout, the space
+/// that would be left empty at the bottom of a page is distributed among the glue elements on the
+/// page in proportion to their stretch.  If the elements of a page only fit if the glue is shrunk,
+/// the glue is shrunk in proportion to its shrink, but not below its natural height minus its
+/// shrink.  This can be used to vertically justify pages or to align the last element of a
+/// layout with the bottom of the page.
+///
+/// Glue created with [`fill`][] consumes all space that is left empty on the page, so that the
+/// following elements are pushed to the bottom of the page.  If there is fill glue on a page, the
+/// other glue on the page keeps its natural height, and the empty space is distributed evenly
+/// among the fill glue elements.  Outside of a layout, fill glue consumes the remaining height of
+/// the available area.
+///
+/// Pages that end with a split element, for example a paragraph that is continued on the next
+/// page, are already filled, so the glue on these pages keeps its natural height.  The glue is
+/// also not adjusted if one of the elements on the page does not support measuring (see
+/// [`Element::measure`][]).
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let mut layout = elements::LinearLayout::vertical();
+/// layout.push(elements::Paragraph::new("Terms and conditions"));
+/// // Move the signature line to the bottom of the page
+/// layout.push(elements::Glue::new(10).with_stretch(1));
+/// layout.push(elements::Paragraph::new("Signature: ____________"));
+/// ```
+///
+/// With fill glue:
+/// ```
+/// use genpdf::elements;
+/// let mut layout = elements::LinearLayout::vertical();
+/// layout.push(elements::Paragraph::new("Terms and conditions"));
+/// layout.push(elements::Glue::fill());
+/// layout.push(elements::Paragraph::new("Signature: ____________"));
+/// ```
+///
+/// [`LinearLayout`]: struct.LinearLayout.html
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+/// [`fill`]: #method.fill
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Glue {
+    natural: Mm,
+    stretch: Mm,
+    shrink: Mm,
+    is_fill: bool,
+}
+
+impl Glue {
+    /// Creates new glue with the given natural height that does not stretch or shrink.
+    pub fn new(natural: impl Into<Mm>) -> Glue {
+        Glue {
+            natural: natural.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates new glue without a natural height that fills all remaining space of the page.
+    pub fn fill() -> Glue {
+        Glue {
+            is_fill: true,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the stretch of this glue.
+    pub fn set_stretch(&mut self, stretch: impl Into<Mm>) {
+        self.stretch = stretch.into();
+    }
+
+    /// Sets the stretch of this glue and returns the glue.
+    pub fn with_stretch(mut self, stretch: impl Into<Mm>) -> Self {
+        self.set_stretch(stretch);
+        self
+    }
+
+    /// Sets the shrink of this glue.
+    pub fn set_shrink(&mut self, shrink: impl Into<Mm>) {
+        self.shrink = shrink.into();
+    }
+
+    /// Sets the shrink of this glue and returns the glue.
+    pub fn with_shrink(mut self, shrink: impl Into<Mm>) -> Self {
+        self.set_shrink(shrink);
+        self
+    }
+
+    /// Returns the natural height of this glue.
+    pub fn natural(&self) -> Mm {
+        self.natural
+    }
+
+    /// Returns the stretch of this glue.
+    pub fn stretch(&self) -> Mm {
+        self.stretch
+    }
+
+    /// Returns the shrink of this glue.
+    pub fn shrink(&self) -> Mm {
+        self.shrink
+    }
+
+    /// Returns whether this glue fills all remaining space of the page, see [`fill`][].
+    ///
+    /// [`fill`]: #method.fill
+    pub fn is_fill(&self) -> bool {
+        self.is_fill
+    }
+
+    /// Returns the height of this glue for the given adjustment, see `LinearLayout::glue_ratio`.
+    fn height(&self, adjustment: GlueAdjustment) -> Mm {
+        let height = match adjustment {
+            GlueAdjustment::Ratio(ratio) if ratio > 0.0 => self.natural + self.stretch * ratio,
+            GlueAdjustment::Ratio(ratio) => self.natural + self.shrink * ratio,
+            GlueAdjustment::Fill(height) if self.is_fill => self.natural + height,
+            GlueAdjustment::Fill(_) => self.natural,
+        };
+        height.max(Mm(0.0))
+    }
+}
+
+/// The adjustment of the glue elements on a page, see `LinearLayout::glue_ratio`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GlueAdjustment {
+    /// The glue is stretched (positive) or shrunk (negative) with the given ratio.
+    Ratio(f64),
+    /// The fill glue is stretched by the given height, the other glue keeps its natural height.
+    Fill(Mm),
+}
+
+impl Element for Glue {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        Ok(self.measure(context, area, style).unwrap_or_default())
+    }
+
+    fn measure(
+        &self,
+        _context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Option<RenderResult> {
+        let height = if self.is_fill {
+            area.size().height
+        } else {
+            self.natural.min(area.size().height)
+        };
+        Some(RenderResult {
+            size: Size::new(0, height),
+            has_more: false,
+        })
+    }
+
+    fn glue(&self) -> Option<Glue> {
+        Some(*self)
+    }
+}
+
+/// A page break.
+///
+/// This element inserts a page break.
+///
+/// # Example
+///
+/// ```
+/// let pb = genpdf::elements::PageBreak::new();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PageBreak {
+    cont: bool,
+}
+
+impl PageBreak {
+    /// Creates a new page break.
+    pub fn new() -> PageBreak {
+        PageBreak::default()
+    }
+}
+
+impl Element for PageBreak {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        if self.cont {
+            Ok(RenderResult::default())
+        } else {
+            self.cont = true;
+            if !area.is_dry_run() {
+                context.request_page_break();
+            }
+            Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            })
+        }
+    }
+
+    fn measure(
+        &self,
+        _context: &Context,
+        _area: render::Area<'_>,
+        _style: Style,
+    ) -> Option<RenderResult> {
+        Some(RenderResult {
+            size: Size::new(0, 0),
+            has_more: !self.cont,
+        })
+    }
+}
+
+/// A hint for a preferred page break position.
+///
+/// If a [`LinearLayout`][] reaches a page break hint and the elements after the hint (up to the
+/// next hint or the end of the layout) do not fit on the current page, the layout may break the
+/// page at the hint instead of splitting the following elements, for example in the middle of a
+/// paragraph.  Whether the hint is used depends on its penalty (0–100) and on the space that
+/// would be left empty on the page:  The page is only broken at the hint if the empty space is at
+/// most (100 − penalty) percent of the height of the area available for the layout on this page.
+/// So a hint with the penalty 0 is always used if the following elements do not fit, while a
+/// hint with the penalty 100 is never used.
+///
+/// The hint is ignored if it is the first element on the page or if the following elements do
+/// not support measuring (see [`Element::measure`][]).
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let mut layout = elements::LinearLayout::vertical();
+/// layout.push(elements::Paragraph::new("First section"));
+/// // Prefer to break here if less than a third of the page is left
+/// layout.push(elements::PageBreakHint::new(67));
+/// layout.push(elements::Paragraph::new("Second section"));
+/// ```
+///
+/// [`LinearLayout`]: struct.LinearLayout.html
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PageBreakHint {
+    penalty: u8,
+}
+
+impl PageBreakHint {
+    /// Creates a new page break hint with the given penalty.
+    ///
+    /// Penalties greater than 100 are treated like 100.
+    pub fn new(penalty: u8) -> PageBreakHint {
+        PageBreakHint {
+            penalty: penalty.min(100),
+        }
+    }
+
+    /// Returns the penalty of this page break hint.
+    pub fn penalty(&self) -> u8 {
+        self.penalty
+    }
+}
+
+impl Element for PageBreakHint {
+    fn render(
+        &mut self,
+        _context: &Context,
+        _area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        Ok(RenderResult::default())
+    }
+
+    fn measure(
+        &self,
+        _context: &Context,
+        _area: render::Area<'_>,
+        _style: Style,
+    ) -> Option<RenderResult> {
+        Some(RenderResult::default())
+    }
+
+    fn page_break_penalty(&self) -> Option<u8> {
+        Some(self.penalty)
+    }
+}
+
+/// A placeholder for missing content.
+///
+/// This element draws a crossed-out box with the given size and an optional label.  It can be
+/// used to sketch a layout before the actual content exists.  It is also used to replace failing
+/// elements if the [`ElementErrorPolicy::SkipAndCollect`][] policy is set.
+///
+/// If the box is wider than the available area, it is shrunk to the width of the area.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let placeholder = elements::Placeholder::new((50, 30)).with_label("Logo");
+/// ```
+///
+/// [`ElementErrorPolicy::SkipAndCollect`]: ../enum.ElementErrorPolicy.html#variant.SkipAndCollect
+#[derive(Clone, Debug, Default)]
+pub struct Placeholder {
+    size: Size,
+    label: Option<String>,
+    line_style: LineStyle,
+}
+
+impl Placeholder {
+    /// Creates a new placeholder with the given size.
+    pub fn new(size: impl Into<Size>) -> Placeholder {
+        Placeholder {
+            size: size.into(),
+            label: None,
+            line_style: LineStyle::new(),
+        }
+    }
+
+    /// Sets the label of this placeholder.
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = Some(label.into());
+    }
+
+    /// Sets the label of this placeholder and returns the placeholder.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.set_label(label);
+        self
+    }
+
+    /// Sets the line style for the box of this placeholder.
+    pub fn set_line_style(&mut self, line_style: impl Into<LineStyle>) {
+        self.line_style = line_style.into();
+    }
+
+    /// Sets the line style for the box of this placeholder and returns the placeholder.
+    pub fn with_line_style(mut self, line_style: impl Into<LineStyle>) -> Self {
+        self.set_line_style(line_style);
+        self
+    }
+}
+
+impl Element for Placeholder {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        if self.size.height > area.size().height {
+            result.has_more = true;
+            return Ok(result);
+        }
+
+        let size = Size::new(self.size.width.min(area.size().width), self.size.height);
+        let top_left = Position::default();
+        let top_right = Position::new(size.width, 0);
+        let bottom_left = Position::new(0, size.height);
+        let bottom_right = Position::new(size.width, size.height);
+        area.draw_line(
+            vec![top_left, top_right, bottom_right, bottom_left, top_left],
+            self.line_style,
+        );
+        area.draw_line(vec![top_left, bottom_right], self.line_style);
+        area.draw_line(vec![top_right, bottom_left], self.line_style);
+
+        if let Some(label) = &self.label {
+            // Truncate the label so that it fits into the box
+            let label = truncate_str(&context.font_cache, style, label, size.width);
+            let metrics = style.metrics(&context.font_cache);
+            let width = style.str_width(&context.font_cache, &label);
+            let position = Position::new(
+                (size.width - width) / 2.0,
+                (size.height - metrics.line_height) / 2.0,
+            );
+            area.print_str(&context.font_cache, position, style, &label)?;
+        }
+
+        result.size = size;
+        Ok(result)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+}
+
+/// A grid of single glyphs or short strings with background colors.
+///
+/// This element can be used for dense grids like chess boards, heatmaps or seat maps that would
+/// be too heavyweight as a [`TableLayout`][].  The cells are filled row by row, and the text of
+/// every cell is centered within the cell and truncated if it is wider than the cell.  Per
+/// default, the cells are squares that fill the available width.  Use [`set_cell_size`][] to set
+/// a fixed size instead.
+///
+/// If the grid does not fit on the current page, it is split between two rows.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style::Color};
+/// let mut board = elements::GlyphGrid::new(8).with_cell_size((10, 10));
+/// for i in 0..64 {
+///     let color = if (i / 8 + i % 8) % 2 == 0 {
+///         Color::Greyscale(230)
+///     } else {
+///         Color::Greyscale(140)
+///     };
+///     board.push_cell(if i == 4 { "♔" } else { "" }, color);
+/// }
+/// ```
+///
+/// [`TableLayout`]: struct.TableLayout.html
+/// [`set_cell_size`]: #method.set_cell_size
+#[derive(Clone, Debug, Default)]
+pub struct GlyphGrid {
+    columns: usize,
+    cells: Vec<(StyledString, Option<Color>)>,
+    cell_size: Option<Size>,
+    render_idx: usize,
+}
+
+impl GlyphGrid {
+    /// Creates a new empty grid with the given number of columns.
+    pub fn new(columns: usize) -> GlyphGrid {
+        GlyphGrid {
+            columns,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the size of the cells of this grid.
+    pub fn set_cell_size(&mut self, cell_size: impl Into<Size>) {
+        self.cell_size = Some(cell_size.into());
+    }
+
+    /// Sets the size of the cells of this grid and returns the grid.
+    pub fn with_cell_size(mut self, cell_size: impl Into<Size>) -> Self {
+        self.set_cell_size(cell_size);
+        self
+    }
+
+    /// Adds a cell with the given text and optional background color to this grid.
+    ///
+    /// The style of the text is combined with the style of the grid.
+    pub fn push_cell(
+        &mut self,
+        text: impl Into<StyledString>,
+        background: impl Into<Option<Color>>,
+    ) {
+        self.cells.push((text.into(), background.into()));
+    }
+
+    /// Adds a cell with the given text and optional background color to this grid and returns the
+    /// grid.
+    pub fn cell(
+        mut self,
+        text: impl Into<StyledString>,
+        background: impl Into<Option<Color>>,
+    ) -> Self {
+        self.push_cell(text, background);
+        self
+    }
+
+    fn resolve_cell_size(&self, width: Mm) -> Size {
+        self.cell_size.unwrap_or_else(|| {
+            let width = width / self.columns as f64;
+            Size::new(width, width)
+        })
+    }
+}
+
+impl Element for GlyphGrid {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        if self.columns == 0 {
+            return Ok(result);
+        }
+
+        let cell_size = self.resolve_cell_size(area.size().width);
+        let num_rows = self.cells.len().div_ceil(self.columns);
+        while self.render_idx < num_rows {
+            if area.size().height < cell_size.height {
+                result.has_more = true;
+                break;
+            }
+            let cells = self.cells.iter().skip(self.render_idx * self.columns);
+            for (column, (text, background)) in cells.take(self.columns).enumerate() {
+                let position = Position::new(cell_size.width * column as f64, 0);
+                if let Some(background) = background {
+                    area.fill_rect(position, cell_size, *background);
+                }
+                if !text.s.is_empty() {
+                    let style = style.and(text.style);
+                    let s = truncate_str(&context.font_cache, style, &text.s, cell_size.width);
+                    if let borrow::Cow::Owned(_) = s {
+                        context
+                            .report_warning(&area, truncation_warning(&text.s, cell_size.width))?;
+                    }
+                    check_glyphs(context, &area, style, &s)?;
+                    let metrics = style.metrics(&context.font_cache);
+                    let width = style.str_width(&context.font_cache, &s);
+                    let offset = Position::new(
+                        (cell_size.width - width) / 2.0,
+                        (cell_size.height - metrics.line_height) / 2.0,
+                    );
+                    area.print_str(&context.font_cache, position + offset, style, &s)?;
+                }
+            }
+            area.add_offset(Position::new(0, cell_size.height));
+            result.size.height += cell_size.height;
+            self.render_idx += 1;
+        }
+        result.size.width = cell_size.width * self.columns.min(self.cells.len()) as f64;
+        Ok(result)
+    }
+
+    fn natural_width(&self, _context: &Context, _style: Style) -> Option<Mm> {
+        let cell_size = self.cell_size?;
+        Some(cell_size.width * self.columns.min(self.cells.len()) as f64)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+}
+
+/// Adds a padding to the wrapped element.
+///
+/// # Examples
+///
+/// Direct usage:
+/// ```
+/// use genpdf::elements;
+/// let p = elements::PaddedElement::new(
+///     elements::Paragraph::new("text"),
+///     genpdf::Margins::trbl(5, 2, 5, 10),
+/// );
+/// ```
+///
+/// Using [`Element::padded`][]:
+/// ```
+/// use genpdf::{elements, Element as _};
+/// let p = elements::Paragraph::new("text")
+///     .padded(genpdf::Margins::trbl(5, 2, 5, 10));
+/// ```
+///
+/// [`Element::padded`]: ../trait.Element.html#method.padded
+#[derive(Clone, Debug, Default)]
+pub struct PaddedElement<E: Element> {
+    element: E,
+    padding: Margins,
+}
+
+impl<E: Element> PaddedElement<E> {
+    /// Creates a new padded element that wraps the given element with the given padding.
+    pub fn new(element: E, padding: impl Into<Margins>) -> PaddedElement<E> {
+        PaddedElement {
+            element,
+            padding: padding.into(),
+        }
+    }
+}
+
+impl<E: Element> Element for PaddedElement<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        area.add_margins(Margins {
+            bottom: Mm(0.0),
+            ..self.padding
+        });
+        let mut result = self.element.render(context, area, style)?;
+        result.size.width += self.padding.left + self.padding.right;
+        result.size.height += self.padding.top + self.padding.bottom;
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element
+            .natural_width(context, style)
+            .map(|width| width + self.padding.left + self.padding.right)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        area.add_margins(Margins {
+            bottom: Mm(0.0),
+            ..self.padding
+        });
+        let mut result = self.element.measure(context, area, style)?;
+        result.size.width += self.padding.left + self.padding.right;
+        result.size.height += self.padding.top + self.padding.bottom;
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        let padding = self.padding;
+        ElementTree::new("PaddedElement")
+            .with_property(
+                "padding",
+                format!(
+                    "{} {} {} {}",
+                    padding.top.0, padding.right.0, padding.bottom.0, padding.left.0
+                ),
+            )
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// Adds a default style to the wrapped element and its children.
+///
+/// # Examples
+///
+/// Direct usage:
+/// ```
+/// use genpdf::{elements, style};
+/// let p = elements::StyledElement::new(
+///     elements::Paragraph::new("text"),
+///     style::Effect::Bold,
+/// );
+/// ```
+///
+/// Using [`Element::styled`][]:
+/// ```
+/// use genpdf::{elements, style, Element as _};
+/// let p = elements::Paragraph::new("text")
+///     .styled(style::Effect::Bold);
+/// ```
+///
+/// [`Element::styled`]: ../trait.Element.html#method.styled
+#[derive(Clone, Debug, Default)]
+pub struct StyledElement<E: Element> {
+    element: E,
+    style: Style,
+}
+
+impl<E: Element> StyledElement<E> {
+    /// Creates a new styled element that wraps the given element with the given style.
+    pub fn new(element: E, style: impl Into<Style>) -> StyledElement<E> {
+        StyledElement {
+            element,
+            style: style.into(),
+        }
+    }
+}
+
+impl<E: Element> Element for StyledElement<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        mut style: Style,
+    ) -> Result<RenderResult, Error> {
+        style.merge(self.style);
+        self.element.render(context, area, style)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element.natural_width(context, style.and(self.style))
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        self.element.measure(context, area, style.and(self.style))
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("StyledElement").with_child(self.element.dump_tree())
+    }
+}
+
+/// Shifts the wrapped element by a horizontal and vertical offset.
+///
+/// The offset only changes the position where the element is drawn.  The size of the element in
+/// the layout and the page breaks are the same as for the unshifted element, so the offset can be
+/// used to fine-tune the position of a logo or to align an icon with the baseline of the
+/// surrounding text.  Positive offsets move the element to the right and down, negative offsets
+/// move it to the left and up.
+///
+/// # Examples
+///
+/// Direct usage:
+/// ```
+/// use genpdf::elements;
+/// let p = elements::Offset::new(elements::Paragraph::new("text"), (2, -1));
+/// ```
+///
+/// Using [`Element::offset`][]:
+/// ```
+/// use genpdf::{elements, Element as _};
+/// let p = elements::Paragraph::new("text").offset((2, -1));
+/// ```
+///
+/// [`Element::offset`]: ../trait.Element.html#method.offset
+#[derive(Clone, Debug, Default)]
+pub struct Offset<E: Element> {
+    element: E,
+    offset: Position,
+}
+
+impl<E: Element> Offset<E> {
+    /// Creates a new offset element that shifts the given element by the given offset.
+    pub fn new(element: E, offset: impl Into<Position>) -> Offset<E> {
+        Offset {
+            element,
+            offset: offset.into(),
+        }
+    }
+
+    fn shift(&self, area: &mut render::Area<'_>) {
+        let size = area.size();
+        area.add_offset(self.offset);
+        area.set_size(size);
+    }
+}
+
+impl<E: Element> Element for Offset<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        self.shift(&mut area);
+        self.element.render(context, area, style)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element.natural_width(context, style)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        self.shift(&mut area);
+        self.element.measure(context, area, style)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("Offset")
+            .with_property("offset", format!("{} {}", self.offset.x.0, self.offset.y.0))
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// The height of the area that is used to measure the full height of an element.
+const MEASURE_HEIGHT: Mm = Mm(100_000.0);
+
+/// Scales the wrapped element down so that it fits into the available area.
+///
+/// The element is laid out with its natural width (see [`Element::natural_width`][]) and then
+/// scaled down uniformly until it fits into the available width and, if set, into the maximum
+/// width and height of the wrapper.  The aspect ratio of the element is preserved, and text, line
+/// widths and images are scaled together with the layout.  Elements that fit without scaling are
+/// rendered as usual, and elements are never scaled up.
+///
+/// The maximum height is only considered if the element supports measuring with
+/// [`Element::measure`][].  The scale factor is determined when the element is rendered for the
+/// first time.  If the scaled element does not fit on the current page, the remaining content is
+/// rendered on the next page with the same scale factor.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let mut table = elements::TableLayout::new(vec![1, 1, 1, 1]);
+/// table.row().element(elements::Text::new("A wide table")).push();
+/// let table = elements::ScaleToFit::new(table).with_max_height(100);
+/// ```
+///
+/// [`Element::natural_width`]: ../trait.Element.html#method.natural_width
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+#[derive(Clone, Debug, Default)]
+pub struct ScaleToFit<E: Element> {
+    element: E,
+    max_width: Option<Mm>,
+    max_height: Option<Mm>,
+    scale: Option<f64>,
+}
+
+impl<E: Element> ScaleToFit<E> {
+    /// Creates a new wrapper that scales the given element to the available area.
+    pub fn new(element: E) -> ScaleToFit<E> {
+        ScaleToFit {
+            element,
+            max_width: None,
+            max_height: None,
+            scale: None,
+        }
+    }
+
+    /// Sets the maximum width of the scaled element.
+    pub fn set_max_width(&mut self, max_width: impl Into<Mm>) {
+        self.max_width = Some(max_width.into());
+    }
+
+    /// Sets the maximum width of the scaled element and returns the wrapper.
+    pub fn with_max_width(mut self, max_width: impl Into<Mm>) -> Self {
+        self.set_max_width(max_width);
+        self
+    }
+
+    /// Sets the maximum height of the scaled element.
+    pub fn set_max_height(&mut self, max_height: impl Into<Mm>) {
+        self.max_height = Some(max_height.into());
+    }
+
+    /// Sets the maximum height of the scaled element and returns the wrapper.
+    pub fn with_max_height(mut self, max_height: impl Into<Mm>) -> Self {
+        self.set_max_height(max_height);
+        self
+    }
+
+    /// Returns the scale factor of the element or calculates it if the element has not been
+    /// rendered yet.
+    fn scale(&self, context: &Context, area: &render::Area<'_>, style: Style) -> f64 {
+        if let Some(scale) = self.scale {
+            return scale;
+        }
+
+        let width = area.size().width;
+        let natural_width = self.element.natural_width(context, style);
+        let mut scale = match natural_width {
+            Some(natural_width) if natural_width > width && natural_width > Mm(0.0) => {
+                width.0 / natural_width.0
+            }
+            _ => 1.0,
+        };
+
+        if let Some(max_height) = self.max_height {
+            let mut measure_area = area.dry_run().scaled(scale);
+            measure_area.set_height(MEASURE_HEIGHT);
+            if let Some(result) = self.element.measure(context, measure_area, style) {
+                let height = result.size.height * scale;
+                if height > max_height && height > Mm(0.0) {
+                    scale *= max_height.0 / height.0;
+                }
+            }
+        }
+        scale
+    }
+
+    /// Limits the given area to the maximum size of the scaled element.
+    fn limit(&self, area: &mut render::Area<'_>) {
+        if let Some(max_width) = self.max_width {
+            area.set_width(area.size().width.min(max_width));
+        }
+        if let Some(max_height) = self.max_height {
+            area.set_height(area.size().height.min(max_height));
+        }
+    }
+}
+
+impl<E: Element> Element for ScaleToFit<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        self.limit(&mut area);
+        let scale = self.scale(context, &area, style);
+        self.scale = Some(scale);
+        if scale < 1.0 {
+            area = area.scaled(scale);
+        }
+        let mut result = self.element.render(context, area, style)?;
+        result.size = Size::new(result.size.width * scale, result.size.height * scale);
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        let width = self.element.natural_width(context, style)?;
+        Some(
+            self.max_width
+                .map_or(width, |max_width| width.min(max_width)),
+        )
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        self.limit(&mut area);
+        let scale = self.scale(context, &area, style);
+        if scale < 1.0 {
+            area = area.scaled(scale);
+        }
+        let mut result = self.element.measure(context, area, style)?;
+        result.size = Size::new(result.size.width * scale, result.size.height * scale);
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        let mut tree = ElementTree::new("ScaleToFit");
+        if let Some(max_width) = self.max_width {
+            tree.push_property("max-width", max_width.0);
+        }
+        if let Some(max_height) = self.max_height {
+            tree.push_property("max-height", max_height.0);
+        }
+        tree.with_child(self.element.dump_tree())
+    }
+}
+
+type SubDocumentCallback = Box<dyn FnOnce(&[fonts::FontFamily<fonts::Font>]) -> Box<dyn Element>>;
+
+/// A component that is authored independently from the document it is rendered in.
+///
+/// A sub-document encapsulates its own default style, fonts and page decorator, so that a library
+/// can produce a standardized component, for example an appendix, that looks the same in every
+/// host document:
+/// - The content is rendered with the style of the sub-document (see [`set_style`][]) instead of
+///   the style of the host document.
+/// - The font families added with [`add_font_family`][] are mapped into the font cache of the
+///   host document when the sub-document is added with [`Document::add_sub_document`][].  The
+///   content is then created by the callback passed to [`new`][], which receives the mapped font
+///   families.  The first font family is used as the default font family of the sub-document.
+/// - The page decorator set with [`set_page_decorator`][] is applied to the available area on
+///   every page that the sub-document is rendered on.
+///
+/// A sub-document without font families can be rendered directly.  Otherwise, rendering fails
+/// unless it has been added to the host document first.
+///
+/// # Example
+///
+/// ```no_run
+/// use genpdf::{elements, fonts};
+/// let font_family = fonts::from_files("./fonts", "LiberationSans", None)
+///     .expect("Failed to load font family");
+/// let appendix_font_family = fonts::from_files("./fonts", "LiberationSerif", None)
+///     .expect("Failed to load font family");
+///
+/// let mut appendix = elements::SubDocument::new(|_fonts| {
+///     elements::LinearLayout::vertical()
+///         .element(elements::Paragraph::new("Appendix"))
+///         .element(elements::Paragraph::new("Terms and conditions"))
+/// });
+/// appendix.add_font_family(appendix_font_family);
+/// appendix.set_style(genpdf::style::Style::new().with_font_size(9));
+///
+/// let mut doc = genpdf::Document::new(font_family);
+/// doc.push(elements::Paragraph::new("Invoice"));
+/// let appendix = doc.add_sub_document(appendix);
+/// doc.push(appendix);
+/// ```
+///
+/// [`new`]: #method.new
+/// [`set_style`]: #method.set_style
+/// [`add_font_family`]: #method.add_font_family
+/// [`set_page_decorator`]: #method.set_page_decorator
+/// [`Document::add_sub_document`]: ../struct.Document.html#method.add_sub_document
+pub struct SubDocument {
+    content_cb: Option<SubDocumentCallback>,
+    element: Option<Box<dyn Element>>,
+    font_families: Vec<fonts::FontFamily<fonts::FontData>>,
+    style: Style,
+    decorator: Option<Box<dyn PageDecorator>>,
+}
+
+impl SubDocument {
+    /// Creates a new sub-document with content that is created by the given callback.
+    ///
+    /// The callback is called once the font families of the sub-document have been mapped into
+    /// the font cache of the host document.  Its argument are the mapped font families in the
+    /// order they have been added with [`add_font_family`][].
+    ///
+    /// [`add_font_family`]: #method.add_font_family
+    pub fn new<F, E>(cb: F) -> SubDocument
+    where
+        F: FnOnce(&[fonts::FontFamily<fonts::Font>]) -> E + 'static,
+        E: Element + 'static,
+    {
+        SubDocument {
+            // We manually box the return type of the callback so that it is easier to write
+            // closures.
+            content_cb: Some(Box::new(move |font_families| Box::new(cb(font_families)))),
+            element: None,
+            font_families: Vec::new(),
+            style: Style::new(),
+            decorator: None,
+        }
+    }
+
+    /// Adds the given font family to this sub-document.
+    ///
+    /// The first font family is used as the default font family of the sub-document.
+    pub fn add_font_family(&mut self, font_family: fonts::FontFamily<fonts::FontData>) {
+        self.font_families.push(font_family);
+    }
+
+    /// Adds the given font family to this sub-document and returns the sub-document.
+    pub fn with_font_family(mut self, font_family: fonts::FontFamily<fonts::FontData>) -> Self {
+        self.add_font_family(font_family);
+        self
+    }
+
+    /// Sets the default style of this sub-document.
+    pub fn set_style(&mut self, style: impl Into<Style>) {
+        self.style = style.into();
+    }
+
+    /// Sets the default style of this sub-document and returns the sub-document.
+    pub fn with_style(mut self, style: impl Into<Style>) -> Self {
+        self.set_style(style);
+        self
+    }
+
+    /// Sets the page decorator of this sub-document.
+    pub fn set_page_decorator<D: PageDecorator + 'static>(&mut self, decorator: D) {
+        self.decorator = Some(Box::new(decorator));
+    }
+
+    /// Sets the page decorator of this sub-document and returns the sub-document.
+    pub fn with_page_decorator<D: PageDecorator + 'static>(mut self, decorator: D) -> Self {
+        self.set_page_decorator(decorator);
+        self
+    }
+
+    /// Maps the font families of this sub-document into the given font cache and creates the
+    /// content.
+    pub(crate) fn map_fonts(&mut self, font_cache: &mut fonts::FontCache) {
+        let font_families: Vec<_> = self
+            .font_families
+            .drain(..)
+            .map(|font_family| font_cache.add_font_family(font_family))
+            .collect();
+        if let Some(font_family) = font_families.first() {
+            self.style = Style::new().with_font_family(*font_family).and(self.style);
+        }
+        if let Some(cb) = self.content_cb.take() {
+            self.element = Some(cb(&font_families));
+        }
+    }
+
+    fn element(&mut self) -> Result<&mut Box<dyn Element>, Error> {
+        if self.element.is_none() && self.font_families.is_empty() {
+            if let Some(cb) = self.content_cb.take() {
+                self.element = Some(cb(&[]));
+            }
+        }
+        self.element.as_mut().ok_or_else(|| {
+            Error::new(
+                "The fonts of the sub-document have not been added to the document",
+                ErrorKind::InvalidData,
+            )
+        })
+    }
+}
+
+impl Element for SubDocument {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        let style = self.style;
+        let mut inner_area = area.clone();
+        if let Some(decorator) = &mut self.decorator {
+            inner_area = decorator.decorate_page(context, inner_area, style)?;
+        }
+        let mut result = self.element()?.render(context, inner_area.clone(), style)?;
+        if result.size == Size::new(0, 0) && result.has_more {
+            // The element did not fit and is moved to the next page, so we must not add the
+            // margins of the decorator.  Otherwise the document would never run out of space.
+            return Ok(result);
+        }
+        let offset = inner_area.origin() - area.origin();
+        result.size.width += area.size().width - inner_area.size().width;
+        result.size.height += offset.y;
+        if !result.has_more {
+            result.size.height += area.size().height - inner_area.size().height - offset.y;
+        }
+        Ok(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        let mut tree = ElementTree::new("SubDocument");
+        if !self.font_families.is_empty() {
+            tree.push_property("fonts", self.font_families.len());
+        }
+        if let Some(element) = &self.element {
+            tree.push_child(element.dump_tree());
+        }
+        tree
+    }
+}
+
+/// Renders the wrapped element in a box with a fixed height.
+///
+/// The box always has the given height, regardless of the size of the wrapped element.  If the
+/// box does not fit into the available area, it is moved to the next page.  Per default, the
+/// element is aligned to the top of the box.  For other alignments, the element is measured with
+/// [`Element::measure`][] before it is rendered.  If the element does not support measuring, it
+/// is aligned to the top.  If the element does not fit into the box, the remaining content is
+/// rendered into a new box on the next page.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let p = elements::FixedHeight::new(elements::Paragraph::new("text"), 20)
+///     .with_alignment(genpdf::VerticalAlignment::Middle);
+/// ```
+///
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+#[derive(Clone, Debug, Default)]
+pub struct FixedHeight<E: Element> {
+    element: E,
+    height: Mm,
+    alignment: VerticalAlignment,
+}
+
+impl<E: Element> FixedHeight<E> {
+    /// Creates a new fixed-height box with the given height that wraps the given element.
+    pub fn new(element: E, height: impl Into<Mm>) -> FixedHeight<E> {
+        FixedHeight {
+            element,
+            height: height.into(),
+            alignment: VerticalAlignment::default(),
+        }
+    }
+
+    /// Sets the vertical alignment of the wrapped element within the box.
+    pub fn set_alignment(&mut self, alignment: VerticalAlignment) {
+        self.alignment = alignment;
+    }
+
+    /// Sets the vertical alignment of the wrapped element within the box and returns the box.
+    pub fn with_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.set_alignment(alignment);
+        self
+    }
+}
+
+impl<E: Element> Element for FixedHeight<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        if area.size().height < self.height {
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        area.set_height(self.height);
+
+        if self.alignment != VerticalAlignment::Top {
+            if let Some(result) = self.element.measure(context, area.clone(), style) {
+                let offset = alignment_offset(self.alignment, result.size.height, self.height);
+                area.add_offset(Position::new(0, offset));
+            }
+        }
+
+        let mut result = self.element.render(context, area, style)?;
+        result.size.height = self.height;
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element.natural_width(context, style)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        if area.size().height < self.height {
+            return Some(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        area.set_height(self.height);
+        let mut result = self.element.measure(context, area, style)?;
+        result.size.height = self.height;
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("FixedHeight")
+            .with_property("height", self.height.0)
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// Renders the wrapped element in a box with a fixed width and height.
+///
+/// This works like [`FixedHeight`][], but the box also has a fixed width:  The wrapped element is
+/// rendered into an area with the given width, and the box always has the given size, regardless
+/// of the size of the wrapped element.  This can be used to align content across independent
+/// tables or layouts.  If the box does not fit into the available area, it is moved to the next
+/// page.  If the element does not fit into the box, the remaining content is rendered into a new
+/// box on the next page.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let p = elements::FixedSize::new(elements::Paragraph::new("text"), (40, 20))
+///     .with_alignment(genpdf::VerticalAlignment::Bottom);
+/// ```
+///
+/// [`FixedHeight`]: struct.FixedHeight.html
+#[derive(Clone, Debug, Default)]
+pub struct FixedSize<E: Element> {
+    element: E,
+    size: Size,
+    alignment: VerticalAlignment,
+}
+
+impl<E: Element> FixedSize<E> {
+    /// Creates a new fixed-size box with the given size that wraps the given element.
+    pub fn new(element: E, size: impl Into<Size>) -> FixedSize<E> {
+        FixedSize {
+            element,
+            size: size.into(),
+            alignment: VerticalAlignment::default(),
+        }
+    }
+
+    /// Sets the vertical alignment of the wrapped element within the box.
+    pub fn set_alignment(&mut self, alignment: VerticalAlignment) {
+        self.alignment = alignment;
+    }
+
+    /// Sets the vertical alignment of the wrapped element within the box and returns the box.
+    pub fn with_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.set_alignment(alignment);
+        self
+    }
+}
+
+impl<E: Element> Element for FixedSize<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        if area.size().height < self.size.height {
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        area.set_size(self.size);
+
+        if self.alignment != VerticalAlignment::Top {
+            if let Some(result) = self.element.measure(context, area.clone(), style) {
+                let offset = alignment_offset(self.alignment, result.size.height, self.size.height);
+                area.add_offset(Position::new(0, offset));
+            }
+        }
+
+        let mut result = self.element.render(context, area, style)?;
+        result.size = self.size;
+        Ok(result)
+    }
+
+    fn natural_width(&self, _context: &Context, _style: Style) -> Option<Mm> {
+        Some(self.size.width)
+    }
+
+    fn measure(
+        &self,
+        _context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Option<RenderResult> {
+        if area.size().height < self.size.height {
+            Some(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            })
+        } else {
+            Some(RenderResult {
+                size: self.size,
+                has_more: false,
+            })
+        }
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("FixedSize")
+            .with_property("width", self.size.width.0)
+            .with_property("height", self.size.height.0)
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// Renders the wrapped element with a minimum height.
+///
+/// If the wrapped element is smaller than the minimum height, the result is padded at the bottom
+/// so that the following element starts below the minimum height.  If the minimum height does not
+/// fit into the available area, the element is moved to the next page.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let p = elements::MinHeight::new(elements::Paragraph::new("text"), 20);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MinHeight<E: Element> {
+    element: E,
+    min_height: Mm,
+}
+
+impl<E: Element> MinHeight<E> {
+    /// Creates a new wrapper with the given minimum height for the given element.
+    pub fn new(element: E, min_height: impl Into<Mm>) -> MinHeight<E> {
+        MinHeight {
+            element,
+            min_height: min_height.into(),
+        }
+    }
+}
+
+impl<E: Element> Element for MinHeight<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        if area.size().height < self.min_height {
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        let mut result = self.element.render(context, area, style)?;
+        result.size.height = result.size.height.max(self.min_height);
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element.natural_width(context, style)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        if area.size().height < self.min_height {
+            return Some(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        let mut result = self.element.measure(context, area, style)?;
+        result.size.height = result.size.height.max(self.min_height);
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("MinHeight")
+            .with_property("min-height", self.min_height.0)
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// Renders the wrapped element with a maximum height.
+///
+/// The wrapped element is rendered into an area that is at most as high as the maximum height.
+/// If the element does not fit into this area, the remaining content is rendered on the next
+/// page, again limited to the maximum height.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let p = elements::MaxHeight::new(elements::Paragraph::new("text"), 50);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MaxHeight<E: Element> {
+    element: E,
+    max_height: Mm,
+}
+
+impl<E: Element> MaxHeight<E> {
+    /// Creates a new wrapper with the given maximum height for the given element.
+    pub fn new(element: E, max_height: impl Into<Mm>) -> MaxHeight<E> {
+        MaxHeight {
+            element,
+            max_height: max_height.into(),
+        }
+    }
+
+    fn limit(&self, area: &mut render::Area<'_>) {
+        area.set_height(area.size().height.min(self.max_height));
+    }
+}
+
+impl<E: Element> Element for MaxHeight<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        self.limit(&mut area);
+        self.element.render(context, area, style)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element.natural_width(context, style)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        self.limit(&mut area);
+        self.element.measure(context, area, style)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("MaxHeight")
+            .with_property("max-height", self.max_height.0)
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// Returns the offset of content with the given height within a box with the given height.
+fn alignment_offset(alignment: VerticalAlignment, content_height: Mm, height: Mm) -> Mm {
+    let free_height = (height - content_height).max(Mm(0.0));
+    match alignment {
+        VerticalAlignment::Top => Mm(0.0),
+        VerticalAlignment::Middle => free_height / 2.0,
+        VerticalAlignment::Bottom => free_height,
+    }
+}
+
+/// Keeps the wrapped element on the same page as the next element.
+///
+/// This wrapper can be used to avoid headings at the bottom of a page that are separated from the
+/// following text.  Before the wrapped element is rendered, it is measured together with the
+/// next element using [`Element::measure`][].  If the next element does not fit completely, the
+/// wrapped element is only rendered if at least the minimum height of the next element fits too,
+/// see [`set_min_height`][].  Otherwise, both elements are moved to the next page.  If the
+/// elements do not fit on the next page either, or if the wrapped element does not support
+/// measuring, they are rendered without further checks.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style, Element as _};
+/// let heading = elements::Paragraph::new("Introduction").styled(style::Style::new().bold());
+/// let body = elements::Paragraph::new("Lorem ipsum dolor sit amet.");
+/// let section = elements::KeepWithNext::new(heading, body).with_min_height(20);
+/// ```
+///
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+/// [`set_min_height`]: #method.set_min_height
+#[derive(Clone, Debug)]
+pub struct KeepWithNext<E: Element, F: Element> {
+    element: E,
+    next: F,
+    min_height: Option<Mm>,
+    element_rendered: bool,
+    deferred: bool,
+}
+
+impl<E: Element, F: Element> KeepWithNext<E, F> {
+    /// Creates a new wrapper that keeps the given element on the same page as the next element.
+    pub fn new(element: E, next: F) -> KeepWithNext<E, F> {
+        KeepWithNext {
+            element,
+            next,
+            min_height: None,
+            element_rendered: false,
+            deferred: false,
+        }
+    }
+
+    /// Sets the minimum height of the next element that has to fit on the same page as the
+    /// wrapped element.
+    ///
+    /// If the minimum height is not set, the wrapped element is rendered if any part of the next
+    /// element fits on the page, for example the first line of a paragraph.
+    pub fn set_min_height(&mut self, min_height: impl Into<Mm>) {
+        self.min_height = Some(min_height.into());
+    }
+
+    /// Sets the minimum height of the next element that has to fit on the same page as the
+    /// wrapped element and returns the wrapper.
+    pub fn with_min_height(mut self, min_height: impl Into<Mm>) -> Self {
+        self.set_min_height(min_height);
+        self
+    }
+
+    /// Checks whether the wrapped element and enough of the next element fit into the given area.
+    /// If the wrapped element cannot be measured, we assume that it fits.
+    fn fits(&self, context: &Context, mut area: render::Area<'_>, style: Style) -> bool {
+        let result = match self.element.measure(context, area.clone(), style) {
+            Some(result) => result,
+            None => return true,
+        };
+        if result.has_more {
+            return false;
+        }
+        area.add_offset(Position::new(0, result.size.height));
+        match (
+            self.min_height,
+            self.next.measure(context, area.clone(), style),
+        ) {
+            (_, Some(result)) if !result.has_more => true,
+            (Some(min_height), _) => area.size().height >= min_height,
+            (None, Some(result)) => result.size.height > Mm(0.0),
+            (None, None) => true,
+        }
+    }
+}
+
+impl<E: Element, F: Element> Element for KeepWithNext<E, F> {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        if !self.element_rendered {
+            if !self.deferred && !self.fits(context, area.clone(), style) {
+                // We request a page break so that the page does not count as empty if this is the
+                // first element on the page.
+                self.deferred = true;
+                if !area.is_dry_run() {
+                    context.request_page_break();
+                }
+                return Ok(RenderResult {
+                    size: Size::new(0, 0),
+                    has_more: true,
+                });
+            }
+            let element_result = self.element.render(context, area.clone(), style)?;
+            area.add_offset(Position::new(0, element_result.size.height));
+            result.size = result.size.stack_vertical(element_result.size);
+            if element_result.has_more {
+                result.has_more = true;
+                return Ok(result);
+            }
+            self.element_rendered = true;
+        }
+        let next_result = self.next.render(context, area, style)?;
+        result.size = result.size.stack_vertical(next_result.size);
+        result.has_more = next_result.has_more;
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        let next_width = self.next.natural_width(context, style)?;
+        if self.element_rendered {
+            Some(next_width)
+        } else {
+            let width = self.element.natural_width(context, style)?;
+            Some(width.max(next_width))
+        }
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        let mut result = RenderResult::default();
+        if !self.element_rendered {
+            let element_result = self.element.measure(context, area.clone(), style)?;
+            if element_result.has_more {
+                return Some(element_result);
+            }
+            area.add_offset(Position::new(0, element_result.size.height));
+            result.size = result.size.stack_vertical(element_result.size);
+        }
+        let next_result = self.next.measure(context, area, style)?;
+        result.size = result.size.stack_vertical(next_result.size);
+        result.has_more = next_result.has_more;
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("KeepWithNext")
+            .with_child(self.element.dump_tree())
+            .with_child(self.next.dump_tree())
+    }
+}
+
+/// Adds the wrapped element to the outline of the document.
+///
+/// Once the wrapped element has been rendered, an [`OutlineEntry`][] with the page number and the
+/// position of the element is added to the document outline.  The outline can be retrieved with
+/// [`Document::render_with_outline`][].
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let heading = elements::OutlineMarker::heading(
+///     elements::Paragraph::new("Introduction"),
+///     1,
+///     "Introduction",
+/// );
+/// ```
+///
+/// [`OutlineEntry`]: ../outline/struct.OutlineEntry.html
+/// [`Document::render_with_outline`]: ../struct.Document.html#method.render_with_outline
+pub struct OutlineMarker<E: Element> {
+    element: E,
+    kind: OutlineKind,
+    title: String,
+    is_registered: bool,
+}
 
-        // Draw the frame.
-        let top_left = Position::default();
-        let top_right = Position::new(frame_area.size().width, 0);
-        let bottom_left = Position::new(0, frame_area.size().height);
-        let bottom_right = Position::new(frame_area.size().width, frame_area.size().height);
+impl<E: Element> OutlineMarker<E> {
+    /// Creates a new outline marker with the given kind and title that wraps the given element.
+    pub fn new(element: E, kind: OutlineKind, title: impl Into<String>) -> OutlineMarker<E> {
+        OutlineMarker {
+            element,
+            kind,
+            title: title.into(),
+            is_registered: false,
+        }
+    }
+
+    /// Creates a new outline marker for a heading with the given level and title.
+    pub fn heading(element: E, level: u8, title: impl Into<String>) -> OutlineMarker<E> {
+        OutlineMarker::new(element, OutlineKind::Heading(level), title)
+    }
+
+    /// Creates a new outline marker for a figure with the given title.
+    pub fn figure(element: E, title: impl Into<String>) -> OutlineMarker<E> {
+        OutlineMarker::new(element, OutlineKind::Figure, title)
+    }
+
+    /// Creates a new outline marker for a table with the given title.
+    pub fn table(element: E, title: impl Into<String>) -> OutlineMarker<E> {
+        OutlineMarker::new(element, OutlineKind::Table, title)
+    }
+
+    /// Creates a new outline marker for an anchor with the given name.
+    pub fn anchor(element: E, name: impl Into<String>) -> OutlineMarker<E> {
+        OutlineMarker::new(element, OutlineKind::Anchor, name)
+    }
+}
+
+impl<E: Element> Element for OutlineMarker<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let page = area.page_number();
+        let origin = area.origin();
+        let is_dry_run = area.is_dry_run();
+        let result = self.element.render(context, area, style)?;
+        // If nothing has been rendered, the element will be moved to the next page.
+        if !self.is_registered
+            && !is_dry_run
+            && (result.size != Size::new(0, 0) || !result.has_more)
+        {
+            context.add_outline_entry(OutlineEntry::new(
+                self.kind,
+                self.title.clone(),
+                page,
+                origin,
+            ));
+            self.is_registered = true;
+        }
+        Ok(result)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.element.natural_width(context, style)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        self.element.measure(context, area, style)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("OutlineMarker")
+            .with_text("title", &self.title)
+            .with_child(self.element.dump_tree())
+    }
+}
+
+/// Adds a frame around the wrapped element.
+///
+/// Per default, all four borders of the frame are drawn with the same line style.  The borders
+/// can also be styled or disabled individually, see [`set_top_border`][] and the related
+/// methods, for example to draw an accent bar on the left side of a blockquote or a rule below a
+/// heading.
+///
+/// Optionally, the corners of the frame can be rounded, see [`set_corner_radius`][], and the
+/// frame can be filled with a background color or gradient, see [`set_fill`][].  If the element is split
+/// over multiple pages, only the corners at the top of the first part and at the bottom of the
+/// last part are rounded.  The corners are only rounded if all borders have the same line style.
+///
+/// # Examples
+///
+/// Direct usage:
+/// ```
+/// use genpdf::elements;
+/// let p = elements::FramedElement::new(
+///     elements::Paragraph::new("text"),
+/// );
+/// ```
+///
+/// Using [`Element::framed`][]:
+/// ```
+/// use genpdf::{elements, style, Element as _};
+/// let p = elements::Paragraph::new("text").framed(style::LineStyle::new());
+/// ```
+///
+/// A callout box with rounded corners and a background color:
+/// ```
+/// use genpdf::{elements, style, Element as _};
+/// let p = elements::Paragraph::new("Note: text")
+///     .padded(2)
+///     .framed(style::Color::Rgb(30, 100, 200))
+///     .with_corner_radius(2)
+///     .with_fill_color(style::Color::Rgb(225, 235, 250));
+/// ```
+///
+/// A blockquote with an accent bar on the left side:
+/// ```
+/// use genpdf::{elements, style, Element as _};
+/// let p = elements::Paragraph::new("Quote")
+///     .padded((0, 0, 0, 3))
+///     .framed(style::LineStyle::new())
+///     .with_borders(None)
+///     .with_left_border(style::LineStyle::new().with_thickness(1));
+/// ```
+///
+/// [`Element::framed`]: ../trait.Element.html#method.framed
+/// [`set_top_border`]: #method.set_top_border
+/// [`set_corner_radius`]: #method.set_corner_radius
+/// [`set_fill`]: #method.set_fill
+#[derive(Clone, Debug, Default)]
+pub struct FramedElement<E: Element> {
+    element: E,
+    is_first: bool,
+    top_border: Option<LineStyle>,
+    right_border: Option<LineStyle>,
+    bottom_border: Option<LineStyle>,
+    left_border: Option<LineStyle>,
+    corner_radius: Mm,
+    fill: Option<Fill>,
+}
+
+impl<E: Element> FramedElement<E> {
+    /// Creates a new framed element that wraps the given element.
+    pub fn new(element: E) -> FramedElement<E> {
+        FramedElement::with_line_style(element, LineStyle::new())
+    }
+
+    /// Creates a new framed element that wraps the given element,
+    /// and with the given line style.
+    pub fn with_line_style(element: E, line_style: impl Into<LineStyle>) -> FramedElement<E> {
+        let line_style = Some(line_style.into());
+        Self {
+            is_first: true,
+            element,
+            top_border: line_style,
+            right_border: line_style,
+            bottom_border: line_style,
+            left_border: line_style,
+            corner_radius: Mm(0.0),
+            fill: None,
+        }
+    }
+
+    /// Sets the line style of all borders, or disables all borders if the line style is `None`.
+    pub fn set_borders(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        let line_style = line_style.into();
+        self.top_border = line_style;
+        self.right_border = line_style;
+        self.bottom_border = line_style;
+        self.left_border = line_style;
+    }
+
+    /// Sets the line style of all borders, or disables all borders if the line style is `None`,
+    /// and returns the framed element.
+    pub fn with_borders(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_borders(line_style);
+        self
+    }
+
+    /// Sets the line style of the top border, or disables the border if the line style is `None`.
+    pub fn set_top_border(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.top_border = line_style.into();
+    }
+
+    /// Sets the line style of the top border, or disables the border if the line style is `None`,
+    /// and returns the framed element.
+    pub fn with_top_border(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_top_border(line_style);
+        self
+    }
+
+    /// Sets the line style of the right border, or disables the border if the line style is
+    /// `None`.
+    pub fn set_right_border(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.right_border = line_style.into();
+    }
+
+    /// Sets the line style of the right border, or disables the border if the line style is
+    /// `None`, and returns the framed element.
+    pub fn with_right_border(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_right_border(line_style);
+        self
+    }
+
+    /// Sets the line style of the bottom border, or disables the border if the line style is
+    /// `None`.
+    pub fn set_bottom_border(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.bottom_border = line_style.into();
+    }
+
+    /// Sets the line style of the bottom border, or disables the border if the line style is
+    /// `None`, and returns the framed element.
+    pub fn with_bottom_border(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_bottom_border(line_style);
+        self
+    }
+
+    /// Sets the line style of the left border, or disables the border if the line style is
+    /// `None`.
+    pub fn set_left_border(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.left_border = line_style.into();
+    }
+
+    /// Sets the line style of the left border, or disables the border if the line style is
+    /// `None`, and returns the framed element.
+    pub fn with_left_border(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_left_border(line_style);
+        self
+    }
+
+    /// Sets the radius of the corners of the frame.
+    pub fn set_corner_radius(&mut self, radius: impl Into<Mm>) {
+        self.corner_radius = radius.into();
+    }
+
+    /// Sets the radius of the corners of the frame and returns the framed element.
+    pub fn with_corner_radius(mut self, radius: impl Into<Mm>) -> Self {
+        self.set_corner_radius(radius);
+        self
+    }
+
+    /// Sets the background color of the frame.
+    pub fn set_fill_color(&mut self, color: Color) {
+        self.set_fill(color);
+    }
+
+    /// Sets the background color of the frame and returns the framed element.
+    pub fn with_fill_color(mut self, color: Color) -> Self {
+        self.set_fill_color(color);
+        self
+    }
+
+    /// Sets the background of the frame to the given color or gradient.
+    pub fn set_fill(&mut self, fill: impl Into<Fill>) {
+        self.fill = Some(fill.into());
+    }
+
+    /// Sets the background of the frame to the given color or gradient and returns the framed
+    /// element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::{elements, style, Element as _};
+    /// let header = elements::Paragraph::new("Spring Sale")
+    ///     .padded(5)
+    ///     .framed(style::LineStyle::new())
+    ///     .with_borders(None)
+    ///     .with_fill(style::Fill::linear_gradient(style::Color::NAVY, style::Color::AQUA, 0.0));
+    /// ```
+    pub fn with_fill(mut self, fill: impl Into<Fill>) -> Self {
+        self.set_fill(fill);
+        self
+    }
 
-        if self.is_first {
-            result.size.height += line_thickness;
-            frame_area.draw_line(vec![top_left, top_right], self.line_style);
+    /// Returns the line style of all borders if all borders are enabled and have the same style.
+    fn uniform_border(&self) -> Option<LineStyle> {
+        let borders = [self.right_border, self.bottom_border, self.left_border];
+        self.top_border
+            .filter(|top| borders.iter().all(|border| border.as_ref() == Some(top)))
+    }
+
+    /// Returns the width of the borders on this page, taking into account that the top border is
+    /// only drawn on the first page.
+    fn border_widths(&self) -> Margins {
+        let width = |border: Option<LineStyle>| border.map(|b| b.thickness()).unwrap_or_default();
+        Margins {
+            top: if self.is_first {
+                width(self.top_border)
+            } else {
+                Mm(0.0)
+            },
+            right: width(self.right_border),
+            bottom: width(self.bottom_border),
+            left: width(self.left_border),
         }
+    }
+}
+
+impl<E: Element> Element for FramedElement<E> {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        // For the element area calculations, we have to take into account the full line thickness.
+        // For the frame, we only need half because we specify the center of the line.
+        let widths = self.border_widths();
+        let mut element_area = area.clone();
+        element_area.add_margins(widths);
 
-        frame_area.draw_line(vec![top_left, bottom_left], self.line_style);
-        frame_area.draw_line(vec![top_right, bottom_right], self.line_style);
+        // Draw the element.  If the frame is filled, the element is drawn on the next layer so
+        // that it is not hidden by the background.
+        if self.fill.is_some() {
+            element_area = element_area.next_layer();
+        }
+        let mut result = self.element.render(context, element_area, style)?;
+        result.size.width = area.size().width;
 
+        let width = area.size().width;
+        let bottom = widths.top + result.size.height + widths.bottom / 2.0;
+        result.size.height += widths.top;
         if !result.has_more {
-            result.size.height += line_thickness;
-            frame_area.draw_line(vec![bottom_left, bottom_right], self.line_style);
+            result.size.height += widths.bottom;
+        }
+
+        // Calculate the corners of the frame, using the center of the lines.
+        let top_left = Position::new(widths.left / 2.0, widths.top / 2.0);
+        let top_right = Position::new(width - widths.right / 2.0, widths.top / 2.0);
+        let bottom_left = Position::new(widths.left / 2.0, bottom);
+        let bottom_right = Position::new(width - widths.right / 2.0, bottom);
+
+        let is_last = !result.has_more;
+        let points = match (self.is_first, is_last) {
+            (true, true) => vec![top_left, top_right, bottom_right, bottom_left, top_left],
+            (true, false) => vec![bottom_left, top_left, top_right, bottom_right],
+            (false, true) => vec![top_left, bottom_left, bottom_right, top_right],
+            (false, false) => vec![top_left, bottom_left, bottom_right, top_right],
+        };
+        let uniform_border = self.uniform_border();
+        let corner_radius = if uniform_border.is_some() {
+            self.corner_radius
+        } else {
+            Mm(0.0)
+        };
+        if let Some(fill) = &self.fill {
+            area.fill_rounded_shape(points.clone(), corner_radius, fill.clone());
+        }
+
+        // The frame is drawn as a single line where possible so that the corners are joined
+        // according to the line style.
+        match uniform_border {
+            Some(line_style) if self.is_first || is_last => {
+                area.draw_rounded_line(points, corner_radius, line_style);
+            }
+            _ => {
+                // Otherwise, every border is drawn separately across the full frame.
+                let height = result.size.height;
+                if let Some(line_style) = self.top_border.filter(|_| self.is_first) {
+                    let y = top_left.y;
+                    area.draw_line(
+                        vec![Position::new(0, y), Position::new(width, y)],
+                        line_style,
+                    );
+                }
+                if let Some(line_style) = self.right_border {
+                    let x = top_right.x;
+                    area.draw_line(
+                        vec![Position::new(x, 0), Position::new(x, height)],
+                        line_style,
+                    );
+                }
+                if let Some(line_style) = self.bottom_border.filter(|_| is_last) {
+                    let y = bottom_left.y;
+                    area.draw_line(
+                        vec![Position::new(0, y), Position::new(width, y)],
+                        line_style,
+                    );
+                }
+                if let Some(line_style) = self.left_border {
+                    let x = top_left.x;
+                    area.draw_line(
+                        vec![Position::new(x, 0), Position::new(x, height)],
+                        line_style,
+                    );
+                }
+            }
         }
 
         self.is_first = false;
 
         Ok(result)
     }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        let widths = self.border_widths();
+        self.element
+            .natural_width(context, style)
+            .map(|width| width + widths.left + widths.right)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        let widths = self.border_widths();
+        let width = area.size().width;
+        area.add_margins(widths);
+        let mut result = self.element.measure(context, area, style)?;
+        result.size.width = width;
+        result.size.height += widths.top;
+        if !result.has_more {
+            result.size.height += widths.bottom;
+        }
+        Some(result)
+    }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("FramedElement").with_child(self.element.dump_tree())
+    }
 }
 
 /// An unordered list of elements with bullet points.
@@ -892,6 +5239,11 @@ impl Element for UnorderedList {
     ) -> Result<RenderResult, Error> {
         self.layout.render(context, area, style)
     }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("UnorderedList")
+            .with_children(self.layout.elements.iter().map(|e| e.dump_tree()))
+    }
 }
 
 impl Default for UnorderedList {
@@ -1010,6 +5362,11 @@ impl Element for OrderedList {
     ) -> Result<RenderResult, Error> {
         self.layout.render(context, area, style)
     }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("OrderedList")
+            .with_children(self.layout.elements.iter().map(|e| e.dump_tree()))
+    }
 }
 
 impl Default for OrderedList {
@@ -1107,6 +5464,12 @@ impl<E: Element> Element for BulletPoint<E> {
         }
         Ok(result)
     }
+
+    fn dump_tree(&self) -> ElementTree {
+        ElementTree::new("BulletPoint")
+            .with_text("bullet", &self.bullet)
+            .with_child(self.element.dump_tree())
+    }
 }
 
 /// A decorator for table cells.
@@ -1126,6 +5489,12 @@ pub trait CellDecorator {
         let _ = (num_columns, num_rows);
     }
 
+    /// Resets the row state of this decorator.
+    ///
+    /// This function is called before the header rows of a table are rendered, so that the
+    /// repeated header rows on a new page are decorated like the first rows of the table.
+    fn reset_rows(&mut self) {}
+
     /// Prepares the cell with the given indizes and returns the area for rendering the cell.
     fn prepare_cell<'p>(
         &self,
@@ -1240,6 +5609,10 @@ impl CellDecorator for FrameCellDecorator {
         self.num_rows = num_rows;
     }
 
+    fn reset_rows(&mut self) {
+        self.last_row = None;
+    }
+
     fn prepare_cell<'p>(
         &self,
         column: usize,
@@ -1347,12 +5720,145 @@ impl CellDecorator for FrameCellDecorator {
                 self.line_style,
             );
         }
-
-        if column + 1 == self.num_columns {
-            self.last_row = Some(row);
+
+        if column + 1 == self.num_columns {
+            self.last_row = Some(row);
+        }
+
+        total_height
+    }
+}
+
+/// A cell decorator that fills the background of table cells.
+///
+/// This decorator fills the cells of a [`TableLayout`][] with a background color.  The color can
+/// be alternated between even and odd rows (zebra striping) and overridden for single columns.
+/// Note that the row index includes the header rows of the table.
+///
+/// The cell contents are rendered on the next layer of the page so that they are drawn on top of
+/// the background.  To combine the background with another decorator, for example a
+/// [`FrameCellDecorator`][], use [`set_decorator`][].
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style};
+/// let decorator = elements::BackgroundCellDecorator::new(style::Color::Greyscale(255))
+///     .with_alternate_color(style::Color::Greyscale(230))
+///     .with_column_color(0, style::Color::Rgb(220, 230, 255))
+///     .with_decorator(elements::FrameCellDecorator::new(true, true, false));
+/// let mut table = elements::TableLayout::new(vec![1, 2]);
+/// table.set_cell_decorator(decorator);
+/// ```
+///
+/// [`TableLayout`]: struct.TableLayout.html
+/// [`FrameCellDecorator`]: struct.FrameCellDecorator.html
+/// [`set_decorator`]: #method.set_decorator
+#[derive(Default)]
+pub struct BackgroundCellDecorator {
+    color: Option<Color>,
+    alternate_color: Option<Color>,
+    column_colors: collections::HashMap<usize, Color>,
+    decorator: Option<Box<dyn CellDecorator>>,
+}
+
+impl BackgroundCellDecorator {
+    /// Creates a new background cell decorator with the given background color.
+    pub fn new(color: impl Into<Option<Color>>) -> BackgroundCellDecorator {
+        BackgroundCellDecorator {
+            color: color.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the background color for the odd rows of the table.
+    pub fn set_alternate_color(&mut self, color: Color) {
+        self.alternate_color = Some(color);
+    }
+
+    /// Sets the background color for the odd rows of the table and returns the decorator.
+    pub fn with_alternate_color(mut self, color: Color) -> Self {
+        self.set_alternate_color(color);
+        self
+    }
+
+    /// Sets the background color for the column with the given index, overriding the row colors.
+    pub fn set_column_color(&mut self, column: usize, color: Color) {
+        self.column_colors.insert(column, color);
+    }
+
+    /// Sets the background color for the column with the given index, overriding the row colors,
+    /// and returns the decorator.
+    pub fn with_column_color(mut self, column: usize, color: Color) -> Self {
+        self.set_column_color(column, color);
+        self
+    }
+
+    /// Sets a decorator that is applied to the cells after the background has been drawn.
+    pub fn set_decorator(&mut self, decorator: impl CellDecorator + 'static) {
+        self.decorator = Some(Box::new(decorator));
+    }
+
+    /// Sets a decorator that is applied to the cells after the background has been drawn and
+    /// returns the background cell decorator.
+    pub fn with_decorator(mut self, decorator: impl CellDecorator + 'static) -> Self {
+        self.set_decorator(decorator);
+        self
+    }
+
+    fn color(&self, column: usize, row: usize) -> Option<Color> {
+        if let Some(color) = self.column_colors.get(&column) {
+            Some(*color)
+        } else if row % 2 == 1 && self.alternate_color.is_some() {
+            self.alternate_color
+        } else {
+            self.color
+        }
+    }
+}
+
+impl CellDecorator for BackgroundCellDecorator {
+    fn set_table_size(&mut self, num_columns: usize, num_rows: usize) {
+        if let Some(decorator) = &mut self.decorator {
+            decorator.set_table_size(num_columns, num_rows);
+        }
+    }
+
+    fn reset_rows(&mut self) {
+        if let Some(decorator) = &mut self.decorator {
+            decorator.reset_rows();
+        }
+    }
+
+    fn prepare_cell<'p>(
+        &self,
+        column: usize,
+        row: usize,
+        area: render::Area<'p>,
+    ) -> render::Area<'p> {
+        let area = match &self.decorator {
+            Some(decorator) => decorator.prepare_cell(column, row, area),
+            None => area,
+        };
+        area.next_layer()
+    }
+
+    fn decorate_cell(
+        &mut self,
+        column: usize,
+        row: usize,
+        has_more: bool,
+        area: render::Area<'_>,
+        row_height: Mm,
+    ) -> Mm {
+        if let Some(color) = self.color(column, row) {
+            let size = Size::new(area.size().width, row_height);
+            area.fill_rect(Position::default(), size, color);
+        }
+        match &mut self.decorator {
+            Some(decorator) => decorator.decorate_cell(column, row, has_more, area, row_height),
+            None => row_height,
         }
-
-        total_height
     }
 }
 
@@ -1392,6 +5898,7 @@ impl CellDecorator for FrameCellDecorator {
 pub struct TableLayoutRow<'a> {
     table_layout: &'a mut TableLayout,
     elements: Vec<Box<dyn Element>>,
+    cell_decorator: Option<Box<dyn CellDecorator>>,
 }
 
 impl<'a> TableLayoutRow<'a> {
@@ -1399,9 +5906,23 @@ impl<'a> TableLayoutRow<'a> {
         TableLayoutRow {
             table_layout,
             elements: Vec::new(),
+            cell_decorator: None,
         }
     }
 
+    /// Sets the cell decorator for this row, overriding the cell decorator of the table.
+    pub fn set_cell_decorator(&mut self, decorator: impl CellDecorator + 'static) {
+        self.cell_decorator = Some(Box::from(decorator));
+    }
+
+    /// Sets the cell decorator for this row, overriding the cell decorator of the table, and
+    /// returns the row.
+    #[must_use]
+    pub fn with_cell_decorator(mut self, decorator: impl CellDecorator + 'static) -> Self {
+        self.set_cell_decorator(decorator);
+        self
+    }
+
     /// Adds the given element to this row.
     pub fn push_element<E: IntoBoxedElement>(&mut self, element: E) {
         self.elements.push(element.into_boxed_element());
@@ -1419,7 +5940,12 @@ impl<'a> TableLayoutRow<'a> {
     /// This method fails if the number of elements in this row does not match the number of
     /// columns in the table.
     pub fn push(self) -> Result<(), Error> {
-        self.table_layout.push_row(self.elements)
+        let row = self.table_layout.rows.len();
+        self.table_layout.push_row(self.elements)?;
+        if let Some(decorator) = self.cell_decorator {
+            self.table_layout.row_decorators.insert(row, decorator);
+        }
+        Ok(())
     }
 }
 
@@ -1430,15 +5956,258 @@ impl<'a, E: IntoBoxedElement> iter::Extend<E> for TableLayoutRow<'a> {
     }
 }
 
+/// The width of a column of a [`TableLayout`][].
+///
+/// The fixed and percentage widths are subtracted from the width of the table first.  The
+/// remaining width is distributed among the weighted columns according to their weights.  If the
+/// fixed and percentage widths exceed the width of the table, they are scaled down proportionally
+/// so that they fill the table, and the other columns have a width of zero.
+///
+/// Auto-fit columns are sized according to the natural width of their content, see
+/// [`Element::natural_width`][], similar to `table-layout: auto` in HTML.  They get the width of
+/// their widest cell before the weighted columns are sized.  If there are no weighted columns, the
+/// auto-fit columns are expanded proportionally to fill the table.  If the content is too wide for
+/// the table, the auto-fit columns are shrunk proportionally.
+///
+/// [`TableLayout`]: struct.TableLayout.html
+/// [`Element::natural_width`]: ../trait.Element.html#method.natural_width
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnWidth {
+    /// A share of the remaining width, relative to the weights of the other weighted columns.
+    Weight(usize),
+    /// A fixed width.
+    Fixed(Mm),
+    /// A percentage (0 to 100) of the table width.
+    Percent(f64),
+    /// A width based on the natural width of the cell contents.
+    ///
+    /// Cells that cannot determine their natural width are ignored.
+    Auto,
+}
+
+impl ColumnWidth {
+    /// Calculates the absolute column widths for a table with the given width and the given
+    /// natural widths of the column contents.
+    fn resolve(column_widths: &[ColumnWidth], natural_widths: &[Mm], width: Mm) -> Vec<Mm> {
+        let natural_width = |i: usize| natural_widths.get(i).copied().unwrap_or(Mm(0.0));
+        let mut total_fixed_width = Mm(0.0);
+        let mut total_weight = 0;
+        let mut total_natural_width = Mm(0.0);
+        let mut num_auto = 0;
+        for (i, column_width) in column_widths.iter().enumerate() {
+            match column_width {
+                ColumnWidth::Weight(weight) => total_weight += weight,
+                ColumnWidth::Fixed(fixed) => total_fixed_width += *fixed,
+                ColumnWidth::Percent(percent) => total_fixed_width += width * (percent / 100.0),
+                ColumnWidth::Auto => {
+                    total_natural_width += natural_width(i);
+                    num_auto += 1;
+                }
+            }
+        }
+        // The fixed and percentage columns are scaled down if they are wider than the table.
+        let fixed_factor = if total_fixed_width > width {
+            width.0 / total_fixed_width.0
+        } else {
+            1.0
+        };
+        let remaining = (width - total_fixed_width * fixed_factor).max(Mm(0.0));
+
+        // The auto-fit columns are scaled down if they are too wide, and they are scaled up if
+        // there are no weighted columns that could use the leftover space.
+        let auto_factor = if total_natural_width > remaining
+            || (total_weight == 0 && total_natural_width > Mm(0.0))
+        {
+            remaining.0 / total_natural_width.0
+        } else {
+            1.0
+        };
+        let auto_width = |i: usize| {
+            if total_weight == 0 && total_natural_width == Mm(0.0) {
+                remaining / num_auto as f64
+            } else {
+                natural_width(i) * auto_factor
+            }
+        };
+        let weighted = (remaining - total_natural_width * auto_factor).max(Mm(0.0));
+
+        column_widths
+            .iter()
+            .enumerate()
+            .map(|(i, column_width)| match column_width {
+                ColumnWidth::Weight(_) if total_weight == 0 => Mm(0.0),
+                ColumnWidth::Weight(weight) => weighted * (*weight as f64 / total_weight as f64),
+                ColumnWidth::Fixed(fixed) => *fixed * fixed_factor,
+                ColumnWidth::Percent(percent) => width * (percent / 100.0 * fixed_factor),
+                ColumnWidth::Auto => auto_width(i),
+            })
+            .collect()
+    }
+}
+
+impl From<usize> for ColumnWidth {
+    fn from(weight: usize) -> ColumnWidth {
+        ColumnWidth::Weight(weight)
+    }
+}
+
+impl From<Mm> for ColumnWidth {
+    fn from(width: Mm) -> ColumnWidth {
+        ColumnWidth::Fixed(width)
+    }
+}
+
+/// The policy for breaking the rows of a [`TableLayout`][] across pages.
+///
+/// [`TableLayout`]: struct.TableLayout.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BreakPolicy {
+    /// Rows that do not fit on the current page are split, and the remaining content of the cells
+    /// is rendered on the next page.
+    Split,
+    /// Rows that do not fit on the current page are moved to the next page.
+    ///
+    /// The rows are measured with [`Element::measure`][] before they are rendered.  If a cell
+    /// does not support measuring, or if a row does not even fit on a new page, the row is split.
+    ///
+    /// [`Element::measure`]: ../trait.Element.html#method.measure
+    KeepRow,
+}
+
+impl Default for BreakPolicy {
+    fn default() -> BreakPolicy {
+        BreakPolicy::Split
+    }
+}
+
+/// A header row of a table layout.
+///
+/// This is a helper struct for populating the header rows of a [`TableLayout`][], see
+/// [`TableLayout::push_header_row`][].  As the header rows are rendered again on every page, the
+/// elements of the row must implement `Clone`.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let mut table = elements::TableLayout::new(vec![1, 1]);
+/// table
+///     .header_row()
+///     .element(elements::Paragraph::new("Name"))
+///     .element(elements::Paragraph::new("Price"))
+///     .push()
+///     .expect("Invalid table row");
+/// ```
+///
+/// [`TableLayout`]: struct.TableLayout.html
+/// [`TableLayout::push_header_row`]: struct.TableLayout.html#method.push_header_row
+pub struct TableLayoutHeaderRow<'a> {
+    table_layout: &'a mut TableLayout,
+    elements: Vec<Box<dyn CloneableElement>>,
+}
+
+impl<'a> TableLayoutHeaderRow<'a> {
+    fn new(table_layout: &'a mut TableLayout) -> TableLayoutHeaderRow<'a> {
+        TableLayoutHeaderRow {
+            table_layout,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Adds the given element to this row.
+    pub fn push_element<E: Element + Clone + 'static>(&mut self, element: E) {
+        self.elements.push(Box::new(element));
+    }
+
+    /// Adds the given element to this row and returns the row.
+    #[must_use]
+    pub fn element<E: Element + Clone + 'static>(mut self, element: E) -> Self {
+        self.push_element(element);
+        self
+    }
+
+    /// Tries to append this header row to the table.
+    ///
+    /// This method fails if the number of elements in this row does not match the number of
+    /// columns in the table.
+    pub fn push(self) -> Result<(), Error> {
+        self.table_layout.push_header_row(self.elements)
+    }
+}
+
+/// A footer row of a table layout.
+///
+/// This is a helper struct for populating the footer rows of a [`TableLayout`][], see
+/// [`TableLayout::push_footer_row`][].  As the footer rows can be repeated on every page, the
+/// elements of the row must implement `Clone`.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::elements;
+/// let mut table = elements::TableLayout::new(vec![1, 1]);
+/// table
+///     .footer_row()
+///     .element(elements::Paragraph::new("Total"))
+///     .element(elements::Paragraph::new("3.70"))
+///     .push()
+///     .expect("Invalid table row");
+/// ```
+///
+/// [`TableLayout`]: struct.TableLayout.html
+/// [`TableLayout::push_footer_row`]: struct.TableLayout.html#method.push_footer_row
+pub struct TableLayoutFooterRow<'a> {
+    table_layout: &'a mut TableLayout,
+    elements: Vec<Box<dyn CloneableElement>>,
+}
+
+impl<'a> TableLayoutFooterRow<'a> {
+    fn new(table_layout: &'a mut TableLayout) -> TableLayoutFooterRow<'a> {
+        TableLayoutFooterRow {
+            table_layout,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Adds the given element to this row.
+    pub fn push_element<E: Element + Clone + 'static>(&mut self, element: E) {
+        self.elements.push(Box::new(element));
+    }
+
+    /// Adds the given element to this row and returns the row.
+    #[must_use]
+    pub fn element<E: Element + Clone + 'static>(mut self, element: E) -> Self {
+        self.push_element(element);
+        self
+    }
+
+    /// Tries to append this footer row to the table.
+    ///
+    /// This method fails if the number of elements in this row does not match the number of
+    /// columns in the table.
+    pub fn push(self) -> Result<(), Error> {
+        self.table_layout.push_footer_row(self.elements)
+    }
+}
+
 /// Arranges elements in columns and rows.
 ///
 /// This struct can be used to layout arbitrary elements in columns in rows, or to draw typical
 /// tables.  You can customize the cell style by providing a [`CellDecorator`][] implementation.
 /// If you want to print a typical table with borders around the cells, use the
-/// [`FrameCellDecorator`][].
+/// [`FrameCellDecorator`][].  The cell decorator can be overridden for single rows with
+/// [`set_row_decorator`][], and the style of the rows can be set with [`set_row_style_cb`][].
+///
+/// If the table is split across multiple pages, the header rows added with [`header_row`][] are
+/// repeated at the top of every page.  The footer rows added with [`footer_row`][] are rendered
+/// after the last row, and they can be repeated at the bottom of every page too, see
+/// [`set_repeat_footer_rows`][].  Per default, rows that do not fit on a page are split.  Use
+/// [`set_row_break_policy`][] to move them to the next page instead.
 ///
 /// The column widths are determined by the weights that have been set in the constructor.  The
-/// table always uses the full width of the provided area.
+/// table always uses the full width of the provided area.  Use [`with_column_widths`][] to mix
+/// weighted columns with columns with a fixed width, a percentage of the table width or a width
+/// that is fitted to the cell contents.
 ///
 /// # Examples
 ///
@@ -1466,11 +6235,29 @@ impl<'a, E: IntoBoxedElement> iter::Extend<E> for TableLayoutRow<'a> {
 ///
 /// [`CellDecorator`]: trait.CellDecorator.html
 /// [`FrameCellDecorator`]: struct.FrameCellDecorator.html
+/// [`header_row`]: #method.header_row
+/// [`footer_row`]: #method.footer_row
+/// [`set_repeat_footer_rows`]: #method.set_repeat_footer_rows
+/// [`with_column_widths`]: #method.with_column_widths
+/// [`set_row_decorator`]: #method.set_row_decorator
+/// [`set_row_style_cb`]: #method.set_row_style_cb
+/// [`set_row_break_policy`]: #method.set_row_break_policy
 pub struct TableLayout {
-    column_weights: Vec<usize>,
+    column_widths: Vec<ColumnWidth>,
     rows: Vec<Vec<Box<dyn Element>>>,
     render_idx: usize,
     cell_decorator: Option<Box<dyn CellDecorator>>,
+    row_decorators: collections::HashMap<usize, Box<dyn CellDecorator>>,
+    row_style_cb: Option<Box<dyn Fn(usize) -> Style>>,
+    vertical_alignments: collections::HashMap<usize, VerticalAlignment>,
+    header_rows: Vec<Vec<Box<dyn CloneableElement>>>,
+    footer_rows: Vec<Vec<Box<dyn CloneableElement>>>,
+    repeat_footer_rows: bool,
+    footer_rendered: bool,
+    natural_widths: Option<Vec<Mm>>,
+    row_break_policy: BreakPolicy,
+    row_deferred: bool,
+    cell_padding: Margins,
 }
 
 impl TableLayout {
@@ -1479,83 +6266,692 @@ impl TableLayout {
     /// The column weights are used to determine the relative width of the columns.  The number of
     /// column weights determines the number of columns in the table.
     pub fn new(column_weights: Vec<usize>) -> TableLayout {
+        TableLayout::with_column_widths(
+            column_weights
+                .into_iter()
+                .map(ColumnWidth::Weight)
+                .collect(),
+        )
+    }
+
+    /// Creates a new table layout with the given column widths.
+    ///
+    /// The number of column widths determines the number of columns in the table.  See
+    /// [`ColumnWidth`][] for more information on how the widths are calculated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements::{ColumnWidth, TableLayout};
+    /// let table = TableLayout::with_column_widths(vec![
+    ///     ColumnWidth::Fixed(15.into()),
+    ///     ColumnWidth::Weight(1),
+    ///     ColumnWidth::Percent(20.0),
+    ///     ColumnWidth::Auto,
+    /// ]);
+    /// ```
+    ///
+    /// [`ColumnWidth`]: enum.ColumnWidth.html
+    pub fn with_column_widths(column_widths: Vec<ColumnWidth>) -> TableLayout {
         TableLayout {
-            column_weights,
+            column_widths,
             rows: Vec::new(),
             render_idx: 0,
             cell_decorator: None,
+            row_decorators: collections::HashMap::new(),
+            row_style_cb: None,
+            vertical_alignments: collections::HashMap::new(),
+            header_rows: Vec::new(),
+            footer_rows: Vec::new(),
+            repeat_footer_rows: false,
+            footer_rendered: false,
+            natural_widths: None,
+            row_break_policy: BreakPolicy::default(),
+            row_deferred: false,
+            cell_padding: Margins::default(),
+        }
+    }
+
+    /// Sets the cell decorator for this table.
+    pub fn set_cell_decorator(&mut self, decorator: impl CellDecorator + 'static) {
+        self.cell_decorator = Some(Box::from(decorator));
+    }
+
+    /// Sets the cell decorator for the row with the given index, overriding the cell decorator of
+    /// the table.
+    ///
+    /// You can also set the cell decorator when creating the row, see
+    /// [`TableLayoutRow::set_cell_decorator`][].
+    ///
+    /// [`TableLayoutRow::set_cell_decorator`]: struct.TableLayoutRow.html#method.set_cell_decorator
+    pub fn set_row_decorator(&mut self, row: usize, decorator: impl CellDecorator + 'static) {
+        self.row_decorators.insert(row, Box::from(decorator));
+    }
+
+    /// Sets a callback that returns the style for the row with the given index.
+    ///
+    /// The returned style is combined with the style of the table and applied to all cells of the
+    /// row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::{elements, style};
+    /// let mut table = elements::TableLayout::new(vec![3, 1]);
+    /// let rows = [("Apples", "2.50"), ("Pears", "1.20"), ("Total", "3.70")];
+    /// let total_row = rows.len() - 1;
+    /// table.set_row_style_cb(move |row| {
+    ///     if row == total_row {
+    ///         style::Style::new().bold()
+    ///     } else {
+    ///         style::Style::new()
+    ///     }
+    /// });
+    /// for (item, price) in &rows {
+    ///     table
+    ///         .row()
+    ///         .element(elements::Paragraph::new(*item))
+    ///         .element(elements::Paragraph::new(*price))
+    ///         .push()
+    ///         .expect("Invalid table row");
+    /// }
+    /// ```
+    pub fn set_row_style_cb(&mut self, cb: impl Fn(usize) -> Style + 'static) {
+        self.row_style_cb = Some(Box::new(cb));
+    }
+
+    /// Sets the padding that is added to the content of every cell.
+    ///
+    /// The padding is added within the area prepared by the cell decorator, so it is the space
+    /// between the content and the frame lines of a [`FrameCellDecorator`][].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::{elements, Margins};
+    /// let mut table = elements::TableLayout::new(vec![1, 1]);
+    /// table.set_cell_padding(Margins::trbl(1, 2, 1, 2));
+    /// ```
+    ///
+    /// [`FrameCellDecorator`]: struct.FrameCellDecorator.html
+    pub fn set_cell_padding(&mut self, padding: impl Into<Margins>) {
+        self.cell_padding = padding.into();
+    }
+
+    /// Sets the padding that is added to the content of every cell and returns the table.
+    pub fn with_cell_padding(mut self, padding: impl Into<Margins>) -> Self {
+        self.set_cell_padding(padding);
+        self
+    }
+
+    /// Sets the policy for breaking rows that do not fit on the current page.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut table = elements::TableLayout::new(vec![1, 1]);
+    /// table.set_row_break_policy(elements::BreakPolicy::KeepRow);
+    /// ```
+    pub fn set_row_break_policy(&mut self, policy: BreakPolicy) {
+        self.row_break_policy = policy;
+    }
+
+    /// Sets the policy for breaking rows that do not fit on the current page and returns the
+    /// table.
+    pub fn with_row_break_policy(mut self, policy: BreakPolicy) -> Self {
+        self.set_row_break_policy(policy);
+        self
+    }
+
+    /// Sets the vertical alignment of the cells in the column with the given index.
+    ///
+    /// Per default, all cells are aligned to the top of the row.  Cells with a different alignment
+    /// are measured with [`Element::measure`][] and rendered after the other cells of the row so
+    /// that they can be aligned to the height of the row.  If a cell does not support measuring
+    /// or does not fit on the current page, it is aligned to the top.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::{elements, VerticalAlignment};
+    /// let mut table = elements::TableLayout::new(vec![1, 3]);
+    /// table.set_vertical_alignment(0, VerticalAlignment::Middle);
+    /// ```
+    ///
+    /// [`Element::measure`]: ../trait.Element.html#method.measure
+    pub fn set_vertical_alignment(&mut self, column: usize, alignment: VerticalAlignment) {
+        self.vertical_alignments.insert(column, alignment);
+    }
+
+    /// Sets the vertical alignment of the cells in the column with the given index and returns
+    /// the table.
+    pub fn with_vertical_alignment(mut self, column: usize, alignment: VerticalAlignment) -> Self {
+        self.set_vertical_alignment(column, alignment);
+        self
+    }
+
+    /// Adds a row to this table using the [`TableLayoutRow`][] helper struct.
+    ///
+    /// [`TableLayoutRow`]: struct.TableLayoutRow.html
+    pub fn row(&mut self) -> TableLayoutRow<'_> {
+        TableLayoutRow::new(self)
+    }
+
+    /// Adds a row to this table.
+    ///
+    /// The number of elements in the given vector must match the number of columns.  Otherwise, an
+    /// error is returned.
+    pub fn push_row(&mut self, row: Vec<Box<dyn Element>>) -> Result<(), Error> {
+        self.check_row_len(row.len())?;
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Adds a header row to this table using the [`TableLayoutHeaderRow`][] helper struct.
+    ///
+    /// [`TableLayoutHeaderRow`]: struct.TableLayoutHeaderRow.html
+    pub fn header_row(&mut self) -> TableLayoutHeaderRow<'_> {
+        TableLayoutHeaderRow::new(self)
+    }
+
+    /// Adds a header row to this table.
+    ///
+    /// Header rows are rendered before the other rows on every page that the table is rendered
+    /// on.  For the cell decorator, the header rows are the first rows of the table, and their
+    /// row state is reset before they are repeated on a new page, see
+    /// [`CellDecorator::reset_rows`][].
+    ///
+    /// The number of elements in the given vector must match the number of columns.  Otherwise, an
+    /// error is returned.
+    ///
+    /// [`CellDecorator::reset_rows`]: trait.CellDecorator.html#method.reset_rows
+    pub fn push_header_row(&mut self, row: Vec<Box<dyn CloneableElement>>) -> Result<(), Error> {
+        self.check_row_len(row.len())?;
+        self.header_rows.push(row);
+        Ok(())
+    }
+
+    /// Adds a footer row to this table using the [`TableLayoutFooterRow`][] helper struct.
+    ///
+    /// [`TableLayoutFooterRow`]: struct.TableLayoutFooterRow.html
+    pub fn footer_row(&mut self) -> TableLayoutFooterRow<'_> {
+        TableLayoutFooterRow::new(self)
+    }
+
+    /// Adds a footer row to this table.
+    ///
+    /// Footer rows are rendered after the last row of the table, for example to show the total of
+    /// an invoice.  For the cell decorator, the footer rows are the last rows of the table.  If
+    /// [`set_repeat_footer_rows`][] is set, they are also rendered at the bottom of every page
+    /// that the table is split on.
+    ///
+    /// The number of elements in the given vector must match the number of columns.  Otherwise, an
+    /// error is returned.
+    ///
+    /// [`set_repeat_footer_rows`]: #method.set_repeat_footer_rows
+    pub fn push_footer_row(&mut self, row: Vec<Box<dyn CloneableElement>>) -> Result<(), Error> {
+        self.check_row_len(row.len())?;
+        self.footer_rows.push(row);
+        Ok(())
+    }
+
+    /// Sets whether the footer rows should be repeated at the bottom of every page that the table
+    /// is split on.
+    ///
+    /// If this option is set, the space for the footer rows is reserved on every page, and the
+    /// footer rows are rendered after the last row that fits on the page.  This can be used for
+    /// placeholder rows like “Carried forward” in invoices that span multiple pages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::elements;
+    /// let mut table = elements::TableLayout::new(vec![3, 1]).with_repeat_footer_rows(true);
+    /// table
+    ///     .footer_row()
+    ///     .element(elements::Paragraph::new("Carried forward / Total"))
+    ///     .element(elements::Paragraph::new("see last page"))
+    ///     .push()
+    ///     .expect("Invalid table row");
+    /// ```
+    pub fn set_repeat_footer_rows(&mut self, repeat_footer_rows: bool) {
+        self.repeat_footer_rows = repeat_footer_rows;
+    }
+
+    /// Sets whether the footer rows should be repeated at the bottom of every page that the table
+    /// is split on and returns the table.
+    pub fn with_repeat_footer_rows(mut self, repeat_footer_rows: bool) -> Self {
+        self.set_repeat_footer_rows(repeat_footer_rows);
+        self
+    }
+
+    fn check_row_len(&self, len: usize) -> Result<(), Error> {
+        if len == self.column_widths.len() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                format!(
+                    "Expected {} elements in table row, received {}",
+                    self.column_widths.len(),
+                    len
+                ),
+                ErrorKind::InvalidData,
+            ))
+        }
+    }
+
+    /// Returns the layout of the columns for a table with the given width.
+    fn resolve_columns(&self, width: Mm) -> ColumnLayout {
+        let natural_widths = self.natural_widths.as_deref().unwrap_or_default();
+        let widths = ColumnWidth::resolve(&self.column_widths, natural_widths, width);
+        let alignments = (0..widths.len())
+            .map(|i| {
+                let alignment = self.vertical_alignments.get(&i).copied();
+                alignment.unwrap_or_default()
+            })
+            .collect();
+        ColumnLayout {
+            widths,
+            alignments,
+            padding: self.cell_padding,
+        }
+    }
+
+    /// Calculates the natural widths of the columns, including the cell padding and the space that
+    /// is required by the cell decorator.
+    fn calculate_natural_widths(
+        &self,
+        context: &Context,
+        area: &render::Area<'_>,
+        style: Style,
+    ) -> Vec<Mm> {
+        if !self.column_widths.contains(&ColumnWidth::Auto) {
+            return Vec::new();
+        }
+
+        let mut natural_widths = vec![Mm(0.0); self.column_widths.len()];
+        let mut update_widths = |widths: Vec<Option<Mm>>| {
+            for (natural_width, width) in natural_widths.iter_mut().zip(widths) {
+                if let Some(width) = width {
+                    *natural_width = natural_width.max(width);
+                }
+            }
+        };
+        for row in self.header_rows.iter().chain(&self.footer_rows) {
+            update_widths(
+                row.iter()
+                    .map(|cell| cell.natural_width(context, style))
+                    .collect(),
+            );
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            let style = self.row_style(i, style);
+            update_widths(
+                row.iter()
+                    .map(|cell| cell.natural_width(context, style))
+                    .collect(),
+            );
+        }
+
+        let padding = self.cell_padding.left + self.cell_padding.right;
+        for (i, natural_width) in natural_widths.iter_mut().enumerate() {
+            *natural_width += padding;
+            if let Some(decorator) = &self.cell_decorator {
+                let cell_area = decorator.prepare_cell(i, 0, area.dry_run());
+                *natural_width += area.size().width - cell_area.size().width;
+            }
+        }
+        natural_widths
+    }
+
+    fn row_style(&self, row: usize, style: Style) -> Style {
+        match &self.row_style_cb {
+            Some(cb) => style.and(cb(row)),
+            None => style,
+        }
+    }
+
+    /// Checks whether any content of the current row fits into the given area.  If the cells
+    /// cannot be measured, we assume that the row fits.
+    fn fits_row_start(&self, context: &Context, area: render::Area<'_>, style: Style) -> bool {
+        let row = self.render_idx;
+        let style = self.row_style(row, style);
+        let columns = self.resolve_columns(area.size().width);
+        let decorator = self
+            .row_decorators
+            .get(&row)
+            .map(|d| d.as_ref())
+            .or(self.cell_decorator.as_deref());
+        let (_, cell_areas) = columns.cell_areas(decorator, self.header_rows.len() + row, &area);
+        let mut fits = false;
+        for (cell, area) in self.rows[row].iter().zip(cell_areas) {
+            match cell.measure(context, area, style) {
+                // A cell without content that does not fit yet, see render_table_cells
+                Some(result) if result.size == Size::new(0, 0) && result.has_more => {}
+                Some(_) => fits = true,
+                None => return true,
+            }
+        }
+        fits
+    }
+
+    /// Checks whether the current row, and the header rows if `with_header` is set, fit into the
+    /// given area.  If the cells cannot be measured, we assume that the row fits.
+    fn fits_row(
+        &self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+        with_header: bool,
+    ) -> bool {
+        let columns = self.resolve_columns(area.size().width);
+        let decorator = self.cell_decorator.as_deref();
+        if with_header {
+            for (i, cells) in self.header_rows.iter().enumerate() {
+                let measure = |j: usize, area| cells[j].measure(context, area, style);
+                match measure_table_cells(&columns, decorator, i, area.clone(), measure) {
+                    Some(result) if result.has_more => return false,
+                    Some(result) => area.add_offset(Position::new(0, result.size.height)),
+                    None => return true,
+                }
+            }
         }
-    }
 
-    /// Sets the cell decorator for this table.
-    pub fn set_cell_decorator(&mut self, decorator: impl CellDecorator + 'static) {
-        self.cell_decorator = Some(Box::from(decorator));
+        let row = self.render_idx;
+        let style = self.row_style(row, style);
+        let decorator = self
+            .row_decorators
+            .get(&row)
+            .map(|d| d.as_ref())
+            .or(decorator);
+        let measure = |j: usize, area| self.rows[row][j].measure(context, area, style);
+        let row = self.header_rows.len() + row;
+        match measure_table_cells(&columns, decorator, row, area, measure) {
+            Some(result) => !result.has_more,
+            None => true,
+        }
     }
 
-    /// Adds a row to this table using the [`TableLayoutRow`][] helper struct.
-    ///
-    /// [`TableLayoutRow`]: struct.TableLayoutRow.html
-    pub fn row(&mut self) -> TableLayoutRow<'_> {
-        TableLayoutRow::new(self)
+    /// Renders the current row and returns the result and whether any content has been rendered.
+    fn render_row(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<(RenderResult, bool), Error> {
+        let row = self.render_idx;
+        let style = self.row_style(row, style);
+        let columns = self.resolve_columns(area.size().width);
+        let decorator = match self.row_decorators.get_mut(&row) {
+            Some(decorator) => Some(decorator),
+            None => self.cell_decorator.as_mut(),
+        };
+        render_table_cells(
+            &columns,
+            decorator,
+            self.header_rows.len() + row,
+            &mut self.rows[row],
+            context,
+            area,
+            style,
+        )
     }
 
-    /// Adds a row to this table.
-    ///
-    /// The number of elements in the given vector must match the number of columns.  Otherwise, an
-    /// error is returned.
-    pub fn push_row(&mut self, row: Vec<Box<dyn Element>>) -> Result<(), Error> {
-        if row.len() == self.column_weights.len() {
-            self.rows.push(row);
-            Ok(())
-        } else {
-            Err(Error::new(
-                format!(
-                    "Expected {} elements in table row, received {}",
-                    self.column_weights.len(),
-                    row.len()
-                ),
-                ErrorKind::InvalidData,
-            ))
+    fn render_header_rows(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        if let Some(decorator) = &mut self.cell_decorator {
+            decorator.reset_rows();
+        }
+        let columns = self.resolve_columns(area.size().width);
+        for (i, header_row) in self.header_rows.iter().enumerate() {
+            let mut cells: Vec<_> = header_row.iter().map(|e| e.clone_element()).collect();
+            let (row_result, _) = render_table_cells(
+                &columns,
+                self.cell_decorator.as_mut(),
+                i,
+                &mut cells,
+                context,
+                area.clone(),
+                style,
+            )?;
+            result.size.height += row_result.size.height;
+            area.add_offset(Position::new(0, row_result.size.height));
+            if row_result.has_more {
+                result.has_more = true;
+                break;
+            }
         }
+        Ok(result)
     }
 
-    fn render_row(
+    fn render_footer_rows(
         &mut self,
         context: &Context,
-        area: render::Area<'_>,
+        mut area: render::Area<'_>,
         style: Style,
     ) -> Result<RenderResult, Error> {
         let mut result = RenderResult::default();
+        let columns = self.resolve_columns(area.size().width);
+        let row_offset = self.header_rows.len() + self.rows.len();
+        for (i, footer_row) in self.footer_rows.iter().enumerate() {
+            let mut cells: Vec<_> = footer_row.iter().map(|e| e.clone_element()).collect();
+            let (row_result, _) = render_table_cells(
+                &columns,
+                self.cell_decorator.as_mut(),
+                row_offset + i,
+                &mut cells,
+                context,
+                area.clone(),
+                style,
+            )?;
+            result.size.height += row_result.size.height;
+            area.add_offset(Position::new(0, row_result.size.height));
+            if row_result.has_more {
+                result.has_more = true;
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Measures the given header or footer rows, starting with the given (decorator) row index,
+    /// by rendering copies of the cells in dry-run mode.
+    ///
+    /// Each row is measured with the full area, so the returned height may exceed the height of
+    /// the area.
+    fn measure_rows(
+        &self,
+        rows: &[Vec<Box<dyn CloneableElement>>],
+        row_offset: usize,
+        context: &Context,
+        area: &render::Area<'_>,
+        style: Style,
+    ) -> RenderResult {
+        let columns = self.resolve_columns(area.size().width);
+        let decorator = self.cell_decorator.as_deref();
+        let mut result = RenderResult::default();
+        for (i, cells) in rows.iter().enumerate() {
+            let measure = |j: usize, area: render::Area<'_>| {
+                let mut cell = cells[j].clone_element();
+                cell.render(context, area, style).ok()
+            };
+            let area = area.dry_run();
+            if let Some(row_result) =
+                measure_table_cells(&columns, decorator, row_offset + i, area, measure)
+            {
+                result.size.height += row_result.size.height;
+                result.has_more |= row_result.has_more;
+            }
+        }
+        result
+    }
 
-        let areas = area.split_horizontally(&self.column_weights);
-        let cell_areas = if let Some(decorator) = &self.cell_decorator {
-            areas
-                .iter()
-                .enumerate()
-                .map(|(i, area)| decorator.prepare_cell(i, self.render_idx, area.clone()))
-                .collect()
-        } else {
-            areas.clone()
-        };
+    /// Calculates the height of the footer rows by rendering copies of the cells in dry-run mode.
+    fn footer_height(&self, context: &Context, area: &render::Area<'_>, style: Style) -> Mm {
+        let row_offset = self.header_rows.len() + self.rows.len();
+        self.measure_rows(&self.footer_rows, row_offset, context, area, style)
+            .size
+            .height
+    }
+}
+
+/// The resolved layout of the columns of a table.
+struct ColumnLayout {
+    widths: Vec<Mm>,
+    alignments: Vec<VerticalAlignment>,
+    padding: Margins,
+}
+
+impl ColumnLayout {
+    /// Splits the given row area into the cell areas, reduced by the cell decorator and the cell
+    /// padding.
+    fn cell_areas<'p>(
+        &self,
+        decorator: Option<&dyn CellDecorator>,
+        row: usize,
+        area: &render::Area<'p>,
+    ) -> (Vec<render::Area<'p>>, Vec<render::Area<'p>>) {
+        let areas = area.split_horizontally_by_widths(&self.widths);
+        let cell_areas = areas
+            .iter()
+            .enumerate()
+            .map(|(i, area)| {
+                let mut area = match decorator {
+                    Some(decorator) => decorator.prepare_cell(i, row, area.clone()),
+                    None => area.clone(),
+                };
+                area.add_margins(self.padding);
+                area
+            })
+            .collect();
+        (areas, cell_areas)
+    }
+
+    /// Returns the height of a row with the given content height.
+    fn row_height(&self, content_height: Mm) -> Mm {
+        content_height + self.padding.top + self.padding.bottom
+    }
+}
 
-        let mut row_height = Mm::from(0);
-        for (area, element) in cell_areas.iter().zip(self.rows[self.render_idx].iter_mut()) {
-            let element_result = element.render(context, area.clone(), style)?;
-            result.has_more |= element_result.has_more;
-            row_height = row_height.max(element_result.size.height);
+/// Renders the cells of a table row with the given (decorator) row index.
+///
+/// Returns the result for the row and whether any content of the cells has been rendered.
+fn render_table_cells(
+    columns: &ColumnLayout,
+    decorator: Option<&mut Box<dyn CellDecorator>>,
+    row: usize,
+    cells: &mut [Box<dyn Element>],
+    context: &Context,
+    area: render::Area<'_>,
+    style: Style,
+) -> Result<(RenderResult, bool), Error> {
+    let mut result = RenderResult::default();
+
+    let (areas, cell_areas) = columns.cell_areas(decorator.as_deref().map(|d| &**d), row, &area);
+
+    // Cells that are not aligned to the top are rendered after the other cells so that we know
+    // the height of the row.
+    let mut aligned_cells = Vec::new();
+    let mut content_height = Mm::from(0);
+    let mut is_empty = true;
+    for (i, (area, element)) in cell_areas.iter().zip(cells.iter_mut()).enumerate() {
+        let alignment = columns.alignments[i];
+        if alignment != VerticalAlignment::Top {
+            match element.measure(context, area.clone(), style) {
+                Some(measured) if !measured.has_more => {
+                    content_height = content_height.max(measured.size.height);
+                    aligned_cells.push((i, alignment, measured.size.height));
+                    is_empty = false;
+                    continue;
+                }
+                _ => {}
+            }
         }
-        result.size.height = row_height;
+        let element_result = render_cell(element, context, area.clone(), style)?;
+        result.has_more |= element_result.has_more;
+        content_height = content_height.max(element_result.size.height);
+        is_empty &= element_result.size == Size::new(0, 0) && element_result.has_more;
+    }
+    for (i, alignment, height) in aligned_cells {
+        let mut area = cell_areas[i].clone();
+        area.add_offset(Position::new(
+            0,
+            alignment_offset(alignment, height, content_height),
+        ));
+        let element_result = render_cell(&mut cells[i], context, area, style)?;
+        result.has_more |= element_result.has_more;
+    }
+    // If no content has been rendered, we do not add the padding so that the row is moved to the
+    // next page.
+    let row_height = if is_empty {
+        content_height
+    } else {
+        columns.row_height(content_height)
+    };
+    result.size.height = row_height;
+
+    if let Some(decorator) = decorator {
+        for (i, area) in areas.into_iter().enumerate() {
+            let height = decorator.decorate_cell(i, row, result.has_more, area, row_height);
+            result.size.height = result.size.height.max(height);
+        }
+    }
 
-        if let Some(decorator) = &mut self.cell_decorator {
-            for (i, area) in areas.into_iter().enumerate() {
-                let height =
-                    decorator.decorate_cell(i, self.render_idx, result.has_more, area, row_height);
-                result.size.height = result.size.height.max(height);
+    Ok((result, !is_empty))
+}
+
+/// Renders a table cell and handles errors according to the [`ElementErrorPolicy`][].
+///
+/// If the cell fails and the error is not returned, a placeholder is rendered instead and the
+/// cell is replaced with an empty element so that it is not rendered again if the row is
+/// continued on the next page.
+///
+/// [`ElementErrorPolicy`]: ../enum.ElementErrorPolicy.html
+fn render_cell(
+    cell: &mut Box<dyn Element>,
+    context: &Context,
+    area: render::Area<'_>,
+    style: Style,
+) -> Result<RenderResult, Error> {
+    match cell.render(context, area.clone(), style) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            let result = render_error_placeholder(context, area.clone(), style, err)?;
+            if !area.is_dry_run() {
+                *cell = Box::new(Break::new(0));
             }
+            Ok(result)
         }
+    }
+}
 
-        Ok(result)
+/// Measures the cells of a table row with the given (decorator) row index using the given
+/// function, see [`Element::measure`][].
+///
+/// [`Element::measure`]: ../trait.Element.html#method.measure
+fn measure_table_cells<'p>(
+    columns: &ColumnLayout,
+    decorator: Option<&dyn CellDecorator>,
+    row: usize,
+    area: render::Area<'p>,
+    measure_cell: impl Fn(usize, render::Area<'p>) -> Option<RenderResult>,
+) -> Option<RenderResult> {
+    let mut result = RenderResult::default();
+    let (areas, cell_areas) = columns.cell_areas(decorator, row, &area);
+    for (i, (area, cell_area)) in areas.iter().zip(cell_areas).enumerate() {
+        // The space that is used by the cell decorator and the cell padding
+        let margin = area.size().height - cell_area.size().height;
+        let cell_result = measure_cell(i, cell_area)?;
+        result.has_more |= cell_result.has_more;
+        result.size.height = result.size.height.max(cell_result.size.height + margin);
     }
+    Some(result)
 }
 
 impl Element for TableLayout {
@@ -1566,15 +6962,83 @@ impl Element for TableLayout {
         style: Style,
     ) -> Result<RenderResult, Error> {
         let mut result = RenderResult::default();
-        if self.column_weights.is_empty() {
+        if self.column_widths.is_empty() {
             return Ok(result);
         }
+        let num_rows = self.header_rows.len() + self.rows.len() + self.footer_rows.len();
         if let Some(decorator) = &mut self.cell_decorator {
-            decorator.set_table_size(self.column_weights.len(), self.rows.len());
+            decorator.set_table_size(self.column_widths.len(), num_rows);
+        }
+        for decorator in self.row_decorators.values_mut() {
+            decorator.set_table_size(self.column_widths.len(), num_rows);
+        }
+        if self.natural_widths.is_none() {
+            self.natural_widths = Some(self.calculate_natural_widths(context, &area, style));
         }
         result.size.width = area.size().width;
+        let available_height = area.size().height;
+        if self.repeat_footer_rows && self.render_idx < self.rows.len() {
+            // Reserve the space for the footer rows at the bottom of the page
+            let footer_height = self.footer_height(context, &area, style);
+            area.set_height((available_height - footer_height).max(Mm(0.0)));
+        }
+        if self.row_break_policy == BreakPolicy::KeepRow
+            && !self.row_deferred
+            && self.render_idx < self.rows.len()
+            && !self.fits_row(context, area.clone(), style, true)
+        {
+            // The next row does not fit on this page, so we move it to the next page.  If it does
+            // not fit on the next page either, it is split.  We request a page break so that the
+            // page does not count as empty if the table is the first element on the page.
+            self.row_deferred = true;
+            if !area.is_dry_run() {
+                context.request_page_break();
+            }
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        let has_header = !self.header_rows.is_empty() && self.render_idx < self.rows.len();
+        if has_header {
+            // If the header rows do not fit on this page, we try again on the next page.  We
+            // report an empty result so that the rendering process is aborted if the header rows
+            // do not even fit on an empty page.
+            // The same applies if not even a part of the first row fits below the header rows.
+            let header_size = self.measure_rows(&self.header_rows, 0, context, &area, style);
+            let mut row_area = area.clone();
+            row_area.add_offset(Position::new(0, header_size.size.height));
+            if header_size.has_more
+                || header_size.size.height > area.size().height
+                || !self.fits_row_start(context, row_area, style)
+            {
+                return Ok(RenderResult {
+                    size: Size::new(0, 0),
+                    has_more: true,
+                });
+            }
+            let header_result = self.render_header_rows(context, area.clone(), style)?;
+            result.size.height += header_result.size.height;
+            area.add_offset(Position::new(0, header_result.size.height));
+        }
+        let mut is_first_row = true;
+        let mut has_content = false;
         while self.render_idx < self.rows.len() {
-            let row_result = self.render_row(context, area.clone(), style)?;
+            if self.row_break_policy == BreakPolicy::KeepRow
+                && !is_first_row
+                && !self.fits_row(context, area.clone(), style, false)
+            {
+                self.row_deferred = true;
+                break;
+            }
+            is_first_row = false;
+            let (row_result, row_has_content) = self.render_row(context, area.clone(), style)?;
+            if row_has_content {
+                // Only reset the deferral once the row has been started so that a row that does
+                // not fit on any page is not deferred forever.
+                self.row_deferred = false;
+                has_content = true;
+            }
             result.size.height += row_result.size.height;
             area.add_offset(Position::new(0, row_result.size.height));
             if row_result.has_more {
@@ -1582,7 +7046,775 @@ impl Element for TableLayout {
             }
             self.render_idx += 1;
         }
-        result.has_more = self.render_idx < self.rows.len();
+        if has_header && !has_content {
+            // Not even a part of the first row fits below the header rows although it could not
+            // be measured.  We do not count the header rows so that the page is considered empty
+            // and the rendering process is aborted if the row does not fit on an empty page
+            // either.
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+        area.set_height(available_height - result.size.height);
+        if self.render_idx < self.rows.len() {
+            if self.repeat_footer_rows && !is_first_row {
+                let footer_result = self.render_footer_rows(context, area, style)?;
+                result.size.height += footer_result.size.height;
+            }
+        } else if !self.footer_rendered {
+            // If the footer rows do not fit on this page, we move them to the next page, unless
+            // nothing has been rendered on this page.
+            let row_offset = self.header_rows.len() + self.rows.len();
+            let footer = self.measure_rows(&self.footer_rows, row_offset, context, &area, style);
+            let fits = !footer.has_more && footer.size.height <= area.size().height;
+            if fits || result.size.height == Mm(0.0) {
+                let footer_result = self.render_footer_rows(context, area, style)?;
+                result.size.height += footer_result.size.height;
+                self.footer_rendered = true;
+            }
+        }
+        result.has_more = self.render_idx < self.rows.len() || !self.footer_rendered;
         Ok(result)
     }
+
+    fn dump_tree(&self) -> ElementTree {
+        fn row_tree<E: Element + ?Sized>(name: &str, row: &[Box<E>]) -> ElementTree {
+            ElementTree::new(name).with_children(row.iter().map(|cell| cell.dump_tree()))
+        }
+
+        ElementTree::new("TableLayout")
+            .with_property("columns", self.column_widths.len())
+            .with_children(
+                self.header_rows
+                    .iter()
+                    .map(|row| row_tree("HeaderRow", row)),
+            )
+            .with_children(self.rows.iter().map(|row| row_tree("Row", row)))
+            .with_children(
+                self.footer_rows
+                    .iter()
+                    .map(|row| row_tree("FooterRow", row)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BreakPolicy, ColumnWidth, Exclusion, FixedHeight, FixedSize, Float, FloatSide, Glue,
+        GlueAdjustment, Inline, KeepWithNext, LinearLayout, MaxHeight, MinHeight, PageBreakHint,
+        Paragraph, Placeholder, ScaleToFit, SubDocument, TableLayout, Text,
+    };
+    use crate::error::{Error, ErrorKind, WarningKind};
+    use crate::style::Style;
+    use crate::{render, Context, Element, ElementErrorPolicy, Mm, RenderResult, Size, StrictMode};
+
+    /// Returns a fake context with the fonts loaded into a new 100 mm × 100 mm renderer.
+    fn setup() -> (Context, render::Renderer) {
+        let mut context = Context::fake();
+        let renderer = render::Renderer::new(Size::new(100, 100), "Test").unwrap();
+        context.font_cache.load_pdf_fonts(&renderer).unwrap();
+        (context, renderer)
+    }
+
+    /// An element that always fails to render.
+    struct Failing;
+
+    impl Element for Failing {
+        fn render(
+            &mut self,
+            _context: &Context,
+            _area: render::Area<'_>,
+            _style: Style,
+        ) -> Result<RenderResult, Error> {
+            Err(Error::new("Failing element", ErrorKind::InvalidData))
+        }
+    }
+
+    /// An element that wraps another element and counts how often it has been drawn.
+    #[derive(Clone)]
+    struct Counted<E: Element>(E, std::rc::Rc<std::cell::Cell<usize>>);
+
+    impl<E: Element> Element for Counted<E> {
+        fn render(
+            &mut self,
+            context: &Context,
+            area: render::Area<'_>,
+            style: Style,
+        ) -> Result<RenderResult, Error> {
+            if !area.is_dry_run() {
+                self.1.set(self.1.get() + 1);
+            }
+            self.0.render(context, area, style)
+        }
+    }
+
+    /// An element that wraps another element but does not support measuring.
+    struct Unmeasured<E: Element>(E);
+
+    impl<E: Element> Element for Unmeasured<E> {
+        fn render(
+            &mut self,
+            context: &Context,
+            area: render::Area<'_>,
+            style: Style,
+        ) -> Result<RenderResult, Error> {
+            self.0.render(context, area, style)
+        }
+    }
+
+    #[test]
+    fn test_column_width_resolve() {
+        let widths = ColumnWidth::resolve(
+            &[
+                ColumnWidth::Fixed(Mm(15.0)),
+                ColumnWidth::Weight(1),
+                ColumnWidth::Percent(25.0),
+                ColumnWidth::Weight(3),
+            ],
+            &[],
+            Mm(200.0),
+        );
+        assert_eq!(widths, vec![Mm(15.0), Mm(33.75), Mm(50.0), Mm(101.25)]);
+
+        let widths = ColumnWidth::resolve(
+            &[ColumnWidth::Fixed(Mm(150.0)), ColumnWidth::Weight(1)],
+            &[],
+            Mm(100.0),
+        );
+        assert_eq!(widths, vec![Mm(100.0), Mm(0.0)]);
+
+        let widths = ColumnWidth::resolve(
+            &[
+                ColumnWidth::Fixed(Mm(120.0)),
+                ColumnWidth::Auto,
+                ColumnWidth::Fixed(Mm(40.0)),
+                ColumnWidth::Percent(40.0),
+            ],
+            &[Mm(10.0)],
+            Mm(100.0),
+        );
+        assert_eq!(widths, vec![Mm(60.0), Mm(0.0), Mm(20.0), Mm(20.0)]);
+    }
+
+    #[test]
+    fn test_column_width_resolve_auto() {
+        let widths = ColumnWidth::resolve(
+            &[ColumnWidth::Auto, ColumnWidth::Weight(1), ColumnWidth::Auto],
+            &[Mm(20.0), Mm(0.0), Mm(30.0)],
+            Mm(100.0),
+        );
+        assert_eq!(widths, vec![Mm(20.0), Mm(50.0), Mm(30.0)]);
+
+        let widths = ColumnWidth::resolve(
+            &[ColumnWidth::Auto, ColumnWidth::Auto],
+            &[Mm(10.0), Mm(30.0)],
+            Mm(100.0),
+        );
+        assert_eq!(widths, vec![Mm(25.0), Mm(75.0)]);
+
+        let widths = ColumnWidth::resolve(
+            &[ColumnWidth::Auto, ColumnWidth::Weight(1), ColumnWidth::Auto],
+            &[Mm(100.0), Mm(0.0), Mm(300.0)],
+            Mm(200.0),
+        );
+        assert_eq!(widths, vec![Mm(50.0), Mm(0.0), Mm(150.0)]);
+
+        let widths = ColumnWidth::resolve(&[ColumnWidth::Auto, ColumnWidth::Auto], &[], Mm(50.0));
+        assert_eq!(widths, vec![Mm(25.0), Mm(25.0)]);
+    }
+
+    #[test]
+    fn test_glue_height() {
+        let glue = Glue::new(10).with_stretch(2).with_shrink(4);
+        assert_eq!(glue.height(GlueAdjustment::Ratio(0.0)), Mm(10.0));
+        assert_eq!(glue.height(GlueAdjustment::Ratio(1.5)), Mm(13.0));
+        assert_eq!(glue.height(GlueAdjustment::Ratio(-0.5)), Mm(8.0));
+        assert_eq!(glue.height(GlueAdjustment::Fill(Mm(20.0))), Mm(10.0));
+
+        let glue = Glue::fill();
+        assert_eq!(glue.height(GlueAdjustment::Ratio(1.5)), Mm(0.0));
+        assert_eq!(glue.height(GlueAdjustment::Fill(Mm(20.0))), Mm(20.0));
+    }
+
+    #[test]
+    fn test_paragraph_fake_context() {
+        let (context, renderer) = setup();
+        let style = Style::new().with_font_size(10);
+
+        // Every character of the test font is 0.6 em wide:  9 * 6 pt = 19.05 mm
+        let mut paragraph = Paragraph::new("Test text");
+        let width = paragraph.natural_width(&context, style).unwrap();
+        assert!((width.0 - 19.05).abs() < 0.001);
+
+        let mut area = renderer.first_page().first_layer().area();
+        area.set_width(Mm(15.0));
+        let result = paragraph.render(&context, area, style).unwrap();
+        assert!(!result.has_more);
+        let line_height = style.metrics(&context.font_cache).line_height;
+        assert!((result.size.height.0 - 2.0 * line_height.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_paragraph_exclusion() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.metrics(&context.font_cache).line_height;
+
+        // Every character is 2.117 mm wide, so the excluded lines can hold 14 characters
+        let mut paragraph = Paragraph::new("Test text Test text Test text Test text Test text");
+        paragraph.exclusion = Some(Exclusion {
+            side: FloatSide::Left,
+            width: Mm(70.0),
+            height: line_height * 1.5,
+        });
+        let result = paragraph.render(&context, area, style).unwrap();
+        assert!(!result.has_more);
+        assert!((result.size.height.0 - 3.0 * line_height.0).abs() < 0.001);
+        assert!((result.size.width.0 - 19.0 * 2.1167).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_scale_to_fit() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.metrics(&context.font_cache).line_height;
+
+        // The natural width of the text is 19.05 mm, see test_paragraph_fake_context
+        let mut text = ScaleToFit::new(Text::new("Test text")).with_max_width(9.525);
+        let result = text.render(&context, area.clone(), style).unwrap();
+        assert!(!result.has_more);
+        assert!((result.size.width.0 - 9.525).abs() < 0.001);
+        assert!((result.size.height.0 - line_height.0 / 2.0).abs() < 0.001);
+
+        let text = ScaleToFit::new(Text::new("Test text")).with_max_height(line_height / 4.0);
+        let result = text.measure(&context, area.clone(), style).unwrap();
+        assert!((result.size.width.0 - 19.05 / 4.0).abs() < 0.001);
+        assert!((result.size.height.0 - line_height.0 / 4.0).abs() < 0.001);
+
+        let text = ScaleToFit::new(Text::new("Test text"));
+        let result = text.measure(&context, area, style).unwrap();
+        assert!((result.size.width.0 - 19.05).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_size_constraints() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.metrics(&context.font_cache).line_height;
+        let text = "Test text Test text Test text";
+
+        let mut p = FixedSize::new(Paragraph::new(text), (30, 40));
+        let result = p.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(30, 40));
+        assert!(!result.has_more);
+
+        let mut p = MinHeight::new(Paragraph::new(text), 40);
+        let result = p.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size.height, Mm(40.0));
+        let mut p = MinHeight::new(Paragraph::new(text), 1);
+        let result = p.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size.height, line_height);
+
+        let mut area = area;
+        area.set_width(Mm(30.0));
+        let mut p = MaxHeight::new(Paragraph::new(text), line_height * 1.5);
+        let result = p.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size.height, line_height);
+        assert!(result.has_more);
+        let result = p.render(&context, area, style).unwrap();
+        assert_eq!(result.size.height, line_height);
+        assert!(!result.has_more);
+    }
+
+    #[test]
+    fn test_text_strict_mode() {
+        let (mut context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let mut text = Text::new("Test text").with_max_width(10);
+
+        text.render(&context, area.clone(), style).unwrap();
+        assert!(context.warnings.borrow().is_empty());
+
+        context.strict_mode = StrictMode::Warn;
+        text.render(&context, area.dry_run(), style).unwrap();
+        assert!(context.warnings.borrow().is_empty());
+        text.render(&context, area.clone(), style).unwrap();
+        let kinds: Vec<_> = context.warnings.borrow().iter().map(|w| w.kind()).collect();
+        assert_eq!(kinds, vec![WarningKind::TruncatedText]);
+
+        context.strict_mode = StrictMode::Error;
+        let err = text.render(&context, area, style).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::Degraded(WarningKind::TruncatedText)
+        ));
+    }
+
+    #[test]
+    fn test_element_error_policy() {
+        let (mut context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.line_height(&context.font_cache);
+        let layout = || {
+            LinearLayout::vertical()
+                .element(Paragraph::new("Test"))
+                .element(Failing)
+                .element(Paragraph::new("Test"))
+        };
+
+        assert!(layout().render(&context, area.clone(), style).is_err());
+        assert!(context.element_errors.borrow().is_empty());
+
+        context.element_error_policy = ElementErrorPolicy::SkipAndCollect;
+        // Dry runs do not collect the error
+        let result = layout().render(&context, area.dry_run(), style).unwrap();
+        assert!(context.element_errors.borrow().is_empty());
+        assert_eq!(result.size.height, line_height * 4.0);
+
+        // The failing element is replaced with a placeholder that is two lines high
+        let result = layout().render(&context, area, style).unwrap();
+        assert!(!result.has_more);
+        assert_eq!(result.size.height, line_height * 4.0);
+        assert_eq!(context.element_errors.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_element_error_policy_table_cell() {
+        let (mut context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.line_height(&context.font_cache);
+        let table = || {
+            let mut table = TableLayout::new(vec![1, 1]);
+            table
+                .row()
+                .element(Failing)
+                .element(Paragraph::new("Test"))
+                .push()
+                .unwrap();
+            table
+        };
+
+        assert!(table().render(&context, area.clone(), style).is_err());
+
+        context.element_error_policy = ElementErrorPolicy::SkipAndCollect;
+        let mut table = table();
+        let result = table.render(&context, area.clone(), style).unwrap();
+        assert!(!result.has_more);
+        assert_eq!(result.size.height, line_height * 2.0);
+        assert_eq!(context.element_errors.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_paragraph_min_font_size() {
+        let (context, renderer) = setup();
+        let mut area = renderer.first_page().first_layer().area();
+        area.set_width(Mm(30.0));
+        let style = Style::new().with_font_size(10);
+        let text = "Test aaaaaaaaaaaaaaaaaaaa";
+
+        // The long word is 20 * 0.6 em wide, so it only fits into 30 mm with at most 7 pt
+        let mut paragraph = Paragraph::new(text).with_min_font_size(4);
+        let result = paragraph.render(&context, area.clone(), style).unwrap();
+        assert!(!result.has_more);
+        let line_height = style.with_font_size(7).line_height(&context.font_cache);
+        assert!((result.size.height.0 - 2.0 * line_height.0).abs() < 0.001);
+
+        // Text that already fits is not shrunk
+        let mut paragraph = Paragraph::new("Test").with_min_font_size(4);
+        let result = paragraph.render(&context, area.clone(), style).unwrap();
+        let line_height = style.line_height(&context.font_cache);
+        assert!((result.size.height.0 - line_height.0).abs() < 0.001);
+
+        let mut paragraph = Paragraph::new(text).with_min_font_size(8);
+        let err = paragraph.render(&context, area, style).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+    }
+
+    #[test]
+    fn test_paragraph_line_breaks() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.line_height(&context.font_cache);
+        let lines = |mut paragraph: Paragraph| {
+            let result = paragraph.render(&context, area.clone(), style).unwrap();
+            (result.size.height.0 / line_height.0).round() as usize
+        };
+
+        assert_eq!(lines(Paragraph::new("Jane Doe\nMain Street 1")), 1);
+        assert_eq!(
+            lines(Paragraph::new("Jane Doe\nMain Street 1").with_newline_breaks(true)),
+            2
+        );
+        assert_eq!(
+            lines(
+                Paragraph::new("Jane Doe")
+                    .line_break()
+                    .string("Main Street 1")
+            ),
+            2
+        );
+        assert_eq!(
+            lines(Paragraph::from_inlines(vec![
+                Inline::Text("Jane Doe".to_owned()),
+                Inline::LineBreak,
+                Inline::Text("Main Street 1".to_owned()),
+            ])),
+            2
+        );
+    }
+
+    #[test]
+    fn test_table_header_rows() {
+        let (context, renderer) = setup();
+        let mut area = renderer.first_page().first_layer().area();
+        area.set_height(Mm(35.0));
+        let style = Style::new();
+        let header_count = std::rc::Rc::new(std::cell::Cell::new(0));
+        let table = || {
+            let mut table = TableLayout::new(vec![1]);
+            let header = Counted(Placeholder::new((10, 10)), header_count.clone());
+            table.push_header_row(vec![Box::new(header)]).unwrap();
+            for _ in 0..3 {
+                table
+                    .push_row(vec![Box::new(Placeholder::new((10, 10)))])
+                    .unwrap();
+            }
+            table
+        };
+
+        // The header row is repeated on every page
+        let mut t = table();
+        let result = t.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(100, 30));
+        assert!(result.has_more);
+        let result = t.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(100, 20));
+        assert!(!result.has_more);
+        assert_eq!(header_count.replace(0), 2);
+
+        // If the first row does not fit below the header row, nothing is rendered
+        let mut t = table();
+        area.set_height(Mm(15.0));
+        let result = t.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(0, 0));
+        assert!(result.has_more);
+        assert_eq!(header_count.get(), 0);
+
+        // If the header row does not fit, nothing is rendered
+        area.set_height(Mm(5.0));
+        let result = t.render(&context, area, style).unwrap();
+        assert_eq!(result.size, Size::new(0, 0));
+        assert!(result.has_more);
+
+        // A row that does not fit below the header row on an empty page aborts the rendering
+        let mut doc = crate::tests::test_document((50, 50));
+        let mut t = table();
+        t.push_row(vec![Box::new(Placeholder::new((10, 45)))])
+            .unwrap();
+        doc.push(t);
+        let err = crate::tests::render_page_count(doc).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+    }
+
+    #[test]
+    fn test_table_keep_row_with_header_rows() {
+        let table = |row: Box<dyn Element>| {
+            let mut table = TableLayout::new(vec![1]).with_row_break_policy(BreakPolicy::KeepRow);
+            table
+                .push_header_row(vec![Box::new(Placeholder::new((10, 10)))])
+                .unwrap();
+            table.push_row(vec![row]).unwrap();
+            table
+        };
+
+        // A row that is taller than the page without the header row is split
+        let mut doc = crate::tests::test_document((50, 50));
+        doc.push(table(Box::new(Paragraph::new("Test ".repeat(100)))));
+        assert!(crate::tests::render_page_count(doc).unwrap() > 1);
+
+        // A row that cannot be split and that does not fit on any page aborts the rendering, even
+        // if empty pages are allowed
+        let mut doc = crate::tests::test_document((50, 50));
+        doc.set_max_empty_pages(1);
+        doc.push(table(Box::new(Placeholder::new((10, 45)))));
+        let err = crate::tests::render_page_count(doc).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+    }
+
+    #[test]
+    fn test_sub_document_deferred() {
+        let sub_document = |height: u8| {
+            let mut decorator = crate::SimplePageDecorator::new();
+            decorator.set_margins(5);
+            SubDocument::new(move |_| Placeholder::new((10, height))).with_page_decorator(decorator)
+        };
+
+        // Content that does not fit on the current page is moved to the next page
+        let mut doc = crate::tests::test_document((50, 50));
+        doc.push(Placeholder::new((10, 30)));
+        doc.push(sub_document(30));
+        assert_eq!(crate::tests::render_page_count(doc).unwrap(), 2);
+
+        // Content that does not fit on any page aborts the rendering
+        let mut doc = crate::tests::test_document((50, 50));
+        doc.set_max_empty_pages(1);
+        doc.push(sub_document(45));
+        let err = crate::tests::render_page_count(doc).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+    }
+
+    #[test]
+    fn test_fixed_height_measure() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new();
+
+        let fixed = FixedHeight::new(Paragraph::new("Test"), 10);
+        let result = fixed.measure(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size.height, Mm(10.0));
+        assert!(!result.has_more);
+
+        // The remaining content of the wrapped element is reported
+        let fixed = FixedHeight::new(Paragraph::new("Test ".repeat(100)), 10);
+        let result = fixed.measure(&context, area, style).unwrap();
+        assert_eq!(result.size.height, Mm(10.0));
+        assert!(result.has_more);
+    }
+
+    #[test]
+    fn test_float_has_more() {
+        let (context, renderer) = setup();
+        let mut area = renderer.first_page().first_layer().area();
+        let style = Style::new();
+        let float = || {
+            let mut layout = LinearLayout::vertical();
+            layout.push(Placeholder::new((10, 10)));
+            layout.push(Placeholder::new((10, 10)));
+            Float::new(Unmeasured(layout), Paragraph::new("Test"))
+        };
+
+        // If nothing fits, the float is moved to the next page
+        area.set_height(Mm(5.0));
+        let result = float().render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(0, 0));
+        assert!(result.has_more);
+
+        // If the floating element is split, rendering fails
+        area.set_height(Mm(15.0));
+        let err = float().render(&context, area, style).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+    }
+
+    #[test]
+    fn test_keep_with_next() {
+        let (context, renderer) = setup();
+        let mut area = renderer.first_page().first_layer().area();
+        let style = Style::new();
+        let keep = || KeepWithNext::new(Placeholder::new((10, 10)), Placeholder::new((10, 10)));
+
+        // Both elements fit
+        let result = keep().render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(10, 20));
+        assert!(!result.has_more);
+
+        // The heading is deferred with a page break if the next element does not fit
+        let mut k = keep();
+        area.set_height(Mm(15.0));
+        let result = k.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(0, 0));
+        assert!(result.has_more);
+        assert!(context.page_break_requested.replace(false));
+        area.set_height(Mm(100.0));
+        let result = k.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size, Size::new(10, 20));
+        assert!(!result.has_more);
+
+        // With a minimum height, a part of the next element is not enough
+        let line_height = style.line_height(&context.font_cache);
+        area.set_height(Mm(10.0) + line_height * 2.0);
+        let text = "Test ".repeat(100);
+        let mut k = KeepWithNext::new(Placeholder::new((10, 10)), Paragraph::new(text.clone()));
+        let result = k.render(&context, area.clone(), style).unwrap();
+        assert!(result.size.height > Mm(10.0));
+        let mut k = KeepWithNext::new(Placeholder::new((10, 10)), Paragraph::new(text))
+            .with_min_height(line_height * 3.0);
+        let result = k.render(&context, area, style).unwrap();
+        assert_eq!(result.size, Size::new(0, 0));
+        assert!(result.has_more);
+    }
+
+    #[test]
+    fn test_paragraph_orphan_widow_control() {
+        let (context, renderer) = setup();
+        let mut area = renderer.first_page().first_layer().area();
+        let style = Style::new().with_font_size(10);
+        let line_height = style.line_height(&context.font_cache);
+        let paragraph = || {
+            let mut p = Paragraph::new("1");
+            for line in &["2", "3", "4", "5"] {
+                p.push_line_break();
+                p.push(*line);
+            }
+            p
+        };
+        let mut lines = |p: &mut Paragraph, max_lines: f64| {
+            area.set_height(line_height * max_lines);
+            let result = p.render(&context, area.clone(), style).unwrap();
+            (
+                (result.size.height.0 / line_height.0).round() as usize,
+                result.has_more,
+            )
+        };
+
+        assert_eq!(lines(&mut paragraph(), 4.0), (4, true));
+
+        // Orphans: the paragraph is moved to the next page, unless it does not fit there either
+        let mut p = paragraph().with_orphan_widow_control(2, 2);
+        assert_eq!(lines(&mut p, 1.0), (0, true));
+        assert!(context.page_break_requested.replace(false));
+        assert_eq!(lines(&mut p, 1.0), (1, true));
+
+        // Widows: more lines are moved to the next page
+        let mut p = paragraph().with_orphan_widow_control(2, 2);
+        assert_eq!(lines(&mut p, 4.0), (3, true));
+        assert_eq!(lines(&mut p, 4.0), (2, false));
+    }
+
+    #[test]
+    fn test_table_footer_rows() {
+        let (context, renderer) = setup();
+        let mut area = renderer.first_page().first_layer().area();
+        area.set_height(Mm(35.0));
+        let style = Style::new();
+        let table = |repeat_footer_rows| {
+            let mut table = TableLayout::new(vec![1]).with_repeat_footer_rows(repeat_footer_rows);
+            table
+                .push_footer_row(vec![Box::new(Placeholder::new((10, 10)))])
+                .unwrap();
+            for _ in 0..3 {
+                table
+                    .push_row(vec![Box::new(Placeholder::new((10, 10)))])
+                    .unwrap();
+            }
+            table
+        };
+        let render = |table: &mut TableLayout| {
+            let result = table.render(&context, area.clone(), style).unwrap();
+            (result.size.height, result.has_more)
+        };
+
+        // The footer row is only rendered at the end of the table
+        let mut t = table(false);
+        assert_eq!(render(&mut t), (Mm(30.0), true));
+        assert_eq!(render(&mut t), (Mm(10.0), false));
+
+        // The footer row is repeated at the bottom of every page
+        let mut t = table(true);
+        assert_eq!(render(&mut t), (Mm(30.0), true));
+        assert_eq!(render(&mut t), (Mm(20.0), false));
+    }
+
+    #[test]
+    fn test_page_break_hint() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new();
+        let line_height = style.line_height(&context.font_cache);
+        let layout = |penalty| {
+            let mut paragraph = Paragraph::new("1");
+            for _ in 0..9 {
+                paragraph.push_line_break();
+                paragraph.push("1");
+            }
+            let mut layout = LinearLayout::vertical();
+            layout.push(Placeholder::new((10, 80)));
+            layout.push(PageBreakHint::new(penalty));
+            layout.push(paragraph);
+            layout
+        };
+
+        // The paragraph does not fit into the remaining 20 mm, so the hint is taken
+        let mut l = layout(50);
+        let result = l.render(&context, area.clone(), style).unwrap();
+        assert_eq!(result.size.height, Mm(80.0));
+        assert!(result.has_more);
+        assert!(context.page_break_requested.replace(false));
+        let result = l.render(&context, area.clone(), style).unwrap();
+        assert!((result.size.height.0 - 10.0 * line_height.0).abs() < 0.001);
+        assert!(!result.has_more);
+
+        // The empty space would exceed 10 % of the page, so the paragraph is split
+        let mut l = layout(90);
+        let result = l.render(&context, area, style).unwrap();
+        assert!(result.size.height > Mm(80.0));
+        assert!(result.has_more);
+        assert!(!context.page_break_requested.get());
+    }
+
+    #[test]
+    fn test_glue_layout() {
+        let (context, renderer) = setup();
+        let area = renderer.first_page().first_layer().area();
+        let style = Style::new();
+        let render = |elements: Vec<Box<dyn Element>>| {
+            let mut layout = LinearLayout::vertical();
+            for element in elements {
+                layout.push(element);
+            }
+            let result = layout.render(&context, area.clone(), style).unwrap();
+            (result.size.height, result.has_more)
+        };
+        let assert_height = |(height, has_more): (Mm, bool), expected: f64| {
+            assert!((height.0 - expected).abs() < 0.01, "{:?}", height);
+            assert!(!has_more);
+        };
+
+        // Fill glue takes the remaining space of the page
+        assert_height(
+            render(vec![
+                Box::new(Placeholder::new((10, 20))),
+                Box::new(Glue::fill()),
+                Box::new(Placeholder::new((10, 20))),
+            ]),
+            100.0,
+        );
+
+        // Stretchable glue is stretched to fill the page
+        assert_height(
+            render(vec![
+                Box::new(Placeholder::new((10, 20))),
+                Box::new(Glue::new(10).with_stretch(20)),
+                Box::new(Placeholder::new((10, 60))),
+            ]),
+            100.0,
+        );
+
+        // Shrinkable glue is shrunk so that the next element fits
+        assert_height(
+            render(vec![
+                Box::new(Placeholder::new((10, 50))),
+                Box::new(Glue::new(20).with_shrink(10)),
+                Box::new(Placeholder::new((10, 40))),
+            ]),
+            100.0,
+        );
+
+        // Glue without stretch keeps its natural height
+        assert_height(
+            render(vec![
+                Box::new(Placeholder::new((10, 20))),
+                Box::new(Glue::new(10)),
+            ]),
+            30.0,
+        );
+    }
 }
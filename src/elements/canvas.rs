@@ -0,0 +1,218 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+use crate::{
+    error::Error,
+    render,
+    style::{Color, Fill, LineStyle, Style, StyledString},
+    Context, Element, Mm, Position, RenderResult, Size,
+};
+
+type CanvasCallback = Box<dyn FnMut(&mut Painter<'_, '_>)>;
+
+/// An area with a fixed height that is drawn by a closure.
+///
+/// The canvas uses the full width of the available area.  When it is rendered, the closure is
+/// called with a [`Painter`][] that can draw lines, shapes and text within the canvas.  If the
+/// canvas does not fit on the current page, it is moved to the next page.  This can be used for
+/// one-off custom graphics without implementing the [`Element`][] trait.
+///
+/// Note that the drawing is not clipped to the canvas.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style, Position};
+/// let canvas = elements::Canvas::new(30, |painter| {
+///     let width = painter.size().width;
+///     let line = vec![Position::new(0, 15), Position::new(width, 15)];
+///     painter.line(line, style::LineStyle::new());
+///     painter.circle((20, 15), 10, style::Color::Rgb(255, 0, 0), None);
+///     painter.text((35, 5), "A red circle");
+/// });
+/// ```
+///
+/// [`Painter`]: struct.Painter.html
+/// [`Element`]: ../trait.Element.html
+pub struct Canvas {
+    height: Mm,
+    draw_cb: CanvasCallback,
+}
+
+impl Canvas {
+    /// Creates a new canvas with the given height that is drawn by the given closure.
+    pub fn new<F>(height: impl Into<Mm>, draw_cb: F) -> Canvas
+    where
+        F: FnMut(&mut Painter<'_, '_>) + 'static,
+    {
+        Canvas {
+            height: height.into(),
+            draw_cb: Box::new(draw_cb),
+        }
+    }
+
+    fn size(&self, area: &render::Area<'_>) -> Option<Size> {
+        if self.height > area.size().height {
+            None
+        } else {
+            Some(Size::new(area.size().width, self.height))
+        }
+    }
+}
+
+impl Element for Canvas {
+    fn render(
+        &mut self,
+        context: &Context,
+        mut area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        let size = match self.size(&area) {
+            Some(size) => size,
+            None => {
+                result.has_more = true;
+                return Ok(result);
+            }
+        };
+
+        area.set_height(size.height);
+        let mut painter = Painter {
+            context,
+            area,
+            style,
+            error: None,
+        };
+        (self.draw_cb)(&mut painter);
+        if let Some(error) = painter.error {
+            return Err(error);
+        }
+
+        result.size = size;
+        Ok(result)
+    }
+
+    fn measure(
+        &self,
+        _context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Option<RenderResult> {
+        let mut result = RenderResult::default();
+        match self.size(&area) {
+            Some(size) => result.size = size,
+            None => result.has_more = true,
+        }
+        Some(result)
+    }
+}
+
+/// Draws on a [`Canvas`][].
+///
+/// All positions are relative to the upper left corner of the canvas.  Errors that occur while
+/// printing text are returned by the canvas once the drawing closure returns.
+///
+/// [`Canvas`]: struct.Canvas.html
+pub struct Painter<'c, 'p> {
+    context: &'c Context,
+    area: render::Area<'p>,
+    style: Style,
+    error: Option<Error>,
+}
+
+impl<'c, 'p> Painter<'c, 'p> {
+    /// Returns the size of the canvas.
+    pub fn size(&self) -> Size {
+        self.area.size()
+    }
+
+    /// Returns the context of the rendering process.
+    pub fn context(&self) -> &'c Context {
+        self.context
+    }
+
+    /// Returns the style of the canvas, i. e. the style of the enclosing element.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns the area of the canvas for lower-level drawing operations.
+    pub fn area(&self) -> &render::Area<'p> {
+        &self.area
+    }
+
+    /// Draws a line with the given points and the given line style, see
+    /// [`Area::draw_line`][].
+    ///
+    /// [`Area::draw_line`]: ../render/struct.Area.html#method.draw_line
+    pub fn line<I>(&mut self, points: I, line_style: LineStyle)
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        self.area.draw_line(points, line_style);
+    }
+
+    /// Draws a rectangle with the given position and size, filled with the given color and
+    /// outlined with the given line style.
+    pub fn rect(
+        &mut self,
+        position: impl Into<Position>,
+        size: impl Into<Size>,
+        fill_color: impl Into<Option<Color>>,
+        line_style: impl Into<Option<LineStyle>>,
+    ) {
+        let position = position.into();
+        let size = size.into();
+        let points = [
+            position,
+            Position::new(position.x + size.width, position.y),
+            Position::new(position.x + size.width, position.y + size.height),
+            Position::new(position.x, position.y + size.height),
+        ];
+        self.area.draw_shape(
+            points.iter().copied(),
+            fill_color.into().map(Fill::from),
+            line_style.into(),
+        );
+    }
+
+    /// Draws a circle with the given center and radius, filled with the given color and outlined
+    /// with the given line style.
+    pub fn circle(
+        &mut self,
+        center: impl Into<Position>,
+        radius: impl Into<Mm>,
+        fill_color: impl Into<Option<Color>>,
+        line_style: impl Into<Option<LineStyle>>,
+    ) {
+        let center = center.into();
+        let radius = radius.into();
+        self.area.draw_ellipse(
+            Position::new(center.x - radius, center.y - radius),
+            Size::new(radius * 2.0, radius * 2.0),
+            fill_color.into().map(Fill::from),
+            line_style.into(),
+        );
+    }
+
+    /// Prints the given text with the upper left corner at the given position.
+    ///
+    /// The style of the text is merged into the style of the canvas.  The text is not wrapped.
+    pub fn text(&mut self, position: impl Into<Position>, text: impl Into<StyledString>) {
+        let text = text.into();
+        let style = self.style.and(text.style);
+        let result = self
+            .area
+            .print_str(&self.context.font_cache, position.into(), style, &text.s);
+        if let Err(err) = result {
+            self.error.get_or_insert(err);
+        }
+    }
+
+    /// Creates a new path that can be drawn on the canvas, see [`Area::path`][].
+    ///
+    /// [`Area::path`]: ../render/struct.Area.html#method.path
+    pub fn path(&self) -> render::Path<'p> {
+        self.area.path()
+    }
+}
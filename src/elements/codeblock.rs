@@ -1,13 +1,94 @@
+use std::fs;
+use std::path;
+
 use crate::{
+    counter::Counters,
+    error::{Context as _, Error},
     fonts,
-    style::{Style, StyledStr},
+    style::{Color, Style, StyledStr, StyledString},
     Element, Mm, Position, RenderResult, Size,
 };
 
+use super::Paragraph;
+
+#[cfg(feature = "code-syntax-highlighting")]
+use crate::syntax_highlighting::ColorScheme;
+
+/// The default number of columns between two tab stops, see [`CodeBlock::set_tab_width`][].
+///
+/// [`CodeBlock::set_tab_width`]: struct.CodeBlock.html#method.set_tab_width
+const TAB_WIDTH: usize = 4;
+
+/// The glyph that is printed for tabs if whitespace is shown.
+const TAB_GLYPH: char = '»';
+
+/// The glyph that is printed for spaces if whitespace is shown.
+const SPACE_GLYPH: char = '·';
+
+/// The color of the glyphs for tabs and spaces if whitespace is shown.
+const WHITESPACE_COLOR: Color = Color::Greyscale(160);
+
+/// The default marker at the start of wrapped lines, see [`CodeBlock::set_wrap_lines`][].
+///
+/// [`CodeBlock::set_wrap_lines`]: struct.CodeBlock.html#method.set_wrap_lines
+const CONTINUATION_MARKER: &str = "» ";
+
+/// The label that is printed before the listing number in the caption.
+const LISTING_LABEL: &str = "Listing";
+
+/// The space between a code block and its caption.
+const CAPTION_SPACING: Mm = Mm(2.0);
+
+/// The position of the caption of a [`CodeBlock`][].
+///
+/// [`CodeBlock`]: struct.CodeBlock.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptionPosition {
+    /// The caption is printed above the code.
+    Above,
+    /// The caption is printed below the code.
+    Below,
+}
+
+impl Default for CaptionPosition {
+    fn default() -> CaptionPosition {
+        CaptionPosition::Below
+    }
+}
+
 /// A Block of Code that is usually rendered using a monospace font and utilizes syntax highlighting
+///
+/// Per default, every line of the code is printed as a single line, even if it is wider than the
+/// available area.  Long lines can be wrapped with [`set_wrap_lines`][].  Tabs are expanded to the
+/// next tab stop, see [`set_tab_width`][], and whitespace can be made visible with
+/// [`set_show_whitespace`][].
+///
+/// A caption can be added with [`set_caption`][].  If the code block is numbered with
+/// [`numbered`][] or [`set_number`][], the caption is prefixed with the listing number, for
+/// example `Listing 3: parser.rs`.  Per default, the caption is printed below the code, see
+/// [`set_caption_position`][].  A caption above the code is always kept together with the first
+/// line of the code.
+///
+/// [`set_wrap_lines`]: #method.set_wrap_lines
+/// [`set_tab_width`]: #method.set_tab_width
+/// [`set_show_whitespace`]: #method.set_show_whitespace
+/// [`set_caption`]: #method.set_caption
+/// [`numbered`]: #method.numbered
+/// [`set_number`]: #method.set_number
+/// [`set_caption_position`]: #method.set_caption_position
 pub struct CodeBlock {
     code: String,
     base_style: Style,
+    wrap_lines: bool,
+    continuation_marker: StyledString,
+    tab_width: usize,
+    show_whitespace: bool,
+    tabs_expanded: bool,
+    caption: Option<StyledString>,
+    number: Option<String>,
+    caption_position: CaptionPosition,
+    caption_paragraph: Option<Paragraph>,
+    is_caption_rendered: bool,
 
     #[cfg(feature = "code-syntax-highlighting")]
     only_regular_font: bool,
@@ -15,6 +96,8 @@ pub struct CodeBlock {
     language: String,
     #[cfg(feature = "code-syntax-highlighting")]
     theme: Option<String>,
+    #[cfg(feature = "code-syntax-highlighting")]
+    color_scheme: Option<ColorScheme>,
 }
 
 impl CodeBlock {
@@ -23,11 +106,29 @@ impl CodeBlock {
     pub fn new(code: &str, base_style: Style) -> Self {
         let code = code.to_string();
 
-        Self { code, base_style }
+        Self {
+            code,
+            base_style,
+            wrap_lines: false,
+            continuation_marker: default_continuation_marker(),
+            tab_width: TAB_WIDTH,
+            show_whitespace: false,
+            tabs_expanded: false,
+            caption: None,
+            number: None,
+            caption_position: CaptionPosition::default(),
+            caption_paragraph: None,
+            is_caption_rendered: false,
+        }
     }
 
     /// Create a new Codeblock that renders the given Code with the provided Theme, assuming the
     /// provided language
+    ///
+    /// If no theme is provided, the default theme of the syntax highlighter is used, see
+    /// [`SyntaxHighlighter::default_theme`][].
+    ///
+    /// [`SyntaxHighlighter::default_theme`]: ../syntax_highlighting/struct.SyntaxHighlighter.html#method.default_theme
     #[cfg(feature = "code-syntax-highlighting")]
     pub fn new(code: &str, language: &str, theme: Option<&str>, base_style: Style) -> Self {
         let code = code.to_string();
@@ -37,9 +138,207 @@ impl CodeBlock {
         Self {
             code,
             base_style,
+            wrap_lines: false,
+            continuation_marker: default_continuation_marker(),
+            tab_width: TAB_WIDTH,
+            show_whitespace: false,
+            tabs_expanded: false,
+            caption: None,
+            number: None,
+            caption_position: CaptionPosition::default(),
+            caption_paragraph: None,
+            is_caption_rendered: false,
             only_regular_font: false,
             language,
             theme,
+            color_scheme: None,
+        }
+    }
+
+    /// Sets the theme for this code block, overriding the default theme of the syntax
+    /// highlighter.
+    #[cfg(feature = "code-syntax-highlighting")]
+    pub fn set_theme(&mut self, theme: impl Into<String>) {
+        self.theme = Some(theme.into());
+    }
+
+    /// Sets the theme for this code block, overriding the default theme of the syntax
+    /// highlighter, and returns the code block.
+    #[cfg(feature = "code-syntax-highlighting")]
+    pub fn with_theme(mut self, theme: impl Into<String>) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    /// Sets the color scheme for this code block.
+    ///
+    /// If no theme is set for this code block, it is highlighted with the default theme of this
+    /// color scheme instead of the default theme of the syntax highlighter.  This is useful for
+    /// code blocks that are rendered on a background that differs from the rest of the document.
+    #[cfg(feature = "code-syntax-highlighting")]
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        self.color_scheme = Some(color_scheme);
+    }
+
+    /// Sets the color scheme for this code block and returns the code block.
+    #[cfg(feature = "code-syntax-highlighting")]
+    pub fn with_color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.set_color_scheme(color_scheme);
+        self
+    }
+
+    /// Sets whether lines that are wider than the available area are wrapped.
+    ///
+    /// If set, long lines are broken at the last character that fits into the area, and the
+    /// following lines start with the continuation marker, see [`set_continuation_marker`][].
+    /// The syntax highlighting is kept for all parts of a wrapped line.
+    ///
+    /// [`set_continuation_marker`]: #method.set_continuation_marker
+    pub fn set_wrap_lines(&mut self, wrap_lines: bool) {
+        self.wrap_lines = wrap_lines;
+    }
+
+    /// Sets whether lines that are wider than the available area are wrapped and returns the code
+    /// block.
+    pub fn with_wrap_lines(mut self, wrap_lines: bool) -> Self {
+        self.set_wrap_lines(wrap_lines);
+        self
+    }
+
+    /// Sets the marker that is printed at the start of wrapped lines.
+    ///
+    /// The style of the marker is merged into the base style of the code block.  Per default,
+    /// the marker is a grey `»` followed by a space.
+    pub fn set_continuation_marker(&mut self, marker: impl Into<StyledString>) {
+        self.continuation_marker = marker.into();
+    }
+
+    /// Sets the marker that is printed at the start of wrapped lines and returns the code block.
+    pub fn with_continuation_marker(mut self, marker: impl Into<StyledString>) -> Self {
+        self.set_continuation_marker(marker);
+        self
+    }
+
+    /// Sets the number of columns between two tab stops (defaults to 4).
+    ///
+    /// Tabs are expanded so that the following character starts at the next tab stop.  The tab
+    /// width only determines the alignment if the base style uses a monospace font.
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.tab_width = tab_width;
+    }
+
+    /// Sets the number of columns between two tab stops and returns the code block.
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.set_tab_width(tab_width);
+        self
+    }
+
+    /// Sets whether tabs and spaces are made visible.
+    ///
+    /// If set, tabs are printed as `»` and spaces as `·` in a light grey color.
+    pub fn set_show_whitespace(&mut self, show_whitespace: bool) {
+        self.show_whitespace = show_whitespace;
+    }
+
+    /// Sets whether tabs and spaces are made visible and returns the code block.
+    pub fn with_show_whitespace(mut self, show_whitespace: bool) -> Self {
+        self.set_show_whitespace(show_whitespace);
+        self
+    }
+
+    /// Sets the caption of this code block.
+    ///
+    /// The style of the caption is merged into the style of the enclosing element, i. e. the
+    /// caption does not use the base style of the code block.
+    pub fn set_caption(&mut self, caption: impl Into<StyledString>) {
+        self.caption = Some(caption.into());
+    }
+
+    /// Sets the caption of this code block and returns the code block.
+    pub fn with_caption(mut self, caption: impl Into<StyledString>) -> Self {
+        self.set_caption(caption);
+        self
+    }
+
+    /// Sets whether the caption is printed above or below the code.
+    pub fn set_caption_position(&mut self, position: CaptionPosition) {
+        self.caption_position = position;
+    }
+
+    /// Sets whether the caption is printed above or below the code and returns the code block.
+    pub fn with_caption_position(mut self, position: CaptionPosition) -> Self {
+        self.set_caption_position(position);
+        self
+    }
+
+    /// Returns the listing number of this code block, if set.
+    pub fn number(&self) -> Option<&str> {
+        self.number.as_deref()
+    }
+
+    /// Sets the listing number of this code block.
+    ///
+    /// The number is printed at the start of the caption.  If no caption is set, only the label
+    /// and the number are printed, for example `Listing 3`.
+    pub fn set_number(&mut self, number: impl Into<String>) {
+        self.number = Some(number.into());
+    }
+
+    /// Sets the listing number of this code block and returns the code block.
+    pub fn with_number(mut self, number: impl Into<String>) -> Self {
+        self.set_number(number);
+        self
+    }
+
+    /// Steps the `listing` counter of the given registry, uses its number as the listing number
+    /// and returns the code block.
+    ///
+    /// The assigned number can be queried with [`number`][] for cross references.
+    ///
+    /// [`number`]: #method.number
+    pub fn numbered(mut self, counters: &mut Counters) -> Self {
+        let number = counters.step("listing");
+        self.set_number(number);
+        self
+    }
+
+    /// Create a new Codeblock that renders the content of the file at the given path
+    ///
+    /// If the `code-syntax-highlighting` feature is enabled, the language is detected from the file
+    /// extension or, if that fails, from the first line of the file.  The code is highlighted with
+    /// the default theme of the syntax highlighter, see
+    /// [`SyntaxHighlighter::set_default_theme`][].
+    ///
+    /// [`SyntaxHighlighter::set_default_theme`]: ../syntax_highlighting/struct.SyntaxHighlighter.html#method.set_default_theme
+    pub fn from_file(path: impl AsRef<path::Path>, base_style: Style) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let code = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read code file {}", path.display()))?;
+
+        #[cfg(feature = "code-syntax-highlighting")]
+        {
+            let language = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or_default();
+            Ok(Self::new(&code, language, None, base_style))
+        }
+        #[cfg(not(feature = "code-syntax-highlighting"))]
+        {
+            Ok(Self::new(&code, base_style))
+        }
+    }
+
+    /// Returns the text of the caption including the listing number, if any.
+    fn caption_text(&self) -> Option<StyledString> {
+        match (&self.number, &self.caption) {
+            (Some(number), Some(caption)) => Some(StyledString::new(
+                format!("{} {}: {}", LISTING_LABEL, number, caption.s),
+                caption.style,
+            )),
+            (Some(number), None) => Some(format!("{} {}", LISTING_LABEL, number).into()),
+            (None, Some(caption)) => Some(caption.clone()),
+            (None, None) => None,
         }
     }
 
@@ -49,36 +348,49 @@ impl CodeBlock {
             .map(|line| vec![StyledStr::new(line, style)])
             .collect()
     }
-}
 
-impl Element for CodeBlock {
-    fn render(
+    /// Renders the code without the caption.
+    fn render_code(
         &mut self,
         context: &crate::Context,
-        mut area: crate::render::Area<'_>,
-        _style: crate::style::Style,
-    ) -> Result<crate::RenderResult, crate::error::Error> {
-        let mut result = RenderResult::default();
-
+        area: crate::render::Area<'_>,
+    ) -> Result<RenderResult, Error> {
         if self.code.is_empty() {
-            return Ok(result);
+            return Ok(RenderResult::default());
+        }
+        if !self.tabs_expanded {
+            self.code = expand_tabs(&self.code, self.tab_width);
+            self.tabs_expanded = true;
         }
 
         let highlighted_lines;
         #[cfg(feature = "code-syntax-highlighting")]
         {
-            if let Some(theme) = self.theme.as_ref() {
-                highlighted_lines = context
-                    .syntax_highlighter
-                    .as_ref()
-                    .expect("Trying to use Codeblocks without syntax highlighter")
+            let highlighter = context.syntax_highlighter.as_ref();
+            let theme = self
+                .theme
+                .as_deref()
+                .or_else(|| self.color_scheme.map(ColorScheme::default_theme));
+            let theme = if let Some(theme) = theme {
+                let highlighter = highlighter.ok_or_else(|| {
+                    Error::new(
+                        "Cannot highlight a code block without a syntax highlighter",
+                        crate::error::ErrorKind::InvalidData,
+                    )
+                })?;
+                Some((highlighter, theme))
+            } else {
+                highlighter.and_then(|h| h.default_theme().map(|theme| (h, theme)))
+            };
+            if let Some((highlighter, theme)) = theme {
+                highlighted_lines = highlighter
                     .highlight(
                         &self.code,
                         &self.language,
                         theme,
                         self.base_style,
                         self.only_regular_font,
-                    )
+                    )?
                     .unwrap_or_else(|| self.dummy_highlighting(self.base_style));
             } else {
                 highlighted_lines = self.dummy_highlighting(self.base_style);
@@ -89,41 +401,375 @@ impl Element for CodeBlock {
             highlighted_lines = self.dummy_highlighting(self.base_style);
         }
 
-        let mut rendered_chars = 0;
+        let lines: Vec<Vec<StyledString>> = highlighted_lines
+            .iter()
+            .map(|line| display_line(line, self.show_whitespace))
+            .collect();
+        let lines: Vec<Vec<StyledStr<'_>>> = lines
+            .iter()
+            .map(|line| line.iter().map(StyledStr::from).collect())
+            .collect();
 
-        for line in highlighted_lines {
-            let width: Mm = line.iter().map(|s| s.width(&context.font_cache)).sum();
-            // Calculate the maximum line height
-            let metrics = line
+        // The wrapped lines and the number of characters of the code in each line
+        let (lines, chars): (Vec<_>, Vec<usize>) = if self.wrap_lines {
+            let marker = StyledStr::new(
+                &self.continuation_marker.s,
+                self.base_style.and(self.continuation_marker.style),
+            );
+            wrap_lines(&lines, marker, &context.font_cache, area.size().width)
+        } else {
+            let chars = lines
                 .iter()
-                .map(|s| s.style.metrics(&context.font_cache))
-                .fold(fonts::Metrics::default(), |max, m| max.max(&m));
-
-            if let Some(mut section) =
-                area.text_section(&context.font_cache, Position::new(0, 0), metrics)
-            {
-                for s in line {
-                    // Trim to remove end line character
-                    section.print_str_xoff_trim(
-                        &s.s.trim_end_matches('\n'),
-                        s.style,
-                        Mm(0.0),
-                        false,
-                    )?;
-                    rendered_chars += s.s.chars().count();
+                .map(|line| line.iter().map(|s| s.s.chars().count()).sum())
+                .collect();
+            (lines, chars)
+        };
+
+        let (result, rendered_lines) = render_lines(&lines, context, area)?;
+        let rendered_chars: usize = chars[..rendered_lines].iter().sum();
+        let rendered_len = self
+            .code
+            .char_indices()
+            .nth(rendered_chars)
+            .map(|(idx, _)| idx)
+            .unwrap_or_else(|| self.code.len());
+        self.code.drain(..rendered_len);
+
+        Ok(result)
+    }
+}
+
+impl Element for CodeBlock {
+    fn render(
+        &mut self,
+        context: &crate::Context,
+        mut area: crate::render::Area<'_>,
+        style: crate::style::Style,
+    ) -> Result<crate::RenderResult, crate::error::Error> {
+        if !self.is_caption_rendered && self.caption_paragraph.is_none() {
+            self.caption_paragraph = self.caption_text().map(Paragraph::new);
+        }
+        let mut caption = match self.caption_paragraph.take() {
+            Some(caption) => caption,
+            None => return self.render_code(context, area),
+        };
+
+        let mut result = RenderResult::default();
+        match self.caption_position {
+            CaptionPosition::Above => {
+                // The caption is only rendered if at least one line of the code fits below it
+                let caption_height = caption
+                    .measure(context, area.clone(), style)
+                    .filter(|result| !result.has_more)
+                    .map(|result| result.size.height + CAPTION_SPACING)
+                    .filter(|height| *height < area.size().height);
+                if let Some(caption_height) = caption_height {
+                    let mut code_area = area.clone();
+                    code_area.add_offset(Position::new(0, caption_height));
+                    let code_result = self.render_code(context, code_area)?;
+                    if code_result.size.height > Mm(0.0) || !code_result.has_more {
+                        let caption_result = caption.render(context, area, style)?;
+                        self.is_caption_rendered = true;
+                        result.size = caption_result.size;
+                        result.size.height = caption_height;
+                        result.size = result.size.stack_vertical(code_result.size);
+                        result.has_more = code_result.has_more;
+                        return Ok(result);
+                    }
                 }
-            } else {
+                // Move the caption and the code to the next page
                 result.has_more = true;
-                break;
             }
-            result.size = result
-                .size
-                .stack_vertical(Size::new(width, metrics.line_height));
-            area.add_offset(Position::new(0, metrics.line_height));
+            CaptionPosition::Below => {
+                result = self.render_code(context, area.clone())?;
+                if !result.has_more {
+                    if result.size.height > Mm(0.0) {
+                        area.add_offset(Position::new(0, result.size.height + CAPTION_SPACING));
+                        result.size.height += CAPTION_SPACING;
+                    }
+                    let caption_result = caption.render(context, area, style)?;
+                    result.size = result.size.stack_vertical(caption_result.size);
+                    result.has_more = caption_result.has_more;
+                    if !result.has_more {
+                        self.is_caption_rendered = true;
+                        return Ok(result);
+                    }
+                }
+            }
+        }
+        self.caption_paragraph = Some(caption);
+        Ok(result)
+    }
+}
+
+/// Expands the tabs in the given code to the next tab stop.
+///
+/// The tab character is kept in the first column of the tab so that it can still be made visible,
+/// and the remaining columns are filled with spaces.  So every character of the returned code
+/// occupies exactly one column.
+fn expand_tabs(code: &str, tab_width: usize) -> String {
+    let tab_width = tab_width.max(1);
+    let mut expanded = String::with_capacity(code.len());
+    let mut column = 0;
+    for c in code.chars() {
+        expanded.push(c);
+        match c {
+            '\t' => {
+                let spaces = tab_width - column % tab_width - 1;
+                for _ in 0..spaces {
+                    expanded.push(' ');
+                }
+                column += spaces + 1;
+            }
+            '\n' => column = 0,
+            _ => column += 1,
         }
+    }
+    expanded
+}
 
-        self.code.drain(..rendered_chars);
+/// Returns the strings that are printed for the given line of code with expanded tabs.
+///
+/// Tabs are replaced with spaces.  If whitespace is shown, tabs and spaces are replaced with
+/// visible glyphs in a separate style instead.  In both cases, the number of characters does not
+/// change.
+fn display_line(line: &[StyledStr<'_>], show_whitespace: bool) -> Vec<StyledString> {
+    let mut strings: Vec<StyledString> = Vec::new();
+    for s in line {
+        let whitespace_style = s.style.with_color(WHITESPACE_COLOR);
+        for c in s.s.chars() {
+            let (c, style) = match c {
+                '\t' if show_whitespace => (TAB_GLYPH, whitespace_style),
+                ' ' if show_whitespace => (SPACE_GLYPH, whitespace_style),
+                '\t' => (' ', s.style),
+                c => (c, s.style),
+            };
+            match strings.last_mut() {
+                Some(last) if last.style == style => last.s.push(c),
+                _ => strings.push(StyledString::new(c.to_string(), style)),
+            }
+        }
+    }
+    strings
+}
 
-        Ok(result)
+/// Returns the default continuation marker for wrapped lines.
+fn default_continuation_marker() -> StyledString {
+    StyledString::new(CONTINUATION_MARKER, Color::Greyscale(128))
+}
+
+/// Breaks the given lines of styled strings so that they fit into the given width.
+///
+/// All lines but the first part of a line start with the given marker.  Returns the wrapped lines
+/// and the number of characters of the original lines in each wrapped line.
+fn wrap_lines<'a>(
+    lines: &[Vec<StyledStr<'a>>],
+    marker: StyledStr<'a>,
+    font_cache: &fonts::FontCache,
+    width: Mm,
+) -> (Vec<Vec<StyledStr<'a>>>, Vec<usize>) {
+    let marker_width = marker.width(font_cache);
+
+    let mut wrapped_lines = Vec::new();
+    let mut wrapped_chars = Vec::new();
+    for line in lines {
+        let mut wrapped_line = Vec::new();
+        let mut line_width = Mm(0.0);
+        let mut chars = 0;
+        for s in line {
+            let mut start = 0;
+            for (idx, c) in s.s.char_indices() {
+                // The line break at the end of the line is not printed
+                let char_width = if c == '\n' {
+                    Mm(0.0)
+                } else {
+                    s.style.str_width(font_cache, &s.s[idx..idx + c.len_utf8()])
+                };
+                // Every line contains at least one character of the code
+                if line_width + char_width > width && chars > 0 {
+                    if idx > start {
+                        wrapped_line.push(StyledStr::new(&s.s[start..idx], s.style));
+                    }
+                    wrapped_lines.push(wrapped_line);
+                    wrapped_chars.push(chars);
+                    wrapped_line = vec![marker];
+                    line_width = marker_width;
+                    chars = 0;
+                    start = idx;
+                }
+                line_width += char_width;
+                chars += 1;
+            }
+            if start < s.s.len() {
+                wrapped_line.push(StyledStr::new(&s.s[start..], s.style));
+            }
+        }
+        wrapped_lines.push(wrapped_line);
+        wrapped_chars.push(chars);
+    }
+    (wrapped_lines, wrapped_chars)
+}
+
+/// Renders the given lines of styled strings without wrapping them and returns the render result
+/// and the number of lines that have been rendered.
+pub(super) fn render_lines(
+    lines: &[Vec<StyledStr<'_>>],
+    context: &crate::Context,
+    mut area: crate::render::Area<'_>,
+) -> Result<(RenderResult, usize), Error> {
+    let mut result = RenderResult::default();
+    let mut rendered_lines = 0;
+
+    for line in lines {
+        let width: Mm = line.iter().map(|s| s.width(&context.font_cache)).sum();
+        // Calculate the maximum line height
+        let metrics = line
+            .iter()
+            .map(|s| s.style.metrics(&context.font_cache))
+            .fold(fonts::Metrics::default(), |max, m| max.max(&m));
+
+        if let Some(mut section) =
+            area.text_section(&context.font_cache, Position::new(0, 0), metrics)
+        {
+            let mut decoration_area = area.clone();
+            for s in line {
+                // Trim to remove end line character
+                let text = s.s.trim_end_matches('\n');
+                section.print_str_xoff_trim(text, s.style, Mm(0.0), false)?;
+
+                let width = s.style.str_width(&context.font_cache, text);
+                super::draw_text_decorations(&decoration_area, s.style, width, metrics);
+                decoration_area.add_offset(Position::new(width, 0));
+            }
+        } else {
+            result.has_more = true;
+            break;
+        }
+        result.size = result
+            .size
+            .stack_vertical(Size::new(width, metrics.line_height));
+        area.add_offset(Position::new(0, metrics.line_height));
+        rendered_lines += 1;
+    }
+
+    Ok((result, rendered_lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_line, expand_tabs, wrap_lines, CodeBlock, WHITESPACE_COLOR};
+    use crate::counter::Counters;
+    use crate::style::{Color, Style, StyledStr, StyledString};
+    use crate::{Context, Mm};
+
+    fn code_block() -> CodeBlock {
+        #[cfg(feature = "code-syntax-highlighting")]
+        {
+            CodeBlock::new("fn main() {}", "rs", None, Style::new())
+        }
+        #[cfg(not(feature = "code-syntax-highlighting"))]
+        {
+            CodeBlock::new("fn main() {}", Style::new())
+        }
+    }
+
+    #[test]
+    fn test_caption_text() {
+        let bold = Style::new().bold();
+        let caption = |block: &CodeBlock| block.caption_text().map(|s| (s.s, s.style));
+        let mut counters = Counters::new();
+
+        assert_eq!(caption(&code_block()), None);
+        let block = code_block().with_caption(StyledString::new("main.rs", bold));
+        assert_eq!(caption(&block), Some(("main.rs".to_owned(), bold)));
+
+        let block = code_block().numbered(&mut counters);
+        assert_eq!(block.number(), Some("1"));
+        assert_eq!(
+            caption(&block),
+            Some(("Listing 1".to_owned(), Style::new()))
+        );
+
+        let block = code_block()
+            .with_caption(StyledString::new("main.rs", bold))
+            .numbered(&mut counters);
+        assert_eq!(
+            caption(&block),
+            Some(("Listing 2: main.rs".to_owned(), bold))
+        );
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("\ta\n  \tb\tc", 4), "\t   a\n  \t b\t  c");
+        assert_eq!(expand_tabs("a\tb", 1), "a\tb");
+        assert_eq!(expand_tabs("a\tb", 0), "a\tb");
+    }
+
+    #[test]
+    fn test_display_line() {
+        let style = Style::new().with_font_size(10);
+        let keyword = style.with_color(Color::Rgb(255, 0, 0));
+        let line = vec![
+            StyledStr::new("\t   if", keyword),
+            StyledStr::new(" x\n", style),
+        ];
+
+        let strings: Vec<_> = display_line(&line, false)
+            .into_iter()
+            .map(|s| (s.s, s.style))
+            .collect();
+        assert_eq!(
+            strings,
+            vec![("    if".to_owned(), keyword), (" x\n".to_owned(), style)]
+        );
+
+        let whitespace = |style: Style| style.with_color(WHITESPACE_COLOR);
+        let strings: Vec<_> = display_line(&line, true)
+            .into_iter()
+            .map(|s| (s.s, s.style))
+            .collect();
+        assert_eq!(
+            strings,
+            vec![
+                ("»···".to_owned(), whitespace(keyword)),
+                ("if".to_owned(), keyword),
+                ("·".to_owned(), whitespace(style)),
+                ("x\n".to_owned(), style),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let context = Context::fake();
+        // Every character of the test font is 0.6 em wide, i. e. 2.117 mm with 10 pt
+        let style = Style::new().with_font_size(10);
+        let keyword = style.with_color(Color::Rgb(255, 0, 0));
+        let marker = StyledStr::new("» ", style);
+        let lines = vec![
+            vec![
+                StyledStr::new("abc", keyword),
+                StyledStr::new("defghij\n", style),
+            ],
+            vec![StyledStr::new("xy", style)],
+        ];
+
+        let (lines, chars) = wrap_lines(&lines, marker, &context.font_cache, Mm(10.0));
+        let lines: Vec<Vec<_>> = lines
+            .iter()
+            .map(|line| line.iter().map(|s| (s.s, s.style)).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                vec![("abc", keyword), ("d", style)],
+                vec![("» ", style), ("ef", style)],
+                vec![("» ", style), ("gh", style)],
+                vec![("» ", style), ("ij\n", style)],
+                vec![("xy", style)],
+            ]
+        );
+        assert_eq!(chars, vec![4, 2, 2, 3, 2]);
     }
 }
@@ -4,11 +4,12 @@
 
 //! Image support for genpdf-rs.
 
+use std::io;
 use std::path;
 
 use image::GenericImageView;
 
-use crate::error::{Context as _, Error, ErrorKind};
+use crate::error::{Context as _, Error, ErrorKind, Warning, WarningKind};
 use crate::{render, style};
 use crate::{Alignment, Context, Element, Mm, Position, RenderResult, Rotation, Scale, Size};
 
@@ -89,36 +90,110 @@ impl Image {
         }
     }
 
-    fn from_image_reader<R>(reader: image::io::Reader<R>) -> Result<Self, Error>
+    fn from_image_reader<R>(
+        reader: image::io::Reader<R>,
+        limits: ImageLimits,
+    ) -> Result<Self, Error>
     where
         R: std::io::BufRead,
         R: std::io::Read,
         R: std::io::Seek,
     {
-        let image = reader
+        let mut reader = reader
             .with_guessed_format()
-            .context("Could not determine image format")?
-            .decode()
-            .context("Could not decode image")?;
+            .context("Could not determine image format")?;
+        if let Some(max_pixels) = limits.max_pixels {
+            // Read the dimensions from the image header before decoding the image
+            let format = reader.format();
+            let mut inner = reader.into_inner();
+            let start = inner.stream_position().context("Could not read image")?;
+            let mut dimensions_reader = image::io::Reader::new(&mut inner);
+            if let Some(format) = format {
+                dimensions_reader.set_format(format);
+            }
+            let (width, height) = dimensions_reader
+                .into_dimensions()
+                .context("Could not read image dimensions")?;
+            let pixels = u64::from(width) * u64::from(height);
+            if pixels > max_pixels {
+                return Err(Error::new(
+                    format!(
+                        "The image has {} x {} = {} pixels, but at most {} pixels are allowed",
+                        width, height, pixels, max_pixels
+                    ),
+                    ErrorKind::InvalidData,
+                ));
+            }
+            inner
+                .seek(io::SeekFrom::Start(start))
+                .context("Could not read image")?;
+            reader = image::io::Reader::new(inner);
+            if let Some(format) = format {
+                reader.set_format(format);
+            }
+        }
+
+        let mut decoding_limits = image::io::Limits::default();
+        decoding_limits.max_alloc = limits.max_memory;
+        reader.limits(decoding_limits);
+        let image = reader.decode().map_err(|err| match err {
+            image::ImageError::Limits(_) => Error::new(
+                format!(
+                    "Could not decode image: the decoded image would exceed the memory limit of \
+                     {} bytes",
+                    limits.max_memory.unwrap_or_default()
+                ),
+                err,
+            ),
+            err => Error::new("Could not decode image", err),
+        })?;
         Self::from_dynamic_image(image)
     }
 
     /// Creates a new image from the given reader.
+    ///
+    /// The image is decoded with the default [`ImageLimits`][].
+    ///
+    /// [`ImageLimits`]: struct.ImageLimits.html
     pub fn from_reader<R>(reader: R) -> Result<Self, Error>
     where
         R: std::io::BufRead,
         R: std::io::Read,
         R: std::io::Seek,
     {
-        Self::from_image_reader(image::io::Reader::new(reader))
+        Self::from_reader_with_limits(reader, ImageLimits::default())
+    }
+
+    /// Creates a new image from the given reader and checks the given limits before and while
+    /// decoding the image.
+    pub fn from_reader_with_limits<R>(reader: R, limits: ImageLimits) -> Result<Self, Error>
+    where
+        R: std::io::BufRead,
+        R: std::io::Read,
+        R: std::io::Seek,
+    {
+        Self::from_image_reader(image::io::Reader::new(reader), limits)
     }
 
     /// Creates a new image by reading from the given path.
+    ///
+    /// The image is decoded with the default [`ImageLimits`][].
+    ///
+    /// [`ImageLimits`]: struct.ImageLimits.html
     pub fn from_path(path: impl AsRef<path::Path>) -> Result<Self, Error> {
+        Self::from_path_with_limits(path, ImageLimits::default())
+    }
+
+    /// Creates a new image by reading from the given path and checks the given limits before and
+    /// while decoding the image.
+    pub fn from_path_with_limits(
+        path: impl AsRef<path::Path>,
+        limits: ImageLimits,
+    ) -> Result<Self, Error> {
         let path = path.as_ref();
         let reader = image::io::Reader::open(path)
             .with_context(|| format!("Could not read image from path {}", path.display()))?;
-        Self::from_image_reader(reader)
+        Self::from_image_reader(reader, limits)
     }
 
     /// Translates the image over to position.
@@ -214,10 +289,92 @@ impl Image {
     }
 }
 
+/// Limits for decoding an [`Image`][].
+///
+/// *Only available if the `images` feature is enabled.*
+///
+/// Images from untrusted sources can be crafted so that decoding them requires huge amounts of
+/// memory (decompression bombs).  These limits are checked when an image is loaded with
+/// [`Image::from_reader_with_limits`][] or [`Image::from_path_with_limits`][]:  The pixel count
+/// is read from the image header before the image is decoded, and the memory limit is passed to
+/// the decoder.  If a limit is exceeded, an error is returned.
+///
+/// Per default, the pixel count is not limited and the memory limit is 512 MiB, just as in the
+/// [`image`][] crate.
+///
+/// # Example
+///
+/// ```no_run
+/// use genpdf::elements;
+/// let limits = elements::ImageLimits::new()
+///     .with_max_pixels(4096 * 4096)
+///     .with_max_memory(64 * 1024 * 1024);
+/// let image = elements::Image::from_path_with_limits("upload.png", limits)
+///     .expect("Failed to load image");
+/// ```
+///
+/// [`Image`]: struct.Image.html
+/// [`Image::from_reader_with_limits`]: struct.Image.html#method.from_reader_with_limits
+/// [`Image::from_path_with_limits`]: struct.Image.html#method.from_path_with_limits
+/// [`image`]: https://lib.rs/crates/image
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageLimits {
+    max_pixels: Option<u64>,
+    max_memory: Option<u64>,
+}
+
+impl ImageLimits {
+    /// Creates new image limits with the default values.
+    pub fn new() -> ImageLimits {
+        ImageLimits::default()
+    }
+
+    /// Creates new image limits that do not restrict the decoding.
+    pub fn unlimited() -> ImageLimits {
+        ImageLimits {
+            max_pixels: None,
+            max_memory: None,
+        }
+    }
+
+    /// Sets the maximum number of pixels (width times height) of the image.
+    pub fn set_max_pixels(&mut self, max_pixels: u64) {
+        self.max_pixels = Some(max_pixels);
+    }
+
+    /// Sets the maximum number of pixels (width times height) of the image and returns the
+    /// limits.
+    pub fn with_max_pixels(mut self, max_pixels: u64) -> Self {
+        self.set_max_pixels(max_pixels);
+        self
+    }
+
+    /// Sets the maximum number of bytes that may be allocated for decoding the image.
+    pub fn set_max_memory(&mut self, max_memory: u64) {
+        self.max_memory = Some(max_memory);
+    }
+
+    /// Sets the maximum number of bytes that may be allocated for decoding the image and returns
+    /// the limits.
+    pub fn with_max_memory(mut self, max_memory: u64) -> Self {
+        self.set_max_memory(max_memory);
+        self
+    }
+}
+
+impl Default for ImageLimits {
+    fn default() -> ImageLimits {
+        ImageLimits {
+            max_pixels: None,
+            max_memory: Some(512 * 1024 * 1024),
+        }
+    }
+}
+
 impl Element for Image {
     fn render(
         &mut self,
-        _context: &Context,
+        context: &Context,
         area: render::Area<'_>,
         _style: style::Style,
     ) -> Result<RenderResult, Error> {
@@ -233,6 +390,21 @@ impl Element for Image {
             self.scale.y = autoscale * curr_scale_value.y;
         }
 
+        let min_scale = context.min_image_scale();
+        if self.scale.x.min(self.scale.y) < min_scale {
+            let warning = Warning::new(
+                format!(
+                    "An image has been scaled by {:.2} x {:.2}, below the minimum scale of {:.2}",
+                    self.scale.x, self.scale.y, min_scale
+                ),
+                WarningKind::DownscaledImage,
+            );
+            if let Err(err) = context.report_warning(&area, warning) {
+                self.scale = curr_scale_value;
+                return Err(err);
+            }
+        }
+
         let mut result = RenderResult::default();
         let true_size = self.get_size();
         let (bb_origin, bb_size) = bounding_box_offset_and_size(&self.rotation, &true_size);
@@ -267,6 +439,15 @@ impl Element for Image {
 
         Ok(result)
     }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: style::Style,
+    ) -> Option<RenderResult> {
+        self.clone().render(context, area.dry_run(), style).ok()
+    }
 }
 
 /// Given the Size of a box (width/height), compute the bounding-box size and offset when
@@ -274,6 +455,9 @@ impl Element for Image {
 /// to the (originally) lower-left corner of the image.
 #[allow(clippy::manual_range_contains)]
 fn bounding_box_offset_and_size(rotation: &Rotation, size: &Size) -> (Position, Size) {
+    // Rotations can be added and subtracted, so they might be out of range
+    let rotation = Rotation::from_degrees(rotation.degrees);
+
     // alpha = rotation, beta = 90 - rotation
     let alpha = rotation.degrees.to_radians();
     let beta = (90.0 - rotation.degrees).to_radians();
@@ -315,7 +499,7 @@ fn bounding_box_offset_and_size(rotation: &Rotation, size: &Size) -> (Position,
 
 #[cfg(test)]
 mod tests {
-    use super::bounding_box_offset_and_size;
+    use super::{bounding_box_offset_and_size, Image, ImageLimits};
     use crate::{Position, Rotation, Size};
     use float_cmp::approx_eq;
 
@@ -548,4 +732,24 @@ mod tests {
         test_position(size, 90.0, Position::new(100, 200));
         test_position(size, 180.0, Position::new(200, 0));
     }
+
+    #[test]
+    fn test_limits() {
+        let path = "examples/images/test_image.png";
+        let image = Image::from_path(path).expect("Failed to load test image");
+        let (width, height) = image::GenericImageView::dimensions(&image.data);
+        let pixels = u64::from(width) * u64::from(height);
+
+        assert!(
+            Image::from_path_with_limits(path, ImageLimits::new().with_max_pixels(pixels)).is_ok()
+        );
+        assert!(
+            Image::from_path_with_limits(path, ImageLimits::new().with_max_pixels(pixels - 1))
+                .is_err()
+        );
+        assert!(
+            Image::from_path_with_limits(path, ImageLimits::new().with_max_memory(16)).is_err()
+        );
+        assert!(Image::from_path_with_limits(path, ImageLimits::unlimited()).is_ok());
+    }
 }
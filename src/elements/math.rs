@@ -1,13 +1,63 @@
+use std::rc::Rc;
+
 use rex::{error::ParseError, parser::ParseNode};
 
 use crate::{
-    math::MathOp, render, style::LineStyle, Alignment, Context, Element, Position, RenderResult,
+    counter::Counters,
+    error::{Error, ErrorKind},
+    math::{MathBlock, MathOp, MathRenderer, MathStyle},
+    render,
+    style::{Color, LineStyle, Style},
+    Alignment, Context, Element, Mm, Position, RenderResult, Size,
 };
 
+/// The space between a formula and its equation number.
+const NUMBER_SPACING: Mm = Mm(5.0);
+
 /// An element that can render LaTeX-styled math formulas to a PDF document
+///
+/// The formula is rendered with the font size and the color of the style of the element, so it
+/// matches the surrounding text.  Parts of the formula can be colored explicitly with `\color`
+/// in the source.
+///
+/// If math rendering has not been enabled with [`Document::enable_math`][] or if the formula
+/// cannot be laid out, rendering fails with a [`Math`][] error.  Formulas cannot be split, so a
+/// formula that does not fit on the current page is moved to the next page.  If it is wider than
+/// the area or higher than a page, rendering fails with a [`PageSizeExceeded`][] error.
+///
+/// Display equations can be numbered with [`numbered`][] or [`with_number`][].  The number is
+/// printed in parentheses at the right edge of the area, aligned to the baseline of the formula.
+/// The formula is then aligned within the remaining width left of the number.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use genpdf::elements::{Math, Paragraph};
+/// # let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)?;
+/// let mut doc = genpdf::Document::new(font_family);
+/// let math = Math::new(r"e^{i\pi} + 1 = 0")
+///     .map_err(|err| format!("{:?}", err))?
+///     .numbered(doc.counters_mut());
+/// let reference = format!("Equation ({}) is Euler's identity.", math.number().unwrap_or_default());
+/// doc.push(math);
+/// doc.push(Paragraph::new(reference));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`numbered`]: #method.numbered
+/// [`with_number`]: #method.with_number
+/// [`Document::enable_math`]: ../struct.Document.html#method.enable_math
+/// [`Math`]: ../error/enum.ErrorKind.html#variant.Math
+/// [`PageSizeExceeded`]: ../error/enum.ErrorKind.html#variant.PageSizeExceeded
 pub struct Math {
+    source: String,
     rex_ast: Vec<ParseNode>,
     alignment: Alignment,
+    scale: f64,
+    style: MathStyle,
+    number: Option<String>,
 }
 
 impl Math {
@@ -15,8 +65,12 @@ impl Math {
     pub fn new(source: &str) -> Result<Self, ParseError<'_>> {
         let rex_ast = rex::parser::parse(source)?;
         Ok(Self {
+            source: source.to_owned(),
             rex_ast,
             alignment: Alignment::Left,
+            scale: 1.0,
+            style: MathStyle::Display,
+            number: None,
         })
     }
 
@@ -30,6 +84,89 @@ impl Math {
     pub fn set_alignment(&mut self, alignment: Alignment) {
         self.alignment = alignment;
     }
+
+    /// Sets the scaling factor for the font size of the Math block
+    ///
+    /// The formula is rendered with the font size of the surrounding style multiplied by this
+    /// factor.  Per default, the factor is 1.
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale;
+    }
+
+    /// Sets the scaling factor for the font size of the Math block and returns the Math block
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.set_scale(scale);
+        self
+    }
+
+    /// Sets the layout style of the Math block
+    ///
+    /// In the [`Text`][] style, large operators like sums and integrals are smaller, limits are
+    /// placed next to the operators instead of above and below them, and fractions are more
+    /// compact.  This is useful for formulas in table cells or other places with limited space.
+    /// Per default, the [`Display`][] style is used.
+    ///
+    /// [`Text`]: ../math/enum.MathStyle.html#variant.Text
+    /// [`Display`]: ../math/enum.MathStyle.html#variant.Display
+    pub fn set_style(&mut self, style: MathStyle) {
+        self.style = style;
+    }
+
+    /// Sets the layout style of the Math block and returns the Math block
+    pub fn with_style(mut self, style: MathStyle) -> Self {
+        self.set_style(style);
+        self
+    }
+
+    /// Renders the Math block in the text (inline) style instead of the display style
+    ///
+    /// This is equivalent to `with_style(MathStyle::Text)`, see [`set_style`][].
+    ///
+    /// [`set_style`]: #method.set_style
+    pub fn inline_style(mut self) -> Self {
+        self.set_inline_style(true);
+        self
+    }
+
+    /// Sets whether the Math block is rendered in the text (inline) style instead of the display
+    /// style
+    pub fn set_inline_style(&mut self, inline_style: bool) {
+        self.style = if inline_style {
+            MathStyle::Text
+        } else {
+            MathStyle::Display
+        };
+    }
+
+    /// Returns the equation number of the Math block, if set
+    pub fn number(&self) -> Option<&str> {
+        self.number.as_deref()
+    }
+
+    /// Sets the equation number of the Math block
+    ///
+    /// The number is printed in parentheses at the right edge of the area.
+    pub fn set_number(&mut self, number: impl Into<String>) {
+        self.number = Some(number.into());
+    }
+
+    /// Sets the equation number of the Math block and returns the Math block
+    pub fn with_number(mut self, number: impl Into<String>) -> Self {
+        self.set_number(number);
+        self
+    }
+
+    /// Steps the `equation` counter of the given registry, uses its number as the equation number
+    /// and returns the Math block
+    ///
+    /// The assigned number can be queried with [`number`][] for cross references.
+    ///
+    /// [`number`]: #method.number
+    pub fn numbered(mut self, counters: &mut Counters) -> Self {
+        let number = counters.step("equation");
+        self.set_number(number);
+        self
+    }
 }
 
 impl Element for Math {
@@ -37,52 +174,256 @@ impl Element for Math {
         &mut self,
         context: &Context,
         area: render::Area<'_>,
-        style: crate::style::Style,
-    ) -> Result<RenderResult, crate::error::Error> {
-        let math_renderer = context
-            .math_renderer
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let math_renderer = get_math_renderer(context)?;
+
+        let font_size = f64::from(style.font_size()) * self.scale;
+        let color = style.color().unwrap_or(Color::Rgb(0, 0, 0));
+        let block =
+            math_renderer.render(&self.source, &self.rex_ast, font_size, color, self.style)?;
+
+        let label = self.number.as_ref().map(|number| format!("({})", number));
+        let label_width = label
             .as_ref()
-            .expect("Tried to use math element without an active math font");
+            .map(|label| style.str_width(&context.font_cache, label) + NUMBER_SPACING)
+            .unwrap_or_default();
 
-        let block = math_renderer.render(style.font_size() as f64, &self.rex_ast);
+        let width = block.size.width + label_width;
+        if width > area.size().width {
+            return Err(Error::new(
+                format!(
+                    "The math formula {:?} requires a width of {:.1} mm, but only {:.1} mm are \
+                     available",
+                    self.source,
+                    width.0,
+                    area.size().width.0
+                ),
+                ErrorKind::PageSizeExceeded,
+            ));
+        }
 
-        let x_origin = match self.alignment {
-            Alignment::Left | Alignment::Justified(_) => 0.0,
-            Alignment::Center => (area.size().width / 2.0 - block.size.width / 2.0).0,
-            Alignment::Right => (area.size().width - block.size.width).0,
-        };
+        let x_origin = formula_x(
+            self.alignment,
+            area.size().width - label_width,
+            block.size.width,
+        );
 
-        for op in block.ops() {
-            match op {
-                MathOp::TextSection(text_section) => {
-                    area.print_positioned_codepoints(
-                        &context.font_cache,
-                        Position::new(x_origin, text_section.y_origin),
-                        text_section.x_offsets.clone().into_iter(),
-                        text_section.glyph_ids.clone().into_iter(),
-                        text_section.font_size,
-                        style
-                            .with_color(text_section.color)
-                            .with_font_family(math_renderer.font_family()),
-                    );
-                }
-                MathOp::Rule(rule) => area.draw_line(
+        let mut result = RenderResult::default();
+        result.size = block.size;
+
+        // The number is printed on the baseline of the formula.  If it extends above the formula,
+        // the formula is moved down.
+        let mut y_origin = Mm(0.0);
+        let mut label_y = Mm(0.0);
+        if label.is_some() {
+            let metrics = style.metrics(&context.font_cache);
+            let baseline = math_baseline(context, math_renderer, &block, font_size, style);
+            y_origin = (metrics.ascent - baseline).max(Mm(0.0));
+            label_y = y_origin + baseline - metrics.ascent;
+            result.size.width = area.size().width;
+            result.size.height = (y_origin + block.size.height).max(label_y + metrics.glyph_height);
+        }
+
+        if result.size.height > area.size().height {
+            // The formula cannot be split, so we move it to the next page unless it is higher
+            // than the content box of a page
+            let page_height = context
+                .page_area()
+                .map(|page_area| page_area.content_box().size.height);
+            if page_height.map(|h| result.size.height > h).unwrap_or(false) {
+                return Err(Error::new(
+                    format!(
+                        "The math formula {:?} requires a height of {:.1} mm, but the pages only \
+                         have a height of {:.1} mm",
+                        self.source,
+                        result.size.height.0,
+                        page_height.unwrap_or_default().0
+                    ),
+                    ErrorKind::PageSizeExceeded,
+                ));
+            }
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+
+        if let Some(label) = &label {
+            let label_x = area.size().width - style.str_width(&context.font_cache, label);
+            area.print_str(
+                &context.font_cache,
+                Position::new(label_x, label_y),
+                style,
+                label,
+            )?;
+        }
+        draw_math_block(
+            context,
+            &area,
+            math_renderer,
+            &block,
+            Position::new(x_origin, y_origin),
+            style,
+        )?;
+
+        Ok(result)
+    }
+}
+
+/// Returns the horizontal offset of a formula with the given width and alignment if the given
+/// width is available for it, i. e. the width of the area without the equation number.
+fn formula_x(alignment: Alignment, available_width: Mm, formula_width: Mm) -> Mm {
+    match alignment {
+        Alignment::Left | Alignment::Justified(_) => Mm(0.0),
+        Alignment::Center => (available_width - formula_width) / 2.0,
+        Alignment::Right => available_width - formula_width,
+    }
+}
+
+/// Returns the math renderer of the given context or an error if math rendering has not been
+/// enabled.
+fn get_math_renderer(context: &Context) -> Result<&MathRenderer, Error> {
+    context.math_renderer.as_ref().ok_or_else(|| {
+        Error::new(
+            "Cannot render a math element without a math font",
+            ErrorKind::Math,
+        )
+    })
+}
+
+/// Parses and renders the given formula in the text style with the font size and the color of
+/// the given style, as used for inline formulas in a paragraph.
+pub(super) fn render_inline_math(
+    context: &Context,
+    source: &str,
+    style: Style,
+) -> Result<Rc<MathBlock>, Error> {
+    let math_renderer = get_math_renderer(context)?;
+    let rex_ast = rex::parser::parse(source).map_err(|err| {
+        Error::new(
+            format!("Failed to parse math formula {:?}: {:?}", source, err),
+            ErrorKind::Math,
+        )
+    })?;
+    let color = style.color().unwrap_or(Color::Rgb(0, 0, 0));
+    math_renderer.render(
+        source,
+        &rex_ast,
+        f64::from(style.font_size()),
+        color,
+        MathStyle::Text,
+    )
+}
+
+/// Returns the distance between the top of the given math block and its baseline if it is drawn
+/// with the given style.
+///
+/// The baseline is the baseline of the glyphs with the given font size.  If there are no such
+/// glyphs, for example for a fraction, the block is centered on the math axis.
+pub(super) fn math_baseline(
+    context: &Context,
+    math_renderer: &MathRenderer,
+    block: &MathBlock,
+    font_size: f64,
+    style: Style,
+) -> Mm {
+    let math_style = style.with_font_family(math_renderer.font_family());
+    let metrics = math_style.metrics(&context.font_cache);
+    let y_origin = block.ops().iter().find_map(|op| match op {
+        MathOp::TextSection(section) if (section.font_size - font_size).abs() < 0.01 => {
+            Some(section.y_origin)
+        }
+        _ => None,
+    });
+    match y_origin {
+        Some(y_origin) => Mm(y_origin) + metrics.ascent,
+        // The math axis is approximately at half of the x-height, i. e. a quarter of the glyph
+        // height above the baseline
+        None => block.size.height / 2.0 + metrics.glyph_height / 4.0,
+    }
+}
+
+/// Draws the given math block with its upper left corner at the given origin.
+pub(super) fn draw_math_block(
+    context: &Context,
+    area: &render::Area<'_>,
+    math_renderer: &MathRenderer,
+    block: &MathBlock,
+    origin: Position,
+    style: Style,
+) -> Result<(), Error> {
+    for op in block.ops() {
+        match op {
+            MathOp::TextSection(text_section) => {
+                area.print_positioned_codepoints(
+                    &context.font_cache,
+                    Position::new(origin.x, origin.y + Mm(text_section.y_origin)),
+                    text_section.x_offsets.clone().into_iter(),
+                    text_section.glyph_ids.clone().into_iter(),
+                    text_section.font_size,
+                    style
+                        .with_color(text_section.color)
+                        .with_font_family(math_renderer.font_family()),
+                )?;
+            }
+            MathOp::Rule(rule) => {
+                let x = origin.x + Mm(rule.x);
+                let y = origin.y + Mm(rule.y);
+                let width = Mm(rule.width);
+                let height = Mm(rule.height);
+                area.draw_line(
                     vec![
-                        Position::new(x_origin + rule.x, rule.y),
-                        Position::new(x_origin + rule.x + rule.width, rule.y),
-                        Position::new(x_origin + rule.x + rule.width, rule.y + rule.height),
-                        Position::new(x_origin + rule.x, rule.y + rule.height),
-                        Position::new(x_origin + rule.x, rule.y),
+                        Position::new(x, y),
+                        Position::new(x + width, y),
+                        Position::new(x + width, y + height),
+                        Position::new(x, y + height),
+                        Position::new(x, y),
                     ],
                     LineStyle::default()
                         .with_color(rule.color)
                         .with_filled(true),
-                ),
+                )
             }
         }
+    }
+    Ok(())
+}
 
-        let mut result = RenderResult::default();
-        result.size = block.size;
-        Ok(result)
+#[cfg(test)]
+mod tests {
+    use super::{formula_x, NUMBER_SPACING};
+    use crate::{Alignment, Mm};
+
+    #[test]
+    fn test_formula_x() {
+        let area_width = Mm(100.0);
+        let formula_width = Mm(60.0);
+        let label_width = Mm(20.0);
+
+        // Without a number, the formula is aligned within the full area
+        assert_eq!(
+            formula_x(Alignment::Center, area_width, formula_width),
+            Mm(20.0)
+        );
+
+        // With a number, the formula box must not intersect the box of the number at the right
+        // edge of the area
+        let label_x = area_width - label_width;
+        let available_width = area_width - label_width - NUMBER_SPACING;
+        for alignment in &[
+            Alignment::Left,
+            Alignment::Center,
+            Alignment::Right,
+            Alignment::Justified(false),
+        ] {
+            let x = formula_x(*alignment, available_width, formula_width);
+            assert!(x >= Mm(0.0));
+            assert!(x + formula_width + NUMBER_SPACING <= label_x);
+        }
+        assert_eq!(
+            formula_x(Alignment::Center, available_width, formula_width),
+            Mm(7.5)
+        );
     }
 }
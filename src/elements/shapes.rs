@@ -0,0 +1,499 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+use crate::{
+    error::Error,
+    render,
+    style::{Color, Fill, LineStyle, Style},
+    Context, Element, Mm, Position, RenderResult, Size,
+};
+
+use super::measure_copy;
+
+/// A rectangle with an optional fill color and outline.
+///
+/// The rectangle is drawn at the given position relative to the upper left corner of the area
+/// that it is rendered in.  The outline is drawn inside of the rectangle, so the rectangle
+/// occupies exactly the given size.  Per default, the rectangle is outlined with the default
+/// [`LineStyle`][] and not filled.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style};
+/// let rect = elements::Rectangle::new((40, 20))
+///     .with_position((5, 0))
+///     .with_fill_color(style::Color::Rgb(200, 220, 255))
+///     .with_line_style(None);
+/// ```
+///
+/// [`LineStyle`]: ../style/struct.LineStyle.html
+#[derive(Clone, Debug)]
+pub struct Rectangle {
+    size: Size,
+    paint: Paint,
+}
+
+impl Rectangle {
+    /// Creates a new rectangle with the given size.
+    pub fn new(size: impl Into<Size>) -> Rectangle {
+        Rectangle {
+            size: size.into(),
+            paint: Paint::default(),
+        }
+    }
+
+    /// Sets the position of the upper left corner of this rectangle, relative to the upper left
+    /// corner of the area.
+    pub fn set_position(&mut self, position: impl Into<Position>) {
+        self.paint.position = position.into();
+    }
+
+    /// Sets the position of this rectangle and returns the rectangle.
+    pub fn with_position(mut self, position: impl Into<Position>) -> Self {
+        self.set_position(position);
+        self
+    }
+
+    /// Sets the fill of this rectangle, i. e. a color or a gradient.
+    pub fn set_fill(&mut self, fill: impl Into<Option<Fill>>) {
+        self.paint.fill = fill.into();
+    }
+
+    /// Sets the fill of this rectangle and returns the rectangle.
+    pub fn with_fill(mut self, fill: impl Into<Option<Fill>>) -> Self {
+        self.set_fill(fill);
+        self
+    }
+
+    /// Sets the fill color of this rectangle.
+    pub fn set_fill_color(&mut self, color: impl Into<Option<Color>>) {
+        self.paint.fill = color.into().map(Fill::from);
+    }
+
+    /// Sets the fill color of this rectangle and returns the rectangle.
+    pub fn with_fill_color(mut self, color: impl Into<Option<Color>>) -> Self {
+        self.set_fill_color(color);
+        self
+    }
+
+    /// Sets the line style for the outline of this rectangle, or disables the outline if `None`.
+    pub fn set_line_style(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.paint.line_style = line_style.into();
+    }
+
+    /// Sets the line style for the outline of this rectangle and returns the rectangle.
+    pub fn with_line_style(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_line_style(line_style);
+        self
+    }
+}
+
+impl Element for Rectangle {
+    fn render(
+        &mut self,
+        _context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        let paint = &self.paint;
+        Ok(paint.render(&area, self.size, |position, size| {
+            let points = [
+                position,
+                Position::new(position.x + size.width, position.y),
+                Position::new(position.x + size.width, position.y + size.height),
+                Position::new(position.x, position.y + size.height),
+            ];
+            area.draw_shape(points.iter().copied(), paint.fill.clone(), paint.line_style);
+        }))
+    }
+
+    fn natural_width(&self, _context: &Context, _style: Style) -> Option<Mm> {
+        Some(self.paint.position.x + self.size.width)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+}
+
+/// An ellipse with an optional fill color and outline.
+///
+/// The ellipse fills a rectangle with the given size that is drawn at the given position relative
+/// to the upper left corner of the area that it is rendered in.  The outline is drawn inside of
+/// this rectangle.  Per default, the ellipse is outlined with the default [`LineStyle`][] and not
+/// filled.  For circles, see [`Circle`][].
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style};
+/// let ellipse = elements::Ellipse::new((40, 20))
+///     .with_fill_color(style::Color::Rgb(255, 220, 200))
+///     .with_line_style(style::LineStyle::new().with_thickness(0.5));
+/// ```
+///
+/// [`LineStyle`]: ../style/struct.LineStyle.html
+/// [`Circle`]: struct.Circle.html
+#[derive(Clone, Debug)]
+pub struct Ellipse {
+    size: Size,
+    paint: Paint,
+}
+
+impl Ellipse {
+    /// Creates a new ellipse that fills a rectangle with the given size.
+    pub fn new(size: impl Into<Size>) -> Ellipse {
+        Ellipse {
+            size: size.into(),
+            paint: Paint::default(),
+        }
+    }
+
+    /// Sets the position of the upper left corner of the bounding box of this ellipse, relative
+    /// to the upper left corner of the area.
+    pub fn set_position(&mut self, position: impl Into<Position>) {
+        self.paint.position = position.into();
+    }
+
+    /// Sets the position of this ellipse and returns the ellipse.
+    pub fn with_position(mut self, position: impl Into<Position>) -> Self {
+        self.set_position(position);
+        self
+    }
+
+    /// Sets the fill of this ellipse, i. e. a color or a gradient.
+    pub fn set_fill(&mut self, fill: impl Into<Option<Fill>>) {
+        self.paint.fill = fill.into();
+    }
+
+    /// Sets the fill of this ellipse and returns the ellipse.
+    pub fn with_fill(mut self, fill: impl Into<Option<Fill>>) -> Self {
+        self.set_fill(fill);
+        self
+    }
+
+    /// Sets the fill color of this ellipse.
+    pub fn set_fill_color(&mut self, color: impl Into<Option<Color>>) {
+        self.paint.fill = color.into().map(Fill::from);
+    }
+
+    /// Sets the fill color of this ellipse and returns the ellipse.
+    pub fn with_fill_color(mut self, color: impl Into<Option<Color>>) -> Self {
+        self.set_fill_color(color);
+        self
+    }
+
+    /// Sets the line style for the outline of this ellipse, or disables the outline if `None`.
+    pub fn set_line_style(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.paint.line_style = line_style.into();
+    }
+
+    /// Sets the line style for the outline of this ellipse and returns the ellipse.
+    pub fn with_line_style(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_line_style(line_style);
+        self
+    }
+}
+
+impl Element for Ellipse {
+    fn render(
+        &mut self,
+        _context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        let paint = &self.paint;
+        Ok(paint.render(&area, self.size, |position, size| {
+            area.draw_ellipse(position, size, paint.fill.clone(), paint.line_style);
+        }))
+    }
+
+    fn natural_width(&self, _context: &Context, _style: Style) -> Option<Mm> {
+        Some(self.paint.position.x + self.size.width)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+}
+
+/// A circle with an optional fill color and outline.
+///
+/// This is a shorthand for an [`Ellipse`][] with the same width and height.  The position refers
+/// to the upper left corner of the bounding box of the circle.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style};
+/// let circle = elements::Circle::new(5)
+///     .with_fill_color(style::Color::Rgb(255, 0, 0))
+///     .with_line_style(None);
+/// ```
+///
+/// [`Ellipse`]: struct.Ellipse.html
+#[derive(Clone, Debug)]
+pub struct Circle {
+    ellipse: Ellipse,
+}
+
+impl Circle {
+    /// Creates a new circle with the given radius.
+    pub fn new(radius: impl Into<Mm>) -> Circle {
+        let diameter = radius.into() * 2.0;
+        Circle {
+            ellipse: Ellipse::new(Size::new(diameter, diameter)),
+        }
+    }
+
+    /// Sets the position of the upper left corner of the bounding box of this circle, relative
+    /// to the upper left corner of the area.
+    pub fn set_position(&mut self, position: impl Into<Position>) {
+        self.ellipse.set_position(position);
+    }
+
+    /// Sets the position of this circle and returns the circle.
+    pub fn with_position(mut self, position: impl Into<Position>) -> Self {
+        self.set_position(position);
+        self
+    }
+
+    /// Sets the fill of this circle, i. e. a color or a gradient.
+    pub fn set_fill(&mut self, fill: impl Into<Option<Fill>>) {
+        self.ellipse.set_fill(fill);
+    }
+
+    /// Sets the fill of this circle and returns the circle.
+    pub fn with_fill(mut self, fill: impl Into<Option<Fill>>) -> Self {
+        self.set_fill(fill);
+        self
+    }
+
+    /// Sets the fill color of this circle.
+    pub fn set_fill_color(&mut self, color: impl Into<Option<Color>>) {
+        self.ellipse.set_fill_color(color);
+    }
+
+    /// Sets the fill color of this circle and returns the circle.
+    pub fn with_fill_color(mut self, color: impl Into<Option<Color>>) -> Self {
+        self.set_fill_color(color);
+        self
+    }
+
+    /// Sets the line style for the outline of this circle, or disables the outline if `None`.
+    pub fn set_line_style(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.ellipse.set_line_style(line_style);
+    }
+
+    /// Sets the line style for the outline of this circle and returns the circle.
+    pub fn with_line_style(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_line_style(line_style);
+        self
+    }
+}
+
+impl Element for Circle {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        self.ellipse.render(context, area, style)
+    }
+
+    fn natural_width(&self, context: &Context, style: Style) -> Option<Mm> {
+        self.ellipse.natural_width(context, style)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        self.ellipse.measure(context, area, style)
+    }
+}
+
+/// A closed polygon with an optional fill color and outline.
+///
+/// The points of the polygon are relative to the position of the polygon, which in turn is
+/// relative to the upper left corner of the area that it is rendered in.  The size of the polygon
+/// is the bounding box of its points, extended by the thickness of the outline so that the
+/// outline is not cut off.  Per default, the polygon is outlined with the default
+/// [`LineStyle`][] and not filled.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style};
+/// let triangle = elements::Polygon::new(vec![(0, 20), (10, 0), (20, 20)])
+///     .with_fill_color(style::Color::Greyscale(200));
+/// ```
+///
+/// [`LineStyle`]: ../style/struct.LineStyle.html
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    points: Vec<Position>,
+    paint: Paint,
+}
+
+impl Polygon {
+    /// Creates a new polygon with the given points.
+    ///
+    /// The polygon is closed automatically, so the last point should not repeat the first point.
+    pub fn new<I, P>(points: I) -> Polygon
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Position>,
+    {
+        Polygon {
+            points: points.into_iter().map(Into::into).collect(),
+            paint: Paint::default(),
+        }
+    }
+
+    /// Sets the position of this polygon, relative to the upper left corner of the area.
+    pub fn set_position(&mut self, position: impl Into<Position>) {
+        self.paint.position = position.into();
+    }
+
+    /// Sets the position of this polygon and returns the polygon.
+    pub fn with_position(mut self, position: impl Into<Position>) -> Self {
+        self.set_position(position);
+        self
+    }
+
+    /// Sets the fill of this polygon, i. e. a color or a gradient.
+    pub fn set_fill(&mut self, fill: impl Into<Option<Fill>>) {
+        self.paint.fill = fill.into();
+    }
+
+    /// Sets the fill of this polygon and returns the polygon.
+    pub fn with_fill(mut self, fill: impl Into<Option<Fill>>) -> Self {
+        self.set_fill(fill);
+        self
+    }
+
+    /// Sets the fill color of this polygon.
+    pub fn set_fill_color(&mut self, color: impl Into<Option<Color>>) {
+        self.paint.fill = color.into().map(Fill::from);
+    }
+
+    /// Sets the fill color of this polygon and returns the polygon.
+    pub fn with_fill_color(mut self, color: impl Into<Option<Color>>) -> Self {
+        self.set_fill_color(color);
+        self
+    }
+
+    /// Sets the line style for the outline of this polygon, or disables the outline if `None`.
+    pub fn set_line_style(&mut self, line_style: impl Into<Option<LineStyle>>) {
+        self.paint.line_style = line_style.into();
+    }
+
+    /// Sets the line style for the outline of this polygon and returns the polygon.
+    pub fn with_line_style(mut self, line_style: impl Into<Option<LineStyle>>) -> Self {
+        self.set_line_style(line_style);
+        self
+    }
+
+    fn size(&self) -> Size {
+        let width = self.points.iter().map(|p| p.x).fold(Mm(0.0), Mm::max);
+        let height = self.points.iter().map(|p| p.y).fold(Mm(0.0), Mm::max);
+        let thickness = self.paint.thickness();
+        Size::new(width + thickness, height + thickness)
+    }
+}
+
+impl Element for Polygon {
+    fn render(
+        &mut self,
+        _context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        let paint = &self.paint;
+        let points = &self.points;
+        Ok(paint.render(&area, self.size(), |position, _| {
+            if points.len() > 2 {
+                let points = points
+                    .iter()
+                    .map(|p| Position::new(position.x + p.x, position.y + p.y));
+                area.draw_shape(points, paint.fill.clone(), paint.line_style);
+            }
+        }))
+    }
+
+    fn natural_width(&self, _context: &Context, _style: Style) -> Option<Mm> {
+        Some(self.paint.position.x + self.size().width)
+    }
+
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: Style,
+    ) -> Option<RenderResult> {
+        measure_copy(self, context, area, style)
+    }
+}
+
+/// The position, fill color and outline shared by all shapes.
+#[derive(Clone, Debug)]
+struct Paint {
+    position: Position,
+    fill: Option<Fill>,
+    line_style: Option<LineStyle>,
+}
+
+impl Paint {
+    fn thickness(&self) -> Mm {
+        self.line_style
+            .map(|line_style| line_style.thickness())
+            .unwrap_or_default()
+    }
+
+    /// Draws a shape with the given outer size, calling `draw` with the position and size of the
+    /// path so that the outline stays within the outer size.
+    fn render<F>(&self, area: &render::Area<'_>, size: Size, draw: F) -> RenderResult
+    where
+        F: FnOnce(Position, Size),
+    {
+        let mut result = RenderResult::default();
+        if self.position.y + size.height > area.size().height {
+            result.has_more = true;
+            return result;
+        }
+
+        let thickness = self.thickness();
+        let inset = thickness / 2.0;
+        draw(
+            Position::new(self.position.x + inset, self.position.y + inset),
+            Size::new(size.width - thickness, size.height - thickness),
+        );
+        result.size = Size::new(self.position.x + size.width, self.position.y + size.height);
+        result
+    }
+}
+
+impl Default for Paint {
+    fn default() -> Paint {
+        Paint {
+            position: Position::default(),
+            fill: None,
+            line_style: Some(LineStyle::new()),
+        }
+    }
+}
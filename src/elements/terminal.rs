@@ -0,0 +1,340 @@
+use std::iter;
+
+use crate::{
+    error::Error,
+    render,
+    style::{Color, Style, StyledStr, StyledString},
+    Context, Element, RenderResult,
+};
+
+use super::codeblock::render_lines;
+
+/// The number of columns between two tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// A block of terminal output that is styled using ANSI escape sequences.
+///
+/// This element can be used to embed the output of command-line tools, for example a test report
+/// or a compiler error.  The text is rendered line by line without wrapping, just like a
+/// [`CodeBlock`][], so the base style should use a monospace font.
+///
+/// The following SGR (select graphic rendition) sequences are supported:
+/// - reset (`0`)
+/// - bold (`1`, `22`), italic (`3`, `23`) and strikethrough (`9`, `29`)
+/// - the 16 standard foreground colors (`30`–`37`, `90`–`97`, `39`)
+/// - 256-color and true color foreground colors (`38;5;n` and `38;2;r;g;b`)
+///
+/// Background colors and all other escape sequences are removed.  A carriage return that is not
+/// followed by a line break discards the current line, so only the final state of progress bars
+/// is rendered.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{elements, style};
+/// let output = "\x1b[1;32mok\x1b[0m: 3 passed; \x1b[31m1 failed\x1b[0m";
+/// let terminal = elements::Terminal::new(output, style::Style::new().with_font_size(9));
+/// ```
+///
+/// [`CodeBlock`]: struct.CodeBlock.html
+pub struct Terminal {
+    lines: Vec<Vec<StyledString>>,
+}
+
+impl Terminal {
+    /// Creates a new terminal element that renders the given output, parsing its ANSI escape
+    /// sequences.
+    ///
+    /// The base style is used for text without SGR attributes, and the attributes are applied on
+    /// top of it.
+    pub fn new(output: &str, base_style: Style) -> Self {
+        Self {
+            lines: parse(output, base_style),
+        }
+    }
+}
+
+impl Element for Terminal {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: render::Area<'_>,
+        _style: Style,
+    ) -> Result<RenderResult, Error> {
+        let lines: Vec<Vec<StyledStr<'_>>> = self
+            .lines
+            .iter()
+            .map(|line| line.iter().map(StyledStr::from).collect())
+            .collect();
+        let (result, rendered_lines) = render_lines(&lines, context, area)?;
+        self.lines.drain(..rendered_lines);
+        Ok(result)
+    }
+}
+
+/// The SGR attributes of the terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Attributes {
+    color: Option<Color>,
+    bold: bool,
+    italic: bool,
+    strikethrough: bool,
+}
+
+impl Attributes {
+    /// Applies the given SGR parameters, for example `1;31`.
+    fn apply(&mut self, params: &str) {
+        let mut params = params.split(';').map(|param| {
+            if param.is_empty() {
+                Some(0)
+            } else {
+                param.parse::<u8>().ok()
+            }
+        });
+        while let Some(param) = params.next() {
+            match param {
+                Some(0) => *self = Attributes::default(),
+                Some(1) => self.bold = true,
+                Some(3) => self.italic = true,
+                Some(9) => self.strikethrough = true,
+                Some(22) => self.bold = false,
+                Some(23) => self.italic = false,
+                Some(29) => self.strikethrough = false,
+                Some(n @ 30..=37) => self.color = Some(indexed_color(n - 30)),
+                Some(38) => self.color = extended_color(&mut params),
+                Some(39) => self.color = None,
+                Some(48) => {
+                    // Background colors are not supported, but we have to skip the arguments.
+                    extended_color(&mut params);
+                }
+                Some(n @ 90..=97) => self.color = Some(indexed_color(n - 90 + 8)),
+                _ => {}
+            }
+        }
+    }
+
+    fn style(&self, base_style: Style) -> Style {
+        let mut style = base_style;
+        if let Some(color) = self.color {
+            style.set_color(color);
+        }
+        if self.bold {
+            style.set_bold();
+        }
+        if self.italic {
+            style.set_italic();
+        }
+        if self.strikethrough {
+            style.set_strikethrough();
+        }
+        style
+    }
+}
+
+/// Parses the arguments of an extended color (`5;n` or `2;r;g;b`).
+fn extended_color(params: &mut impl Iterator<Item = Option<u8>>) -> Option<Color> {
+    match params.next()?? {
+        5 => params.next()?.map(indexed_color),
+        2 => Some(Color::Rgb(
+            params.next()??,
+            params.next()??,
+            params.next()??,
+        )),
+        _ => None,
+    }
+}
+
+/// Returns the color with the given index in the xterm 256-color palette.
+fn indexed_color(index: u8) -> Color {
+    const STANDARD_COLORS: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => {
+            let (r, g, b) = STANDARD_COLORS[usize::from(index)];
+            Color::Rgb(r, g, b)
+        }
+        16..=231 => {
+            let index = usize::from(index - 16);
+            Color::Rgb(
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[index / 6 % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        }
+        _ => Color::Greyscale(8 + (index - 232) * 10),
+    }
+}
+
+/// Splits the given terminal output into lines of styled strings.
+///
+/// Every line contains at least one (possibly empty) string so that empty lines have the height
+/// of the base style.
+fn parse(output: &str, base_style: Style) -> Vec<Vec<StyledString>> {
+    let mut parser = Parser::new(base_style);
+    let mut chars = output.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    // Control sequence:  parameter and intermediate bytes, then a final byte
+                    let params: String =
+                        iter::from_fn(|| chars.next_if(|c| ('\x20'..='\x3f').contains(c)))
+                            .collect();
+                    if chars.next() == Some('m') {
+                        parser.apply(&params);
+                    }
+                }
+                Some(']') => {
+                    // Operating system command:  terminated by BEL or ST (ESC \)
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\n' => parser.newline(),
+            '\r' => {
+                if chars.peek() != Some(&'\n') {
+                    parser.clear_line();
+                }
+            }
+            '\t' => {
+                let spaces = TAB_WIDTH - parser.column % TAB_WIDTH;
+                for _ in 0..spaces {
+                    parser.push(' ');
+                }
+            }
+            c if c.is_control() => {}
+            c => parser.push(c),
+        }
+    }
+    parser.finish()
+}
+
+struct Parser {
+    base_style: Style,
+    attributes: Attributes,
+    lines: Vec<Vec<StyledString>>,
+    line: Vec<StyledString>,
+    text: String,
+    column: usize,
+}
+
+impl Parser {
+    fn new(base_style: Style) -> Parser {
+        Parser {
+            base_style,
+            attributes: Attributes::default(),
+            lines: Vec::new(),
+            line: Vec::new(),
+            text: String::new(),
+            column: 0,
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.column += 1;
+    }
+
+    fn apply(&mut self, params: &str) {
+        self.flush();
+        self.attributes.apply(params);
+    }
+
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            let style = self.attributes.style(self.base_style);
+            self.line
+                .push(StyledString::new(self.text.split_off(0), style));
+        }
+    }
+
+    fn newline(&mut self) {
+        self.flush();
+        if self.line.is_empty() {
+            let style = self.attributes.style(self.base_style);
+            self.line.push(StyledString::new(String::new(), style));
+        }
+        self.lines.push(self.line.split_off(0));
+        self.column = 0;
+    }
+
+    fn clear_line(&mut self) {
+        self.text.clear();
+        self.line.clear();
+        self.column = 0;
+    }
+
+    fn finish(mut self) -> Vec<Vec<StyledString>> {
+        self.flush();
+        if !self.line.is_empty() {
+            self.lines.push(self.line);
+        }
+        self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{indexed_color, parse};
+    use crate::style::{Color, Style};
+
+    #[test]
+    fn test_parse() {
+        let base_style = Style::new().with_font_size(9);
+        let lines = parse(
+            "\x1b[1;31mfailed\x1b[0m: a\tb\r\n\ndone 50%\rdone \x1b[38;5;46m100%\x1b[m\n",
+            base_style,
+        );
+        let lines: Vec<Vec<_>> = lines
+            .iter()
+            .map(|line| line.iter().map(|s| (s.s.as_str(), s.style)).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                vec![
+                    (
+                        "failed",
+                        base_style.bold().with_color(Color::Rgb(205, 0, 0))
+                    ),
+                    (": a       b", base_style),
+                ],
+                vec![("", base_style)],
+                vec![
+                    ("done ", base_style),
+                    ("100%", base_style.with_color(Color::Rgb(0, 255, 0))),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_indexed_color() {
+        assert_eq!(indexed_color(9), Color::Rgb(255, 0, 0));
+        assert_eq!(indexed_color(196), Color::Rgb(255, 0, 0));
+        assert_eq!(indexed_color(232), Color::Greyscale(8));
+        assert_eq!(indexed_color(255), Color::Greyscale(238));
+    }
+}
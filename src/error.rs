@@ -81,11 +81,15 @@ impl error::Error for Error {
             ErrorKind::InvalidFont => None,
             ErrorKind::PageSizeExceeded => None,
             ErrorKind::UnsupportedEncoding => None,
+            ErrorKind::Degraded(_) => None,
+            #[cfg(feature = "math")]
+            ErrorKind::Math => None,
             ErrorKind::IoError(err) => Some(err),
             ErrorKind::PdfError(err) => Some(err),
             ErrorKind::PdfIndexError(err) => Some(err),
             #[cfg(feature = "images")]
             ErrorKind::ImageError(err) => Some(err),
+            ErrorKind::External(err) => Some(err.as_ref()),
         }
     }
 }
@@ -104,6 +108,9 @@ pub enum ErrorKind {
     PageSizeExceeded,
     /// A string with unsupported characters was used with a built-in font.
     UnsupportedEncoding,
+    /// The layout had to be degraded and the strict mode is set to
+    /// [`StrictMode::Error`](../enum.StrictMode.html#variant.Error).
+    Degraded(WarningKind),
     /// An IO error.
     IoError(io::Error),
     /// An error caused by invalid data in `printpdf`.
@@ -115,6 +122,15 @@ pub enum ErrorKind {
     /// *Only available if the `images` feature is enabled.*
     #[cfg(feature = "images")]
     ImageError(image::ImageError),
+    /// An error caused by a math formula that could not be parsed or laid out, or by a math
+    /// element that is rendered without a math font.
+    ///
+    /// *Only available if the `math` feature is enabled.*
+    #[cfg(feature = "math")]
+    Math,
+    /// An error caused by a third-party library without a dedicated error kind, for example
+    /// `syntect`.
+    External(Box<dyn error::Error + Send + Sync + 'static>),
 }
 
 impl From<io::Error> for ErrorKind {
@@ -152,3 +168,58 @@ impl From<image::ImageError> for ErrorKind {
         ErrorKind::ImageError(error)
     }
 }
+
+/// A warning about a degraded layout that is reported in strict mode.
+///
+/// If the [`StrictMode::Warn`][] mode is set, the warnings are collected and returned by
+/// [`RenderReport::warnings`][].
+///
+/// [`StrictMode::Warn`]: ../enum.StrictMode.html#variant.Warn
+/// [`RenderReport::warnings`]: ../struct.RenderReport.html#method.warnings
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    msg: String,
+    kind: WarningKind,
+}
+
+impl Warning {
+    /// Creates a new warning.
+    pub fn new(msg: impl Into<String>, kind: WarningKind) -> Warning {
+        Warning {
+            msg: msg.into(),
+            kind,
+        }
+    }
+
+    /// Returns the kind of this warning.
+    pub fn kind(&self) -> WarningKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl From<Warning> for Error {
+    fn from(warning: Warning) -> Error {
+        Error::new(warning.msg, ErrorKind::Degraded(warning.kind))
+    }
+}
+
+/// The kind of a [`Warning`](struct.Warning.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A string was truncated because it did not fit into the available width.
+    TruncatedText,
+    /// The font does not contain a glyph for a character, so the character was rendered as the
+    /// replacement glyph of the font.
+    MissingGlyph,
+    /// An image was scaled below the minimum image scale.
+    DownscaledImage,
+    /// A line of justified text is wider than the available width.
+    OverfullLine,
+}
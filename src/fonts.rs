@@ -6,7 +6,12 @@
 //! Before you can use a font in a PDF document, you have to load the [`FontData`][] for it, either
 //! from a file ([`FontData::load`][]) or from bytes ([`FontData::new`][]).  See the [`rusttype`][]
 //! crate for the supported data formats.  Use the [`from_files`][] function to load a font family
-//! from a set of files following the default naming conventions.
+//! from a set of files following the default naming conventions.  If the `system-fonts` feature
+//! is enabled, the [`from_system`][] function loads a font family that is installed on the system.
+//! For tests, [`test_font_family`][] provides a generated font family without font files.
+//!
+//! Icon fonts like Font Awesome or Material Icons can be registered together with an [`IconMap`][]
+//! that maps icon names to characters, see [`FontCache::add_icon_font`][].
 //!
 //! The [`FontCache`][] caches all loaded fonts.  A [`Font`][] is a reference to a cached font in
 //! the [`FontCache`][].  A [`FontFamily`][] is a collection of a regular, a bold, an italic and a
@@ -33,6 +38,13 @@
 //! [`FontCache`][] they have been created with.  If you dont use the low-level [`render`][] module
 //! directly, only use the [`Document::add_font_family`][] method to add fonts!
 //!
+//! # Metrics
+//!
+//! The metrics of a [`Font`][] can be used to calculate the size of text for custom layouts.  They
+//! are the same metrics that `genpdf` uses to render text, see for example
+//! [`Font::str_width`][], [`Font::cap_height`][] and [`Font::metrics`][].  Use
+//! [`Document::font_cache`][] to access the font cache before rendering the document.
+//!
 //! # Internals
 //!
 //! There are two types of font data: A [`FontData`][] instance stores information about the glyph
@@ -48,11 +60,19 @@
 //! [`render`]: ../render/
 //! [`Document`]: ../struct.Document.html
 //! [`Document::add_font_family`]: ../struct.Document.html#method.add_font_family
+//! [`Document::font_cache`]: ../struct.Document.html#method.font_cache
+//! [`Font::str_width`]: struct.Font.html#method.str_width
+//! [`Font::cap_height`]: struct.Font.html#method.cap_height
+//! [`Font::metrics`]: struct.Font.html#method.metrics
 //! [`Style`]: ../style/struct.Style.html
 //! [`from_files`]: fn.from_files.html
+//! [`from_system`]: fn.from_system.html
+//! [`test_font_family`]: fn.test_font_family.html
 //! [`Builtin`]: enum.Builtin.html
 //! [`FontCache`]: struct.FontCache.html
 //! [`FontCache::load_pdf_fonts`]: struct.FontCache.html#method.load_pdf_fonts
+//! [`FontCache::add_icon_font`]: struct.FontCache.html#method.add_icon_font
+//! [`IconMap`]: struct.IconMap.html
 //! [`FontData`]: struct.FontData.html
 //! [`FontData::new`]: struct.FontData.html#method.new
 //! [`FontData::load`]: struct.FontData.html#method.load
@@ -64,13 +84,18 @@
 //! [`printpdf::IndirectFontRef`]: https://docs.rs/printpdf/0.3.2/printpdf/types/plugins/graphics/two_dimensional/font/struct.IndirectFontRef.html
 //! [Windows-1252]: https://en.wikipedia.org/wiki/Windows-1252
 
+use std::collections;
 use std::fmt;
 use std::fs;
 use std::path;
+use std::sync;
+
+use owned_ttf_parser::{self as ttf, AsFaceRef as _};
 
 use crate::error::{Context as _, Error, ErrorKind};
 use crate::render;
 use crate::style::Style;
+use crate::wrap;
 use crate::Mm;
 
 /// Stores font data that can be referenced by a [`Font`][] or [`FontFamily`][].
@@ -89,6 +114,7 @@ pub struct FontCache {
     // a font, but the default font is always loaded in new, so this options is always some
     // (outside of new).
     default_font_family: Option<FontFamily<Font>>,
+    icon_fonts: Vec<(FontFamily<Font>, IconMap)>,
 }
 
 impl FontCache {
@@ -98,6 +124,7 @@ impl FontCache {
             fonts: Vec::new(),
             pdf_fonts: Vec::new(),
             default_font_family: None,
+            icon_fonts: Vec::new(),
         };
         font_cache.default_font_family = Some(font_cache.add_font_family(default_font_family));
         font_cache
@@ -124,6 +151,36 @@ impl FontCache {
         }
     }
 
+    /// Adds the given icon font with the given mapping from icon names to characters to the cache
+    /// and returns a reference to it.
+    ///
+    /// As icon fonts don’t have style variants, the returned font family uses the given font for
+    /// all variants.  The registered icons can be looked up with [`get_icon`][].  If multiple icon
+    /// fonts contain an icon with the same name, the font that has been added first is used.
+    ///
+    /// [`get_icon`]: #method.get_icon
+    pub fn add_icon_font(&mut self, font_data: FontData, icons: IconMap) -> FontFamily<Font> {
+        let font = self.add_font(font_data);
+        let family = FontFamily {
+            regular: font,
+            bold: font,
+            italic: font,
+            bold_italic: font,
+        };
+        self.icon_fonts.push((family, icons));
+        family
+    }
+
+    /// Returns the font family and the character of the icon with the given name, if it has been
+    /// registered with [`add_icon_font`][].
+    ///
+    /// [`add_icon_font`]: #method.add_icon_font
+    pub fn get_icon(&self, name: &str) -> Option<(FontFamily<Font>, char)> {
+        self.icon_fonts
+            .iter()
+            .find_map(|(family, icons)| icons.get(name).map(|c| (*family, c)))
+    }
+
     /// Embeds all loaded fonts into the document generated by the given renderer and caches a
     /// reference to them.
     pub fn load_pdf_fonts(&mut self, renderer: &render::Renderer) -> Result<(), Error> {
@@ -166,6 +223,14 @@ impl FontCache {
     pub fn get_rt_font(&self, font: Font) -> &rusttype::Font<'static> {
         &self.fonts[font.idx].rt_font
     }
+
+    fn get_gpos_kerning(&self, font: Font) -> Option<&GposKerning> {
+        self.fonts[font.idx].gpos_kerning.as_deref()
+    }
+
+    fn get_small_caps(&self, font: Font) -> Option<&SmallCaps> {
+        self.fonts[font.idx].small_caps.as_deref()
+    }
 }
 
 /// The data for a font that is cached by a [`FontCache`][].
@@ -176,6 +241,8 @@ pub struct FontData {
     rt_font: rusttype::Font<'static>,
     raw_data: RawFontData,
     allow_subsetting: bool,
+    gpos_kerning: Option<sync::Arc<GposKerning>>,
+    small_caps: Option<sync::Arc<SmallCaps>>,
 }
 
 impl FontData {
@@ -193,9 +260,19 @@ impl FontData {
         } else {
             RawFontData::Embedded(data.clone())
         };
-        let rt_font = rusttype::Font::try_from_vec(data)
-            .ok_or(ErrorKind::InvalidFont)
+        // The parsed font is shared by rusttype and the readers for the OpenType layout tables.
+        let face = ttf::OwnedFace::from_vec(data, 0)
+            .map(sync::Arc::new)
+            .map_err(|_| ErrorKind::InvalidFont)
             .context("Failed to read rusttype font")?;
+        let gpos_kerning = GposKerning::new(&face).map(sync::Arc::new);
+        // Built-in fonts are printed using their character codes, so we cannot substitute glyphs.
+        let small_caps = if builtin.is_some() {
+            None
+        } else {
+            SmallCaps::new(&face).map(sync::Arc::new)
+        };
+        let rt_font = rusttype::Font::Owned(face);
         if rt_font.units_per_em() == 0 {
             Err(Error::new(
                 "The font is not scalable",
@@ -206,6 +283,8 @@ impl FontData {
                 rt_font,
                 raw_data,
                 allow_subsetting: true,
+                gpos_kerning,
+                small_caps,
             })
         }
     }
@@ -245,6 +324,252 @@ impl FontData {
     }
 }
 
+/// A mapping from icon names to the characters of an icon font, see
+/// [`FontCache::add_icon_font`][].
+///
+/// The mapping can be filled manually, parsed from a code points file with
+/// [`from_codepoints`][], or read from the glyph names stored in the font with
+/// [`from_glyph_names`][].
+///
+/// # Example
+///
+/// ```
+/// use genpdf::fonts;
+/// let icons = fonts::IconMap::from_codepoints("check_circle e86c\nwarning e002\n")
+///     .expect("Invalid code points file")
+///     .with_icon("check-circle", '\u{e86c}');
+/// assert_eq!(icons.get("warning"), Some('\u{e002}'));
+/// ```
+///
+/// [`FontCache::add_icon_font`]: struct.FontCache.html#method.add_icon_font
+/// [`from_codepoints`]: #method.from_codepoints
+/// [`from_glyph_names`]: #method.from_glyph_names
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IconMap {
+    icons: collections::HashMap<String, char>,
+}
+
+impl IconMap {
+    /// Creates a new empty icon mapping.
+    pub fn new() -> IconMap {
+        IconMap::default()
+    }
+
+    /// Parses a code points file that contains one icon per line, consisting of the name and the
+    /// hexadecimal code point separated by whitespace.
+    ///
+    /// This is the format of the `codepoints` file that is distributed with Material Icons.  Empty
+    /// lines are ignored.
+    pub fn from_codepoints(s: &str) -> Result<IconMap, Error> {
+        let mut icons = IconMap::new();
+        for (i, line) in s.lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let (name, code) = match (parts.next(), parts.next(), parts.next()) {
+                (None, _, _) => continue,
+                (Some(name), Some(code), None) => (name, code),
+                _ => {
+                    return Err(Error::new(
+                        format!("Invalid icon definition in line {}: {}", i + 1, line),
+                        ErrorKind::InvalidData,
+                    ))
+                }
+            };
+            let c = u32::from_str_radix(code, 16)
+                .ok()
+                .and_then(std::char::from_u32)
+                .ok_or_else(|| {
+                    Error::new(
+                        format!("Invalid code point for icon {}: {}", name, code),
+                        ErrorKind::InvalidData,
+                    )
+                })?;
+            icons.insert(name, c);
+        }
+        Ok(icons)
+    }
+
+    /// Reads the icon names from the glyph names of the given font data.
+    ///
+    /// Many icon fonts, for example Font Awesome, store the icon names as glyph names in their
+    /// `post` table.  Every glyph that is mapped to a character and that has a name is added to
+    /// the mapping.
+    pub fn from_glyph_names(data: &[u8]) -> Result<IconMap, Error> {
+        let face = ttf::Face::from_slice(data, 0).map_err(|err| {
+            Error::new(
+                format!("Failed to read icon font: {}", err),
+                ErrorKind::InvalidFont,
+            )
+        })?;
+        let mut icons = IconMap::new();
+        let subtables = face.tables().cmap.iter().flat_map(|cmap| cmap.subtables);
+        for subtable in subtables.filter(|subtable| subtable.is_unicode()) {
+            subtable.codepoints(|code| {
+                let name = subtable
+                    .glyph_index(code)
+                    .and_then(|glyph_id| face.glyph_name(glyph_id));
+                if let (Some(name), Some(c)) = (name, std::char::from_u32(code)) {
+                    icons.icons.entry(name.to_owned()).or_insert(c);
+                }
+            });
+        }
+        Ok(icons)
+    }
+
+    /// Adds an icon with the given name and character to this mapping.
+    pub fn insert(&mut self, name: impl Into<String>, c: char) {
+        self.icons.insert(name.into(), c);
+    }
+
+    /// Adds an icon with the given name and character to this mapping and returns the mapping.
+    pub fn with_icon(mut self, name: impl Into<String>, c: char) -> Self {
+        self.insert(name, c);
+        self
+    }
+
+    /// Returns the character of the icon with the given name, if it exists.
+    pub fn get(&self, name: &str) -> Option<char> {
+        self.icons.get(name).copied()
+    }
+
+    /// Returns the number of icons in this mapping.
+    pub fn len(&self) -> usize {
+        self.icons.len()
+    }
+
+    /// Returns whether this mapping is empty.
+    pub fn is_empty(&self) -> bool {
+        self.icons.is_empty()
+    }
+}
+
+/// The pair adjustments of the `kern` feature in the GPOS table of a font.
+///
+/// rusttype only supports the legacy `kern` table, but most modern fonts only store their kerning
+/// data in the GPOS table.
+#[derive(Debug)]
+struct GposKerning {
+    face: sync::Arc<ttf::OwnedFace>,
+    lookups: Vec<u16>,
+}
+
+impl GposKerning {
+    /// Reads the indices of the lookups for the `kern` feature from the given font, or returns
+    /// `None` if the font does not have a GPOS table with kerning data.
+    fn new(face: &sync::Arc<ttf::OwnedFace>) -> Option<GposKerning> {
+        let lookups = feature_lookups(face.as_face_ref().tables().gpos, b"kern");
+        if lookups.is_empty() {
+            None
+        } else {
+            Some(GposKerning {
+                face: face.clone(),
+                lookups,
+            })
+        }
+    }
+
+    /// Returns the horizontal adjustment for the given glyph pair in font units.
+    fn pair_kerning(&self, first: u16, second: u16) -> i16 {
+        use ttf::gpos::{PairAdjustment, PositioningSubtable};
+
+        let (first, second) = (ttf::GlyphId(first), ttf::GlyphId(second));
+        let gpos = match self.face.as_face_ref().tables().gpos {
+            Some(gpos) => gpos,
+            None => return 0,
+        };
+        let mut kerning = 0i16;
+        for lookup in self.lookups.iter().filter_map(|idx| gpos.lookups.get(*idx)) {
+            // Only the first subtable of a lookup that covers the first glyph is applied, even if
+            // it does not contain an adjustment for the pair.
+            let value = lookup
+                .subtables
+                .into_iter::<PositioningSubtable<'_>>()
+                .filter_map(|subtable| match subtable {
+                    PositioningSubtable::Pair(pair) => Some(pair),
+                    _ => None,
+                })
+                .find_map(|pair| {
+                    let idx = pair.coverage().get(first)?;
+                    let values = match pair {
+                        PairAdjustment::Format1 { sets, .. } => {
+                            sets.get(idx).and_then(|set| set.get(second))
+                        }
+                        PairAdjustment::Format2 {
+                            classes, matrix, ..
+                        } => matrix.get((classes.0.get(first), classes.1.get(second))),
+                    };
+                    Some(values.map(|(value, _)| value.x_advance).unwrap_or(0))
+                });
+            kerning = kerning.saturating_add(value.unwrap_or(0));
+        }
+        kerning
+    }
+}
+
+/// The single substitutions of the `smcp` feature in the GSUB table of a font that replace
+/// lowercase letters with small capitals.
+#[derive(Debug)]
+struct SmallCaps {
+    face: sync::Arc<ttf::OwnedFace>,
+    lookups: Vec<u16>,
+}
+
+impl SmallCaps {
+    /// Reads the indices of the lookups for the `smcp` feature from the given font, or returns
+    /// `None` if the font does not have a GSUB table with small capitals.
+    fn new(face: &sync::Arc<ttf::OwnedFace>) -> Option<SmallCaps> {
+        let lookups = feature_lookups(face.as_face_ref().tables().gsub, b"smcp");
+        if lookups.is_empty() {
+            None
+        } else {
+            Some(SmallCaps {
+                face: face.clone(),
+                lookups,
+            })
+        }
+    }
+
+    /// Returns the small capital for the given glyph, or `None` if there is no substitution.
+    fn substitute(&self, glyph: u16) -> Option<u16> {
+        use ttf::gsub::{SingleSubstitution, SubstitutionSubtable};
+
+        let glyph = ttf::GlyphId(glyph);
+        let gsub = self.face.as_face_ref().tables().gsub?;
+        self.lookups
+            .iter()
+            .filter_map(|idx| gsub.lookups.get(*idx))
+            .flat_map(|lookup| lookup.subtables.into_iter::<SubstitutionSubtable<'_>>())
+            .find_map(|subtable| match subtable {
+                SubstitutionSubtable::Single(SingleSubstitution::Format1 { coverage, delta }) => {
+                    coverage.get(glyph)?;
+                    Some((i32::from(glyph.0) + i32::from(delta)) as u16)
+                }
+                SubstitutionSubtable::Single(SingleSubstitution::Format2 {
+                    coverage,
+                    substitutes,
+                }) => substitutes.get(coverage.get(glyph)?).map(|glyph| glyph.0),
+                _ => None,
+            })
+    }
+}
+
+/// Returns the sorted indices of the lookups for the feature with the given tag in the given GSUB
+/// or GPOS table.
+fn feature_lookups(
+    table: Option<ttf::opentype_layout::LayoutTable<'_>>,
+    tag: &[u8; 4],
+) -> Vec<u16> {
+    let tag = ttf::Tag::from_bytes(tag);
+    let mut lookups: Vec<u16> = table
+        .iter()
+        .flat_map(|table| table.features)
+        .filter(|feature| feature.tag == tag)
+        .flat_map(|feature| feature.lookup_indices)
+        .collect();
+    lookups.sort_unstable();
+    lookups.dedup();
+    lookups
+}
+
 #[derive(Clone, Debug)]
 enum RawFontData {
     Builtin(printpdf::BuiltinFont),
@@ -382,6 +707,10 @@ pub struct Font {
     line_height: Mm,
     glyph_height: Mm,
     ascent: Mm,
+    descent: Mm,
+    line_gap: Mm,
+    cap_height: Mm,
+    x_height: Mm,
 }
 
 impl Font {
@@ -395,7 +724,22 @@ impl Font {
         let scale = rusttype::Scale::uniform(glyph_height);
 
         let ascent = v_metrics.ascent / units_per_em;
-        let line_height = glyph_height + v_metrics.line_gap / units_per_em;
+        let descent = -v_metrics.descent / units_per_em;
+        let line_gap = v_metrics.line_gap / units_per_em;
+        let line_height = glyph_height + line_gap;
+
+        // The cap height and the x-height are measured using the bounding boxes of the H and x
+        // glyphs.  If the glyphs are not available, we fall back to the ascent.
+        let glyph_top = |c: char| {
+            rt_font
+                .glyph(c)
+                .scaled(scale)
+                .exact_bounding_box()
+                .map(|rect| -rect.min.y)
+                .unwrap_or(ascent)
+        };
+        let cap_height = glyph_top('H');
+        let x_height = glyph_top('x');
 
         Font {
             idx,
@@ -404,6 +748,10 @@ impl Font {
             line_height: printpdf::Pt(f64::from(line_height)).into(),
             glyph_height: printpdf::Pt(f64::from(glyph_height)).into(),
             ascent: printpdf::Pt(f64::from(ascent)).into(),
+            descent: printpdf::Pt(f64::from(descent)).into(),
+            line_gap: printpdf::Pt(f64::from(line_gap)).into(),
+            cap_height: printpdf::Pt(f64::from(cap_height)).into(),
+            x_height: printpdf::Pt(f64::from(x_height)).into(),
         }
     }
 
@@ -427,8 +775,39 @@ impl Font {
         self.ascent * f64::from(font_size)
     }
 
+    /// Returns the descent for text with this font and the given font size.
+    ///
+    /// The descent is the distance between the baseline and the lowest point of the glyphs.  It
+    /// is returned as a positive value.
+    pub fn descent(&self, font_size: u8) -> Mm {
+        self.descent * f64::from(font_size)
+    }
+
+    /// Returns the line gap for text with this font and the given font size.
+    ///
+    /// The line gap is the additional space between two lines, so the line height is the sum of
+    /// the glyph height and the line gap.
+    pub fn line_gap(&self, font_size: u8) -> Mm {
+        self.line_gap * f64::from(font_size)
+    }
+
+    /// Returns the cap height, i. e. the height of capital letters above the baseline, for text
+    /// with this font and the given font size.
+    pub fn cap_height(&self, font_size: u8) -> Mm {
+        self.cap_height * f64::from(font_size)
+    }
+
+    /// Returns the x-height, i. e. the height of lowercase letters above the baseline, for text
+    /// with this font and the given font size.
+    pub fn x_height(&self, font_size: u8) -> Mm {
+        self.x_height * f64::from(font_size)
+    }
+
     /// Returns the width of a character with this font and the given font size.
     ///
+    /// This is the advance width of the glyph, i. e. the horizontal distance between the origin of
+    /// this character and the origin of the next character (without kerning).
+    ///
     /// The given [`FontCache`][] must be the font cache that loaded this font.
     ///
     /// [`FontCache`]: struct.FontCache.html
@@ -462,23 +841,40 @@ impl Font {
 
     /// Returns the width of a string with this font and the given font size.
     ///
-    /// The given [`FontCache`][] must be the font cache that loaded this font.
+    /// Soft hyphens are ignored as they are not rendered.  The given [`FontCache`][] must be the
+    /// font cache that loaded this font.
     ///
     /// [`FontCache`]: struct.FontCache.html
     pub fn str_width(&self, font_cache: &FontCache, s: &str, font_size: u8) -> Mm {
-        let str_width: Mm = font_cache
-            .get_rt_font(*self)
-            .glyphs_for(s.chars())
+        let s = wrap::remove_soft_hyphens(s);
+        self.glyphs_width(
+            font_cache,
+            &self.glyph_ids(font_cache, s.chars()),
+            font_size,
+        )
+    }
+
+    /// Returns the width of a sequence of glyphs with this font and the given font size, including
+    /// the kerning between the glyphs.
+    ///
+    /// The given [`FontCache`][] must be the font cache that loaded this font.
+    ///
+    /// [`FontCache`]: struct.FontCache.html
+    pub fn glyphs_width(&self, font_cache: &FontCache, glyph_ids: &[u16], font_size: u8) -> Mm {
+        let font = font_cache.get_rt_font(*self);
+        let glyphs_width: Mm = glyph_ids
+            .iter()
+            .map(|id| font.glyph(rusttype::GlyphId(*id)))
             .map(|g| g.scaled(self.scale).h_metrics().advance_width)
             .map(|w| Mm::from(printpdf::Pt(f64::from(w * f32::from(font_size)))))
             .sum();
         let kerning_width: Mm = self
-            .kerning(font_cache, s.chars())
+            .glyph_kerning(font_cache, glyph_ids)
             .into_iter()
             .map(|val| val * f32::from(font_size))
             .map(|val| Mm::from(printpdf::Pt(f64::from(val))))
             .sum();
-        str_width + kerning_width
+        glyphs_width + kerning_width
     }
 
     /// Returns the kerning data for the given sequence of characters.
@@ -486,6 +882,9 @@ impl Font {
     /// The *i*-th value of the returned data is the amount of kerning to insert before the *i*-th
     /// character of the sequence.
     ///
+    /// If the font has a GPOS table with a `kern` feature, its pair adjustments are used.
+    /// Otherwise, the kerning data is read from the `kern` table.
+    ///
     /// The given [`FontCache`][] must be the font cache that loaded this font.
     ///
     /// [`FontCache`]: struct.FontCache.html
@@ -493,15 +892,38 @@ impl Font {
     where
         I: IntoIterator<Item = char>,
     {
+        self.glyph_kerning(font_cache, &self.glyph_ids(font_cache, iter))
+    }
+
+    /// Returns the kerning data for the given sequence of glyphs, see [`kerning`][].
+    ///
+    /// The given [`FontCache`][] must be the font cache that loaded this font.
+    ///
+    /// [`kerning`]: #method.kerning
+    /// [`FontCache`]: struct.FontCache.html
+    pub fn glyph_kerning(&self, font_cache: &FontCache, glyph_ids: &[u16]) -> Vec<f32> {
         let font = font_cache.get_rt_font(*self);
-        font.glyphs_for(iter.into_iter())
-            .scan(None, |last, g| {
+        let gpos_kerning = font_cache.get_gpos_kerning(*self);
+        // The same scale factor that rusttype uses for the kern table
+        let factor = font.scale_for_pixel_height(self.scale.y) * (self.scale.x / self.scale.y);
+        glyph_ids
+            .iter()
+            .scan(None::<u16>, |last, id| {
                 let pos = if let Some(last) = last {
-                    Some(font.pair_kerning(self.scale, *last, g.id()))
+                    if let Some(gpos_kerning) = gpos_kerning {
+                        let kerning = gpos_kerning.pair_kerning(*last, *id);
+                        Some(factor * f32::from(kerning))
+                    } else {
+                        Some(font.pair_kerning(
+                            self.scale,
+                            rusttype::GlyphId(*last),
+                            rusttype::GlyphId(*id),
+                        ))
+                    }
                 } else {
                     Some(0.0)
                 };
-                *last = Some(g.id());
+                *last = Some(*id);
                 pos
             })
             .collect()
@@ -522,6 +944,37 @@ impl Font {
             .collect()
     }
 
+    /// Returns whether this font provides small capitals using the `smcp` OpenType feature.
+    ///
+    /// Built-in fonts never provide small capitals.  The given [`FontCache`][] must be the font
+    /// cache that loaded this font.
+    ///
+    /// [`FontCache`]: struct.FontCache.html
+    pub fn has_small_caps(&self, font_cache: &FontCache) -> bool {
+        font_cache.get_small_caps(*self).is_some()
+    }
+
+    /// Returns the glyph IDs for the given sequence of characters, replacing glyphs with the small
+    /// capitals of the `smcp` OpenType feature if available.
+    ///
+    /// The given [`FontCache`][] must be the font cache that loaded this font.
+    ///
+    /// [`FontCache`]: struct.FontCache.html
+    pub fn small_caps_glyph_ids<I>(&self, font_cache: &FontCache, iter: I) -> Vec<u16>
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut glyph_ids = self.glyph_ids(font_cache, iter);
+        if let Some(small_caps) = font_cache.get_small_caps(*self) {
+            for id in &mut glyph_ids {
+                if let Some(small_cap) = small_caps.substitute(*id) {
+                    *id = small_cap;
+                }
+            }
+        }
+        glyph_ids
+    }
+
     /// Calculate the metrics of a given font size for this font.
     pub fn metrics(&self, font_size: u8) -> Metrics {
         Metrics::new(
@@ -570,6 +1023,175 @@ pub fn from_files(
     })
 }
 
+/// Loads the font family with the given name from the fonts installed on the system.
+///
+/// The system font directories are searched for fonts with the given family name, for example
+/// `"Noto Sans"`.  If there is no font for a style (bold, italic or bold italic), the closest
+/// match of the family is used instead.  Font collections (for example `.ttc` files) with more
+/// than one font are not supported.
+///
+/// This function scans all installed fonts, so it should not be called repeatedly.
+///
+/// *Only available if the `system-fonts` feature is enabled.*
+#[cfg(feature = "system-fonts")]
+pub fn from_system(name: &str) -> Result<FontFamily<FontData>, Error> {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    Ok(FontFamily {
+        regular: from_system_db(&db, name, FontStyle::Regular)?,
+        bold: from_system_db(&db, name, FontStyle::Bold)?,
+        italic: from_system_db(&db, name, FontStyle::Italic)?,
+        bold_italic: from_system_db(&db, name, FontStyle::BoldItalic)?,
+    })
+}
+
+#[cfg(feature = "system-fonts")]
+fn from_system_db(db: &fontdb::Database, name: &str, style: FontStyle) -> Result<FontData, Error> {
+    let (weight, font_style) = match style {
+        FontStyle::Regular => (fontdb::Weight::NORMAL, fontdb::Style::Normal),
+        FontStyle::Bold => (fontdb::Weight::BOLD, fontdb::Style::Normal),
+        FontStyle::Italic => (fontdb::Weight::NORMAL, fontdb::Style::Italic),
+        FontStyle::BoldItalic => (fontdb::Weight::BOLD, fontdb::Style::Italic),
+    };
+    let query = fontdb::Query {
+        families: &[fontdb::Family::Name(name)],
+        weight,
+        style: font_style,
+        ..Default::default()
+    };
+    let id = db.query(&query).ok_or_else(|| {
+        Error::new(
+            format!("Could not find system font {}-{}", name, style),
+            ErrorKind::InvalidFont,
+        )
+    })?;
+    let data = db
+        .with_face_data(id, |data, index| {
+            if index == 0 {
+                Some(data.to_vec())
+            } else {
+                None
+            }
+        })
+        .flatten()
+        .ok_or_else(|| {
+            Error::new(
+                format!("Could not load system font {}-{}", name, style),
+                ErrorKind::InvalidFont,
+            )
+        })?;
+    FontData::new(data, None)
+}
+
+/// Returns a font family with a minimal, generated font that can be used for testing.
+///
+/// Every character of the test font has an advance width of 0.6 em, the ascent is 0.8 em and the
+/// descent is 0.2 em.  The glyphs don’t have outlines.  Instead, the built-in Courier font family
+/// that has the same character widths is used in the generated PDF file.  Therefore, the test font
+/// can only be used with characters that are supported by the [Windows-1252][] encoding.
+///
+/// This font family makes it possible to test the layout of custom elements without shipping
+/// font files, see [`Context::fake`][].
+///
+/// [`Context::fake`]: ../struct.Context.html#method.fake
+/// [Windows-1252]: https://en.wikipedia.org/wiki/Windows-1252
+pub fn test_font_family() -> FontFamily<FontData> {
+    let data = test_font_data();
+    let font = |style| {
+        FontData::new(data.clone(), Some(Builtin::Courier.style(style)))
+            .expect("Failed to load generated test font")
+    };
+    FontFamily {
+        regular: font(FontStyle::Regular),
+        bold: font(FontStyle::Bold),
+        italic: font(FontStyle::Italic),
+        bold_italic: font(FontStyle::BoldItalic),
+    }
+}
+
+/// Generates a TrueType font with one glyph for all characters, see [`test_font_family`][].
+///
+/// [`test_font_family`]: fn.test_font_family.html
+fn test_font_data() -> Vec<u8> {
+    test_font_data_with_tables(Vec::new())
+}
+
+/// Generates the test font with the given additional tables, see [`test_font_data`][].
+///
+/// [`test_font_data`]: fn.test_font_data.html
+fn test_font_data_with_tables(extra_tables: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+    const UNITS_PER_EM: u16 = 1000;
+    const ADVANCE_WIDTH: u16 = 600;
+    const ASCENT: i16 = 800;
+    const DESCENT: i16 = -200;
+
+    fn be16(data: &mut Vec<u8>, values: &[u16]) {
+        for value in values {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    let mut head = Vec::new();
+    be16(
+        &mut head,
+        &[1, 0, 1, 0, 0, 0, 0x5f0f, 0x3cf5, 0, UNITS_PER_EM],
+    );
+    be16(&mut head, &[0; 8]);
+    be16(
+        &mut head,
+        &[0, DESCENT as u16, ADVANCE_WIDTH, ASCENT as u16],
+    );
+    be16(&mut head, &[0, 8, 2, 0, 0]);
+
+    let mut hhea = Vec::new();
+    be16(
+        &mut hhea,
+        &[1, 0, ASCENT as u16, DESCENT as u16, 0, ADVANCE_WIDTH],
+    );
+    be16(&mut hhea, &[0, 0, ADVANCE_WIDTH, 1, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    // Two glyphs:  .notdef and the glyph that is used for all characters
+    let mut maxp = Vec::new();
+    be16(&mut maxp, &[0, 0x5000, 2]);
+
+    let mut hmtx = Vec::new();
+    be16(&mut hmtx, &[ADVANCE_WIDTH, 0, 0]);
+
+    // A format 13 (many-to-one) subtable that maps all characters except for the control
+    // characters to glyph 1
+    let mut cmap = Vec::new();
+    be16(&mut cmap, &[0, 1, 0, 6, 0, 12]);
+    be16(&mut cmap, &[13, 0, 0, 28, 0, 0, 0, 1]);
+    be16(&mut cmap, &[0, 0x20, 0x10, 0xffff, 0, 1]);
+
+    let mut tables = vec![
+        (b"cmap", cmap),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"maxp", maxp),
+    ];
+    tables.extend(extra_tables);
+    tables.sort_by_key(|(tag, _)| *tag);
+    // The binary search parameters are only valid for up to seven tables.
+    let num_tables = tables.len() as u16;
+    let mut data = Vec::new();
+    be16(&mut data, &[1, 0, num_tables, 64, 2, num_tables * 16 - 64]);
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, table) in &tables {
+        data.extend_from_slice(*tag);
+        be16(&mut data, &[0, 0]);
+        data.extend_from_slice(&(offset as u32).to_be_bytes());
+        data.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += table.len() + (4 - table.len() % 4) % 4;
+    }
+    for (_, table) in &tables {
+        data.extend_from_slice(table);
+        data.resize(data.len() + (4 - table.len() % 4) % 4, 0);
+    }
+    data
+}
+
 /// The metrics of a font at a given scale.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Metrics {
@@ -600,3 +1222,52 @@ impl Metrics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{test_font_data_with_tables, test_font_family, FontCache, FontData};
+
+    /// Generates a GPOS table with one lookup for the `kern` feature with the given subtables.
+    fn gpos(subtables: &[Vec<u16>]) -> Vec<u8> {
+        // The header with the offsets of the script, feature and lookup lists and an empty script
+        // list
+        let mut gpos = vec![1, 0, 10, 12, 26, 0];
+        // The feature list with the kern feature that uses the first lookup
+        gpos.extend_from_slice(&[1, 0x6b65, 0x726e, 8, 0, 1, 0]);
+        // The lookup list with one pair adjustment lookup
+        gpos.extend_from_slice(&[1, 4, 2, 0, subtables.len() as u16]);
+        let mut offset = 2 * (3 + subtables.len());
+        for subtable in subtables {
+            gpos.push(offset as u16);
+            offset += 2 * subtable.len();
+        }
+        for subtable in subtables {
+            gpos.extend_from_slice(subtable);
+        }
+        gpos.iter()
+            .flat_map(|value| value.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    /// Generates a format 1 pair adjustment subtable for the pair of glyph 1 and the given glyph.
+    fn pair_pos(second: u16, x_advance: i16) -> Vec<u16> {
+        vec![1, 18, 4, 0, 1, 12, 1, second, x_advance as u16, 1, 1, 1]
+    }
+
+    fn kerning(subtables: &[Vec<u16>]) -> Vec<f32> {
+        let mut font_cache = FontCache::new(test_font_family());
+        let data = test_font_data_with_tables(vec![(b"GPOS", gpos(subtables))]);
+        let font = font_cache.add_font(FontData::new(data, None).unwrap());
+        font.kerning(&font_cache, "AV".chars())
+    }
+
+    #[test]
+    fn test_gpos_kerning() {
+        assert_eq!(kerning(&[pair_pos(1, -100)]), vec![0.0, -0.1]);
+        // Only the first subtable that covers the first glyph is applied
+        assert_eq!(
+            kerning(&[pair_pos(0, -50), pair_pos(1, -100)]),
+            vec![0.0, 0.0]
+        );
+    }
+}
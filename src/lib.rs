@@ -91,6 +91,13 @@
 //! repeated until all elements have been rendered completely, that means until all elements return
 //! a [`RenderResult`][] with `has_more == false`.
 //!
+//! If an element does not render any content on a new page but still returns `has_more == true`,
+//! it will never fit on a page and the rendering process is aborted with a
+//! [`PageSizeExceeded`][] error.  This behavior can be configured with the
+//! [`Document::set_min_content_height`][] and [`Document::set_max_empty_pages`][] methods.
+//! Elements that want to force a page break should call [`Context::request_page_break`][]
+//! instead of returning an empty [`RenderResult`][].
+//!
 //! Elements may print to the provided area using the methods of the [`Area`][] struct, or by
 //! calling the `render` method of other elements, or both.
 //!
@@ -129,7 +136,10 @@
 //! [`render`]: ./render/
 //! [`elements`]: ./elements/
 //! [`Context`]: struct.Context.html
+//! [`Context::request_page_break`]: struct.Context.html#method.request_page_break
 //! [`Document`]: struct.Document.html
+//! [`Document::set_max_empty_pages`]: struct.Document.html#method.set_max_empty_pages
+//! [`Document::set_min_content_height`]: struct.Document.html#method.set_min_content_height
 //! [`Document::render`]: struct.Document.html#method.render
 //! [`Document::render_to_file`]: struct.Document.html#method.render_to_file
 //! [`Document::load_font_family`]: struct.Document.html#method.load_font_family
@@ -137,6 +147,7 @@
 //! [`Element::render`]: trait.Element.html#tymethod.render
 //! [`Element::styled`]: trait.Element.html#tymethod.styled
 //! [`PageDecorator`]: trait.PageDecorator.html
+//! [`PageSizeExceeded`]: error/enum.ErrorKind.html#variant.PageSizeExceeded
 //! [`RenderResult`]: struct.RenderResult.html
 //! [`LinearLayout`]: elements/struct.LinearLayout.html
 //! [`StyledElement`]: elements/StyledElement.html
@@ -155,19 +166,29 @@
 
 mod wrap;
 
+pub mod bench;
+pub mod compare;
+pub mod counter;
+pub mod einvoice;
 pub mod elements;
 pub mod error;
 pub mod fonts;
 #[cfg(feature = "math")]
 pub mod math;
+pub mod normalize;
+pub mod outline;
 pub mod render;
 pub mod style;
 #[cfg(feature = "code-syntax-highlighting")]
 pub mod syntax_highlighting;
+pub mod tree;
 
+use std::cell;
 use std::fs;
 use std::io;
 use std::path;
+#[cfg(feature = "code-syntax-highlighting")]
+use std::sync;
 
 use derive_more::{
     Add, AddAssign, Div, DivAssign, From, Into, Mul, MulAssign, Sub, SubAssign, Sum,
@@ -213,6 +234,7 @@ use syntax_highlighting::SyntaxHighlighter;
     SubAssign,
     Sum,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mm(pub f64);
 
 impl Mm {
@@ -220,6 +242,22 @@ impl Mm {
     pub fn max(self, other: Mm) -> Mm {
         Mm(self.0.max(other.0))
     }
+
+    /// Returns the minimum of this value and the given value.
+    pub fn min(self, other: Mm) -> Mm {
+        Mm(self.0.min(other.0))
+    }
+
+    /// Rounds this value to the given number of decimal places.
+    ///
+    /// ```
+    /// use genpdf::Mm;
+    /// assert_eq!(Mm(0.1 + 0.2).round(2), Mm(0.3));
+    /// ```
+    pub fn round(self, decimals: u8) -> Mm {
+        let factor = 10f64.powi(decimals.into());
+        Mm((self.0 * factor).round() / factor)
+    }
 }
 
 impl From<i8> for Mm {
@@ -291,9 +329,11 @@ impl From<Mm> for printpdf::Pt {
 
 /// The alignment of a [`Paragraph`][] or ['Image'][].
 ///
-/// The default alignment is left-flushed.
+/// The default alignment is left-flushed.  The extra word spacing of justified paragraphs can be
+/// limited with [`Paragraph::set_max_word_spacing`][].
 ///
 /// [`Paragraph`]: elements/struct.Paragraph.html
+/// [`Paragraph::set_max_word_spacing`]: elements/struct.Paragraph.html#method.set_max_word_spacing
 /// [`Image`]: elements/struct.Image.html
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Alignment {
@@ -316,6 +356,100 @@ impl Default for Alignment {
     }
 }
 
+/// The vertical alignment of an element.
+///
+/// The default alignment is top-aligned.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum VerticalAlignment {
+    /// Aligned to the top.
+    Top,
+    /// Centered.
+    Middle,
+    /// Aligned to the bottom.
+    Bottom,
+}
+
+impl Default for VerticalAlignment {
+    fn default() -> VerticalAlignment {
+        VerticalAlignment::Top
+    }
+}
+
+/// The behavior if an element fails to render.
+///
+/// The default policy is to abort the rendering process.
+///
+/// The policy is applied by the containers that arrange independent elements:  the
+/// [`LinearLayout`][] (and therefore the [`Document`][], [`Columns`][] and the lists) and the
+/// cells of a [`TableLayout`][].  Wrappers around a single element, for example
+/// [`FramedElement`][], [`KeepWithNext`][] or [`Float`][], pass the error on to the enclosing
+/// container, which then replaces the whole wrapper.
+///
+/// [`Document`]: struct.Document.html
+/// [`LinearLayout`]: elements/struct.LinearLayout.html
+/// [`Columns`]: elements/struct.Columns.html
+/// [`TableLayout`]: elements/struct.TableLayout.html
+/// [`FramedElement`]: elements/struct.FramedElement.html
+/// [`KeepWithNext`]: elements/struct.KeepWithNext.html
+/// [`Float`]: elements/struct.Float.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ElementErrorPolicy {
+    /// Abort the rendering process and return the error.
+    Abort,
+    /// Skip the failing element, collect the error and continue with the next element.
+    ///
+    /// The skipped element is replaced by a [`Placeholder`][] that displays the error message.
+    /// The collected errors are returned by [`Document::render_with_report`][].
+    ///
+    /// [`Document::render_with_report`]: struct.Document.html#method.render_with_report
+    /// [`Placeholder`]: elements/struct.Placeholder.html
+    SkipAndCollect,
+}
+
+impl Default for ElementErrorPolicy {
+    fn default() -> ElementErrorPolicy {
+        ElementErrorPolicy::Abort
+    }
+}
+
+/// The behavior if the layout of an element has to be degraded.
+///
+/// Some elements silently fall back to a degraded layout if their content does not fit, for
+/// example by truncating text or by scaling down an image.  In strict mode, these fallbacks are
+/// reported as [`Warning`][]s, see [`WarningKind`][] for the list of detected fallbacks.  The
+/// default mode is to ignore degradations.
+///
+/// Degradations are not reported for dry runs, see [`render::Area::dry_run`][].
+///
+/// [`Warning`]: error/struct.Warning.html
+/// [`WarningKind`]: error/enum.WarningKind.html
+/// [`render::Area::dry_run`]: render/struct.Area.html#method.dry_run
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StrictMode {
+    /// Ignore degraded layouts.
+    Disabled,
+    /// Collect warnings for degraded layouts and continue the rendering process.
+    ///
+    /// The collected warnings are returned by [`Document::render_with_report`][].
+    ///
+    /// [`Document::render_with_report`]: struct.Document.html#method.render_with_report
+    Warn,
+    /// Return an error with the [`ErrorKind::Degraded`][] kind for degraded layouts.
+    ///
+    /// The error is handled like any other element error according to the
+    /// [`ElementErrorPolicy`][].
+    ///
+    /// [`ErrorKind::Degraded`]: error/enum.ErrorKind.html#variant.Degraded
+    /// [`ElementErrorPolicy`]: enum.ElementErrorPolicy.html
+    Error,
+}
+
+impl Default for StrictMode {
+    fn default() -> StrictMode {
+        StrictMode::Disabled
+    }
+}
+
 /// A position on a PDF layer, measured in millimeters.
 ///
 /// All positions used by `genpdf` are measured from the top left corner of the reference area.
@@ -351,8 +485,14 @@ pub struct Rotation {
 
 impl Rotation {
     /// Creates a new rotation with the given number of degrees.
+    ///
+    /// Infinite and NaN values are treated as no rotation.
     pub fn from_degrees(degrees: f64) -> Self {
-        let degrees = degrees % 360.0;
+        let degrees = if degrees.is_finite() {
+            degrees % 360.0
+        } else {
+            0.0
+        };
         let degrees = if degrees > 180.0 {
             degrees - 360.0
         } else if degrees < -180.0 {
@@ -455,6 +595,35 @@ impl<W: Into<Mm>, H: Into<Mm>> From<(W, H)> for Size {
     }
 }
 
+/// A rectangle on a PDF page, measured in millimeters from the upper left corner of the page.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    /// The position of the upper left corner of the rectangle.
+    pub origin: Position,
+    /// The size of the rectangle.
+    pub size: Size,
+}
+
+impl Rect {
+    /// Creates a new rectangle with the given origin and size.
+    pub fn new(origin: impl Into<Position>, size: impl Into<Size>) -> Rect {
+        Rect {
+            origin: origin.into(),
+            size: size.into(),
+        }
+    }
+
+    /// Returns true if this rectangle and the given rectangle overlap.
+    ///
+    /// Rectangles that only share an edge do not overlap.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.origin.x < other.origin.x + other.size.width
+            && other.origin.x < self.origin.x + self.size.width
+            && self.origin.y < other.origin.y + other.size.height
+            && other.origin.y < self.origin.y + self.size.height
+    }
+}
+
 /// A paper size like A4, legal or letter.
 ///
 /// This enum provides variants for typical paper sizes that can be converted into [`Size`][]
@@ -483,6 +652,7 @@ impl From<PaperSize> for Size {
 
 /// The margins of an area, measured in millimeters.
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Margins {
     /// The top margin of the area.
     pub top: Mm,
@@ -584,6 +754,7 @@ impl<T: Into<Mm>> From<T> for Margins {
 pub struct Document {
     root: elements::LinearLayout,
     title: String,
+    language: Option<String>,
     context: Context,
     style: style::Style,
     paper_size: Size,
@@ -591,6 +762,11 @@ pub struct Document {
     conformance: Option<printpdf::PdfConformance>,
     creation_date: Option<printpdf::OffsetDateTime>,
     modification_date: Option<printpdf::OffsetDateTime>,
+    min_content_height: Mm,
+    max_empty_pages: usize,
+    coordinate_precision: Option<u8>,
+    debug_grid: Option<DebugGrid>,
+    counters: counter::Counters,
 }
 
 impl Document {
@@ -600,6 +776,7 @@ impl Document {
         Document {
             root: elements::LinearLayout::vertical(),
             title: String::new(),
+            language: None,
             context: Context::new(font_cache),
             style: style::Style::new(),
             paper_size: PaperSize::A4.into(),
@@ -607,6 +784,11 @@ impl Document {
             conformance: None,
             creation_date: None,
             modification_date: None,
+            min_content_height: Mm(0.0),
+            max_empty_pages: 0,
+            coordinate_precision: None,
+            debug_grid: None,
+            counters: counter::Counters::new(),
         }
     }
 
@@ -624,16 +806,127 @@ impl Document {
         self.context.font_cache.add_font_family(font_family)
     }
 
+    /// Adds the fonts of the given sub-document to the font cache for this document and returns
+    /// the sub-document so that it can be added to the document content.
+    ///
+    /// This method creates the content of the sub-document with its font families mapped into the
+    /// font cache of this document, see [`SubDocument`][] for details.  The returned sub-document
+    /// may only be used for this document.
+    ///
+    /// [`SubDocument`]: elements/struct.SubDocument.html
+    pub fn add_sub_document(
+        &mut self,
+        mut sub_document: elements::SubDocument,
+    ) -> elements::SubDocument {
+        sub_document.map_fonts(&mut self.context.font_cache);
+        sub_document
+    }
+
+    /// Adds the given icon font to the font cache for this document and returns a reference to it.
+    ///
+    /// The icons can then be added to paragraphs by name using [`Paragraph::push_icon`][].  See
+    /// [`FontCache::add_icon_font`][] for details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use genpdf::{elements, fonts};
+    /// let font_family = fonts::from_files("./fonts", "LiberationSans", None)
+    ///     .expect("Failed to load font family");
+    /// let mut doc = genpdf::Document::new(font_family);
+    /// let data = std::fs::read("./fonts/fa-solid-900.ttf").expect("Failed to read icon font");
+    /// let icons = fonts::IconMap::from_glyph_names(&data).expect("Failed to read icon names");
+    /// let font = fonts::FontData::new(data, None).expect("Failed to load icon font");
+    /// doc.add_icon_font(font, icons);
+    /// let mut p = elements::Paragraph::default();
+    /// p.push_icon("circle-check", genpdf::style::Color::Rgb(0, 128, 0));
+    /// p.push(" All tests passed");
+    /// doc.push(p);
+    /// ```
+    ///
+    /// [`Paragraph::push_icon`]: elements/struct.Paragraph.html#method.push_icon
+    /// [`FontCache::add_icon_font`]: fonts/struct.FontCache.html#method.add_icon_font
+    pub fn add_icon_font(
+        &mut self,
+        font_data: fonts::FontData,
+        icons: fonts::IconMap,
+    ) -> fonts::FontFamily<fonts::Font> {
+        self.context.font_cache.add_icon_font(font_data, icons)
+    }
+
+    /// Returns the counters of this document.
+    ///
+    /// See the [`counter`][] module for details.
+    ///
+    /// [`counter`]: counter/index.html
+    pub fn counters(&self) -> &counter::Counters {
+        &self.counters
+    }
+
+    /// Returns a mutable reference to the counters of this document that can be used to number
+    /// headings, figures, tables, listings and equations.
+    ///
+    /// See the [`counter`][] module for details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use genpdf::elements;
+    /// let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+    ///     .expect("Failed to load font family");
+    /// let mut doc = genpdf::Document::new(font_family);
+    /// doc.counters_mut().set_parent("figure", "chapter");
+    /// let number = doc.counters_mut().step("chapter");
+    /// doc.push(elements::Paragraph::new(format!("{} Introduction", number)));
+    /// ```
+    ///
+    /// [`counter`]: counter/index.html
+    pub fn counters_mut(&mut self) -> &mut counter::Counters {
+        &mut self.counters
+    }
+
     /// Enables math rendering by providing a font with a valid MATH header.
+    ///
+    /// If the font data cannot be parsed as an OpenType font, an error is returned.  The parsed
+    /// font is kept in memory until the program exits, so this method should only be called once
+    /// per math font.
     #[cfg(feature = "math")]
-    pub fn enable_math(&mut self, math_font_data: &[u8], math_font_family: FontFamily<Font>) {
-        self.context.math_renderer = Some(MathRenderer::new(math_font_data, math_font_family));
+    pub fn enable_math(
+        &mut self,
+        math_font_data: &[u8],
+        math_font_family: FontFamily<Font>,
+    ) -> Result<(), error::Error> {
+        self.context.math_renderer = Some(MathRenderer::new(math_font_data, math_font_family)?);
+        Ok(())
     }
 
     /// Enables syntax highlighting
+    ///
+    /// The highlighter can be passed as an owned value or as an [`Arc`][], so that it only has to
+    /// be loaded once if multiple documents are generated, possibly in multiple threads.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use genpdf::syntax_highlighting::SyntaxHighlighter;
+    ///
+    /// let highlighter = Arc::new(SyntaxHighlighter::load_defaults());
+    /// for _ in 0..2 {
+    ///     let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+    ///         .expect("Failed to load font family");
+    ///     let mut doc = genpdf::Document::new(font_family);
+    ///     doc.enable_syntax_highlighting(highlighter.clone());
+    /// }
+    /// ```
+    ///
+    /// [`Arc`]: https://doc.rust-lang.org/std/sync/struct.Arc.html
     #[cfg(feature = "code-syntax-highlighting")]
-    pub fn enable_syntax_highlighting(&mut self, syntax_highlighter: SyntaxHighlighter) {
-        self.context.syntax_highlighter = Some(syntax_highlighter);
+    pub fn enable_syntax_highlighting(
+        &mut self,
+        syntax_highlighter: impl Into<sync::Arc<SyntaxHighlighter>>,
+    ) {
+        self.context.syntax_highlighter = Some(syntax_highlighter.into());
     }
 
     /// Returns the font cache used by this document.
@@ -646,21 +939,101 @@ impl Document {
         &self.context.font_cache
     }
 
+    /// Returns the width of the given string when it is rendered with the given style.
+    ///
+    /// The given style is combined with the default style of this document, and the text
+    /// normalization of this document is applied to the string, so the returned width is the
+    /// width that a [`Text`][] element with this string and style would have in this document.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use genpdf::style;
+    /// let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+    ///     .expect("Failed to load font family");
+    /// let doc = genpdf::Document::new(font_family);
+    /// let width = doc.str_width("Total", &style::Style::new().bold());
+    /// ```
+    ///
+    /// [`Text`]: elements/struct.Text.html
+    pub fn str_width(&self, s: &str, style: &style::Style) -> Mm {
+        let s = self.context.text_normalization.apply(s);
+        self.style
+            .and(*style)
+            .str_width(&self.context.font_cache, &s)
+    }
+
+    /// Returns the height of a line of text with the given style.
+    ///
+    /// The given style is combined with the default style of this document, so the line spacing
+    /// and the font size of this document are taken into account.
+    pub fn line_height(&self, style: &style::Style) -> Mm {
+        self.style.and(*style).line_height(&self.context.font_cache)
+    }
+
     /// Activates hyphenation and sets the hyphentor to use.
     ///
+    /// This hyphenator is used for all paragraphs without a hyphenation language, see
+    /// [`Paragraph::set_hyphenation_language`][].
+    ///
     /// *Only available if the `hyphenation` feature is enabled.*
+    ///
+    /// [`Paragraph::set_hyphenation_language`]: elements/struct.Paragraph.html#method.set_hyphenation_language
     #[cfg(feature = "hyphenation")]
     pub fn set_hyphenator(&mut self, hyphenator: hyphenation::Standard) {
         self.context.hyphenator = Some(hyphenator);
     }
 
+    /// Adds a hyphenator for the given language.
+    ///
+    /// The hyphenator is used for all paragraphs with this hyphenation language, see
+    /// [`Paragraph::set_hyphenation_language`][].  Paragraphs with a language that has no
+    /// hyphenator are not hyphenated.
+    ///
+    /// *Only available if the `hyphenation` feature is enabled.*
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hyphenation::{Language, Load as _};
+    /// let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+    ///     .expect("Failed to load font family");
+    /// let mut doc = genpdf::Document::new(font_family);
+    /// let hyphenator = hyphenation::Standard::from_embedded(Language::EnglishUS)
+    ///     .expect("Failed to load hyphenator");
+    /// doc.add_hyphenator(Language::EnglishUS, hyphenator);
+    /// doc.push(
+    ///     genpdf::elements::Paragraph::new("Hyphenation")
+    ///         .with_hyphenation_language(Language::EnglishUS),
+    /// );
+    /// ```
+    ///
+    /// [`Paragraph::set_hyphenation_language`]: elements/struct.Paragraph.html#method.set_hyphenation_language
+    #[cfg(feature = "hyphenation")]
+    pub fn add_hyphenator(
+        &mut self,
+        language: hyphenation::Language,
+        hyphenator: hyphenation::Standard,
+    ) {
+        self.context.hyphenators.insert(language, hyphenator);
+    }
+
     /// Sets the title of the PDF document.
     ///
-    /// If this method is not called, the PDF title will be empty.
+    /// If this method is not called, the PDF title will be empty.  Otherwise, PDF viewers are
+    /// instructed to display the title instead of the file name.
     pub fn set_title(&mut self, title: impl Into<String>) {
         self.title = title.into();
     }
 
+    /// Sets the natural language of the PDF document as a language tag, for example `de-DE`.
+    ///
+    /// The language is used by screen readers and checked by accessibility validators.  If this
+    /// method is not called, no language is written to the PDF document.
+    pub fn set_language(&mut self, language: impl Into<String>) {
+        self.language = Some(language.into());
+    }
+
     /// Sets the default font size in points for this document.
     ///
     /// If this method is not called, the default value of 12 points is used.
@@ -720,6 +1093,134 @@ impl Document {
         ));
     }
 
+    /// Sets the policy for elements that fail to render.
+    ///
+    /// If this method is not called, [`ElementErrorPolicy::Abort`][] is used and the rendering
+    /// process is cancelled if an element returns an error.  With
+    /// [`ElementErrorPolicy::SkipAndCollect`][], failing elements are skipped and the errors are
+    /// returned by [`render_with_report`][].
+    ///
+    /// [`ElementErrorPolicy::Abort`]: enum.ElementErrorPolicy.html#variant.Abort
+    /// [`ElementErrorPolicy::SkipAndCollect`]: enum.ElementErrorPolicy.html#variant.SkipAndCollect
+    /// [`render_with_report`]: #method.render_with_report
+    pub fn set_element_error_policy(&mut self, policy: ElementErrorPolicy) {
+        self.context.element_error_policy = policy;
+    }
+
+    /// Sets the strict mode that determines how degraded layouts are reported.
+    ///
+    /// If this method is not called, [`StrictMode::Disabled`][] is used and degraded layouts are
+    /// not reported.  This can be used by automated pipelines to detect quality regressions, see
+    /// [`StrictMode`][] for details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+    ///     .expect("Failed to load font family");
+    /// let mut doc = genpdf::Document::new(font_family);
+    /// doc.set_strict_mode(genpdf::StrictMode::Warn);
+    /// doc.push(genpdf::elements::Text::new("A very long product description").with_max_width(20));
+    /// let report = doc.render_with_report(std::io::sink()).expect("Failed to render document");
+    /// for warning in report.warnings() {
+    ///     eprintln!("{}", warning);
+    /// }
+    /// ```
+    ///
+    /// [`StrictMode::Disabled`]: enum.StrictMode.html#variant.Disabled
+    /// [`StrictMode`]: enum.StrictMode.html
+    pub fn set_strict_mode(&mut self, mode: StrictMode) {
+        self.context.strict_mode = mode;
+    }
+
+    /// Sets the minimum scale factor for images in strict mode.
+    ///
+    /// If an image is rendered with a smaller scale factor (including the scaling to the page
+    /// width), a [`WarningKind::DownscaledImage`][] warning is reported.  If this method is not
+    /// called, the minimum scale factor is 0.5.
+    ///
+    /// [`WarningKind::DownscaledImage`]: error/enum.WarningKind.html#variant.DownscaledImage
+    pub fn set_min_image_scale(&mut self, scale: f64) {
+        self.context.min_image_scale = scale;
+    }
+
+    /// Sets the normalization for the text of the [`Text`][] and [`Paragraph`][] elements of this
+    /// document.
+    ///
+    /// If this method is not called, the text is not normalized.
+    ///
+    /// [`Text`]: elements/struct.Text.html
+    /// [`Paragraph`]: elements/struct.Paragraph.html
+    pub fn set_text_normalization(&mut self, normalization: normalize::TextNormalization) {
+        self.context.text_normalization = normalization;
+    }
+
+    /// Sets the minimum height of the content that has to be rendered on a page before a new page
+    /// is added.
+    ///
+    /// If the content rendered on a page is lower than this height (or if no content has been
+    /// rendered at all) and the document has more content, the page counts as empty, see
+    /// [`set_max_empty_pages`][].  Explicit page breaks requested with
+    /// [`Context::request_page_break`][] are always allowed.  If this method is not called, only
+    /// pages without any content count as empty.
+    ///
+    /// [`set_max_empty_pages`]: #method.set_max_empty_pages
+    /// [`Context::request_page_break`]: struct.Context.html#method.request_page_break
+    pub fn set_min_content_height(&mut self, min_content_height: impl Into<Mm>) {
+        self.min_content_height = min_content_height.into();
+    }
+
+    /// Sets the maximum number of consecutive empty pages before the rendering process is
+    /// aborted with a [`PageSizeExceeded`][] error.
+    ///
+    /// This can be useful if the page decorator provides a smaller area on the first page than on
+    /// the following pages.  If this method is not called, the rendering process is aborted on
+    /// the first empty page.
+    ///
+    /// [`PageSizeExceeded`]: error/enum.ErrorKind.html#variant.PageSizeExceeded
+    pub fn set_max_empty_pages(&mut self, max_empty_pages: usize) {
+        self.max_empty_pages = max_empty_pages;
+    }
+
+    /// Sets the number of decimal places of the coordinates written to the PDF document.
+    ///
+    /// The positions of the elements are calculated with floating-point arithmetic, so the
+    /// position of a border that is reached on two different ways, for example in a long table,
+    /// can differ by tiny fractions of a millimeter.  If this method is called, all coordinates
+    /// are rounded to the given number of decimal places (in millimeters) before they are written
+    /// to the document, so that such positions are identical.  Two decimal places are sufficient
+    /// for most documents.  If this method is not called, the coordinates are not rounded.
+    pub fn set_coordinate_precision(&mut self, decimals: u8) {
+        self.coordinate_precision = Some(decimals);
+    }
+
+    /// Enables the debug grid for all pages of this document.
+    ///
+    /// The debug grid draws a millimeter ruler along the page edges and a grid over the content
+    /// area of every page, see [`DebugGrid`][].  It is meant for designing layouts and should not
+    /// be used for production documents.
+    ///
+    /// [`DebugGrid`]: struct.DebugGrid.html
+    pub fn set_debug_grid(&mut self, debug_grid: DebugGrid) {
+        self.debug_grid = Some(debug_grid);
+    }
+
+    /// Sets whether the remaining area should be recorded after every top-level element.
+    ///
+    /// If enabled, an [`AreaSnapshot`][] is recorded every time an element that has been added
+    /// with [`push`][] is rendered on a page.  The snapshots can be retrieved from the
+    /// [`RenderReport`][] returned by [`render_with_report`][] and can be used to analyze where
+    /// space is lost, for example because an element is moved to the next page.  Per default,
+    /// no snapshots are recorded.
+    ///
+    /// [`AreaSnapshot`]: struct.AreaSnapshot.html
+    /// [`RenderReport`]: struct.RenderReport.html
+    /// [`push`]: #method.push
+    /// [`render_with_report`]: #method.render_with_report
+    pub fn set_record_area_snapshots(&mut self, record: bool) {
+        self.root.set_record_area_snapshots(record);
+    }
+
     /// Sets the creation date of the PDF file.
     pub fn set_creation_date(&mut self, date: printpdf::OffsetDateTime) {
         self.creation_date = Some(date);
@@ -742,13 +1243,55 @@ impl Document {
         self.root.push(element);
     }
 
+    /// Returns a description of the elements that have been added to this document for
+    /// debugging, see the [`tree`][] module.
+    ///
+    /// [`tree`]: tree/index.html
+    pub fn dump_tree(&self) -> tree::ElementTree {
+        self.root.dump_tree()
+    }
+
     /// Renders this document into a PDF file and writes it to the given writer.
     ///
     /// The given writer is always wrapped in a buffered writer.  For details on the rendering
     /// process, see the [Rendering Process section of the crate
     /// documentation](index.html#rendering-process).
-    pub fn render(mut self, w: impl io::Write) -> Result<(), error::Error> {
+    pub fn render(self, w: impl io::Write) -> Result<(), error::Error> {
+        self.render_with_outline(w).map(|_| ())
+    }
+
+    /// Renders this document into a PDF file, writes it to the given writer and returns the
+    /// outline of the rendered document.
+    ///
+    /// The outline contains all entries that have been registered during the rendering process,
+    /// for example by [`OutlineMarker`][] elements.  Otherwise, this method is identical to
+    /// [`render`][].
+    ///
+    /// [`OutlineMarker`]: elements/struct.OutlineMarker.html
+    /// [`render`]: #method.render
+    pub fn render_with_outline(self, w: impl io::Write) -> Result<outline::Outline, error::Error> {
+        self.render_with_report(w).map(|report| report.outline)
+    }
+
+    /// Renders this document into a PDF file, writes it to the given writer and returns a report
+    /// on the rendering process.
+    ///
+    /// The report contains the outline of the rendered document, the errors of all elements
+    /// that have been skipped according to the [`ElementErrorPolicy`][] and the warnings
+    /// collected according to the [`StrictMode`][].  Otherwise, this method is identical to
+    /// [`render`][].
+    ///
+    /// [`ElementErrorPolicy`]: enum.ElementErrorPolicy.html
+    /// [`StrictMode`]: enum.StrictMode.html
+    /// [`render`]: #method.render
+    pub fn render_with_report(mut self, w: impl io::Write) -> Result<RenderReport, error::Error> {
         let mut renderer = render::Renderer::new(self.paper_size, &self.title)?;
+        if let Some(language) = self.language {
+            renderer = renderer.with_language(language);
+        }
+        if !self.title.is_empty() {
+            renderer = renderer.with_display_doc_title(true);
+        }
         if let Some(conformance) = self.conformance {
             renderer = renderer.with_conformance(conformance);
         }
@@ -758,17 +1301,52 @@ impl Document {
         if let Some(modification_date) = self.modification_date {
             renderer = renderer.with_modification_date(modification_date);
         }
+        if let Some(decimals) = self.coordinate_precision {
+            renderer = renderer.with_coordinate_precision(decimals);
+        }
         self.context.font_cache.load_pdf_fonts(&renderer)?;
+        let mut empty_pages = 0;
+        let mut area_snapshots = Vec::new();
+        let mut page_areas = Vec::new();
         loop {
             let mut area = renderer.last_page().last_layer().area();
+            let page_area = area.clone();
             if let Some(decorator) = &mut self.decorator {
                 area = decorator.decorate_page(&self.context, area, self.style)?;
             }
+            let current_page_area = PageArea {
+                page: area.page_number(),
+                page_box: page_area.rect(),
+                content_box: area.rect(),
+                reserved: self.context.reserved_rects.take(),
+            };
+            page_areas.push(current_page_area.clone());
+            self.context.page_area.replace(Some(current_page_area));
+            if let Some(debug_grid) = &self.debug_grid {
+                debug_grid.draw(&self.context, &page_area, &area, self.style)?;
+            }
+            let available = area.size();
             let result = self.root.render(&self.context, area, self.style)?;
+            for mut snapshot in self.root.take_area_snapshots() {
+                snapshot.page = renderer.page_count();
+                area_snapshots.push(snapshot);
+            }
+            let page_break = self.context.page_break_requested.replace(false);
             if result.has_more {
-                if result.size == Size::new(0, 0) {
+                let is_empty =
+                    result.size == Size::new(0, 0) || result.size.height < self.min_content_height;
+                if is_empty && !page_break {
+                    empty_pages += 1;
+                } else {
+                    empty_pages = 0;
+                }
+                if empty_pages > self.max_empty_pages {
                     return Err(error::Error::new(
-                        "Could not fit an element on a new page",
+                        format!(
+                            "Could not fit an element on a new page with an available area of \
+                             {:.1} x {:.1} mm",
+                            available.width.0, available.height.0
+                        ),
                         error::ErrorKind::PageSizeExceeded,
                     ));
                 }
@@ -777,7 +1355,16 @@ impl Document {
                 break;
             }
         }
-        renderer.write(w)
+        let mut outline = self.context.outline.into_inner();
+        outline.set_page_count(renderer.page_count());
+        renderer.write(w)?;
+        Ok(RenderReport {
+            outline,
+            errors: self.context.element_errors.into_inner(),
+            warnings: self.context.warnings.into_inner(),
+            area_snapshots,
+            page_areas,
+        })
     }
 
     /// Renders this document into a PDF file at the given path.
@@ -800,6 +1387,188 @@ impl<E: elements::IntoBoxedElement> std::iter::Extend<E> for Document {
     }
 }
 
+/// A report on the rendering process of a [`Document`][].
+///
+/// This struct is returned by [`Document::render_with_report`][].
+///
+/// [`Document`]: struct.Document.html
+/// [`Document::render_with_report`]: struct.Document.html#method.render_with_report
+#[derive(Debug)]
+pub struct RenderReport {
+    outline: outline::Outline,
+    errors: Vec<error::Error>,
+    warnings: Vec<error::Warning>,
+    area_snapshots: Vec<AreaSnapshot>,
+    page_areas: Vec<PageArea>,
+}
+
+impl RenderReport {
+    /// Returns the outline of the rendered document.
+    pub fn outline(&self) -> &outline::Outline {
+        &self.outline
+    }
+
+    /// Returns the errors of the elements that have been skipped during the rendering process.
+    ///
+    /// This is always empty unless the [`ElementErrorPolicy::SkipAndCollect`][] policy is used.
+    ///
+    /// [`ElementErrorPolicy::SkipAndCollect`]: enum.ElementErrorPolicy.html#variant.SkipAndCollect
+    pub fn errors(&self) -> &[error::Error] {
+        &self.errors
+    }
+
+    /// Returns the warnings about degraded layouts in the order of rendering.
+    ///
+    /// This is always empty unless the [`StrictMode::Warn`][] mode is used.
+    ///
+    /// [`StrictMode::Warn`]: enum.StrictMode.html#variant.Warn
+    pub fn warnings(&self) -> &[error::Warning] {
+        &self.warnings
+    }
+
+    /// Returns the area snapshots that have been recorded during the rendering process in the
+    /// order of rendering.
+    ///
+    /// This is always empty unless [`Document::set_record_area_snapshots`][] has been called.
+    ///
+    /// [`Document::set_record_area_snapshots`]: struct.Document.html#method.set_record_area_snapshots
+    pub fn area_snapshots(&self) -> &[AreaSnapshot] {
+        &self.area_snapshots
+    }
+
+    /// Returns the layout of the rendered pages in the order of the pages.
+    pub fn page_areas(&self) -> &[PageArea] {
+        &self.page_areas
+    }
+}
+
+/// The layout of a page:  the content box that is available for the document content and the
+/// rectangles that are reserved for page decorations like headers and footers.
+///
+/// The page area is determined after the [`PageDecorator`][] has been applied to a page.  During
+/// the rendering process, elements can access the area of the current page with
+/// [`Context::page_area`][], for example to place content outside of the content box without
+/// overlapping the decorations.  After the rendering process, the areas of all pages are
+/// returned by [`RenderReport::page_areas`][].
+///
+/// Page decorators can reserve rectangles with [`Context::reserve_rect`][].
+/// [`SimplePageDecorator`][] reserves the area of the header.
+///
+/// [`PageDecorator`]: trait.PageDecorator.html
+/// [`SimplePageDecorator`]: struct.SimplePageDecorator.html
+/// [`Context::page_area`]: struct.Context.html#method.page_area
+/// [`Context::reserve_rect`]: struct.Context.html#method.reserve_rect
+/// [`RenderReport::page_areas`]: struct.RenderReport.html#method.page_areas
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageArea {
+    page: usize,
+    page_box: Rect,
+    content_box: Rect,
+    reserved: Vec<Rect>,
+}
+
+impl PageArea {
+    /// Returns the number of the page, starting with 1.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the rectangle of the full page.
+    pub fn page_box(&self) -> Rect {
+        self.page_box
+    }
+
+    /// Returns the rectangle that is available for the document content.
+    pub fn content_box(&self) -> Rect {
+        self.content_box
+    }
+
+    /// Returns the rectangles that have been reserved by the page decorator.
+    pub fn reserved(&self) -> &[Rect] {
+        &self.reserved
+    }
+
+    /// Returns true if the given rectangle overlaps neither the content box nor the reserved
+    /// rectangles.
+    pub fn is_free(&self, rect: &Rect) -> bool {
+        !rect.intersects(&self.content_box) && !self.reserved.iter().any(|r| rect.intersects(r))
+    }
+}
+
+/// The area that remained on a page after rendering a top-level element of a [`Document`][].
+///
+/// An element that is split over multiple pages produces one snapshot per page.  The snapshots
+/// are only recorded if [`Document::set_record_area_snapshots`][] has been called and are
+/// returned by [`RenderReport::area_snapshots`][].
+///
+/// # Example
+///
+/// ```no_run
+/// let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+///     .expect("Failed to load font family");
+/// let mut doc = genpdf::Document::new(font_family);
+/// doc.set_record_area_snapshots(true);
+/// doc.push(genpdf::elements::Paragraph::new("Test"));
+/// let report = doc
+///     .render_with_report(std::io::sink())
+///     .expect("Failed to render document");
+/// for snapshot in report.area_snapshots() {
+///     println!(
+///         "element {} on page {}: {:.1} mm remaining",
+///         snapshot.element(),
+///         snapshot.page(),
+///         snapshot.remaining_height().0,
+///     );
+/// }
+/// ```
+///
+/// [`Document`]: struct.Document.html
+/// [`Document::set_record_area_snapshots`]: struct.Document.html#method.set_record_area_snapshots
+/// [`RenderReport::area_snapshots`]: struct.RenderReport.html#method.area_snapshots
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AreaSnapshot {
+    element: usize,
+    page: usize,
+    available_height: Mm,
+    height: Mm,
+    has_more: bool,
+}
+
+impl AreaSnapshot {
+    /// Returns the index of the element in the order in which the elements have been added to
+    /// the document, starting with 0.
+    pub fn element(&self) -> usize {
+        self.element
+    }
+
+    /// Returns the page on which the element has been rendered, starting with 1.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the height that was available before the element has been rendered.
+    pub fn available_height(&self) -> Mm {
+        self.available_height
+    }
+
+    /// Returns the height of the element on this page.
+    pub fn height(&self) -> Mm {
+        self.height
+    }
+
+    /// Returns the height that remained after the element has been rendered.
+    ///
+    /// This does not include the spacing of the root layout.
+    pub fn remaining_height(&self) -> Mm {
+        self.available_height - self.height
+    }
+
+    /// Returns whether the element is continued on the next page.
+    pub fn has_more(&self) -> bool {
+        self.has_more
+    }
+}
+
 /// The result of the rendering process.
 ///
 /// This struct is returned by implementations of the [`Element::render`][] method.  It contains
@@ -827,7 +1596,12 @@ pub struct RenderResult {
 /// is added to the document.  The decorator can prepare the page before it is filled with the
 /// actual content.  See [`SimplePageDecorator`][] for a basic implementation.
 ///
+/// Decorators should reserve the parts of the page they draw on using [`Context::reserve_rect`][]
+/// so that they are included in the [`PageArea`][] of the page.
+///
 /// [`Document`]: struct.Document.html
+/// [`Context::reserve_rect`]: struct.Context.html#method.reserve_rect
+/// [`PageArea`]: struct.PageArea.html
 /// [`set_page_decorator`]: struct.Document.html#method.set_page_decorator
 /// [`SimplePageDecorator`]: struct.SimplePageDecorator.html
 /// [`decorate_page`]: #tymethod.decorate_page
@@ -851,14 +1625,18 @@ type HeaderCallback = Box<dyn Fn(usize) -> Box<dyn Element>>;
 /// Per default, this decorator does not modify the page.  If margins have been set with the
 /// [`set_margins`][] method, they are applied to every page.  If a header callback is configured
 /// with the [`set_header`][] method, it will be called for every page and its return value will be
-/// rendered at the beginning of the page (after the margins have been applied).
+/// rendered at the beginning of the page (after the margins have been applied).  For documents
+/// that are printed on both sides and bound, a gutter can be added with the
+/// [`set_binding_offset`][] method.
 ///
 /// [`set_margins`]: #method.set_margins
 /// [`set_header`]: #method.set_header
+/// [`set_binding_offset`]: #method.set_binding_offset
 #[derive(Default)]
 pub struct SimplePageDecorator {
     page: usize,
     margins: Option<Margins>,
+    binding_offset: Option<Mm>,
     header_cb: Option<HeaderCallback>,
 }
 
@@ -875,6 +1653,21 @@ impl SimplePageDecorator {
         self.margins = Some(margins.into());
     }
 
+    /// Sets the binding offset (gutter) for all pages of this document.
+    ///
+    /// The offset is added to the inner margin of every page, so the content area is shifted
+    /// towards the outer edge:  on odd pages (right-hand pages), it is added to the left margin;
+    /// on even pages (left-hand pages), it is added to the right margin.
+    ///
+    /// ```
+    /// let mut decorator = genpdf::SimplePageDecorator::new();
+    /// decorator.set_margins(20);
+    /// decorator.set_binding_offset(8);
+    /// ```
+    pub fn set_binding_offset(&mut self, offset: impl Into<Mm>) {
+        self.binding_offset = Some(offset.into());
+    }
+
     /// Sets the header generator for this document.
     ///
     /// The given closure will be called once per page.  Its argument is the page number (starting
@@ -901,15 +1694,165 @@ impl PageDecorator for SimplePageDecorator {
         if let Some(margins) = self.margins {
             area.add_margins(margins);
         }
+        if let Some(offset) = self.binding_offset {
+            if self.page % 2 == 1 {
+                area.add_margins(Margins::trbl(0, 0, 0, offset));
+            } else {
+                area.add_margins(Margins::trbl(0, offset, 0, 0));
+            }
+        }
         if let Some(cb) = &self.header_cb {
             let mut element = cb(self.page);
             let result = element.render(context, area.clone(), style)?;
+            context.reserve_rect(Rect::new(
+                area.origin(),
+                Size::new(area.size().width, result.size.height),
+            ));
             area.add_offset(Position::new(0, result.size.height));
         }
         Ok(area)
     }
 }
 
+/// A ruler and grid overlay for designing layouts.
+///
+/// If a debug grid is set for a document with [`Document::set_debug_grid`][], a millimeter ruler
+/// is drawn along the top and the left edge of every page, and a grid is drawn over the content
+/// area that remains after the [`PageDecorator`][] has been applied.  The ruler is measured from
+/// the upper left corner of the page, and the grid lines start at the upper left corner of the
+/// content area, so positions can be read off directly for elements and for page decorators.
+/// The grid is drawn before the content of the page.
+///
+/// # Example
+///
+/// ```no_run
+/// let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+///     .expect("Failed to load font family");
+/// let mut doc = genpdf::Document::new(font_family);
+/// doc.set_debug_grid(genpdf::DebugGrid::new().with_spacing(5));
+/// ```
+///
+/// [`Document::set_debug_grid`]: struct.Document.html#method.set_debug_grid
+/// [`PageDecorator`]: trait.PageDecorator.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DebugGrid {
+    spacing: Mm,
+    color: style::Color,
+}
+
+impl DebugGrid {
+    /// Creates a new debug grid with a spacing of 10 mm.
+    pub fn new() -> DebugGrid {
+        DebugGrid::default()
+    }
+
+    /// Sets the spacing between the grid lines.
+    pub fn set_spacing(&mut self, spacing: impl Into<Mm>) {
+        self.spacing = spacing.into();
+    }
+
+    /// Sets the spacing between the grid lines and returns the grid.
+    pub fn with_spacing(mut self, spacing: impl Into<Mm>) -> Self {
+        self.set_spacing(spacing);
+        self
+    }
+
+    /// Sets the color of the ruler and the grid lines.
+    pub fn set_color(&mut self, color: style::Color) {
+        self.color = color;
+    }
+
+    /// Sets the color of the ruler and the grid lines and returns the grid.
+    pub fn with_color(mut self, color: style::Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    fn draw(
+        &self,
+        context: &Context,
+        page_area: &render::Area<'_>,
+        content_area: &render::Area<'_>,
+        style: style::Style,
+    ) -> Result<(), error::Error> {
+        let line_style = style::LineStyle::new()
+            .with_thickness(0.1)
+            .with_color(self.color);
+        let label_style = style.with_font_size(5).with_color(self.color);
+
+        // Ruler along the top and the left edge of the page, with a label every centimeter
+        let page_size = page_area.size();
+        let horizontal = (0..)
+            .take_while(|mm| Mm::from(*mm) <= page_size.width)
+            .map(|mm| (mm, true));
+        let vertical = (0..)
+            .take_while(|mm| Mm::from(*mm) <= page_size.height)
+            .map(|mm| (mm, false));
+        for (mm, horizontal) in horizontal.chain(vertical) {
+            let length = match mm % 10 {
+                0 => Mm(3.0),
+                5 => Mm(2.0),
+                _ => Mm(1.0),
+            };
+            let offset = Mm::from(mm);
+            let (start, end, label_position) = if horizontal {
+                (
+                    Position::new(offset, 0),
+                    Position::new(offset, length),
+                    Position::new(offset + Mm(0.5), Mm(1.0)),
+                )
+            } else {
+                (
+                    Position::new(0, offset),
+                    Position::new(length, offset),
+                    Position::new(Mm(1.0), offset + Mm(0.5)),
+                )
+            };
+            page_area.draw_line(vec![start, end], line_style);
+            if mm % 10 == 0 && mm > 0 {
+                page_area.print_str(
+                    &context.font_cache,
+                    label_position,
+                    label_style,
+                    mm.to_string(),
+                )?;
+            }
+        }
+
+        // Grid over the content area
+        if self.spacing > Mm(0.0) {
+            let size = content_area.size();
+            let mut x = Mm(0.0);
+            while x <= size.width {
+                content_area.draw_line(
+                    vec![Position::new(x, 0), Position::new(x, size.height)],
+                    line_style,
+                );
+                x += self.spacing;
+            }
+            let mut y = Mm(0.0);
+            while y <= size.height {
+                content_area.draw_line(
+                    vec![Position::new(0, y), Position::new(size.width, y)],
+                    line_style,
+                );
+                y += self.spacing;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for DebugGrid {
+    fn default() -> DebugGrid {
+        DebugGrid {
+            spacing: Mm(10.0),
+            color: style::Color::Rgb(120, 170, 230),
+        }
+    }
+}
+
 /// An element of a PDF document.
 ///
 /// This trait is implemented by all elements that can be added to a [`Document`][].  Implementors
@@ -962,6 +1905,76 @@ pub trait Element {
         style: style::Style,
     ) -> Result<RenderResult, error::Error>;
 
+    /// Returns the width that this element needs to render its content without line breaks, if
+    /// it can be determined before rendering.
+    ///
+    /// This width is used by [`TableLayout`][] to calculate the width of columns with the
+    /// [`ColumnWidth::Auto`][] sizing mode.  The default implementation returns `None`.
+    ///
+    /// [`TableLayout`]: elements/struct.TableLayout.html
+    /// [`ColumnWidth::Auto`]: elements/enum.ColumnWidth.html#variant.Auto
+    fn natural_width(&self, context: &Context, style: style::Style) -> Option<Mm> {
+        let _ = (context, style);
+        None
+    }
+
+    /// Returns the result that the next call to [`render`][] with the same arguments would
+    /// return, without drawing anything, if the element supports measuring.
+    ///
+    /// This method is used to align elements vertically, for example by the [`FixedHeight`][]
+    /// wrapper and by the [`TableLayout`][] to align cells within a row.  Elements that implement
+    /// [`Clone`][] can implement this method by rendering a copy of the element on a
+    /// [dry run area][].  The default implementation returns `None`.
+    ///
+    /// [`render`]: #tymethod.render
+    /// [`FixedHeight`]: elements/struct.FixedHeight.html
+    /// [`TableLayout`]: elements/struct.TableLayout.html
+    /// [`Clone`]: https://doc.rust-lang.org/std/clone/trait.Clone.html
+    /// [dry run area]: render/struct.Area.html#method.dry_run
+    fn measure(
+        &self,
+        context: &Context,
+        area: render::Area<'_>,
+        style: style::Style,
+    ) -> Option<RenderResult> {
+        let _ = (context, area, style);
+        None
+    }
+
+    /// Returns the penalty for a page break before this element if it is a page break hint.
+    ///
+    /// This method is used by [`LinearLayout`][] to find preferred page break positions, see
+    /// [`PageBreakHint`][].  The default implementation returns `None`.
+    ///
+    /// [`LinearLayout`]: elements/struct.LinearLayout.html
+    /// [`PageBreakHint`]: elements/struct.PageBreakHint.html
+    fn page_break_penalty(&self) -> Option<u8> {
+        None
+    }
+
+    /// Returns the glue of this element if it is vertical glue.
+    ///
+    /// This method is used by [`LinearLayout`][] to distribute the space that is left empty on a
+    /// page, see [`Glue`][].  The default implementation returns `None`.
+    ///
+    /// [`LinearLayout`]: elements/struct.LinearLayout.html
+    /// [`Glue`]: elements/struct.Glue.html
+    fn glue(&self) -> Option<elements::Glue> {
+        None
+    }
+
+    /// Returns a description of this element and its children for debugging, see the [`tree`][]
+    /// module.
+    ///
+    /// Container elements should add the trees of their children to the returned tree.  The
+    /// default implementation returns a tree without children that is named after the type of
+    /// the element.
+    ///
+    /// [`tree`]: tree/index.html
+    fn dump_tree(&self) -> tree::ElementTree {
+        tree::ElementTree::for_type::<Self>()
+    }
+
     /// Draws a frame around this element using the given line style.
     fn framed(self, line_style: impl Into<style::LineStyle>) -> elements::FramedElement<Self>
     where
@@ -978,6 +1991,14 @@ pub trait Element {
         elements::PaddedElement::new(self, padding)
     }
 
+    /// Shifts this element by the given offset without changing its size in the layout.
+    fn offset(self, offset: impl Into<Position>) -> elements::Offset<Self>
+    where
+        Self: Sized,
+    {
+        elements::Offset::new(self, offset)
+    }
+
     /// Sets the default style for this element and its children.
     fn styled(self, style: impl Into<style::Style>) -> elements::StyledElement<Self>
     where
@@ -1004,6 +2025,9 @@ pub struct Context {
     #[cfg(feature = "hyphenation")]
     pub hyphenator: Option<hyphenation::Standard>,
 
+    #[cfg(feature = "hyphenation")]
+    hyphenators: std::collections::HashMap<hyphenation::Language, hyphenation::Standard>,
+
     /// The math renderer for this process.
     /// If it is `None`, no math font was registered
     #[cfg(feature = "math")]
@@ -1013,7 +2037,18 @@ pub struct Context {
     ///
     /// If it is None, syntax highlighting is disabled.
     #[cfg(feature = "code-syntax-highlighting")]
-    pub syntax_highlighter: Option<SyntaxHighlighter>,
+    pub syntax_highlighter: Option<sync::Arc<SyntaxHighlighter>>,
+
+    outline: cell::RefCell<outline::Outline>,
+    element_error_policy: ElementErrorPolicy,
+    element_errors: cell::RefCell<Vec<error::Error>>,
+    strict_mode: StrictMode,
+    min_image_scale: f64,
+    warnings: cell::RefCell<Vec<error::Warning>>,
+    page_break_requested: cell::Cell<bool>,
+    text_normalization: normalize::TextNormalization,
+    reserved_rects: cell::RefCell<Vec<Rect>>,
+    page_area: cell::RefCell<Option<PageArea>>,
 }
 
 impl Context {
@@ -1025,6 +2060,16 @@ impl Context {
             math_renderer: None,
             #[cfg(feature = "code-syntax-highlighting")]
             syntax_highlighter: None,
+            outline: Default::default(),
+            element_error_policy: ElementErrorPolicy::default(),
+            element_errors: Default::default(),
+            strict_mode: StrictMode::default(),
+            min_image_scale: 0.5,
+            warnings: Default::default(),
+            page_break_requested: Default::default(),
+            text_normalization: Default::default(),
+            reserved_rects: Default::default(),
+            page_area: Default::default(),
         }
     }
 
@@ -1033,10 +2078,181 @@ impl Context {
         Context {
             font_cache,
             hyphenator: None,
+            hyphenators: Default::default(),
             #[cfg(feature = "math")]
             math_renderer: None,
             #[cfg(feature = "code-syntax-highlighting")]
             syntax_highlighter: None,
+            outline: Default::default(),
+            element_error_policy: ElementErrorPolicy::default(),
+            element_errors: Default::default(),
+            strict_mode: StrictMode::default(),
+            min_image_scale: 0.5,
+            warnings: Default::default(),
+            page_break_requested: Default::default(),
+            text_normalization: Default::default(),
+            reserved_rects: Default::default(),
+            page_area: Default::default(),
+        }
+    }
+
+    /// Creates a new context with the test font family that can be used to render elements
+    /// without a [`Document`][].
+    ///
+    /// The default font family of the font cache is [`fonts::test_font_family`][], so no font
+    /// files are required.  This is useful for unit tests of custom elements.  Before elements
+    /// are rendered, the fonts have to be loaded into the renderer with
+    /// [`FontCache::load_pdf_fonts`][].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use genpdf::{elements, render, style, Element as _};
+    /// let mut context = genpdf::Context::fake();
+    /// let renderer = render::Renderer::new(genpdf::PaperSize::A4, "Test")?;
+    /// context.font_cache.load_pdf_fonts(&renderer)?;
+    ///
+    /// let area = renderer.first_page().first_layer().area();
+    /// let mut paragraph = elements::Paragraph::new("Test");
+    /// let result = paragraph.render(&context, area, style::Style::new())?;
+    /// assert!(!result.has_more);
+    /// # Ok::<(), genpdf::error::Error>(())
+    /// ```
+    ///
+    /// [`Document`]: struct.Document.html
+    /// [`fonts::test_font_family`]: fonts/fn.test_font_family.html
+    /// [`FontCache::load_pdf_fonts`]: fonts/struct.FontCache.html#method.load_pdf_fonts
+    pub fn fake() -> Context {
+        Context::new(fonts::FontCache::new(fonts::test_font_family()))
+    }
+
+    /// Returns the hyphenator for the given language.
+    ///
+    /// If no language is given, the default hyphenator set with [`Document::set_hyphenator`][]
+    /// is returned.  Otherwise, the hyphenator added for this language with
+    /// [`Document::add_hyphenator`][] is returned.
+    ///
+    /// *Only available if the `hyphenation` feature is enabled.*
+    ///
+    /// [`Document::set_hyphenator`]: struct.Document.html#method.set_hyphenator
+    /// [`Document::add_hyphenator`]: struct.Document.html#method.add_hyphenator
+    #[cfg(feature = "hyphenation")]
+    pub fn hyphenator_for(
+        &self,
+        language: Option<hyphenation::Language>,
+    ) -> Option<&hyphenation::Standard> {
+        match language {
+            Some(language) => self.hyphenators.get(&language),
+            None => self.hyphenator.as_ref(),
+        }
+    }
+
+    /// Adds the given entry to the outline of the rendered document.
+    ///
+    /// The outline is returned by [`Document::render_with_outline`][].
+    ///
+    /// [`Document::render_with_outline`]: struct.Document.html#method.render_with_outline
+    pub fn add_outline_entry(&self, entry: outline::OutlineEntry) {
+        self.outline.borrow_mut().push(entry);
+    }
+
+    /// Requests a page break after the current page.
+    ///
+    /// Elements that force a page break should call this method and return a [`RenderResult`][]
+    /// with `has_more == true`.  Then the document adds a new page even if the element did not
+    /// render any content on the current page.
+    ///
+    /// [`RenderResult`]: struct.RenderResult.html
+    pub fn request_page_break(&self) {
+        self.page_break_requested.set(true);
+    }
+
+    /// Reserves the given rectangle of the current page, for example for a header or a footer.
+    ///
+    /// This method should be called by [`PageDecorator`][] implementations for all parts of the
+    /// page that they draw on.  The reserved rectangles are part of the [`PageArea`][] of the
+    /// page.
+    ///
+    /// [`PageDecorator`]: trait.PageDecorator.html
+    /// [`PageArea`]: struct.PageArea.html
+    pub fn reserve_rect(&self, rect: Rect) {
+        self.reserved_rects.borrow_mut().push(rect);
+    }
+
+    /// Returns the area of the current page, or `None` if the page decorator is still being
+    /// applied.
+    ///
+    /// See [`PageArea`][] for details.
+    ///
+    /// [`PageArea`]: struct.PageArea.html
+    pub fn page_area(&self) -> Option<PageArea> {
+        self.page_area.borrow().clone()
+    }
+
+    /// Returns the normalization that text elements should apply to their text.
+    pub fn text_normalization(&self) -> &normalize::TextNormalization {
+        &self.text_normalization
+    }
+
+    /// Returns the policy for elements that fail to render.
+    pub fn element_error_policy(&self) -> ElementErrorPolicy {
+        self.element_error_policy
+    }
+
+    /// Handles an error returned by an element according to the [`ElementErrorPolicy`][].
+    ///
+    /// If the policy is [`ElementErrorPolicy::Abort`][], the error is returned.  Otherwise, it is
+    /// collected and `Ok(())` is returned, so that the container can skip the failing element.
+    ///
+    /// [`ElementErrorPolicy`]: enum.ElementErrorPolicy.html
+    /// [`ElementErrorPolicy::Abort`]: enum.ElementErrorPolicy.html#variant.Abort
+    pub fn handle_element_error(&self, error: error::Error) -> Result<(), error::Error> {
+        match self.element_error_policy {
+            ElementErrorPolicy::Abort => Err(error),
+            ElementErrorPolicy::SkipAndCollect => {
+                self.element_errors.borrow_mut().push(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the strict mode that determines how degraded layouts are reported.
+    pub fn strict_mode(&self) -> StrictMode {
+        self.strict_mode
+    }
+
+    /// Returns the minimum scale factor for images in strict mode, see
+    /// [`Document::set_min_image_scale`][].
+    ///
+    /// [`Document::set_min_image_scale`]: struct.Document.html#method.set_min_image_scale
+    pub fn min_image_scale(&self) -> f64 {
+        self.min_image_scale
+    }
+
+    /// Reports a degraded layout on the given area according to the [`StrictMode`][].
+    ///
+    /// If the strict mode is [`StrictMode::Error`][], the warning is converted into an error and
+    /// returned.  If it is [`StrictMode::Warn`][], the warning is collected.  Warnings for dry run
+    /// areas are ignored.
+    ///
+    /// [`StrictMode`]: enum.StrictMode.html
+    /// [`StrictMode::Error`]: enum.StrictMode.html#variant.Error
+    /// [`StrictMode::Warn`]: enum.StrictMode.html#variant.Warn
+    pub fn report_warning(
+        &self,
+        area: &render::Area<'_>,
+        warning: error::Warning,
+    ) -> Result<(), error::Error> {
+        if area.is_dry_run() {
+            return Ok(());
+        }
+        match self.strict_mode {
+            StrictMode::Disabled => Ok(()),
+            StrictMode::Warn => {
+                self.warnings.borrow_mut().push(warning);
+                Ok(())
+            }
+            StrictMode::Error => Err(warning.into()),
         }
     }
 }
@@ -1069,6 +2285,21 @@ mod tests {
         }
     }
 
+    /// Renders the given document and returns the number of pages.
+    pub(crate) fn render_page_count(doc: super::Document) -> Result<usize, super::error::Error> {
+        let mut pdf = Vec::new();
+        doc.render(&mut pdf)?;
+        let pdf = lopdf::Document::load_mem(&pdf).expect("Failed to load rendered document");
+        Ok(pdf.get_pages().len())
+    }
+
+    /// Creates a new document with the test font family and the given paper size.
+    pub(crate) fn test_document(paper_size: impl Into<super::Size>) -> super::Document {
+        let mut doc = super::Document::new(super::fonts::test_font_family());
+        doc.set_paper_size(paper_size);
+        doc
+    }
+
     #[test]
     fn test_rotation() {
         use super::Rotation;
@@ -1089,4 +2320,91 @@ mod tests {
         assert_eq!(Some(-90.0), Rotation::from(-450.0).degrees());
         assert_eq!(Some(-180.0), Rotation::from(-540.0).degrees());
     }
+
+    #[test]
+    fn test_page_break() {
+        use super::elements::{PageBreak, Placeholder, Text};
+        use super::error::ErrorKind;
+
+        let mut doc = test_document((50, 50));
+        doc.push(Text::new("Test"));
+        doc.push(PageBreak::new());
+        doc.push(Text::new("Test"));
+        assert_eq!(render_page_count(doc).unwrap(), 2);
+
+        // Pages that are empty because of an explicit page break are not counted as empty pages
+        let mut doc = test_document((50, 50));
+        doc.push(PageBreak::new());
+        doc.push(PageBreak::new());
+        doc.push(Text::new("Test"));
+        assert_eq!(render_page_count(doc).unwrap(), 3);
+
+        // An element that does not fit on an empty page aborts the rendering process
+        let mut doc = test_document((50, 50));
+        doc.push(Text::new("Test"));
+        doc.push(Placeholder::new((10, 60)));
+        let err = render_page_count(doc).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+
+        let mut doc = test_document((50, 50));
+        doc.set_max_empty_pages(1);
+        doc.push(Placeholder::new((10, 60)));
+        let err = render_page_count(doc).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::PageSizeExceeded));
+    }
+
+    #[test]
+    fn test_gradient_stops() {
+        use super::elements::Text;
+        use super::style::{Color, Fill, LineStyle};
+        use super::Element as _;
+
+        fn find_shadings(object: &lopdf::Object, shadings: &mut Vec<lopdf::Dictionary>) {
+            match object {
+                lopdf::Object::Dictionary(dict) => {
+                    if dict.has(b"ShadingType") {
+                        shadings.push(dict.clone());
+                    }
+                    for (_, value) in dict.iter() {
+                        find_shadings(value, shadings);
+                    }
+                }
+                lopdf::Object::Array(array) => {
+                    for value in array {
+                        find_shadings(value, shadings);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let render = |stops: Vec<(f64, Color)>| {
+            let mut doc = test_document((50, 50));
+            let fill = Fill::LinearGradient { stops, angle: 0.0 };
+            doc.push(Text::new("Test").framed(LineStyle::new()).with_fill(fill));
+            let mut pdf = Vec::new();
+            doc.render(&mut pdf).unwrap();
+            let pdf = lopdf::Document::load_mem(&pdf).unwrap();
+            let mut shadings = Vec::new();
+            for object in pdf.objects.values() {
+                find_shadings(object, &mut shadings);
+            }
+            shadings
+        };
+
+        // A gradient without stops is not drawn
+        assert!(render(Vec::new()).is_empty());
+
+        // A gradient with a single stop uses the color of the stop for the whole shape
+        let shadings = render(vec![(0.5, Color::Greyscale(0))]);
+        assert_eq!(shadings.len(), 1);
+        let function = shadings[0].get(b"Function").unwrap().as_dict().unwrap();
+        let functions = function.get(b"Functions").unwrap().as_array().unwrap();
+        for function in functions {
+            let function = function.as_dict().unwrap();
+            let c0 = function.get(b"C0").unwrap();
+            let c1 = function.get(b"C1").unwrap();
+            assert_eq!(format!("{:?}", c0), format!("{:?}", c1));
+        }
+    }
 }
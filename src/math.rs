@@ -1,11 +1,19 @@
 //! Implements a ReX-based math renderer for genpdf
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::rc::Rc;
 
 use font::{Font, OpenTypeFont};
 use rex::{font::FontContext, parser::ParseNode, Backend};
 
-use crate::{fonts::FontFamily, style::Color, Size};
+use crate::{
+    error::{Error, ErrorKind},
+    fonts::FontFamily,
+    style::Color,
+    Size,
+};
 
 /// Maximum difference between two y-offsets to be inserted into same batch
 const POSITIONING_ACCURACY: f64 = 0.01; // Unit: millimeters
@@ -21,6 +29,7 @@ fn mm_to_em(mm: f64, font_size: f64) -> f64 {
 }
 
 /// A batch of glyphs with the same color, y-offset and size, that can be rendered as a single text seciton
+#[derive(Debug)]
 pub struct TextSection {
     /// Vertical offset of the text section, in mm
     pub y_origin: f64,
@@ -49,6 +58,7 @@ impl TextSection {
 }
 
 /// A colored, filled rectangle
+#[derive(Debug)]
 pub struct Rule {
     /// Rect x origin in mm
     pub x: f64,
@@ -63,6 +73,7 @@ pub struct Rule {
 }
 
 /// A drawing command
+#[derive(Debug)]
 pub enum MathOp {
     /// See TextSection for details
     TextSection(TextSection),
@@ -72,20 +83,31 @@ pub enum MathOp {
 }
 
 /// Receives and holds math drawing commands from ReX
+#[derive(Debug)]
 pub struct MathBlock {
     /// Bounding box of the math block
     pub size: Size,
     math_ops: Vec<MathOp>,
+    default_color: Color,
     current_color: Color,
 }
 
 impl MathBlock {
     /// Creates a new, empty math block with black color and the given bounding box
     pub fn new(size: Size) -> Self {
+        Self::with_color(size, Color::Rgb(0, 0, 0))
+    }
+
+    /// Creates a new, empty math block with the given default color and bounding box
+    ///
+    /// The default color is used for all glyphs and rules that are not colored explicitly with
+    /// `\color` in the source.
+    pub fn with_color(size: Size, color: Color) -> Self {
         Self {
             size,
             math_ops: Vec::new(),
-            current_color: Color::Rgb(0, 0, 0),
+            default_color: color,
+            current_color: color,
         }
     }
 
@@ -184,15 +206,45 @@ impl Backend for MathBlock {
     }
 
     fn end_color(&mut self) {
-        self.current_color = Color::Rgb(0, 0, 0);
+        self.current_color = self.default_color;
     }
 }
 
+/// The layout style of a formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MathStyle {
+    /// The display style for formulas on their own line, with large operators and limits above
+    /// and below the operators.
+    Display,
+    /// The text style for formulas within text, with smaller operators, limits next to the
+    /// operators and more compact fractions.
+    Text,
+}
+
+impl Default for MathStyle {
+    fn default() -> MathStyle {
+        MathStyle::Display
+    }
+}
+
+/// The source and the settings of a rendered formula
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct CacheKey {
+    source: String,
+    font_size: u64,
+    color: Color,
+    style: MathStyle,
+}
+
 /// Wrapper for the ReX renderer structure
+///
+/// The font context with the parsed MATH tables of the font is created once per renderer, and
+/// the rendered math blocks are cached so that identical formulas are only laid out once.
 pub struct MathRenderer {
     font_family: FontFamily<crate::fonts::Font>,
-    font: Box<OpenTypeFont>,
+    font_ctx: FontContext<'static>,
     rex_renderer: rex::Renderer,
+    cache: RefCell<HashMap<CacheKey, Rc<MathBlock>>>,
 }
 
 impl Debug for MathRenderer {
@@ -206,33 +258,81 @@ impl MathRenderer {
     pub(crate) fn new(
         math_font_data: &[u8],
         math_font_family: FontFamily<crate::fonts::Font>,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let font = font::parse(math_font_data)
-            .expect("Failed to decode math font")
-            .downcast_box::<OpenTypeFont>();
-
-        match font {
-            Ok(font) => Self {
-                font,
-                rex_renderer: rex::Renderer::new(),
-                font_family: math_font_family,
-            },
-            Err(_) => panic!("Not an OpenType font"),
+            .map_err(|err| {
+                Error::new(
+                    format!("Failed to decode math font: {:?}", err),
+                    ErrorKind::InvalidFont,
+                )
+            })?
+            .downcast_box::<OpenTypeFont>()
+            .map_err(|_| {
+                Error::new(
+                    "The math font is not an OpenType font",
+                    ErrorKind::InvalidFont,
+                )
+            })?;
+
+        // The font context borrows the font, so the font is leaked to keep it alive as long as
+        // the font context.  This means that the parsed font is never freed, but it is only
+        // loaded once per call to Document::enable_math.
+        let font: &'static OpenTypeFont = Box::leak(font);
+        let font_ctx = FontContext::new(font);
+
+        Ok(Self {
+            font_ctx,
+            rex_renderer: rex::Renderer::new(),
+            font_family: math_font_family,
+            cache: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Renders the given formula, or returns the cached math block if the same source has already
+    /// been rendered with the same settings.
+    pub(crate) fn render(
+        &self,
+        source: &str,
+        rex_ast: &[ParseNode],
+        font_size: f64,
+        color: Color,
+        style: MathStyle,
+    ) -> Result<Rc<MathBlock>, Error> {
+        let key = CacheKey {
+            source: source.to_owned(),
+            font_size: font_size.to_bits(),
+            color,
+            style,
+        };
+        if let Some(math_block) = self.cache.borrow().get(&key) {
+            return Ok(math_block.clone());
         }
+        let math_block = Rc::new(self.layout(rex_ast, font_size, color, style)?);
+        self.cache.borrow_mut().insert(key, math_block.clone());
+        Ok(math_block)
     }
 
-    pub(crate) fn render(&self, font_size: f64, rex_ast: &[ParseNode]) -> MathBlock {
+    fn layout(
+        &self,
+        rex_ast: &[ParseNode],
+        font_size: f64,
+        color: Color,
+        style: MathStyle,
+    ) -> Result<MathBlock, Error> {
         use rex::{
             layout::engine::layout,
             layout::{Grid, Layout, LayoutSettings},
         };
 
-        let rex_font_ctx = FontContext::new(&self.font); // todo maybe don't reinstantiate every time
-        let rex_layout_settings =
-            LayoutSettings::new(&rex_font_ctx, font_size, rex::layout::Style::Display);
+        let rex_style = match style {
+            MathStyle::Display => rex::layout::Style::Display,
+            MathStyle::Text => rex::layout::Style::Text,
+        };
+        let rex_layout_settings = LayoutSettings::new(&self.font_ctx, font_size, rex_style);
 
-        // Todo: Figure out if this can reasonably panic or not
-        let rex_math_block = layout(&rex_ast, rex_layout_settings).expect("Failed to layout math");
+        let rex_math_block = layout(&rex_ast, rex_layout_settings).map_err(|err| {
+            Error::new(format!("Failed to layout math: {:?}", err), ErrorKind::Math)
+        })?;
 
         let mut rex_grid = Grid::new();
         rex_grid.insert(0, 0, rex_math_block.as_node());
@@ -242,10 +342,10 @@ impl MathRenderer {
 
         let (x0, y0, x1, y1) = self.rex_renderer.size(&rex_layout);
         let size = Size::new((x1 - x0) * PX_TO_MM, (y1 - y0) * PX_TO_MM);
-        let mut math_block = MathBlock::new(size);
+        let mut math_block = MathBlock::with_color(size, color);
         self.rex_renderer.render(&rex_layout, &mut math_block);
 
-        math_block
+        Ok(math_block)
     }
 
     pub(crate) fn font_family(&self) -> FontFamily<crate::fonts::Font> {
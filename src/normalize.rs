@@ -0,0 +1,228 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Normalization of the text of a document.
+//!
+//! User-generated content often contains artifacts like repeated spaces, tabs, carriage returns or
+//! control characters that cannot be rendered or that break the width calculation for text
+//! wrapping.  A [`TextNormalization`][] can be set for a document using
+//! [`Document::set_text_normalization`][] to clean up the text of the [`Text`][] and
+//! [`Paragraph`][] elements before it is rendered.
+//!
+//! [`TextNormalization`]: struct.TextNormalization.html
+//! [`Document::set_text_normalization`]: ../struct.Document.html#method.set_text_normalization
+//! [`Text`]: ../elements/struct.Text.html
+//! [`Paragraph`]: ../elements/struct.Paragraph.html
+
+use std::borrow;
+
+/// Settings for the normalization of text.
+///
+/// Per default, all normalizations are disabled.  They are applied in the following order:
+/// 1. Unicode normalization to NFC (requires the `unicode-normalization` feature)
+/// 2. Conversion of carriage returns (`"\r\n"` and `'\r'`) to line breaks (`'\n'`)
+/// 3. Conversion of tabs to spaces
+/// 4. Removal of control characters other than line breaks
+/// 5. Collapsing of repeated spaces
+/// 6. Removal of trailing whitespace before line breaks
+///
+/// # Example
+///
+/// ```
+/// use genpdf::normalize::TextNormalization;
+/// let normalization = TextNormalization::new()
+///     .with_tab_width(4)
+///     .with_collapse_spaces(true);
+/// assert_eq!(normalization.apply("a\tb  c"), "a b c");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextNormalization {
+    normalize_newlines: bool,
+    tab_width: Option<usize>,
+    strip_control_chars: bool,
+    collapse_spaces: bool,
+    trim_trailing_whitespace: bool,
+    #[cfg(feature = "unicode-normalization")]
+    nfc: bool,
+}
+
+impl TextNormalization {
+    /// Creates a new text normalization with all normalizations disabled.
+    pub fn new() -> TextNormalization {
+        TextNormalization::default()
+    }
+
+    /// Sets whether carriage returns should be converted to line breaks.
+    pub fn set_normalize_newlines(&mut self, normalize_newlines: bool) {
+        self.normalize_newlines = normalize_newlines;
+    }
+
+    /// Sets whether carriage returns should be converted to line breaks and returns the
+    /// normalization.
+    pub fn with_normalize_newlines(mut self, normalize_newlines: bool) -> Self {
+        self.set_normalize_newlines(normalize_newlines);
+        self
+    }
+
+    /// Sets the number of spaces that a tab should be converted to.
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.tab_width = Some(tab_width);
+    }
+
+    /// Sets the number of spaces that a tab should be converted to and returns the
+    /// normalization.
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.set_tab_width(tab_width);
+        self
+    }
+
+    /// Sets whether control characters other than line breaks should be removed.
+    ///
+    /// If no tab width is set, tabs are removed too.
+    pub fn set_strip_control_chars(&mut self, strip_control_chars: bool) {
+        self.strip_control_chars = strip_control_chars;
+    }
+
+    /// Sets whether control characters other than line breaks should be removed and returns the
+    /// normalization.
+    pub fn with_strip_control_chars(mut self, strip_control_chars: bool) -> Self {
+        self.set_strip_control_chars(strip_control_chars);
+        self
+    }
+
+    /// Sets whether repeated spaces should be collapsed to a single space.
+    pub fn set_collapse_spaces(&mut self, collapse_spaces: bool) {
+        self.collapse_spaces = collapse_spaces;
+    }
+
+    /// Sets whether repeated spaces should be collapsed to a single space and returns the
+    /// normalization.
+    pub fn with_collapse_spaces(mut self, collapse_spaces: bool) -> Self {
+        self.set_collapse_spaces(collapse_spaces);
+        self
+    }
+
+    /// Sets whether whitespace before line breaks should be removed.
+    ///
+    /// Whitespace at the end of a string is kept as the string could be followed by another string
+    /// in the same line.
+    pub fn set_trim_trailing_whitespace(&mut self, trim_trailing_whitespace: bool) {
+        self.trim_trailing_whitespace = trim_trailing_whitespace;
+    }
+
+    /// Sets whether whitespace before line breaks should be removed and returns the
+    /// normalization.
+    pub fn with_trim_trailing_whitespace(mut self, trim_trailing_whitespace: bool) -> Self {
+        self.set_trim_trailing_whitespace(trim_trailing_whitespace);
+        self
+    }
+
+    /// Sets whether the text should be converted to the Unicode Normalization Form C (NFC).
+    ///
+    /// *Only available if the `unicode-normalization` feature is enabled.*
+    #[cfg(feature = "unicode-normalization")]
+    pub fn set_nfc(&mut self, nfc: bool) {
+        self.nfc = nfc;
+    }
+
+    /// Sets whether the text should be converted to the Unicode Normalization Form C (NFC) and
+    /// returns the normalization.
+    ///
+    /// *Only available if the `unicode-normalization` feature is enabled.*
+    #[cfg(feature = "unicode-normalization")]
+    pub fn with_nfc(mut self, nfc: bool) -> Self {
+        self.set_nfc(nfc);
+        self
+    }
+
+    /// Returns whether any normalization is enabled.
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(feature = "unicode-normalization")]
+        {
+            if self.nfc {
+                return true;
+            }
+        }
+        self.normalize_newlines
+            || self.tab_width.is_some()
+            || self.strip_control_chars
+            || self.collapse_spaces
+            || self.trim_trailing_whitespace
+    }
+
+    /// Applies this normalization to the given string.
+    pub fn apply<'s>(&self, s: &'s str) -> borrow::Cow<'s, str> {
+        if !self.is_enabled() {
+            return s.into();
+        }
+
+        #[cfg(feature = "unicode-normalization")]
+        let s: borrow::Cow<'_, str> = if self.nfc {
+            use unicode_normalization::UnicodeNormalization as _;
+            s.nfc().collect::<String>().into()
+        } else {
+            s.into()
+        };
+
+        let mut normalized = String::with_capacity(s.len());
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' if self.normalize_newlines => {
+                    if chars.peek() != Some(&'\n') {
+                        self.push_newline(&mut normalized);
+                    }
+                }
+                '\n' => self.push_newline(&mut normalized),
+                '\t' if self.tab_width.is_some() => {
+                    for _ in 0..self.tab_width.unwrap_or_default() {
+                        self.push_space(&mut normalized);
+                    }
+                }
+                ' ' => self.push_space(&mut normalized),
+                c if self.strip_control_chars && c.is_control() => {}
+                c => normalized.push(c),
+            }
+        }
+        normalized.into()
+    }
+
+    fn push_space(&self, s: &mut String) {
+        if !(self.collapse_spaces && s.ends_with(' ')) {
+            s.push(' ');
+        }
+    }
+
+    fn push_newline(&self, s: &mut String) {
+        if self.trim_trailing_whitespace {
+            s.truncate(s.trim_end_matches(&[' ', '\t'][..]).len());
+        }
+        s.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextNormalization;
+
+    #[test]
+    fn test_disabled() {
+        let normalization = TextNormalization::new();
+        assert!(!normalization.is_enabled());
+        assert_eq!(normalization.apply("a \t b\r\n"), "a \t b\r\n");
+    }
+
+    #[test]
+    fn test_apply() {
+        let normalization = TextNormalization::new()
+            .with_normalize_newlines(true)
+            .with_tab_width(2)
+            .with_strip_control_chars(true)
+            .with_collapse_spaces(true)
+            .with_trim_trailing_whitespace(true);
+        assert_eq!(
+            normalization.apply("a\t b  c \r\nd\u{7}\re "),
+            "a b c\nd\ne "
+        );
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Structured summary of a rendered document.
+//!
+//! During the rendering process, elements wrapped in an [`OutlineMarker`][] register an
+//! [`OutlineEntry`][] with the page number and the position at which they have been rendered.
+//! Once the document has been rendered with [`Document::render_with_outline`][], the collected
+//! [`Outline`][] can be used to build a navigation or an index that references the PDF document
+//! without having to parse it again.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use genpdf::{elements, outline};
+//! let font_family = genpdf::fonts::from_files("./fonts", "LiberationSans", None)
+//!     .expect("Failed to load font family");
+//! let mut doc = genpdf::Document::new(font_family);
+//! doc.push(elements::OutlineMarker::heading(
+//!     elements::Paragraph::new("Introduction"),
+//!     1,
+//!     "Introduction",
+//! ));
+//! let file = std::fs::File::create("output.pdf").expect("Failed to create file");
+//! let outline = doc.render_with_outline(file).expect("Failed to render document");
+//! for entry in outline.headings() {
+//!     println!("{} (page {})", entry.title(), entry.page());
+//! }
+//! ```
+//!
+//! [`Document::render_with_outline`]: ../struct.Document.html#method.render_with_outline
+//! [`Outline`]: struct.Outline.html
+//! [`OutlineEntry`]: struct.OutlineEntry.html
+//! [`OutlineMarker`]: ../elements/struct.OutlineMarker.html
+
+use std::fmt::Write as _;
+
+use crate::Position;
+
+/// The kind of an [`OutlineEntry`][].
+///
+/// [`OutlineEntry`]: struct.OutlineEntry.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OutlineKind {
+    /// A heading with the given level, starting with 1 for the top level.
+    Heading(u8),
+    /// A figure, for example an image.
+    Figure,
+    /// A table.
+    Table,
+    /// A named position in the document.
+    Anchor,
+}
+
+impl OutlineKind {
+    fn name(&self) -> &'static str {
+        match self {
+            OutlineKind::Heading(_) => "heading",
+            OutlineKind::Figure => "figure",
+            OutlineKind::Table => "table",
+            OutlineKind::Anchor => "anchor",
+        }
+    }
+}
+
+/// An entry of a document [`Outline`][].
+///
+/// [`Outline`]: struct.Outline.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineEntry {
+    kind: OutlineKind,
+    title: String,
+    page: usize,
+    position: Position,
+}
+
+impl OutlineEntry {
+    /// Creates a new outline entry.
+    ///
+    /// The page number starts with 1, and the position is measured from the upper left corner of
+    /// the page.
+    pub fn new(
+        kind: OutlineKind,
+        title: impl Into<String>,
+        page: usize,
+        position: impl Into<Position>,
+    ) -> OutlineEntry {
+        OutlineEntry {
+            kind,
+            title: title.into(),
+            page,
+            position: position.into(),
+        }
+    }
+
+    /// Returns the kind of this entry.
+    pub fn kind(&self) -> OutlineKind {
+        self.kind
+    }
+
+    /// Returns the title of this entry (or the name, for anchors).
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the number of the page this entry has been rendered on, starting with 1.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the position of this entry, measured from the upper left corner of the page.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the heading level of this entry, or `None` if it is not a heading.
+    pub fn level(&self) -> Option<u8> {
+        match self.kind {
+            OutlineKind::Heading(level) => Some(level),
+            _ => None,
+        }
+    }
+}
+
+/// The outline of a rendered document.
+///
+/// The entries are stored in the order in which they have been rendered.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outline {
+    entries: Vec<OutlineEntry>,
+    page_count: usize,
+}
+
+impl Outline {
+    /// Creates a new empty outline.
+    pub fn new() -> Outline {
+        Outline::default()
+    }
+
+    /// Adds an entry to this outline.
+    pub fn push(&mut self, entry: OutlineEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns all entries of this outline.
+    pub fn entries(&self) -> &[OutlineEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of pages of the rendered document.
+    pub fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    pub(crate) fn set_page_count(&mut self, page_count: usize) {
+        self.page_count = page_count;
+    }
+
+    /// Returns an iterator over all headings of this outline.
+    pub fn headings(&self) -> impl Iterator<Item = &OutlineEntry> {
+        self.entries.iter().filter(|e| e.level().is_some())
+    }
+
+    /// Returns an iterator over all figures of this outline.
+    pub fn figures(&self) -> impl Iterator<Item = &OutlineEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.kind == OutlineKind::Figure)
+    }
+
+    /// Returns an iterator over all tables of this outline.
+    pub fn tables(&self) -> impl Iterator<Item = &OutlineEntry> {
+        self.entries.iter().filter(|e| e.kind == OutlineKind::Table)
+    }
+
+    /// Returns the anchor with the given name, if it exists.
+    pub fn anchor(&self, name: &str) -> Option<&OutlineEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.kind == OutlineKind::Anchor && e.title == name)
+    }
+
+    /// Serializes this outline to a JSON string.
+    ///
+    /// The generated object has a `page_count` field and an `entries` array.  Every entry has the
+    /// fields `kind`, `title`, `page`, `x` and `y` (in millimeters), and `level` for headings.
+    /// Positions that are not finite are serialized as `null`.
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        write!(s, "{{\"page_count\":{},\"entries\":[", self.page_count).unwrap();
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            write!(s, "{{\"kind\":\"{}\",", entry.kind.name()).unwrap();
+            if let Some(level) = entry.level() {
+                write!(s, "\"level\":{},", level).unwrap();
+            }
+            s.push_str("\"title\":");
+            push_json_str(&mut s, &entry.title);
+            write!(s, ",\"page\":{},\"x\":", entry.page).unwrap();
+            push_json_number(&mut s, entry.position.x.0);
+            s.push_str(",\"y\":");
+            push_json_number(&mut s, entry.position.y.0);
+            s.push('}');
+        }
+        s.push_str("]}");
+        s
+    }
+}
+
+fn push_json_number(s: &mut String, value: f64) {
+    if value.is_finite() {
+        write!(s, "{}", value).unwrap();
+    } else {
+        // JSON does not support NaN and infinite values
+        s.push_str("null");
+    }
+}
+
+fn push_json_str(s: &mut String, value: &str) {
+    s.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(s, "\\u{:04x}", c as u32).unwrap(),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Outline, OutlineEntry, OutlineKind};
+
+    #[test]
+    fn test_to_json() {
+        let mut outline = Outline::new();
+        outline.set_page_count(2);
+        outline.push(OutlineEntry::new(
+            OutlineKind::Heading(1),
+            "Intro \"1\"",
+            1,
+            (10, 20),
+        ));
+        outline.push(OutlineEntry::new(OutlineKind::Anchor, "end", 2, (0, 5.5)));
+        assert_eq!(
+            outline.to_json(),
+            "{\"page_count\":2,\"entries\":[\
+             {\"kind\":\"heading\",\"level\":1,\"title\":\"Intro \\\"1\\\"\",\"page\":1,\"x\":10,\"y\":20},\
+             {\"kind\":\"anchor\",\"title\":\"end\",\"page\":2,\"x\":0,\"y\":5.5}]}"
+        );
+        assert_eq!(outline.headings().count(), 1);
+        assert_eq!(outline.anchor("end").map(|e| e.page()), Some(2));
+
+        let mut outline = Outline::new();
+        outline.push(OutlineEntry::new(
+            OutlineKind::Anchor,
+            "nan",
+            1,
+            (f64::NAN, f64::INFINITY),
+        ));
+        assert_eq!(
+            outline.to_json(),
+            "{\"page_count\":0,\"entries\":[\
+             {\"kind\":\"anchor\",\"title\":\"nan\",\"page\":1,\"x\":null,\"y\":null}]}"
+        );
+    }
+}
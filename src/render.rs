@@ -27,13 +27,17 @@ use printpdf::Pt;
 
 use crate::error::{Context as _, Error, ErrorKind};
 use crate::fonts;
-use crate::style::{Color, LineStyle, Style};
-use crate::{Margins, Mm, Position, Size};
+use crate::style::{
+    Color, Fill, InlineAlignment, LineCap, LineJoin, LineStyle, Style, TextRenderingMode,
+};
+use crate::wrap;
+use crate::{Margins, Mm, Position, Rect, Size};
 
 #[cfg(feature = "images")]
 use crate::{Rotation, Scale};
 
 /// A position relative to the top left corner of a layer.
+#[derive(Clone, Copy)]
 struct LayerPosition(Position);
 
 impl LayerPosition {
@@ -47,10 +51,32 @@ struct UserSpacePosition(Position);
 
 impl UserSpacePosition {
     pub fn from_layer(layer: &Layer<'_>, position: LayerPosition) -> Self {
-        Self(Position::new(
-            position.0.x,
-            layer.page.size.height - position.0.y,
-        ))
+        let x = position.0.x;
+        let y = layer.page.size.height - position.0.y;
+        match layer.page.coordinate_precision {
+            Some(decimals) => Self(Position::new(x.round(decimals), y.round(decimals))),
+            None => Self(Position::new(x, y)),
+        }
+    }
+}
+
+/// A uniform scaling of the content of a layer around an origin, see [`Area::scaled`][].
+///
+/// [`Area::scaled`]: struct.Area.html#method.scaled
+#[derive(Clone, Copy)]
+struct Transform {
+    origin: LayerPosition,
+    scale: f64,
+}
+
+impl Transform {
+    /// Applies this transformation to the given position.
+    fn apply(&self, position: Position) -> Position {
+        let origin = self.origin.0;
+        Position::new(
+            origin.x + (position.x - origin.x) * self.scale,
+            origin.y + (position.y - origin.y) * self.scale,
+        )
     }
 }
 
@@ -77,6 +103,9 @@ pub struct Renderer {
     doc: printpdf::PdfDocumentReference,
     // invariant: pages.len() >= 1
     pages: Vec<Page>,
+    language: Option<String>,
+    display_doc_title: bool,
+    coordinate_precision: Option<u8>,
 }
 
 impl Renderer {
@@ -91,11 +120,14 @@ impl Renderer {
         );
         let page_ref = doc.get_page(page_idx);
         let layer_ref = page_ref.get_layer(layer_idx);
-        let page = Page::new(page_ref, layer_ref, size);
+        let page = Page::new(page_ref, layer_ref, size, 1, None);
 
         Ok(Renderer {
             doc,
             pages: vec![page],
+            language: None,
+            display_doc_title: false,
+            coordinate_precision: None,
         })
     }
 
@@ -117,6 +149,35 @@ impl Renderer {
         self
     }
 
+    /// Sets the natural language of the generated PDF document, for example `de-DE`.
+    ///
+    /// The language is written to the `Lang` entry of the document catalog.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Sets whether PDF viewers should display the document title instead of the file name.
+    ///
+    /// If this is set, the `DisplayDocTitle` viewer preference is written to the document
+    /// catalog.
+    pub fn with_display_doc_title(mut self, display_doc_title: bool) -> Self {
+        self.display_doc_title = display_doc_title;
+        self
+    }
+
+    /// Sets the number of decimal places of the coordinates written to the PDF document.
+    ///
+    /// If this is set, all positions are rounded to the given number of decimal places (in
+    /// millimeters) before they are written to the document.
+    pub fn with_coordinate_precision(mut self, decimals: u8) -> Self {
+        self.coordinate_precision = Some(decimals);
+        for page in &mut self.pages {
+            page.coordinate_precision = Some(decimals);
+        }
+        self
+    }
+
     /// Adds a new page with the given size to the document.
     pub fn add_page(&mut self, size: impl Into<Size>) {
         let size = size.into();
@@ -125,7 +186,14 @@ impl Renderer {
                 .add_page(size.width.into(), size.height.into(), "Layer 1");
         let page_ref = self.doc.get_page(page_idx);
         let layer_ref = page_ref.get_layer(layer_idx);
-        self.pages.push(Page::new(page_ref, layer_ref, size))
+        let number = self.pages.len() + 1;
+        self.pages.push(Page::new(
+            page_ref,
+            layer_ref,
+            size,
+            number,
+            self.coordinate_precision,
+        ))
     }
 
     /// Returns the number of pages in this document.
@@ -197,12 +265,417 @@ impl Renderer {
 
     /// Writes this PDF document to a writer.
     pub fn write(self, w: impl io::Write) -> Result<(), Error> {
-        self.doc
-            .save(&mut io::BufWriter::new(w))
+        let has_links = self
+            .pages
+            .iter()
+            .any(|page| !page.links.borrow().is_empty());
+        let has_opacities = self
+            .pages
+            .iter()
+            .any(|page| !page.opacities.borrow().is_empty());
+        let has_shadings = self
+            .pages
+            .iter()
+            .any(|page| !page.shadings.borrow().is_empty());
+        if self.language.is_none()
+            && !self.display_doc_title
+            && !has_links
+            && !has_opacities
+            && !has_shadings
+        {
+            return self
+                .doc
+                .save(&mut io::BufWriter::new(w))
+                .context("Failed to save document");
+        }
+
+        // printpdf does not support these catalog entries, internal links, opacity and shadings,
+        // so we have to add them to the generated document.
+        let bytes = self
+            .doc
+            .save_to_bytes()
+            .context("Failed to save document")?;
+        let catalog_error = |err: lopdf::Error| {
+            Error::new(
+                format!("Failed to update the document catalog: {}", err),
+                ErrorKind::Internal,
+            )
+        };
+        let mut doc = lopdf::Document::load_mem(&bytes).map_err(catalog_error)?;
+        let catalog_id = doc
+            .trailer
+            .get(b"Root")
+            .and_then(lopdf::Object::as_reference)
+            .map_err(catalog_error)?;
+        let catalog = doc
+            .get_object_mut(catalog_id)
+            .and_then(lopdf::Object::as_dict_mut)
+            .map_err(catalog_error)?;
+        if let Some(language) = &self.language {
+            catalog.set("Lang", lopdf::Object::string_literal(language.as_str()));
+        }
+        if self.display_doc_title {
+            catalog.set(
+                "ViewerPreferences",
+                lopdf::dictionary! { "DisplayDocTitle" => true },
+            );
+        }
+        if has_links {
+            add_links(&self.pages, &mut doc).map_err(|err| {
+                Error::new(format!("Failed to add links: {}", err), ErrorKind::Internal)
+            })?;
+        }
+        if has_opacities {
+            add_opacities(&self.pages, &mut doc).map_err(|err| {
+                Error::new(
+                    format!("Failed to add opacity: {}", err),
+                    ErrorKind::Internal,
+                )
+            })?;
+        }
+        if has_shadings {
+            add_shadings(&self.pages, &mut doc).map_err(|err| {
+                Error::new(
+                    format!("Failed to add gradients: {}", err),
+                    ErrorKind::Internal,
+                )
+            })?;
+        }
+        doc.save_to(&mut io::BufWriter::new(w))
             .context("Failed to save document")
     }
 }
 
+/// Adds the links of the given pages as link annotations to the given document.
+fn add_links(pages: &[Page], doc: &mut lopdf::Document) -> Result<(), lopdf::Error> {
+    let page_ids = doc.get_pages();
+    let to_pt = |length: Mm| lopdf::Object::Real(printpdf::Pt::from(length).0 as f32);
+    for (page, page_id) in pages.iter().zip(page_ids.values()) {
+        let mut annotations = Vec::new();
+        for link in page.links.borrow().iter() {
+            // Links to pages that do not exist are ignored, for example if the link targets
+            // have been determined in a previous rendering pass of a different document.
+            let target_id = match page_ids.get(&(link.page as u32)) {
+                Some(target_id) => *target_id,
+                None => continue,
+            };
+            let target_height = pages[link.page - 1].size.height;
+            let rect = link.rect;
+            let mut annotation = lopdf::Dictionary::new();
+            annotation.set("Type", lopdf::Object::Name(b"Annot".to_vec()));
+            annotation.set("Subtype", lopdf::Object::Name(b"Link".to_vec()));
+            annotation.set(
+                "Rect",
+                vec![
+                    to_pt(rect.origin.x),
+                    to_pt(page.size.height - rect.origin.y - rect.size.height),
+                    to_pt(rect.origin.x + rect.size.width),
+                    to_pt(page.size.height - rect.origin.y),
+                ],
+            );
+            annotation.set("Border", vec![0.into(), 0.into(), 0.into()]);
+            annotation.set(
+                "Dest",
+                vec![
+                    target_id.into(),
+                    lopdf::Object::Name(b"XYZ".to_vec()),
+                    to_pt(link.position.x),
+                    to_pt(target_height - link.position.y),
+                    lopdf::Object::Null,
+                ],
+            );
+            annotations.push(doc.add_object(annotation).into());
+        }
+        if !annotations.is_empty() {
+            let page = doc
+                .get_object_mut(*page_id)
+                .and_then(lopdf::Object::as_dict_mut)?;
+            // printpdf already writes an array with its own annotations to every page.
+            if let Ok(existing) = page
+                .get_mut(b"Annots")
+                .and_then(lopdf::Object::as_array_mut)
+            {
+                existing.extend(annotations);
+            } else {
+                page.set("Annots", annotations);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds the graphics states for the opacities used on the given pages to the resources of the
+/// pages in the given document.
+fn add_opacities(pages: &[Page], doc: &mut lopdf::Document) -> Result<(), lopdf::Error> {
+    let page_ids = doc.get_pages();
+    for (page, page_id) in pages.iter().zip(page_ids.values()) {
+        let opacities = page.opacities.borrow();
+        if opacities.is_empty() {
+            continue;
+        }
+        let resources_id = doc
+            .get_dictionary(*page_id)?
+            .get(b"Resources")?
+            .as_reference()?;
+        let resources = doc.get_dictionary_mut(resources_id)?;
+        if !resources.has(b"ExtGState") {
+            resources.set("ExtGState", lopdf::Dictionary::new());
+        }
+        let states = resources.get_mut(b"ExtGState")?.as_dict_mut()?;
+        for (idx, opacity) in opacities.iter().enumerate() {
+            let mut state = lopdf::Dictionary::new();
+            state.set("Type", lopdf::Object::Name(b"ExtGState".to_vec()));
+            state.set("CA", lopdf::Object::Real(*opacity as f32));
+            state.set("ca", lopdf::Object::Real(*opacity as f32));
+            states.set(opacity_state_name(idx), state);
+        }
+    }
+    Ok(())
+}
+
+fn opacity_state_name(idx: usize) -> String {
+    format!("GenpdfOpacity{}", idx)
+}
+
+/// Adds the shadings used on the given pages to the resources of the pages in the given document.
+fn add_shadings(pages: &[Page], doc: &mut lopdf::Document) -> Result<(), lopdf::Error> {
+    let page_ids = doc.get_pages();
+    for (page, page_id) in pages.iter().zip(page_ids.values()) {
+        let shadings = page.shadings.borrow();
+        if shadings.is_empty() {
+            continue;
+        }
+        let resources_id = doc
+            .get_dictionary(*page_id)?
+            .get(b"Resources")?
+            .as_reference()?;
+        let resources = doc.get_dictionary_mut(resources_id)?;
+        if !resources.has(b"Shading") {
+            resources.set("Shading", lopdf::Dictionary::new());
+        }
+        let dict = resources.get_mut(b"Shading")?.as_dict_mut()?;
+        for (idx, shading) in shadings.iter().enumerate() {
+            dict.set(shading_name(idx), shading.to_dictionary());
+        }
+    }
+    Ok(())
+}
+
+fn shading_name(idx: usize) -> String {
+    format!("GenpdfShading{}", idx)
+}
+
+/// An axial or radial shading that is used to paint a gradient [`Fill`][].
+///
+/// [`Fill`]: ../style/enum.Fill.html
+#[derive(Clone, Debug, PartialEq)]
+struct Shading {
+    /// The shading type:  2 for axial and 3 for radial shadings.
+    shading_type: i64,
+    /// The coordinates of the shading in user space (in points).
+    coords: Vec<f64>,
+    stops: Vec<(f64, Color)>,
+}
+
+impl Shading {
+    /// Creates the shading for the given gradient fill of a shape with the given bounding box
+    /// (upper left and lower right corner).
+    fn new(
+        layer: &Layer<'_>,
+        fill: &Fill,
+        top_left: LayerPosition,
+        bottom_right: LayerPosition,
+    ) -> Option<Shading> {
+        let (x0, y0) = (top_left.0.x, top_left.0.y);
+        let width = bottom_right.0.x - x0;
+        let height = bottom_right.0.y - y0;
+        let to_pt = |pos: Position| {
+            let pos = layer.transform_position(LayerPosition(pos));
+            vec![Pt::from(pos.x).0, Pt::from(pos.y).0]
+        };
+        match fill {
+            Fill::Solid(_) => None,
+            Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops, .. }
+                if stops.is_empty() =>
+            {
+                None
+            }
+            Fill::LinearGradient { stops, angle } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                // The gradient line goes through the center and reaches the corners
+                let half_length = (width.0 * cos.abs() + height.0 * sin.abs()) / 2.0;
+                let center = Position::new(x0 + width / 2.0, y0 + height / 2.0);
+                let delta = Position::new(half_length * cos, half_length * sin);
+                let mut coords = to_pt(Position::new(center.x - delta.x, center.y - delta.y));
+                coords.extend(to_pt(center + delta));
+                Some(Shading {
+                    shading_type: 2,
+                    coords,
+                    stops: stops.clone(),
+                })
+            }
+            Fill::RadialGradient {
+                stops,
+                center,
+                radius,
+            } => {
+                let center = Position::new(x0 + width * center.0, y0 + height * center.1);
+                let radius = Mm(radius * width.0.hypot(height.0) / 2.0);
+                let mut coords = to_pt(center);
+                coords.push(0.0);
+                coords.extend(to_pt(center));
+                coords.push(Pt::from(radius).0);
+                Some(Shading {
+                    shading_type: 3,
+                    coords,
+                    stops: stops.clone(),
+                })
+            }
+        }
+    }
+
+    fn to_dictionary(&self) -> lopdf::Dictionary {
+        let real = |value: f64| lopdf::Object::Real(value as f32);
+        let color_space = shading_color_space(&self.stops);
+
+        // Extend the first and the last stop to the bounds of the domain
+        let mut stops = self.stops.clone();
+        if let Some(&(offset, color)) = stops.first() {
+            if offset > 0.0 {
+                stops.insert(0, (0.0, color));
+            }
+        }
+        if let Some(&(offset, color)) = stops.last() {
+            if offset < 1.0 || stops.len() == 1 {
+                stops.push((1.0, color));
+            }
+        }
+
+        let components = |color: Color| -> lopdf::Object {
+            color_components(color, color_space)
+                .into_iter()
+                .map(real)
+                .collect::<Vec<_>>()
+                .into()
+        };
+        let mut functions: Vec<lopdf::Object> = stops
+            .windows(2)
+            .map(|pair| {
+                let mut function = lopdf::Dictionary::new();
+                function.set("FunctionType", 2);
+                function.set("Domain", vec![real(0.0), real(1.0)]);
+                function.set("C0", components(pair[0].1));
+                function.set("C1", components(pair[1].1));
+                function.set("N", 1);
+                function.into()
+            })
+            .collect();
+        let function = if functions.len() == 1 {
+            functions.remove(0)
+        } else {
+            let bounds: Vec<_> = stops[1..stops.len() - 1]
+                .iter()
+                .map(|(offset, _)| real(offset.clamp(0.0, 1.0)))
+                .collect();
+            let encode: Vec<_> = functions
+                .iter()
+                .flat_map(|_| vec![real(0.0), real(1.0)])
+                .collect();
+            let mut function = lopdf::Dictionary::new();
+            function.set("FunctionType", 3);
+            function.set("Domain", vec![real(0.0), real(1.0)]);
+            function.set("Functions", functions);
+            function.set("Bounds", bounds);
+            function.set("Encode", encode);
+            function.into()
+        };
+
+        let mut shading = lopdf::Dictionary::new();
+        shading.set("ShadingType", self.shading_type);
+        shading.set(
+            "ColorSpace",
+            lopdf::Object::Name(color_space.as_bytes().to_vec()),
+        );
+        shading.set(
+            "Coords",
+            self.coords.iter().copied().map(real).collect::<Vec<_>>(),
+        );
+        shading.set("Function", function);
+        shading.set("Extend", vec![true.into(), true.into()]);
+        shading
+    }
+}
+
+/// Returns the color space for the given color stops, falling back to RGB for mixed colors.
+fn shading_color_space(stops: &[(f64, Color)]) -> &'static str {
+    let all = |f: fn(&Color) -> bool| stops.iter().all(|(_, color)| f(color));
+    if all(|color| matches!(color, Color::Cmyk(..))) {
+        "DeviceCMYK"
+    } else if all(|color| matches!(color, Color::Greyscale(..))) {
+        "DeviceGray"
+    } else {
+        "DeviceRGB"
+    }
+}
+
+/// Returns the components of the given color in the given color space.
+fn color_components(color: Color, color_space: &str) -> Vec<f64> {
+    let value = |v: u8| f64::from(v) / 255.0;
+    match (color, color_space) {
+        (Color::Cmyk(c, m, y, k), "DeviceCMYK") => vec![value(c), value(m), value(y), value(k)],
+        (Color::Greyscale(v), "DeviceGray") => vec![value(v)],
+        (Color::Rgb(r, g, b), _) => vec![value(r), value(g), value(b)],
+        (Color::Cmyk(c, m, y, k), _) => {
+            let k = 1.0 - value(k);
+            vec![
+                (1.0 - value(c)) * k,
+                (1.0 - value(m)) * k,
+                (1.0 - value(y)) * k,
+            ]
+        }
+        (Color::Greyscale(v), _) => vec![value(v); 3],
+    }
+}
+
+/// Converts the given points using the point format of `printpdf::Line` into path construction
+/// operators.
+fn path_operations(points: &[(UserSpacePosition, bool)]) -> Vec<lopdf::content::Operation> {
+    use lopdf::content::Operation;
+
+    let coords = |pos: &UserSpacePosition| -> Vec<lopdf::Object> {
+        vec![
+            lopdf::Object::Real(Pt::from(pos.x).0 as f32),
+            lopdf::Object::Real(Pt::from(pos.y).0 as f32),
+        ]
+    };
+    let mut operations = Vec::new();
+    if let Some((first, _)) = points.first() {
+        operations.push(Operation::new("m", coords(first)));
+    }
+    let mut i = 1;
+    while i < points.len() {
+        if points[i - 1].1 && points[i].1 && i + 2 < points.len() {
+            let mut operands = coords(&points[i].0);
+            operands.extend(coords(&points[i + 1].0));
+            operands.extend(coords(&points[i + 2].0));
+            operations.push(Operation::new("c", operands));
+            i += 3;
+        } else {
+            operations.push(Operation::new("l", coords(&points[i].0)));
+            i += 1;
+        }
+    }
+    operations
+}
+
+/// A link from a rectangle on a page to a position on a page of the same document.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Link {
+    rect: Rect,
+    page: usize,
+    position: Position,
+}
+
 /// A page of a PDF document.
 ///
 /// This is a wrapper around a [`printpdf::PdfPageReference`][].
@@ -211,7 +684,12 @@ impl Renderer {
 pub struct Page {
     page: printpdf::PdfPageReference,
     size: Size,
+    number: usize,
     layers: Layers,
+    links: cell::RefCell<Vec<Link>>,
+    opacities: cell::RefCell<Vec<f64>>,
+    shadings: cell::RefCell<Vec<Shading>>,
+    coordinate_precision: Option<u8>,
 }
 
 impl Page {
@@ -219,14 +697,26 @@ impl Page {
         page: printpdf::PdfPageReference,
         layer: printpdf::PdfLayerReference,
         size: Size,
+        number: usize,
+        coordinate_precision: Option<u8>,
     ) -> Page {
         Page {
             page,
             size,
+            number,
+            coordinate_precision,
             layers: Layers::new(layer),
+            links: Default::default(),
+            opacities: Default::default(),
+            shadings: Default::default(),
         }
     }
 
+    /// Returns the number of this page, starting with 1.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
     /// Adds a new layer with the given name to the page.
     pub fn add_layer(&mut self, name: impl Into<String>) {
         let layer = self.page.add_layer(name);
@@ -253,6 +743,36 @@ impl Page {
         Layer::new(self, self.layers.last())
     }
 
+    /// Returns the name of the graphics state resource with the given opacity.
+    ///
+    /// The graphics states are added to the page resources when the document is written.
+    fn opacity_state(&self, opacity: f64) -> String {
+        let mut opacities = self.opacities.borrow_mut();
+        let idx = opacities
+            .iter()
+            .position(|o| *o == opacity)
+            .unwrap_or_else(|| {
+                opacities.push(opacity);
+                opacities.len() - 1
+            });
+        opacity_state_name(idx)
+    }
+
+    /// Returns the name of the shading resource for the given shading.
+    ///
+    /// The shadings are added to the page resources when the document is written.
+    fn shading(&self, shading: Shading) -> String {
+        let mut shadings = self.shadings.borrow_mut();
+        let idx = shadings
+            .iter()
+            .position(|s| *s == shading)
+            .unwrap_or_else(|| {
+                shadings.push(shading);
+                shadings.len() - 1
+            });
+        shading_name(idx)
+    }
+
     fn next_layer(&self, layer: &printpdf::PdfLayerReference) -> Layer<'_> {
         let layer = self.layers.next(layer).unwrap_or_else(|| {
             let layer = self
@@ -313,19 +833,32 @@ impl Layers {
 pub struct Layer<'p> {
     page: &'p Page,
     data: rc::Rc<LayerData>,
+    dry_run: bool,
+    transforms: Vec<Transform>,
 }
 
 impl<'p> Layer<'p> {
     fn new(page: &'p Page, data: rc::Rc<LayerData>) -> Layer<'p> {
-        Layer { page, data }
+        Layer {
+            page,
+            data,
+            dry_run: false,
+            transforms: Vec::new(),
+        }
     }
 
     /// Returns the next layer of this page.
     ///
     /// If this layer is not the last layer, the existing next layer is used.  If it is the last
-    /// layer, a new layer is created and added to the page.
+    /// layer, a new layer is created and added to the page.  For a dry run, no layer is created
+    /// and this layer is returned instead.
     pub fn next(&self) -> Layer<'p> {
-        self.page.next_layer(&self.data.layer)
+        if self.dry_run {
+            return self.clone();
+        }
+        let mut layer = self.page.next_layer(&self.data.layer);
+        layer.transforms = self.transforms.clone();
+        layer
     }
 
     /// Returns a drawable area for this layer.
@@ -333,6 +866,62 @@ impl<'p> Layer<'p> {
         Area::new(self.clone(), Position::default(), self.page.size)
     }
 
+    fn add_link(&self, mut link: Link) {
+        if self.dry_run {
+            return;
+        }
+        for transform in self.transforms.iter().rev() {
+            let bottom_right = Position::new(
+                link.rect.origin.x + link.rect.size.width,
+                link.rect.origin.y + link.rect.size.height,
+            );
+            let origin = transform.apply(link.rect.origin);
+            let bottom_right = transform.apply(bottom_right);
+            link.rect = Rect::new(
+                origin,
+                Size::new(bottom_right.x - origin.x, bottom_right.y - origin.y),
+            );
+        }
+        self.page.links.borrow_mut().push(link);
+    }
+
+    /// Saves the graphics state and applies the transformations of this layer, if there are any.
+    ///
+    /// The returned state has to be passed to [`end_transform`][] after drawing.
+    ///
+    /// [`end_transform`]: #method.end_transform
+    fn begin_transform(&self) -> Option<LayerState> {
+        if self.dry_run || self.transforms.is_empty() {
+            return None;
+        }
+        let layer = &self.data.layer;
+        layer.save_graphics_state();
+        for transform in &self.transforms {
+            let origin = self.transform_position(transform.origin);
+            let scale = transform.scale;
+            let x = printpdf::Pt::from(origin.x).0 * (1.0 - scale);
+            let y = printpdf::Pt::from(origin.y).0 * (1.0 - scale);
+            let operands = [scale, 0.0, 0.0, scale, x, y]
+                .iter()
+                .map(|&value| lopdf::Object::Real(value as f32))
+                .collect();
+            layer.add_operation(lopdf::content::Operation::new("cm", operands));
+        }
+        Some(self.data.state())
+    }
+
+    /// Restores the graphics state saved by [`begin_transform`][].
+    ///
+    /// [`begin_transform`]: #method.begin_transform
+    fn end_transform(&self, state: Option<LayerState>) {
+        if let Some(state) = state {
+            self.data.layer.restore_graphics_state();
+            // The graphics state changes made within the transformation are discarded, so we
+            // have to reset the cached state
+            self.data.restore_state(state);
+        }
+    }
+
     #[cfg(feature = "images")]
     fn add_image(
         &self,
@@ -342,6 +931,9 @@ impl<'p> Layer<'p> {
         rotation: Rotation,
         dpi: Option<f64>,
     ) {
+        if self.dry_run {
+            return;
+        }
         let dynamic_image = printpdf::Image::from_dynamic_image(image);
         let position = self.transform_position(position);
 
@@ -360,28 +952,140 @@ impl<'p> Layer<'p> {
             dpi,
         };
 
+        let state = self.begin_transform();
         dynamic_image.add_to_layer(self.data.layer.clone(), transform);
+        self.end_transform(state);
     }
 
     fn add_line_shape<I>(&self, points: I, filled: bool)
     where
         I: IntoIterator<Item = LayerPosition>,
     {
+        if self.dry_run {
+            return;
+        }
+        let mut points: Vec<_> = points.into_iter().map(|pos| pos.0).collect();
+        // If the line ends at its starting point, we close the path so that the last corner is
+        // joined like all other corners.
+        let is_closed = points.len() > 2 && points.first() == points.last();
+        if is_closed {
+            points.pop();
+        }
         let line_points: Vec<_> = points
             .into_iter()
-            .map(|pos| (self.transform_position(pos).into(), false))
+            .map(|pos| (self.transform_position(LayerPosition(pos)).into(), false))
             .collect();
         let line = printpdf::Line {
             points: line_points,
-            is_closed: false,
+            is_closed,
             has_fill: filled,
             has_stroke: true,
             is_clipping_path: false,
         };
+        let state = self.begin_transform();
+        self.data.layer.add_shape(line);
+        self.end_transform(state);
+    }
+
+    fn add_path(
+        &self,
+        points: Vec<(LayerPosition, bool)>,
+        is_closed: bool,
+        filled: bool,
+        stroked: bool,
+    ) {
+        if self.dry_run {
+            return;
+        }
+        let points: Vec<_> = points
+            .into_iter()
+            .map(|(pos, is_bezier)| (self.transform_position(pos).into(), is_bezier))
+            .collect();
+        let line = printpdf::Line {
+            points,
+            is_closed,
+            has_fill: filled,
+            has_stroke: stroked,
+            is_clipping_path: false,
+        };
+        let state = self.begin_transform();
+        self.data.layer.add_shape(line);
+        self.end_transform(state);
+    }
+
+    /// Fills the closed path with the given points with the given gradient.
+    fn add_shading(&self, points: Vec<(LayerPosition, bool)>, fill: &Fill) {
+        if self.dry_run || points.is_empty() {
+            return;
+        }
+        let (mut top_left, mut bottom_right) = (points[0].0 .0, points[0].0 .0);
+        for (pos, _) in &points {
+            top_left.x = top_left.x.min(pos.0.x);
+            top_left.y = top_left.y.min(pos.0.y);
+            bottom_right.x = bottom_right.x.max(pos.0.x);
+            bottom_right.y = bottom_right.y.max(pos.0.y);
+        }
+        let shading = Shading::new(
+            self,
+            fill,
+            LayerPosition(top_left),
+            LayerPosition(bottom_right),
+        );
+        let shading = match shading {
+            Some(shading) => shading,
+            None => return,
+        };
+        let name = self.page.shading(shading);
+
+        // Use the path as a clipping path and paint the shading within it
+        let points: Vec<_> = points
+            .into_iter()
+            .map(|(pos, is_bezier)| (self.transform_position(pos), is_bezier))
+            .collect();
+        let state = self.begin_transform();
+        let layer = &self.data.layer;
+        layer.save_graphics_state();
+        for operation in path_operations(&points) {
+            layer.add_operation(operation);
+        }
+        for operator in &["h", "W", "n"] {
+            layer.add_operation(lopdf::content::Operation::new(operator, vec![]));
+        }
+        layer.add_operation(lopdf::content::Operation::new(
+            "sh",
+            vec![lopdf::Object::Name(name.into_bytes())],
+        ));
+        layer.restore_graphics_state();
+        self.end_transform(state);
+    }
+
+    fn add_filled_shape<I>(&self, points: I)
+    where
+        I: IntoIterator<Item = LayerPosition>,
+    {
+        if self.dry_run {
+            return;
+        }
+        let line_points: Vec<_> = points
+            .into_iter()
+            .map(|pos| (self.transform_position(pos).into(), false))
+            .collect();
+        let line = printpdf::Line {
+            points: line_points,
+            is_closed: true,
+            has_fill: true,
+            has_stroke: false,
+            is_clipping_path: false,
+        };
+        let state = self.begin_transform();
         self.data.layer.add_shape(line);
+        self.end_transform(state);
     }
 
     fn set_fill_color(&self, color: Option<Color>) {
+        if self.dry_run {
+            return;
+        }
         if self.data.update_fill_color(color) {
             self.data
                 .layer
@@ -390,6 +1094,9 @@ impl<'p> Layer<'p> {
     }
 
     fn set_outline_thickness(&self, thickness: Mm) {
+        if self.dry_run {
+            return;
+        }
         if self.data.update_outline_thickness(thickness) {
             self.data
                 .layer
@@ -398,39 +1105,176 @@ impl<'p> Layer<'p> {
     }
 
     fn set_outline_color(&self, color: Color) {
+        if self.dry_run {
+            return;
+        }
         if self.data.update_outline_color(color) {
             self.data.layer.set_outline_color(color.into());
         }
     }
 
+    fn set_line_cap(&self, cap: LineCap) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_line_cap(cap) {
+            self.data.layer.set_line_cap_style(cap.into());
+        }
+    }
+
+    fn set_line_join(&self, join: LineJoin) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_line_join(join) {
+            self.data.layer.set_line_join_style(join.into());
+        }
+    }
+
+    fn set_dash_pattern(&self, dash_pattern: Option<(Mm, Mm)>) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_dash_pattern(dash_pattern) {
+            let to_pt = |length: Mm| (printpdf::Pt::from(length).0.round() as i64).max(1);
+            let pattern = if let Some((dash, gap)) = dash_pattern {
+                printpdf::LineDashPattern {
+                    dash_1: Some(to_pt(dash)),
+                    gap_1: Some(to_pt(gap)),
+                    ..Default::default()
+                }
+            } else {
+                printpdf::LineDashPattern::default()
+            };
+            self.data.layer.set_line_dash_pattern(pattern);
+        }
+    }
+
+    fn set_opacity(&self, opacity: f64) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_opacity(opacity) {
+            let name = self.page.opacity_state(opacity);
+            self.data
+                .layer
+                .add_operation(lopdf::content::Operation::new(
+                    "gs",
+                    vec![lopdf::Object::Name(name.into_bytes())],
+                ));
+        }
+    }
+
+    fn set_character_spacing(&self, spacing: Mm) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_character_spacing(spacing) {
+            self.data
+                .layer
+                .set_character_spacing(printpdf::Pt::from(spacing).0);
+        }
+    }
+
+    fn set_text_rendering_mode(&self, mode: TextRenderingMode) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_text_rendering_mode(mode) {
+            self.data.layer.set_text_rendering_mode(mode.into());
+        }
+    }
+
+    fn set_text_rise(&self, rise: Mm) {
+        if self.dry_run {
+            return;
+        }
+        if self.data.update_text_rise(rise) {
+            self.data.layer.set_line_offset(printpdf::Pt::from(rise).0);
+        }
+    }
+
+    fn begin_actual_text(&self, text: &str) {
+        if self.dry_run {
+            return;
+        }
+        // Text strings in content streams are encoded as UTF-16BE with a byte order mark.
+        let mut bytes = vec![0xfe, 0xff];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        let mut properties = lopdf::Dictionary::new();
+        properties.set(
+            "ActualText",
+            lopdf::Object::String(bytes, lopdf::StringFormat::Hexadecimal),
+        );
+        self.data
+            .layer
+            .add_operation(lopdf::content::Operation::new(
+                "BDC",
+                vec![lopdf::Object::Name(b"Span".to_vec()), properties.into()],
+            ));
+    }
+
+    fn end_marked_content(&self) {
+        if self.dry_run {
+            return;
+        }
+        self.data
+            .layer
+            .add_operation(lopdf::content::Operation::new("EMC", Vec::new()));
+    }
+
     fn set_text_cursor(&self, cursor: LayerPosition) {
+        if self.dry_run {
+            return;
+        }
         let cursor = self.transform_position(cursor);
         self.data
             .layer
             .set_text_cursor(cursor.x.into(), cursor.y.into());
     }
 
-    fn begin_text_section(&self) {
+    fn begin_text_section(&self) -> Option<LayerState> {
+        if self.dry_run {
+            return None;
+        }
+        let state = self.begin_transform();
         self.data.layer.begin_text_section();
+        state
     }
 
-    fn end_text_section(&self) {
+    fn end_text_section(&self, state: Option<LayerState>) {
+        if self.dry_run {
+            return;
+        }
         self.data.layer.end_text_section();
+        self.end_transform(state);
     }
 
     fn add_line_break(&self) {
+        if self.dry_run {
+            return;
+        }
         self.data.layer.add_line_break();
     }
 
     fn set_line_height(&self, line_height: Mm) {
+        if self.dry_run {
+            return;
+        }
         self.data.layer.set_line_height(line_height.0);
     }
 
     fn set_font(&self, font: &printpdf::IndirectFontRef, font_size: u8) {
+        if self.dry_run {
+            return;
+        }
         self.data.layer.set_font(font, font_size.into());
     }
 
     fn set_font_f64(&self, font: &printpdf::IndirectFontRef, font_size: f64) {
+        if self.dry_run {
+            return;
+        }
         self.data.layer.set_font(font, font_size);
     }
 
@@ -439,6 +1283,9 @@ impl<'p> Layer<'p> {
         P: IntoIterator<Item = i64>,
         C: IntoIterator<Item = u16>,
     {
+        if self.dry_run {
+            return;
+        }
         self.data
             .layer
             .write_positioned_codepoints(positions.into_iter().zip(codepoints.into_iter()));
@@ -457,9 +1304,61 @@ struct LayerData {
     fill_color: cell::Cell<Color>,
     outline_color: cell::Cell<Color>,
     outline_thickness: cell::Cell<Mm>,
+    line_cap: cell::Cell<LineCap>,
+    line_join: cell::Cell<LineJoin>,
+    dash_pattern: cell::Cell<Option<(Mm, Mm)>>,
+    character_spacing: cell::Cell<Mm>,
+    text_rise: cell::Cell<Mm>,
+    text_rendering_mode: cell::Cell<TextRenderingMode>,
+    opacity: cell::Cell<f64>,
+}
+
+/// The cached graphics state of a layer, see [`LayerData`][].
+///
+/// [`LayerData`]: struct.LayerData.html
+#[derive(Clone, Copy, Debug)]
+struct LayerState {
+    fill_color: Color,
+    outline_color: Color,
+    outline_thickness: Mm,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    dash_pattern: Option<(Mm, Mm)>,
+    character_spacing: Mm,
+    text_rise: Mm,
+    text_rendering_mode: TextRenderingMode,
+    opacity: f64,
 }
 
 impl LayerData {
+    pub fn state(&self) -> LayerState {
+        LayerState {
+            fill_color: self.fill_color.get(),
+            outline_color: self.outline_color.get(),
+            outline_thickness: self.outline_thickness.get(),
+            line_cap: self.line_cap.get(),
+            line_join: self.line_join.get(),
+            dash_pattern: self.dash_pattern.get(),
+            character_spacing: self.character_spacing.get(),
+            text_rise: self.text_rise.get(),
+            text_rendering_mode: self.text_rendering_mode.get(),
+            opacity: self.opacity.get(),
+        }
+    }
+
+    pub fn restore_state(&self, state: LayerState) {
+        self.fill_color.set(state.fill_color);
+        self.outline_color.set(state.outline_color);
+        self.outline_thickness.set(state.outline_thickness);
+        self.line_cap.set(state.line_cap);
+        self.line_join.set(state.line_join);
+        self.dash_pattern.set(state.dash_pattern);
+        self.character_spacing.set(state.character_spacing);
+        self.text_rise.set(state.text_rise);
+        self.text_rendering_mode.set(state.text_rendering_mode);
+        self.opacity.set(state.opacity);
+    }
+
     pub fn update_fill_color(&self, color: Option<Color>) -> bool {
         let color = color.unwrap_or(Color::Rgb(0, 0, 0));
         self.fill_color.replace(color) != color
@@ -472,6 +1371,34 @@ impl LayerData {
     pub fn update_outline_thickness(&self, thickness: Mm) -> bool {
         self.outline_thickness.replace(thickness) != thickness
     }
+
+    pub fn update_line_cap(&self, cap: LineCap) -> bool {
+        self.line_cap.replace(cap) != cap
+    }
+
+    pub fn update_line_join(&self, join: LineJoin) -> bool {
+        self.line_join.replace(join) != join
+    }
+
+    pub fn update_dash_pattern(&self, dash_pattern: Option<(Mm, Mm)>) -> bool {
+        self.dash_pattern.replace(dash_pattern) != dash_pattern
+    }
+
+    pub fn update_character_spacing(&self, spacing: Mm) -> bool {
+        self.character_spacing.replace(spacing) != spacing
+    }
+
+    pub fn update_text_rise(&self, rise: Mm) -> bool {
+        self.text_rise.replace(rise) != rise
+    }
+
+    pub fn update_text_rendering_mode(&self, mode: TextRenderingMode) -> bool {
+        self.text_rendering_mode.replace(mode) != mode
+    }
+
+    pub fn update_opacity(&self, opacity: f64) -> bool {
+        self.opacity.replace(opacity) != opacity
+    }
 }
 
 impl From<printpdf::PdfLayerReference> for LayerData {
@@ -481,6 +1408,13 @@ impl From<printpdf::PdfLayerReference> for LayerData {
             fill_color: Color::Rgb(0, 0, 0).into(),
             outline_color: Color::Rgb(0, 0, 0).into(),
             outline_thickness: Mm::from(printpdf::Pt(1.0)).into(),
+            line_cap: LineCap::default().into(),
+            line_join: LineJoin::default().into(),
+            dash_pattern: None.into(),
+            character_spacing: Mm(0.0).into(),
+            text_rise: Mm(0.0).into(),
+            text_rendering_mode: TextRenderingMode::default().into(),
+            opacity: 1.0.into(),
         }
     }
 }
@@ -534,6 +1468,75 @@ impl<'p> Area<'p> {
         self.size
     }
 
+    /// Returns the origin of this area, measured from the upper left corner of the page.
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    /// Returns the rectangle of this area, measured from the upper left corner of the page.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.origin, self.size)
+    }
+
+    /// Adds a link to the given position on the page with the given number, starting with 1.
+    ///
+    /// The link covers the rectangle with the given position, relative to the upper left corner
+    /// of this area, and the given size.  The target position is measured from the upper left
+    /// corner of the target page.  Links to pages that do not exist when the document is written
+    /// are ignored.
+    pub fn add_link(
+        &self,
+        position: Position,
+        size: impl Into<Size>,
+        page: usize,
+        target: impl Into<Position>,
+    ) {
+        self.layer.add_link(Link {
+            rect: Rect::new(self.origin + position, size.into()),
+            page,
+            position: target.into(),
+        });
+    }
+
+    /// Returns the number of the page this area is drawn on, starting with 1.
+    pub fn page_number(&self) -> usize {
+        self.layer.page.number
+    }
+
+    /// Returns a copy of this area that does not draw anything.
+    ///
+    /// Elements can be rendered on the returned area to measure the size they need without
+    /// changing the document.  Note that rendering an element usually changes its state, so this
+    /// should only be used with a copy of the element.
+    pub fn dry_run(&self) -> Area<'p> {
+        let mut area = self.clone();
+        area.layer.dry_run = true;
+        area
+    }
+
+    /// Returns whether this area is used for a dry run, see [`dry_run`][].
+    ///
+    /// [`dry_run`]: #method.dry_run
+    pub fn is_dry_run(&self) -> bool {
+        self.layer.dry_run
+    }
+
+    /// Returns a copy of this area that scales everything drawn on it by the given factor.
+    ///
+    /// The content is scaled around the upper left corner of this area by changing the current
+    /// transformation matrix, so font sizes, line widths and images are scaled too.  The size of
+    /// the returned area is divided by the factor so that it covers the same rectangle of the
+    /// page as this area.  The factor must be positive.
+    pub fn scaled(&self, scale: f64) -> Area<'p> {
+        let mut area = self.clone();
+        area.layer.transforms.push(Transform {
+            origin: LayerPosition::from_area(self, Position::default()),
+            scale,
+        });
+        area.size = Size::new(self.size.width / scale, self.size.height / scale);
+        area
+    }
+
     /// Adds the given offset to the area, reducing the drawable area.
     pub fn add_offset(&mut self, offset: impl Into<Position>) {
         let offset = offset.into();
@@ -566,10 +1569,22 @@ impl<'p> Area<'p> {
     pub fn split_horizontally(&self, weights: &[usize]) -> Vec<Area<'p>> {
         let total_weight: usize = weights.iter().sum();
         let factor = self.size.width / total_weight as f64;
-        let widths = weights.iter().map(|weight| factor * *weight as f64);
+        let widths: Vec<_> = weights
+            .iter()
+            .map(|weight| factor * *weight as f64)
+            .collect();
+        self.split_horizontally_by_widths(&widths)
+    }
+
+    /// Splits this area horizontally into areas with the given widths.
+    ///
+    /// The returned vector has the same number of elements as the provided slice.  The areas are
+    /// arranged from left to right, starting at the left edge of this area.  The widths are not
+    /// checked against the width of this area.
+    pub fn split_horizontally_by_widths(&self, widths: &[Mm]) -> Vec<Area<'p>> {
         let mut offset = Mm(0.0);
         let mut areas = Vec::new();
-        for width in widths {
+        for &width in widths {
             let mut area = self.clone();
             area.origin.x += offset;
             area.size.width = width;
@@ -598,28 +1613,204 @@ impl<'p> Area<'p> {
         rotation: Rotation,
         dpi: Option<f64>,
     ) {
+        self.layer.set_opacity(1.0);
         self.layer
             .add_image(image, self.position(position), scale, rotation, dpi);
     }
 
     /// Draws a line with the given points and the given line style.
     ///
-    /// The points are relative to the upper left corner of the area.
+    /// The points are relative to the upper left corner of the area.  If the last point is equal
+    /// to the first point, the line is closed so that all corners are joined according to the
+    /// line style.
     pub fn draw_line<I>(&self, points: I, line_style: LineStyle)
     where
         I: IntoIterator<Item = Position>,
     {
+        self.set_line_style(line_style);
+        self.layer.add_line_shape(
+            points.into_iter().map(|pos| self.position(pos)),
+            line_style.filled(),
+        );
+    }
+
+    /// Draws a line with the given points and the given line style, rounding the corners with the
+    /// given radius.
+    ///
+    /// The points are relative to the upper left corner of the area.  The corners are drawn as
+    /// circular arcs that start and end on the adjacent line segments.  The radius is reduced for
+    /// corners with segments that are shorter than twice the radius.  If the last point is equal
+    /// to the first point, the line is closed and the corner at the first point is rounded too.
+    pub fn draw_rounded_line<I>(&self, points: I, radius: impl Into<Mm>, line_style: LineStyle)
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        let (path, is_closed) = rounded_path(points.into_iter().collect(), radius.into());
+        self.set_line_style(line_style);
+        self.layer.add_path(
+            path.into_iter()
+                .map(|(pos, is_bezier)| (self.position(pos), is_bezier))
+                .collect(),
+            is_closed,
+            line_style.filled(),
+            true,
+        );
+    }
+
+    /// Fills the shape with the given points with the given color or gradient, rounding the
+    /// corners with the given radius.
+    ///
+    /// The points are relative to the upper left corner of the area.  The corners are rounded like
+    /// in [`draw_rounded_line`][].  If the last point is not equal to the first point, the shape
+    /// is closed with a straight line and the corners at the first and the last point are not
+    /// rounded.  The shape is not outlined.
+    ///
+    /// [`draw_rounded_line`]: #method.draw_rounded_line
+    pub fn fill_rounded_shape<I>(&self, points: I, radius: impl Into<Mm>, fill: impl Into<Fill>)
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        let (path, _) = rounded_path(points.into_iter().collect(), radius.into());
+        let path = path
+            .into_iter()
+            .map(|(pos, is_bezier)| (self.position(pos), is_bezier))
+            .collect();
+        self.paint_path(path, true, Some(&fill.into()), None);
+    }
+
+    /// Draws a closed shape with the given points, filled with the given color or gradient and
+    /// outlined with the given line style.
+    ///
+    /// The points are relative to the upper left corner of the area.  If neither a fill nor a
+    /// line style is set, nothing is drawn.
+    pub fn draw_shape<I>(&self, points: I, fill: Option<Fill>, line_style: Option<LineStyle>)
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        let path = points
+            .into_iter()
+            .map(|pos| (self.position(pos), false))
+            .collect();
+        self.paint_path(path, true, fill.as_ref(), line_style);
+    }
+
+    /// Draws an ellipse that fills the rectangle with the given position and size, filled with the
+    /// given color or gradient and outlined with the given line style.
+    ///
+    /// The position of the upper left corner of the rectangle is relative to the upper left corner
+    /// of the area.  If neither a fill nor a line style is set, nothing is drawn.
+    pub fn draw_ellipse(
+        &self,
+        position: Position,
+        size: Size,
+        fill: Option<Fill>,
+        line_style: Option<LineStyle>,
+    ) {
+        let rx = size.width / 2.0;
+        let ry = size.height / 2.0;
+        let (cx, cy) = (position.x + rx, position.y + ry);
+        let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+        // Four cubic Bézier curves, one per quadrant, starting at the rightmost point
+        let points = [
+            (cx + rx, cy),
+            (cx + rx, cy + ky),
+            (cx + kx, cy + ry),
+            (cx, cy + ry),
+            (cx - kx, cy + ry),
+            (cx - rx, cy + ky),
+            (cx - rx, cy),
+            (cx - rx, cy - ky),
+            (cx - kx, cy - ry),
+            (cx, cy - ry),
+            (cx + kx, cy - ry),
+            (cx + rx, cy - ky),
+            (cx + rx, cy),
+        ];
+        let path = points
+            .iter()
+            .map(|&(x, y)| (self.position(Position::new(x, y)), true))
+            .collect();
+        self.paint_path(path, true, fill.as_ref(), line_style);
+    }
+
+    /// Creates a new empty path that can be drawn on this area.
+    ///
+    /// See [`Path`][] for details.
+    ///
+    /// [`Path`]: struct.Path.html
+    pub fn path(&self) -> Path<'p> {
+        Path::new(self.clone())
+    }
+
+    fn paint_path(
+        &self,
+        path: Vec<(LayerPosition, bool)>,
+        is_closed: bool,
+        fill: Option<&Fill>,
+        line_style: Option<LineStyle>,
+    ) {
+        let filled = match (fill, line_style) {
+            (None, None) => return,
+            (Some(fill), _) => {
+                if let Some(line_style) = line_style {
+                    self.set_line_style(line_style);
+                } else {
+                    self.layer.set_opacity(1.0);
+                }
+                if let Fill::Solid(color) = fill {
+                    self.layer.set_fill_color(Some(*color));
+                    true
+                } else {
+                    self.layer.add_shading(path.clone(), fill);
+                    if line_style.is_none() {
+                        return;
+                    }
+                    false
+                }
+            }
+            (None, Some(line_style)) => {
+                self.set_line_style(line_style);
+                line_style.filled()
+            }
+        };
+        self.layer
+            .add_path(path, is_closed, filled, line_style.is_some());
+    }
+
+    fn set_line_style(&self, line_style: LineStyle) {
         self.layer.set_outline_thickness(line_style.thickness());
         self.layer.set_outline_color(line_style.color());
+        self.layer.set_line_cap(line_style.cap());
+        self.layer.set_line_join(line_style.join());
+        self.layer.set_dash_pattern(line_style.dash_pattern());
+        self.layer.set_opacity(line_style.opacity());
         if line_style.filled() {
             self.layer.set_fill_color(Some(line_style.color()));
         } else {
             self.layer.set_fill_color(None);
         }
-        self.layer.add_line_shape(
-            points.into_iter().map(|pos| self.position(pos)),
-            line_style.filled(),
-        );
+    }
+
+    /// Fills a rectangle with the given position and size with the given color or gradient.
+    ///
+    /// The position of the upper left corner of the rectangle is relative to the upper left corner
+    /// of the area.  The rectangle is not outlined.
+    pub fn fill_rect(&self, position: Position, size: Size, fill: impl Into<Fill>) {
+        let points = [
+            position,
+            Position::new(position.x + size.width, position.y),
+            Position::new(position.x + size.width, position.y + size.height),
+            Position::new(position.x, position.y + size.height),
+        ];
+        match fill.into() {
+            Fill::Solid(color) => {
+                self.layer.set_fill_color(Some(color));
+                self.layer.set_opacity(1.0);
+                self.layer
+                    .add_filled_shape(points.iter().map(|pos| self.position(*pos)));
+            }
+            fill => self.draw_shape(points.iter().copied(), Some(fill), None),
+        }
     }
 
     /// Tries to draw the given string at the given position and returns `true` if the area was
@@ -661,6 +1852,8 @@ impl<'p> Area<'p> {
     }
 
     /// Creates a new text section at the given position, and writes codepoints (actually glyph IDs) with kerning into it
+    ///
+    /// Returns `true` if the area was large enough to create the text section.
     pub fn print_positioned_codepoints<C, P>(
         &self,
         font_cache: &fonts::FontCache,
@@ -669,14 +1862,18 @@ impl<'p> Area<'p> {
         codepoints: C,
         font_size: f64,
         style: Style,
-    ) where
+    ) -> Result<bool, Error>
+    where
         C: IntoIterator<Item = u16>,
         P: IntoIterator<Item = f64>,
     {
-        let mut section = self
-            .text_section(font_cache, origin, style.metrics(font_cache))
-            .unwrap();
-        section.print_positioned_codepoints(style, positions, codepoints, font_size);
+        if let Some(mut section) = self.text_section(font_cache, origin, style.metrics(font_cache))
+        {
+            section.print_positioned_codepoints(style, positions, codepoints, font_size)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 
     /// Returns a position relative to the top left corner of this area.
@@ -685,6 +1882,222 @@ impl<'p> Area<'p> {
     }
 }
 
+/// The distance of the Bézier control points from the end points of a quarter circle with radius 1.
+const KAPPA: f64 = 0.552_284_75;
+
+/// Converts the given polyline into a path with rounded corners and returns the path and whether
+/// it is closed.
+///
+/// Every point of the path has a flag that indicates whether it is the start point or a control
+/// point of a cubic Bézier curve, as expected by [`printpdf::Line`][].
+fn rounded_path(mut points: Vec<Position>, radius: Mm) -> (Vec<(Position, bool)>, bool) {
+    let is_closed = points.len() > 2 && points.first() == points.last();
+    if is_closed {
+        points.pop();
+    }
+    let n = points.len();
+    let distance = |a: Position, b: Position| (a.x - b.x).0.hypot((a.y - b.y).0);
+    // Returns the point on the segment from a to b with the given distance from a.
+    let towards = |a: Position, b: Position, d: f64| {
+        let f = d / distance(a, b);
+        Position::new(a.x + (b.x - a.x) * f, a.y + (b.y - a.y) * f)
+    };
+
+    let mut path = Vec::with_capacity(n * 4);
+    for (i, &corner) in points.iter().enumerate() {
+        let prev = if i > 0 {
+            Some(points[i - 1])
+        } else if is_closed {
+            points.last().copied()
+        } else {
+            None
+        };
+        let next = if i + 1 < n {
+            Some(points[i + 1])
+        } else if is_closed {
+            points.first().copied()
+        } else {
+            None
+        };
+        if let (Some(prev), Some(next)) = (prev, next) {
+            let r = radius
+                .0
+                .min(distance(prev, corner) / 2.0)
+                .min(distance(next, corner) / 2.0);
+            if r > 0.0 {
+                let start = towards(corner, prev, r);
+                let end = towards(corner, next, r);
+                path.push((start, true));
+                path.push((towards(start, corner, r * KAPPA), true));
+                path.push((towards(end, corner, r * KAPPA), true));
+                path.push((end, false));
+                continue;
+            }
+        }
+        path.push((corner, false));
+    }
+    (path, is_closed)
+}
+
+/// A path that consists of straight lines and cubic Bézier curves and that is drawn on an area.
+///
+/// Paths are created with [`Area::path`][].  A path consists of one or more subpaths:
+/// [`move_to`][] starts a new subpath, [`line_to`][] and [`curve_to`][] extend the current
+/// subpath and [`close`][] closes it with a straight line to its start point.  All positions are
+/// relative to the upper left corner of the area.  Once the path is complete, it can be drawn with
+/// [`stroke`][], [`fill`][] or [`fill_and_stroke`][].  The same path can be drawn multiple times.
+///
+/// Every subpath is filled separately, so a subpath cannot cut a hole into another subpath.
+///
+/// # Example
+///
+/// ```
+/// use genpdf::{render, style};
+/// let renderer = render::Renderer::new(genpdf::PaperSize::A4, "Test")?;
+/// let area = renderer.first_page().first_layer().area();
+/// area.path()
+///     .move_to((10, 20))
+///     .curve_to((10, 10), (30, 10), (30, 20))
+///     .line_to((20, 30))
+///     .close()
+///     .fill_and_stroke(style::Color::Rgb(255, 220, 200), style::LineStyle::new());
+/// # Ok::<(), genpdf::error::Error>(())
+/// ```
+///
+/// [`Area::path`]: struct.Area.html#method.path
+/// [`move_to`]: #method.move_to
+/// [`line_to`]: #method.line_to
+/// [`curve_to`]: #method.curve_to
+/// [`close`]: #method.close
+/// [`stroke`]: #method.stroke
+/// [`fill`]: #method.fill
+/// [`fill_and_stroke`]: #method.fill_and_stroke
+pub struct Path<'p> {
+    area: Area<'p>,
+    subpaths: Vec<Subpath>,
+}
+
+/// A subpath of a [`Path`](struct.Path.html), using the point format of `printpdf::Line`.
+struct Subpath {
+    points: Vec<(Position, bool)>,
+    is_closed: bool,
+}
+
+impl<'p> Path<'p> {
+    fn new(area: Area<'p>) -> Path<'p> {
+        Path {
+            area,
+            subpaths: Vec::new(),
+        }
+    }
+
+    /// Starts a new subpath at the given position.
+    pub fn move_to(&mut self, position: impl Into<Position>) -> &mut Self {
+        self.subpaths.push(Subpath {
+            points: vec![(position.into(), false)],
+            is_closed: false,
+        });
+        self
+    }
+
+    /// Adds a straight line from the current point to the given position.
+    ///
+    /// If there is no current subpath, this is equivalent to [`move_to`][].
+    ///
+    /// [`move_to`]: #method.move_to
+    pub fn line_to(&mut self, position: impl Into<Position>) -> &mut Self {
+        let position = position.into();
+        match self.current_subpath() {
+            Some(subpath) => subpath.points.push((position, false)),
+            None => {
+                self.move_to(position);
+            }
+        }
+        self
+    }
+
+    /// Adds a cubic Bézier curve from the current point to the given end position, using the
+    /// given control points.
+    ///
+    /// If there is no current subpath, the curve starts at the first control point.
+    pub fn curve_to(
+        &mut self,
+        control1: impl Into<Position>,
+        control2: impl Into<Position>,
+        end: impl Into<Position>,
+    ) -> &mut Self {
+        let control1 = control1.into();
+        if self.current_subpath().is_none() {
+            self.move_to(control1);
+        }
+        if let Some(subpath) = self.current_subpath() {
+            // printpdf draws a curve if the start point and the first control point are flagged
+            if let Some(start) = subpath.points.last_mut() {
+                start.1 = true;
+            }
+            subpath.points.push((control1, true));
+            subpath.points.push((control2.into(), false));
+            subpath.points.push((end.into(), false));
+        }
+        self
+    }
+
+    /// Closes the current subpath with a straight line to its start point.
+    ///
+    /// The next segment starts a new subpath at the start point of the closed subpath.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.is_closed = true;
+        }
+        self
+    }
+
+    /// Draws the outline of this path with the given line style.
+    ///
+    /// If the line style is filled, the path is filled with the line color too, see
+    /// [`LineStyle::set_filled`][].
+    ///
+    /// [`LineStyle::set_filled`]: ../style/struct.LineStyle.html#method.set_filled
+    pub fn stroke(&self, line_style: LineStyle) {
+        self.paint(None, Some(line_style));
+    }
+
+    /// Fills this path with the given color or gradient without drawing its outline.
+    pub fn fill(&self, fill: impl Into<Fill>) {
+        self.paint(Some(&fill.into()), None);
+    }
+
+    /// Fills this path with the given color or gradient and draws its outline with the given
+    /// line style.
+    pub fn fill_and_stroke(&self, fill: impl Into<Fill>, line_style: LineStyle) {
+        self.paint(Some(&fill.into()), Some(line_style));
+    }
+
+    /// Returns the subpath that is extended by the next segment, starting a new subpath at the
+    /// start point of the last subpath if it has been closed.
+    fn current_subpath(&mut self) -> Option<&mut Subpath> {
+        let start = match self.subpaths.last() {
+            Some(subpath) if subpath.is_closed => subpath.points[0].0,
+            Some(_) => return self.subpaths.last_mut(),
+            None => return None,
+        };
+        self.move_to(start);
+        self.subpaths.last_mut()
+    }
+
+    fn paint(&self, fill: Option<&Fill>, line_style: Option<LineStyle>) {
+        for subpath in &self.subpaths {
+            let points = subpath
+                .points
+                .iter()
+                .map(|&(pos, is_bezier)| (self.area.position(pos), is_bezier))
+                .collect();
+            self.area
+                .paint_path(points, subpath.is_closed, fill, line_style);
+        }
+    }
+}
+
 /// A text section that is drawn on an area of a PDF layer.
 pub struct TextSection<'f, 'p> {
     font_cache: &'f fonts::FontCache,
@@ -692,6 +2105,10 @@ pub struct TextSection<'f, 'p> {
     is_first: bool,
     metrics: fonts::Metrics,
     font: Option<(printpdf::IndirectFontRef, u8)>,
+    state: Option<LayerState>,
+    // The horizontal offsets of the start of the current line and of the text cursor
+    line_start: Mm,
+    cursor: Mm,
 }
 
 impl<'f, 'p> TextSection<'f, 'p> {
@@ -704,7 +2121,7 @@ impl<'f, 'p> TextSection<'f, 'p> {
             return None;
         }
 
-        area.layer.begin_text_section();
+        let state = area.layer.begin_text_section();
         area.layer.set_line_height(metrics.line_height);
 
         Some(TextSection {
@@ -713,16 +2130,42 @@ impl<'f, 'p> TextSection<'f, 'p> {
             is_first: true,
             metrics,
             font: None,
+            state,
+            line_start: Mm(0.0),
+            cursor: Mm(0.0),
         })
     }
 
-    fn set_text_cursor(&self, x_offset: Mm) {
+    /// Returns the horizontal offset of the text cursor from the origin of this text section, i. e.
+    /// the end of the text that has been printed on the current line.
+    pub fn cursor(&self) -> Mm {
+        self.cursor
+    }
+
+    fn set_text_cursor(&mut self, x_offset: Mm) {
+        self.line_start = x_offset;
+        self.cursor = x_offset;
         let cursor = self
             .area
             .position(Position::new(x_offset, self.metrics.ascent));
         self.area.layer.set_text_cursor(cursor);
     }
 
+    /// Returns the baseline shift for the inline alignment of the given style relative to the
+    /// metrics of this text section.
+    fn alignment_shift(&self, style: Style) -> Mm {
+        let line = self.metrics;
+        let metrics = style.font(self.font_cache).metrics(style.font_size());
+        let descent = |metrics: fonts::Metrics| metrics.glyph_height - metrics.ascent;
+        let center = |metrics: fonts::Metrics| metrics.ascent - metrics.glyph_height / 2.0;
+        match style.inline_alignment() {
+            InlineAlignment::Top => line.ascent - metrics.ascent,
+            InlineAlignment::Middle => center(line) - center(metrics),
+            InlineAlignment::Bottom => descent(metrics) - descent(line),
+            InlineAlignment::Baseline | InlineAlignment::Rise(_) => Mm(0.0),
+        }
+    }
+
     fn set_font(&mut self, font: &printpdf::IndirectFontRef, font_size: u8) {
         let font_is_set = self
             .font
@@ -750,6 +2193,7 @@ impl<'f, 'p> TextSection<'f, 'p> {
         } else {
             self.area.layer.add_line_break();
             self.area.add_offset((0, self.metrics.line_height));
+            self.cursor = self.line_start;
             true
         }
     }
@@ -781,7 +2225,7 @@ impl<'f, 'p> TextSection<'f, 'p> {
         if self.is_first {
             if trim_first_word {
                 // If the first word is literally just space, ignore it to preserve alignment
-                s = s.trim_start();
+                s = wrap::trim_start(s);
                 if s.is_empty() {
                     return Ok(());
                 }
@@ -798,7 +2242,45 @@ impl<'f, 'p> TextSection<'f, 'p> {
         }
         self.is_first = false;
 
-        let mut positions = font.kerning(self.font_cache, s.chars());
+        // Soft hyphens are only rendered if the line is broken at them, see the wrap module
+        let s = wrap::remove_soft_hyphens(s);
+        let s = s.as_ref();
+
+        // Small capitals are stored with the original text so that text extraction returns the
+        // true case.  If the font does not support them, they are emulated with uppercase letters
+        // in a smaller font size.
+        if style.is_small_caps() {
+            self.area.layer.begin_actual_text(s);
+            let result = if font.has_small_caps(self.font_cache) {
+                self.print_glyphs(s, style, extra_word_spacing)
+            } else {
+                let mut extra_word_spacing = Mm::from(extra_word_spacing);
+                style
+                    .small_caps_runs(s)
+                    .into_iter()
+                    .try_for_each(|(run, style)| {
+                        let result =
+                            self.print_str_xoff_trim(run, style, extra_word_spacing, false);
+                        extra_word_spacing = Mm(0.0);
+                        result
+                    })
+            };
+            self.area.layer.end_marked_content();
+            result
+        } else {
+            self.print_glyphs(s, style, extra_word_spacing)
+        }
+    }
+
+    fn print_glyphs(&mut self, s: &str, style: Style, extra_word_spacing: Pt) -> Result<(), Error> {
+        let font = style.font(self.font_cache);
+        let glyph_ids = if style.is_small_caps() {
+            font.small_caps_glyph_ids(self.font_cache, s.chars())
+        } else {
+            font.glyph_ids(self.font_cache, s.chars())
+        };
+
+        let mut positions = font.glyph_kerning(self.font_cache, &glyph_ids);
         if let Some(first_char_pos) = positions.get_mut(0) {
             *first_char_pos += extra_word_spacing.0 as f32;
         }
@@ -813,19 +2295,39 @@ impl<'f, 'p> TextSection<'f, 'p> {
             // Built-in fonts always use the Windows-1252 encoding
             encode_win1252(s)?
         } else {
-            font.glyph_ids(&self.font_cache, s.chars())
+            glyph_ids
         };
 
-        let font = self
-            .font_cache
-            .get_pdf_font(font)
-            .expect("Could not find PDF font in font cache");
+        let font = self.font_cache.get_pdf_font(font).ok_or_else(|| {
+            Error::new(
+                "Could not find PDF font in font cache",
+                ErrorKind::InvalidFont,
+            )
+        })?;
         self.area.layer.set_fill_color(style.color());
+        self.area
+            .layer
+            .set_character_spacing(style.letter_spacing());
+        self.area
+            .layer
+            .set_text_rise(style.baseline_shift() + self.alignment_shift(style));
+        self.area.layer.set_opacity(style.opacity());
+        let mode = style.text_rendering_mode();
+        self.area.layer.set_text_rendering_mode(mode);
+        if mode.is_stroked() {
+            let color = style.stroke_color().or_else(|| style.color());
+            self.area
+                .layer
+                .set_outline_color(color.unwrap_or(Color::Rgb(0, 0, 0)));
+            self.area.layer.set_outline_thickness(style.stroke_width());
+        }
         self.set_font(font, style.font_size());
 
         self.area
             .layer
             .write_positioned_codepoints(positions, codepoints);
+        self.cursor += style.str_width(self.font_cache, s)
+            + Mm::from(Pt(extra_word_spacing.0 * f64::from(style.font_size())));
         Ok(())
     }
 
@@ -844,17 +2346,27 @@ impl<'f, 'p> TextSection<'f, 'p> {
         positions: P,
         codepoints: C,
         font_size: f64,
-    ) where
+    ) -> Result<(), Error>
+    where
         P: IntoIterator<Item = f64>,
         C: IntoIterator<Item = u16>,
     {
         let font = style.font(self.font_cache);
 
-        let font = self
-            .font_cache
-            .get_pdf_font(font)
-            .expect("Could not find PDF font in font cache");
+        let font = self.font_cache.get_pdf_font(font).ok_or_else(|| {
+            Error::new(
+                "Could not find PDF font in font cache",
+                ErrorKind::InvalidFont,
+            )
+        })?;
         self.area.layer.set_fill_color(style.color());
+        // The positions already contain the spacing between the glyphs.
+        self.area.layer.set_character_spacing(Mm(0.0));
+        self.area.layer.set_text_rise(Mm(0.0));
+        self.area
+            .layer
+            .set_text_rendering_mode(TextRenderingMode::Fill);
+        self.area.layer.set_opacity(style.opacity());
         self.set_font_f64(font, font_size);
         self.set_text_cursor(Mm(0.0));
 
@@ -863,12 +2375,13 @@ impl<'f, 'p> TextSection<'f, 'p> {
         self.area
             .layer
             .write_positioned_codepoints(positions, codepoints);
+        Ok(())
     }
 }
 
 impl<'f, 'p> Drop for TextSection<'f, 'p> {
     fn drop(&mut self) {
-        self.area.layer.end_text_section();
+        self.area.layer.end_text_section(self.state.take());
     }
 }
 
@@ -8,7 +8,8 @@
 //! a [`Cow<'_, str>`][] with a [`Style`][] annotation.
 //!
 //! A [`Style`][] is a combination of a [`FontFamily`][], a font size, a line spacing factor, a
-//! [`Color`][] and a combination of [`Effect`][]s (bold or italic).
+//! letter spacing, a [`Color`][] and a combination of [`Effect`][]s (bold, italic, underlined,
+//! superscript, subscript or small caps).
 //!
 //! # Example
 //!
@@ -32,8 +33,20 @@
 
 use std::borrow;
 use std::iter;
+use std::str;
 
+use crate::error::{Error, ErrorKind};
 use crate::fonts;
+use crate::wrap;
+
+/// The factor for the font size of superscript and subscript text.
+const SCRIPT_FONT_SCALE: f64 = 0.7;
+/// The baseline shift of superscript text relative to the base font size.
+const SUPERSCRIPT_SHIFT: f64 = 0.33;
+/// The baseline shift of subscript text relative to the base font size.
+const SUBSCRIPT_SHIFT: f64 = 0.15;
+/// The factor for the font size of lowercase letters in emulated small capitals.
+const SMALL_CAPS_FONT_SCALE: f64 = 0.8;
 use crate::Mm;
 
 /// A color, represented by RGB, CMYK or Greyscale values.
@@ -47,7 +60,20 @@ use crate::Mm;
 /// let cyan = genpdf::style::Color::Cmyk(255, 0, 0, 0);
 /// let grey = genpdf::style::Color::Greyscale(127);
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///
+/// Colors can also be created from hex strings, HSL values, fractional CMYK or greyscale values
+/// or web color names:
+///
+/// ```
+/// use genpdf::style::Color;
+/// assert_eq!(Color::from_hex("#1a73e8").unwrap(), Color::Rgb(0x1a, 0x73, 0xe8));
+/// assert_eq!(Color::from_hsl(120.0, 1.0, 0.25), Color::GREEN);
+/// assert_eq!(Color::from_cmyk(1.0, 0.5, 0.0, 0.2), Color::Cmyk(255, 128, 0, 51));
+/// assert_eq!(Color::from_greyscale(0.5), Color::Greyscale(128));
+/// assert_eq!("navy".parse::<Color>().unwrap(), Color::NAVY);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// An RGB color with red, green and blue values between 0 and 255.
     Rgb(u8, u8, u8),
@@ -57,6 +83,167 @@ pub enum Color {
     Greyscale(u8),
 }
 
+impl Color {
+    /// The web color black (`#000000`).
+    pub const BLACK: Color = Color::Rgb(0, 0, 0);
+    /// The web color silver (`#c0c0c0`).
+    pub const SILVER: Color = Color::Rgb(192, 192, 192);
+    /// The web color gray (`#808080`).
+    pub const GRAY: Color = Color::Rgb(128, 128, 128);
+    /// The web color white (`#ffffff`).
+    pub const WHITE: Color = Color::Rgb(255, 255, 255);
+    /// The web color maroon (`#800000`).
+    pub const MAROON: Color = Color::Rgb(128, 0, 0);
+    /// The web color red (`#ff0000`).
+    pub const RED: Color = Color::Rgb(255, 0, 0);
+    /// The web color purple (`#800080`).
+    pub const PURPLE: Color = Color::Rgb(128, 0, 128);
+    /// The web color fuchsia (`#ff00ff`).
+    pub const FUCHSIA: Color = Color::Rgb(255, 0, 255);
+    /// The web color green (`#008000`).
+    pub const GREEN: Color = Color::Rgb(0, 128, 0);
+    /// The web color lime (`#00ff00`).
+    pub const LIME: Color = Color::Rgb(0, 255, 0);
+    /// The web color olive (`#808000`).
+    pub const OLIVE: Color = Color::Rgb(128, 128, 0);
+    /// The web color yellow (`#ffff00`).
+    pub const YELLOW: Color = Color::Rgb(255, 255, 0);
+    /// The web color navy (`#000080`).
+    pub const NAVY: Color = Color::Rgb(0, 0, 128);
+    /// The web color blue (`#0000ff`).
+    pub const BLUE: Color = Color::Rgb(0, 0, 255);
+    /// The web color teal (`#008080`).
+    pub const TEAL: Color = Color::Rgb(0, 128, 128);
+    /// The web color aqua (`#00ffff`).
+    pub const AQUA: Color = Color::Rgb(0, 255, 255);
+    /// The web color orange (`#ffa500`).
+    pub const ORANGE: Color = Color::Rgb(255, 165, 0);
+
+    const NAMED_COLORS: [(&'static str, Color); 19] = [
+        ("black", Color::BLACK),
+        ("silver", Color::SILVER),
+        ("gray", Color::GRAY),
+        ("grey", Color::GRAY),
+        ("white", Color::WHITE),
+        ("maroon", Color::MAROON),
+        ("red", Color::RED),
+        ("purple", Color::PURPLE),
+        ("fuchsia", Color::FUCHSIA),
+        ("green", Color::GREEN),
+        ("lime", Color::LIME),
+        ("olive", Color::OLIVE),
+        ("yellow", Color::YELLOW),
+        ("navy", Color::NAVY),
+        ("blue", Color::BLUE),
+        ("teal", Color::TEAL),
+        ("aqua", Color::AQUA),
+        ("cyan", Color::AQUA),
+        ("orange", Color::ORANGE),
+    ];
+
+    /// Parses an RGB color from a hex string like `#1a73e8` or `#fff`.
+    ///
+    /// The leading `#` is optional.  Both the six-digit and the three-digit notation are
+    /// supported.
+    pub fn from_hex(s: &str) -> Result<Color, Error> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        let digits: Option<Vec<u8>> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect();
+        match digits.as_deref() {
+            Some(&[r, g, b]) => Ok(Color::Rgb(r * 17, g * 17, b * 17)),
+            Some(&[r1, r2, g1, g2, b1, b2]) => {
+                Ok(Color::Rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2))
+            }
+            _ => Err(Error::new(
+                format!("Invalid hex color: {}", s),
+                ErrorKind::InvalidData,
+            )),
+        }
+    }
+
+    /// Returns the web color with the given name, or `None` if the name is unknown.
+    ///
+    /// The sixteen basic web colors as well as `grey`, `cyan` and `orange` are supported.  The
+    /// name is matched case-insensitively.
+    pub fn from_name(name: &str) -> Option<Color> {
+        Color::NAMED_COLORS
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    /// Creates an RGB color from the given hue, saturation and lightness.
+    ///
+    /// The hue is measured in degrees, and the saturation and the lightness range from 0 to 1.
+    /// Values outside of these ranges are wrapped or clamped.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Color {
+        let hue = hue.rem_euclid(360.0);
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue {
+            h if h < 60.0 => (chroma, x, 0.0),
+            h if h < 120.0 => (x, chroma, 0.0),
+            h if h < 180.0 => (0.0, chroma, x),
+            h if h < 240.0 => (0.0, x, chroma),
+            h if h < 300.0 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        let to_u8 = |value: f64| ((value + m) * 255.0).round() as u8;
+        Color::Rgb(to_u8(r), to_u8(g), to_u8(b))
+    }
+
+    /// Creates a CMYK color from the given cyan, magenta, yellow and key values.
+    ///
+    /// The values range from 0 to 1, so a brand color specified as `C100 M50 Y0 K20` can be
+    /// created with `Color::from_cmyk(1.0, 0.5, 0.0, 0.2)`.  Values outside of this range are
+    /// clamped.  The resulting color is written to the PDF document in the CMYK color space.
+    pub fn from_cmyk(cyan: f64, magenta: f64, yellow: f64, key: f64) -> Color {
+        Color::Cmyk(
+            fraction_to_u8(cyan),
+            fraction_to_u8(magenta),
+            fraction_to_u8(yellow),
+            fraction_to_u8(key),
+        )
+    }
+
+    /// Creates a greyscale color from the given value between 0 (black) and 1 (white).
+    ///
+    /// Values outside of this range are clamped.
+    pub fn from_greyscale(value: f64) -> Color {
+        Color::Greyscale(fraction_to_u8(value))
+    }
+}
+
+fn fraction_to_u8(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl str::FromStr for Color {
+    type Err = Error;
+
+    /// Parses a hex color (see [`Color::from_hex`][]) or a web color name (see
+    /// [`Color::from_name`][]).
+    ///
+    /// [`Color::from_hex`]: #method.from_hex
+    /// [`Color::from_name`]: #method.from_name
+    fn from_str(s: &str) -> Result<Color, Error> {
+        if s.starts_with('#') {
+            Color::from_hex(s)
+        } else if let Some(color) = Color::from_name(s) {
+            Ok(color)
+        } else {
+            Color::from_hex(s)
+                .map_err(|_| Error::new(format!("Invalid color: {}", s), ErrorKind::InvalidData))
+        }
+    }
+}
+
 impl From<Color> for printpdf::Color {
     fn from(color: Color) -> printpdf::Color {
         match color {
@@ -80,13 +267,244 @@ impl From<Color> for printpdf::Color {
     }
 }
 
-/// A text effect (bold or italic).
+/// The fill of a shape or of the background of an element:  a solid color or a gradient.
+///
+/// Gradients are defined by color stops, i. e. pairs of an offset between 0.0 and 1.0 and a
+/// color.  The stops must be sorted by their offset.  Before the first and after the last stop,
+/// the color of the stop is extended.  The gradient is stretched over the bounding box of the
+/// filled shape.  If the stops use different color spaces, the colors are converted to RGB.  A
+/// gradient with a single stop fills the shape with the color of the stop, and a gradient without
+/// stops is not drawn.
+///
+/// # Examples
+///
+/// ```
+/// use genpdf::style::{Color, Fill};
+/// let solid = Fill::from(Color::Rgb(0, 0, 128));
+/// let band = Fill::LinearGradient {
+///     stops: vec![(0.0, Color::NAVY), (0.7, Color::BLUE), (1.0, Color::AQUA)],
+///     angle: 0.0,
+/// };
+/// let spot = Fill::radial_gradient(Color::WHITE, Color::Greyscale(200));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fill {
+    /// A solid color.
+    Solid(Color),
+    /// A linear (axial) gradient.
+    LinearGradient {
+        /// The color stops of the gradient.
+        stops: Vec<(f64, Color)>,
+        /// The direction of the gradient in degrees, measured clockwise from the left-to-right
+        /// direction, so that 90.0 is a top-to-bottom gradient.
+        ///
+        /// The gradient starts and ends at the corners of the bounding box, so that the first
+        /// and the last stop are always visible.
+        angle: f64,
+    },
+    /// A radial gradient from the center of a circle to its edge.
+    RadialGradient {
+        /// The color stops of the gradient.
+        stops: Vec<(f64, Color)>,
+        /// The center of the circle, relative to the width and the height of the bounding box,
+        /// so that `(0.5, 0.5)` is the center of the bounding box.
+        center: (f64, f64),
+        /// The radius of the circle, relative to half of the diagonal of the bounding box, so
+        /// that 1.0 reaches the corners of the bounding box if the circle is centered.
+        radius: f64,
+    },
+}
+
+impl Fill {
+    /// Creates a linear gradient from the first to the second color in the given direction, see
+    /// [`Fill::LinearGradient`][].
+    ///
+    /// [`Fill::LinearGradient`]: #variant.LinearGradient
+    pub fn linear_gradient(from: Color, to: Color, angle: f64) -> Fill {
+        Fill::LinearGradient {
+            stops: vec![(0.0, from), (1.0, to)],
+            angle,
+        }
+    }
+
+    /// Creates a centered radial gradient from the inner to the outer color that reaches the
+    /// corners of the bounding box, see [`Fill::RadialGradient`][].
+    ///
+    /// [`Fill::RadialGradient`]: #variant.RadialGradient
+    pub fn radial_gradient(inner: Color, outer: Color) -> Fill {
+        Fill::RadialGradient {
+            stops: vec![(0.0, inner), (1.0, outer)],
+            center: (0.5, 0.5),
+            radius: 1.0,
+        }
+    }
+}
+
+impl From<Color> for Fill {
+    fn from(color: Color) -> Fill {
+        Fill::Solid(color)
+    }
+}
+
+/// A text effect (bold, italic, underlined, superscript, subscript or small caps).
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Effect {
     /// Bold text.
     Bold,
     /// Italic text.
     Italic,
+    /// Underlined text, see [`Style::set_underline`][].
+    ///
+    /// [`Style::set_underline`]: struct.Style.html#method.set_underline
+    Underline,
+    /// Superscript text, see [`Style::set_superscript`][].
+    ///
+    /// [`Style::set_superscript`]: struct.Style.html#method.set_superscript
+    Superscript,
+    /// Subscript text, see [`Style::set_subscript`][].
+    ///
+    /// [`Style::set_subscript`]: struct.Style.html#method.set_subscript
+    Subscript,
+    /// Small capitals, see [`Style::set_small_caps`][].
+    ///
+    /// [`Style::set_small_caps`]: struct.Style.html#method.set_small_caps
+    SmallCaps,
+}
+
+/// The additional spacing between the characters of a string, see
+/// [`Style::set_letter_spacing`][].
+///
+/// [`Style::set_letter_spacing`]: struct.Style.html#method.set_letter_spacing
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LetterSpacing {
+    /// An absolute spacing.
+    Mm(Mm),
+    /// A spacing relative to the font size, with 1.0 meaning the font size.
+    Em(f64),
+}
+
+impl LetterSpacing {
+    /// Returns the absolute spacing for the given font size in points.
+    pub fn to_mm(&self, font_size: u8) -> Mm {
+        match *self {
+            LetterSpacing::Mm(spacing) => spacing,
+            LetterSpacing::Em(spacing) => Mm::from(printpdf::Pt(spacing * f64::from(font_size))),
+        }
+    }
+}
+
+impl From<Mm> for LetterSpacing {
+    fn from(spacing: Mm) -> LetterSpacing {
+        LetterSpacing::Mm(spacing)
+    }
+}
+
+/// The height of a line of text, see [`Style::set_line_height`][].
+///
+/// [`Style::set_line_height`]: struct.Style.html#method.set_line_height
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineHeight {
+    /// A factor for the line height of the font, with 1.0 meaning single line spacing.
+    Factor(f64),
+    /// An absolute line height.
+    Mm(Mm),
+}
+
+impl From<f64> for LineHeight {
+    fn from(factor: f64) -> LineHeight {
+        LineHeight::Factor(factor)
+    }
+}
+
+impl From<Mm> for LineHeight {
+    fn from(line_height: Mm) -> LineHeight {
+        LineHeight::Mm(line_height)
+    }
+}
+
+/// The vertical alignment of a string within its line, see [`Style::set_inline_alignment`][].
+///
+/// This is useful for lines that mix strings with different font sizes or fonts, for example
+/// icons from an icon font.
+///
+/// [`Style::set_inline_alignment`]: struct.Style.html#method.set_inline_alignment
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InlineAlignment {
+    /// The string is printed on the baseline of the line.
+    Baseline,
+    /// The top of the glyphs of the string is aligned with the top of the glyphs of the line.
+    Top,
+    /// The center of the glyphs of the string is aligned with the center of the glyphs of the
+    /// line.
+    Middle,
+    /// The bottom of the glyphs of the string is aligned with the bottom of the glyphs of the
+    /// line.
+    Bottom,
+    /// The string is raised by the given distance above the baseline, or lowered for negative
+    /// values.
+    Rise(Mm),
+}
+
+impl Default for InlineAlignment {
+    fn default() -> InlineAlignment {
+        InlineAlignment::Baseline
+    }
+}
+
+impl From<Mm> for InlineAlignment {
+    fn from(rise: Mm) -> InlineAlignment {
+        InlineAlignment::Rise(rise)
+    }
+}
+
+/// The way the glyphs of a text are painted, see [`Style::set_text_rendering_mode`][].
+///
+/// [`Style::set_text_rendering_mode`]: struct.Style.html#method.set_text_rendering_mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextRenderingMode {
+    /// The glyphs are filled with the text color.
+    Fill,
+    /// The outlines of the glyphs are stroked with the stroke color.
+    Stroke,
+    /// The glyphs are filled with the text color and their outlines are stroked with the stroke
+    /// color.
+    FillStroke,
+    /// The glyphs are neither filled nor stroked, but the text can still be selected and
+    /// extracted.
+    Invisible,
+}
+
+impl Default for TextRenderingMode {
+    fn default() -> TextRenderingMode {
+        TextRenderingMode::Fill
+    }
+}
+
+impl TextRenderingMode {
+    /// Returns whether the outlines of the glyphs are stroked in this mode.
+    pub fn is_stroked(&self) -> bool {
+        matches!(
+            self,
+            TextRenderingMode::Stroke | TextRenderingMode::FillStroke
+        )
+    }
+}
+
+impl From<TextRenderingMode> for printpdf::TextRenderingMode {
+    fn from(mode: TextRenderingMode) -> printpdf::TextRenderingMode {
+        match mode {
+            TextRenderingMode::Fill => printpdf::TextRenderingMode::Fill,
+            TextRenderingMode::Stroke => printpdf::TextRenderingMode::Stroke,
+            TextRenderingMode::FillStroke => printpdf::TextRenderingMode::FillStroke,
+            TextRenderingMode::Invisible => printpdf::TextRenderingMode::Invisible,
+        }
+    }
 }
 
 /// A style annotation for a string.
@@ -95,25 +513,55 @@ pub enum Effect {
 /// - a font family, see [`FontFamily`][] (defaults to the [`FontCache`][] default)
 /// - a font size in points (defaults to 12)
 /// - a line spacing factor, with 1 meaning single line spacing (defaults to 1)
+/// - a line height that overrides the line spacing factor, see [`LineHeight`][] (defaults to
+///   none)
+/// - a letter spacing, see [`LetterSpacing`][] (defaults to no additional spacing)
 /// - an outline color, see [`Color`][] (defaults to black)
 /// - a combination of text effects, see [`Effect`][] (defaults to none)
+/// - a text rendering mode with a stroke color and width, see [`TextRenderingMode`][] (defaults
+///   to filled text)
+/// - an opacity between 0 (transparent) and 1 (opaque) (defaults to 1)
+/// - a vertical alignment within the line, see [`InlineAlignment`][] (defaults to the baseline)
 ///
 /// All properties are optional.  If they are not set, they can be inferred from parent styles or
 /// from the defaults.
 ///
+/// If the `serde` feature is enabled, styles can be serialized and deserialized.  As fonts are
+/// only available during the rendering process, the font family is not serialized.
+///
 /// [`Color`]: enum.Color.html
 /// [`Effect`]: enum.Effect.html
+/// [`InlineAlignment`]: enum.InlineAlignment.html
+/// [`LetterSpacing`]: enum.LetterSpacing.html
+/// [`LineHeight`]: enum.LineHeight.html
+/// [`TextRenderingMode`]: enum.TextRenderingMode.html
 /// [`FontFamily`]: ../fonts/struct.FontFamily.html
 /// [`FontCache`]: ../fonts/struct.FontCache.html
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
+    #[cfg_attr(feature = "serde", serde(skip))]
     font_family: Option<fonts::FontFamily<fonts::Font>>,
     font_size: Option<u8>,
     line_spacing: Option<f64>,
+    line_height: Option<LineHeight>,
+    letter_spacing: Option<LetterSpacing>,
     color: Option<Color>,
     is_bold: bool,
     is_italic: bool,
     is_strikethrough: bool,
+    is_underline: bool,
+    underline_thickness: Option<Mm>,
+    underline_offset: Option<Mm>,
+    underline_color: Option<Color>,
+    is_superscript: bool,
+    is_subscript: bool,
+    is_small_caps: bool,
+    text_rendering_mode: Option<TextRenderingMode>,
+    stroke_color: Option<Color>,
+    stroke_width: Option<Mm>,
+    opacity: Option<f64>,
+    inline_alignment: Option<InlineAlignment>,
 }
 
 impl Style {
@@ -131,6 +579,12 @@ impl Style {
         if let Some(font_size) = style.font_size {
             self.font_size = Some(font_size);
         }
+        if let Some(line_height) = style.line_height {
+            self.line_height = Some(line_height);
+        }
+        if let Some(letter_spacing) = style.letter_spacing {
+            self.letter_spacing = Some(letter_spacing);
+        }
         if let Some(color) = style.color {
             self.color = Some(color);
         }
@@ -143,6 +597,42 @@ impl Style {
         if style.is_strikethrough {
             self.is_strikethrough = true;
         }
+        if style.is_underline {
+            self.is_underline = true;
+        }
+        if let Some(thickness) = style.underline_thickness {
+            self.underline_thickness = Some(thickness);
+        }
+        if let Some(offset) = style.underline_offset {
+            self.underline_offset = Some(offset);
+        }
+        if let Some(color) = style.underline_color {
+            self.underline_color = Some(color);
+        }
+        if style.is_superscript {
+            self.set_superscript();
+        }
+        if style.is_subscript {
+            self.set_subscript();
+        }
+        if style.is_small_caps {
+            self.is_small_caps = true;
+        }
+        if let Some(mode) = style.text_rendering_mode {
+            self.text_rendering_mode = Some(mode);
+        }
+        if let Some(color) = style.stroke_color {
+            self.stroke_color = Some(color);
+        }
+        if let Some(width) = style.stroke_width {
+            self.stroke_width = Some(width);
+        }
+        if let Some(opacity) = style.opacity {
+            self.opacity = Some(opacity);
+        }
+        if let Some(alignment) = style.inline_alignment {
+            self.inline_alignment = Some(alignment);
+        }
     }
 
     /// Combines this style and the given style and returns the result.
@@ -172,15 +662,61 @@ impl Style {
     }
 
     /// Returns the font size for this style in points, or 12 if no font size is set.
+    ///
+    /// For superscript and subscript text, this is the reduced font size that is used to print
+    /// the text, see [`base_font_size`][].
+    ///
+    /// [`base_font_size`]: #method.base_font_size
     pub fn font_size(&self) -> u8 {
+        let font_size = self.base_font_size();
+        if self.is_superscript || self.is_subscript {
+            (f64::from(font_size) * SCRIPT_FONT_SCALE).round().max(1.0) as u8
+        } else {
+            font_size
+        }
+    }
+
+    /// Returns the font size for this style in points without the reduction for superscript and
+    /// subscript text, or 12 if no font size is set.
+    pub fn base_font_size(&self) -> u8 {
         self.font_size.unwrap_or(12)
     }
 
+    /// Returns the vertical offset of the baseline for this style, with positive values meaning
+    /// upwards, or zero if neither superscript nor subscript nor a rise is set.
+    ///
+    /// The offset includes the rise set with [`InlineAlignment::Rise`][], but not the offsets
+    /// for the other inline alignments as they depend on the line.
+    ///
+    /// [`InlineAlignment::Rise`]: enum.InlineAlignment.html#variant.Rise
+    pub fn baseline_shift(&self) -> Mm {
+        let rise = match self.inline_alignment {
+            Some(InlineAlignment::Rise(rise)) => rise,
+            _ => Mm(0.0),
+        };
+        let factor = if self.is_superscript {
+            SUPERSCRIPT_SHIFT
+        } else if self.is_subscript {
+            -SUBSCRIPT_SHIFT
+        } else {
+            return rise;
+        };
+        rise + Mm::from(printpdf::Pt(factor * f64::from(self.base_font_size())))
+    }
+
     /// Returns the line spacing factor for this style, or 1 if no line spacing factor is set.
     pub fn line_spacing(&self) -> f64 {
         self.line_spacing.unwrap_or(1.0)
     }
 
+    /// Returns the additional spacing between characters for this style, or zero if no letter
+    /// spacing is set.
+    pub fn letter_spacing(&self) -> Mm {
+        self.letter_spacing
+            .map(|spacing| spacing.to_mm(self.font_size()))
+            .unwrap_or_default()
+    }
+
     /// Sets the bold effect for this style.
     pub fn set_bold(&mut self) {
         self.is_bold = true;
@@ -225,6 +761,50 @@ impl Style {
         self
     }
 
+    /// Sets the line height for this style, overriding the line spacing factor.
+    ///
+    /// In contrast to the line spacing factor, the line height is merged into the parent style,
+    /// so it can be used to change the leading of single elements, for example a heading or the
+    /// cells of a table.  The line height can either be a factor for the line height of the font
+    /// or an absolute length.  If a line contains strings with different line heights, the
+    /// maximum is used:
+    ///
+    /// ```
+    /// use genpdf::{style, Mm};
+    /// let tight = style::Style::new().with_line_height(0.9);
+    /// let fixed = style::Style::new().with_line_height(Mm(8.0));
+    /// ```
+    pub fn set_line_height(&mut self, line_height: impl Into<LineHeight>) {
+        self.line_height = Some(line_height.into());
+    }
+
+    /// Sets the line height for this style, overriding the line spacing factor, and returns it.
+    pub fn with_line_height(mut self, line_height: impl Into<LineHeight>) -> Style {
+        self.set_line_height(line_height);
+        self
+    }
+
+    /// Sets the additional spacing between characters for this style.
+    ///
+    /// The spacing is inserted after every character, including spaces, and is considered when
+    /// calculating the width of a string, so wrapped text accounts for it.  It can either be an
+    /// absolute length or relative to the font size:
+    ///
+    /// ```
+    /// use genpdf::{style, Mm};
+    /// let heading = style::Style::new().with_letter_spacing(Mm(0.5));
+    /// let small_caps = style::Style::new().with_letter_spacing(style::LetterSpacing::Em(0.1));
+    /// ```
+    pub fn set_letter_spacing(&mut self, letter_spacing: impl Into<LetterSpacing>) {
+        self.letter_spacing = Some(letter_spacing.into());
+    }
+
+    /// Sets the additional spacing between characters for this style and returns it.
+    pub fn with_letter_spacing(mut self, letter_spacing: impl Into<LetterSpacing>) -> Style {
+        self.set_letter_spacing(letter_spacing);
+        self
+    }
+
     /// Sets the font size in points for this style.
     pub fn set_font_size(&mut self, font_size: u8) {
         self.font_size = Some(font_size);
@@ -263,6 +843,313 @@ impl Style {
         self.is_strikethrough
     }
 
+    /// Sets the underline effect.
+    ///
+    /// The appearance of the underline can be configured with [`set_underline_thickness`][],
+    /// [`set_underline_offset`][] and [`set_underline_color`][].  Underlines are drawn by the
+    /// [`Paragraph`][], [`Text`][] and [`CodeBlock`][] elements.
+    ///
+    /// [`set_underline_thickness`]: #method.set_underline_thickness
+    /// [`set_underline_offset`]: #method.set_underline_offset
+    /// [`set_underline_color`]: #method.set_underline_color
+    /// [`Paragraph`]: ../elements/struct.Paragraph.html
+    /// [`Text`]: ../elements/struct.Text.html
+    /// [`CodeBlock`]: ../elements/struct.CodeBlock.html
+    pub fn set_underline(&mut self) {
+        self.is_underline = true;
+    }
+
+    /// Sets the underline effect and returns it.
+    pub fn with_underline(mut self) -> Self {
+        self.is_underline = true;
+        self
+    }
+
+    /// Returns whether the underline effect is set.
+    pub fn is_underline(&self) -> bool {
+        self.is_underline
+    }
+
+    /// Sets the thickness of the underline.
+    pub fn set_underline_thickness(&mut self, thickness: impl Into<Mm>) {
+        self.underline_thickness = Some(thickness.into());
+    }
+
+    /// Sets the thickness of the underline and returns the style.
+    pub fn with_underline_thickness(mut self, thickness: impl Into<Mm>) -> Self {
+        self.set_underline_thickness(thickness);
+        self
+    }
+
+    /// Returns the thickness of the underline, or 0.3 mm if no thickness is set.
+    pub fn underline_thickness(&self) -> Mm {
+        self.underline_thickness.unwrap_or(Mm(0.3))
+    }
+
+    /// Sets the distance between the baseline and the underline.
+    pub fn set_underline_offset(&mut self, offset: impl Into<Mm>) {
+        self.underline_offset = Some(offset.into());
+    }
+
+    /// Sets the distance between the baseline and the underline and returns the style.
+    pub fn with_underline_offset(mut self, offset: impl Into<Mm>) -> Self {
+        self.set_underline_offset(offset);
+        self
+    }
+
+    /// Returns the distance between the baseline and the underline, if set.
+    ///
+    /// If no offset is set, the underline is drawn between the baseline and the descent of the
+    /// font.
+    pub fn underline_offset(&self) -> Option<Mm> {
+        self.underline_offset
+    }
+
+    /// Sets the color of the underline.
+    pub fn set_underline_color(&mut self, color: Color) {
+        self.underline_color = Some(color);
+    }
+
+    /// Sets the color of the underline and returns the style.
+    pub fn with_underline_color(mut self, color: Color) -> Self {
+        self.set_underline_color(color);
+        self
+    }
+
+    /// Returns the color of the underline, if set.
+    ///
+    /// If no underline color is set, the underline is drawn in the color of the text.
+    pub fn underline_color(&self) -> Option<Color> {
+        self.underline_color
+    }
+
+    /// Sets the superscript effect and removes the subscript effect.
+    ///
+    /// Superscript text is printed with a smaller font size (see [`font_size`][]) and a raised
+    /// baseline (see [`baseline_shift`][]).  The line metrics returned by [`metrics`][] include
+    /// the shift, so lines containing superscript text are high enough to fit it.
+    ///
+    /// ```
+    /// use genpdf::{elements, style};
+    /// let mut p = elements::Paragraph::new("x");
+    /// p.push_styled("2", style::Effect::Superscript);
+    /// p.push(" + H");
+    /// p.push_styled("2", style::Effect::Subscript);
+    /// p.push("O");
+    /// ```
+    ///
+    /// [`font_size`]: #method.font_size
+    /// [`baseline_shift`]: #method.baseline_shift
+    /// [`metrics`]: #method.metrics
+    pub fn set_superscript(&mut self) {
+        self.is_superscript = true;
+        self.is_subscript = false;
+    }
+
+    /// Sets the superscript effect, removes the subscript effect and returns the style.
+    pub fn with_superscript(mut self) -> Self {
+        self.set_superscript();
+        self
+    }
+
+    /// Returns whether the superscript effect is set.
+    pub fn is_superscript(&self) -> bool {
+        self.is_superscript
+    }
+
+    /// Sets the subscript effect and removes the superscript effect.
+    ///
+    /// Subscript text is printed with a smaller font size (see [`font_size`][]) and a lowered
+    /// baseline (see [`baseline_shift`][]).
+    ///
+    /// [`font_size`]: #method.font_size
+    /// [`baseline_shift`]: #method.baseline_shift
+    pub fn set_subscript(&mut self) {
+        self.is_subscript = true;
+        self.is_superscript = false;
+    }
+
+    /// Sets the subscript effect, removes the superscript effect and returns the style.
+    pub fn with_subscript(mut self) -> Self {
+        self.set_subscript();
+        self
+    }
+
+    /// Returns whether the subscript effect is set.
+    pub fn is_subscript(&self) -> bool {
+        self.is_subscript
+    }
+
+    /// Sets the small caps effect.
+    ///
+    /// If the font supports the `smcp` OpenType feature, lowercase letters are printed using its
+    /// small capitals.  Otherwise, they are emulated by printing the uppercase letters with a
+    /// smaller font size.  In both cases, the original text is stored in the PDF file so that
+    /// text extraction returns the true case.
+    pub fn set_small_caps(&mut self) {
+        self.is_small_caps = true;
+    }
+
+    /// Sets the small caps effect and returns the style.
+    pub fn with_small_caps(mut self) -> Self {
+        self.set_small_caps();
+        self
+    }
+
+    /// Returns whether the small caps effect is set.
+    pub fn is_small_caps(&self) -> bool {
+        self.is_small_caps
+    }
+
+    /// Sets the vertical alignment of strings with this style within their line.
+    ///
+    /// This can be used to nudge icons or text with a different font size so that they sit
+    /// correctly on the line:
+    ///
+    /// ```
+    /// use genpdf::{elements, style, Mm};
+    /// let mut p = elements::Paragraph::new("Status: ");
+    /// p.push_styled("OK", style::Style::new()
+    ///     .with_font_size(8)
+    ///     .with_inline_alignment(style::InlineAlignment::Middle));
+    /// p.push_styled("*", style::Style::new().with_inline_alignment(Mm(0.5)));
+    /// ```
+    pub fn set_inline_alignment(&mut self, alignment: impl Into<InlineAlignment>) {
+        self.inline_alignment = Some(alignment.into());
+    }
+
+    /// Sets the vertical alignment of strings with this style within their line and returns the
+    /// style.
+    pub fn with_inline_alignment(mut self, alignment: impl Into<InlineAlignment>) -> Self {
+        self.set_inline_alignment(alignment);
+        self
+    }
+
+    /// Returns the vertical alignment of strings with this style within their line.
+    pub fn inline_alignment(&self) -> InlineAlignment {
+        self.inline_alignment.unwrap_or_default()
+    }
+
+    /// Sets the text rendering mode, for example to print outlined text.
+    ///
+    /// The outlines of the glyphs are stroked with the color set with [`set_stroke_color`][] and
+    /// the width set with [`set_stroke_width`][]:
+    ///
+    /// ```
+    /// use genpdf::style;
+    /// let outlined = style::Style::new()
+    ///     .with_text_rendering_mode(style::TextRenderingMode::FillStroke)
+    ///     .with_color(style::Color::Rgb(255, 255, 255))
+    ///     .with_stroke_color(style::Color::Rgb(0, 0, 0))
+    ///     .with_stroke_width(0.5);
+    /// ```
+    ///
+    /// [`set_stroke_color`]: #method.set_stroke_color
+    /// [`set_stroke_width`]: #method.set_stroke_width
+    pub fn set_text_rendering_mode(&mut self, mode: TextRenderingMode) {
+        self.text_rendering_mode = Some(mode);
+    }
+
+    /// Sets the text rendering mode and returns the style.
+    pub fn with_text_rendering_mode(mut self, mode: TextRenderingMode) -> Self {
+        self.set_text_rendering_mode(mode);
+        self
+    }
+
+    /// Returns the text rendering mode, or [`TextRenderingMode::Fill`][] if no mode is set.
+    ///
+    /// [`TextRenderingMode::Fill`]: enum.TextRenderingMode.html#variant.Fill
+    pub fn text_rendering_mode(&self) -> TextRenderingMode {
+        self.text_rendering_mode.unwrap_or_default()
+    }
+
+    /// Sets the color for stroking the outlines of the glyphs.
+    pub fn set_stroke_color(&mut self, color: Color) {
+        self.stroke_color = Some(color);
+    }
+
+    /// Sets the color for stroking the outlines of the glyphs and returns the style.
+    pub fn with_stroke_color(mut self, color: Color) -> Self {
+        self.set_stroke_color(color);
+        self
+    }
+
+    /// Returns the color for stroking the outlines of the glyphs, if set.
+    ///
+    /// If no stroke color is set, the outlines are stroked in the color of the text.
+    pub fn stroke_color(&self) -> Option<Color> {
+        self.stroke_color
+    }
+
+    /// Sets the width for stroking the outlines of the glyphs.
+    pub fn set_stroke_width(&mut self, width: impl Into<Mm>) {
+        self.stroke_width = Some(width.into());
+    }
+
+    /// Sets the width for stroking the outlines of the glyphs and returns the style.
+    pub fn with_stroke_width(mut self, width: impl Into<Mm>) -> Self {
+        self.set_stroke_width(width);
+        self
+    }
+
+    /// Returns the width for stroking the outlines of the glyphs, or 0.3 mm if no width is set.
+    pub fn stroke_width(&self) -> Mm {
+        self.stroke_width.unwrap_or(Mm(0.3))
+    }
+
+    /// Sets the opacity of the text, from 0 (transparent) to 1 (opaque).
+    ///
+    /// Values outside of this range are clamped.  This can be used for watermarks:
+    ///
+    /// ```
+    /// use genpdf::style;
+    /// let watermark = style::Style::new().with_font_size(60).with_opacity(0.2);
+    /// ```
+    pub fn set_opacity(&mut self, opacity: f64) {
+        self.opacity = Some(opacity.clamp(0.0, 1.0));
+    }
+
+    /// Sets the opacity of the text and returns the style.
+    pub fn with_opacity(mut self, opacity: f64) -> Self {
+        self.set_opacity(opacity);
+        self
+    }
+
+    /// Returns the opacity of the text, or 1 if no opacity is set.
+    pub fn opacity(&self) -> f64 {
+        self.opacity.unwrap_or(1.0)
+    }
+
+    /// Splits the given string into runs for emulated small capitals.
+    ///
+    /// Lowercase letters are converted to uppercase and use a reduced font size.  The returned
+    /// styles do not have the small caps effect.
+    pub(crate) fn small_caps_runs(&self, s: &str) -> Vec<(String, Style)> {
+        let mut upper = *self;
+        upper.is_small_caps = false;
+        let mut lower = upper;
+        let font_size = f64::from(self.base_font_size()) * SMALL_CAPS_FONT_SCALE;
+        lower.set_font_size(font_size.round().max(1.0) as u8);
+
+        let mut runs: Vec<(String, Style)> = Vec::new();
+        for c in s.chars() {
+            let is_lowercase = c.is_lowercase();
+            let style = if is_lowercase { lower } else { upper };
+            match runs.last_mut() {
+                Some((_, run_style)) if *run_style == style => {}
+                _ => runs.push((String::new(), style)),
+            }
+            if let Some((run, _)) = runs.last_mut() {
+                if is_lowercase {
+                    run.extend(c.to_uppercase());
+                } else {
+                    run.push(c);
+                }
+            }
+        }
+        runs
+    }
+
     /// Calculates the width of the given character with this style using the data in the given
     /// font cache.
     ///
@@ -270,8 +1157,12 @@ impl Style {
     ///
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn char_width(&self, font_cache: &fonts::FontCache, c: char) -> Mm {
+        if self.is_small_caps {
+            return self.str_width(font_cache, c.encode_utf8(&mut [0; 4]));
+        }
         self.font(font_cache)
             .char_width(font_cache, c, self.font_size())
+            + self.letter_spacing()
     }
 
     /// Returns the width of the empty space between the origin of the glyph bounding
@@ -281,8 +1172,16 @@ impl Style {
     ///
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn char_left_side_bearing(&self, font_cache: &fonts::FontCache, c: char) -> Mm {
-        self.font(font_cache)
-            .char_left_side_bearing(font_cache, c, self.font_size())
+        let font = self.font(font_cache);
+        if self.is_small_caps && !font.has_small_caps(font_cache) {
+            let runs = self.small_caps_runs(c.encode_utf8(&mut [0; 4]));
+            if let Some((s, style)) = runs.first() {
+                if let Some(c) = s.chars().next() {
+                    return style.char_left_side_bearing(font_cache, c);
+                }
+            }
+        }
+        font.char_left_side_bearing(font_cache, c, self.font_size())
     }
 
     /// Calculates the width of the given string with this style using the data in the given font
@@ -293,7 +1192,27 @@ impl Style {
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn str_width(&self, font_cache: &fonts::FontCache, s: &str) -> Mm {
         let font = self.font(font_cache);
-        font.str_width(font_cache, s, self.font_size())
+        let width = if self.is_small_caps {
+            let s = wrap::remove_soft_hyphens(s);
+            if !font.has_small_caps(font_cache) {
+                return self
+                    .small_caps_runs(&s)
+                    .iter()
+                    .map(|(s, style)| style.str_width(font_cache, s))
+                    .sum();
+            }
+            let glyph_ids = font.small_caps_glyph_ids(font_cache, s.chars());
+            font.glyphs_width(font_cache, &glyph_ids, self.font_size())
+        } else {
+            font.str_width(font_cache, s, self.font_size())
+        };
+        let letter_spacing = self.letter_spacing();
+        if letter_spacing == Mm(0.0) {
+            width
+        } else {
+            let chars = wrap::remove_soft_hyphens(s).chars().count();
+            width + letter_spacing * chars as f64
+        }
     }
 
     /// Returns the font family for this style or the default font family using the given font
@@ -323,7 +1242,8 @@ impl Style {
     ///
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn line_height(&self, font_cache: &fonts::FontCache) -> Mm {
-        self.font(font_cache).get_line_height(self.font_size()) * self.line_spacing()
+        self.scale_line_height(self.font(font_cache).get_line_height(self.font_size()))
+            + Mm(self.baseline_shift().0.abs())
     }
 
     /// Calculate the metrics of the font for this style using the data in the given font cache.
@@ -333,9 +1253,23 @@ impl Style {
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn metrics(&self, font_cache: &fonts::FontCache) -> fonts::Metrics {
         let mut metrics = self.font(font_cache).metrics(self.font_size());
-        metrics.line_height *= self.line_spacing();
+        metrics.line_height = self.scale_line_height(metrics.line_height);
+        // Reserve space for the shifted glyphs above or below the regular glyph box.
+        let shift = self.baseline_shift();
+        metrics.line_height += Mm(shift.0.abs());
+        metrics.glyph_height += Mm(shift.0.abs());
+        metrics.ascent += shift.max(Mm(0.0));
         metrics
     }
+
+    /// Applies the line height or the line spacing factor to the given line height of the font.
+    fn scale_line_height(&self, font_line_height: Mm) -> Mm {
+        match self.line_height {
+            Some(LineHeight::Factor(factor)) => font_line_height * factor,
+            Some(LineHeight::Mm(line_height)) => line_height,
+            None => font_line_height * self.line_spacing(),
+        }
+    }
 }
 
 impl From<Color> for Style {
@@ -350,6 +1284,10 @@ impl From<Effect> for Style {
         match effect {
             Effect::Bold => style.bold(),
             Effect::Italic => style.italic(),
+            Effect::Underline => style.with_underline(),
+            Effect::Superscript => style.with_superscript(),
+            Effect::Subscript => style.with_subscript(),
+            Effect::SmallCaps => style.with_small_caps(),
         }
     }
 }
@@ -475,15 +1413,17 @@ impl<'s> StyledStr<'s> {
         self.style.str_width(font_cache, &self.s)
     }
 
-    /// Calculates the width of the this string if it was trimmed at the end with this style using 
+    /// Calculates the width of the this string if it was trimmed at the end with this style using
     /// the data in the given font cache.
     ///
+    /// Non-breaking spaces at the end of the string are not trimmed.
+    ///
     /// If the font family is set for the style, it must have been created by the given
     /// [`FontCache`][].
     ///
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn width_trimmed_end(&self, font_cache: &fonts::FontCache) -> Mm {
-        self.style.str_width(font_cache, &self.s.trim_end())
+        self.style.str_width(font_cache, wrap::trim_end(&self.s))
     }
 }
 
@@ -545,15 +1485,17 @@ impl<'s> StyledCow<'s> {
         self.style.str_width(font_cache, self.s.as_ref())
     }
 
-    /// Calculates the width of the this string if it was trimmed at the end with this style using 
+    /// Calculates the width of the this string if it was trimmed at the end with this style using
     /// the data in the given font cache.
     ///
+    /// Non-breaking spaces at the end of the string are not trimmed.
+    ///
     /// If the font family is set for the style, it must have been created by the given
     /// [`FontCache`][].
     ///
     /// [`FontCache`]: ../fonts/struct.FontCache.html
     pub fn width_trimmed_end(&self, font_cache: &fonts::FontCache) -> Mm {
-        self.style.str_width(font_cache, &self.s.trim_end())
+        self.style.str_width(font_cache, wrap::trim_end(&self.s))
     }
 }
 
@@ -598,16 +1540,27 @@ impl<'s> From<StyledString> for StyledCow<'s> {
 /// The style consists of:
 /// - the line thickness in millimeters (defaults to 0.1)
 /// - the color of the line, see [`Color`][] (defaults to black)
+/// - the shape of the line ends, see [`LineCap`][] (defaults to butt caps)
+/// - the shape of the corners, see [`LineJoin`][] (defaults to miter joins)
+/// - an optional dash pattern (defaults to a solid line)
+/// - an opacity between 0 (transparent) and 1 (opaque) (defaults to 1)
 ///
 /// Note that a line thickness of 0.0 does not make the line disappear, but rather makes it appear
 /// 1px wide across all devices and resolutions.
 ///
 /// [`Color`]: enum.Color.html
+/// [`LineCap`]: enum.LineCap.html
+/// [`LineJoin`]: enum.LineJoin.html
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineStyle {
     thickness: Mm,
     color: Color,
     filled: bool,
+    cap: LineCap,
+    join: LineJoin,
+    dash_pattern: Option<(Mm, Mm)>,
+    opacity: f64,
 }
 
 impl Default for LineStyle {
@@ -616,6 +1569,10 @@ impl Default for LineStyle {
             thickness: Mm::from(0.1),
             color: Color::Rgb(0, 0, 0),
             filled: false,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            dash_pattern: None,
+            opacity: 1.0,
         }
     }
 }
@@ -688,4 +1645,202 @@ impl LineStyle {
     pub fn filled(&self) -> bool {
         self.filled
     }
+
+    /// Sets the shape of the line ends.
+    pub fn set_cap(&mut self, cap: LineCap) {
+        self.cap = cap;
+    }
+
+    /// Sets the shape of the line ends and returns the line style.
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.set_cap(cap);
+        self
+    }
+
+    /// Returns the shape of the line ends.
+    pub fn cap(&self) -> LineCap {
+        self.cap
+    }
+
+    /// Sets the shape of the corners where two line segments meet.
+    pub fn set_join(&mut self, join: LineJoin) {
+        self.join = join;
+    }
+
+    /// Sets the shape of the corners where two line segments meet and returns the line style.
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.set_join(join);
+        self
+    }
+
+    /// Returns the shape of the corners where two line segments meet.
+    pub fn join(&self) -> LineJoin {
+        self.join
+    }
+
+    /// Sets the dash pattern of the line, consisting of the length of the dashes and the length
+    /// of the gaps between them.
+    ///
+    /// The lengths are rounded to full points when the line is drawn.
+    pub fn set_dash_pattern(&mut self, dash: impl Into<Mm>, gap: impl Into<Mm>) {
+        self.dash_pattern = Some((dash.into(), gap.into()));
+    }
+
+    /// Sets the dash pattern of the line and returns the line style.
+    ///
+    /// See [`set_dash_pattern`][] for details.
+    ///
+    /// [`set_dash_pattern`]: #method.set_dash_pattern
+    pub fn with_dash_pattern(mut self, dash: impl Into<Mm>, gap: impl Into<Mm>) -> Self {
+        self.set_dash_pattern(dash, gap);
+        self
+    }
+
+    /// Removes the dash pattern so that the line is drawn solid.
+    pub fn clear_dash_pattern(&mut self) {
+        self.dash_pattern = None;
+    }
+
+    /// Returns the length of the dashes and gaps of the line, or `None` if the line is solid.
+    pub fn dash_pattern(&self) -> Option<(Mm, Mm)> {
+        self.dash_pattern
+    }
+
+    /// Sets the opacity of the line and its fill, from 0 (transparent) to 1 (opaque).
+    ///
+    /// Values outside of this range are clamped.
+    pub fn set_opacity(&mut self, opacity: f64) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    /// Sets the opacity of the line and its fill and returns the line style.
+    pub fn with_opacity(mut self, opacity: f64) -> Self {
+        self.set_opacity(opacity);
+        self
+    }
+
+    /// Returns the opacity of the line and its fill.
+    pub fn opacity(&self) -> f64 {
+        self.opacity
+    }
+}
+
+/// The shape of the ends of a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineCap {
+    /// The line ends exactly at its end point.
+    Butt,
+    /// The line ends with a half circle around its end point.
+    Round,
+    /// The line is extended by half of its thickness beyond its end point.
+    Square,
+}
+
+impl Default for LineCap {
+    fn default() -> LineCap {
+        LineCap::Butt
+    }
+}
+
+impl From<LineCap> for printpdf::LineCapStyle {
+    fn from(cap: LineCap) -> printpdf::LineCapStyle {
+        match cap {
+            LineCap::Butt => printpdf::LineCapStyle::Butt,
+            LineCap::Round => printpdf::LineCapStyle::Round,
+            LineCap::Square => printpdf::LineCapStyle::ProjectingSquare,
+        }
+    }
+}
+
+/// The shape of the corners where two segments of a line meet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineJoin {
+    /// The outer edges of the segments are extended until they meet in a sharp corner.
+    Miter,
+    /// The corner is rounded.
+    Round,
+    /// The corner is cut off.
+    Bevel,
+}
+
+impl Default for LineJoin {
+    fn default() -> LineJoin {
+        LineJoin::Miter
+    }
+}
+
+impl From<LineJoin> for printpdf::LineJoinStyle {
+    fn from(join: LineJoin) -> printpdf::LineJoinStyle {
+        match join {
+            LineJoin::Miter => printpdf::LineJoinStyle::Miter,
+            LineJoin::Round => printpdf::LineJoinStyle::Round,
+            LineJoin::Bevel => printpdf::LineJoinStyle::Limit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Style};
+
+    #[test]
+    fn test_color_from_hex() {
+        assert_eq!(
+            Color::from_hex("#1a73e8").unwrap(),
+            Color::Rgb(26, 115, 232)
+        );
+        assert_eq!(Color::from_hex("1A73E8").unwrap(), Color::Rgb(26, 115, 232));
+        assert_eq!(Color::from_hex("#fa0").unwrap(), Color::Rgb(255, 170, 0));
+        assert!(Color::from_hex("#1a73e").is_err());
+        assert!(Color::from_hex("#1a73eg").is_err());
+        assert!(Color::from_hex("").is_err());
+    }
+
+    #[test]
+    fn test_color_from_hsl() {
+        assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), Color::RED);
+        assert_eq!(Color::from_hsl(240.0, 1.0, 0.25), Color::NAVY);
+        assert_eq!(Color::from_hsl(-180.0, 1.0, 0.5), Color::AQUA);
+        assert_eq!(Color::from_hsl(39.0, 1.0, 0.5), Color::Rgb(255, 166, 0));
+        assert_eq!(Color::from_hsl(123.0, 0.0, 0.5), Color::Rgb(128, 128, 128));
+    }
+
+    #[test]
+    fn test_color_from_cmyk() {
+        assert_eq!(
+            Color::from_cmyk(0.0, 0.0, 0.0, 1.0),
+            Color::Cmyk(0, 0, 0, 255)
+        );
+        assert_eq!(
+            Color::from_cmyk(1.5, -1.0, 0.5, 0.0),
+            Color::Cmyk(255, 0, 128, 0)
+        );
+        assert_eq!(Color::from_greyscale(0.0), Color::Greyscale(0));
+        assert_eq!(Color::from_greyscale(0.9), Color::Greyscale(230));
+    }
+
+    #[test]
+    fn test_color_from_str() {
+        assert_eq!("Teal".parse::<Color>().unwrap(), Color::TEAL);
+        assert_eq!("#808000".parse::<Color>().unwrap(), Color::OLIVE);
+        assert_eq!("c0c0c0".parse::<Color>().unwrap(), Color::SILVER);
+        assert!("transparent".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_small_caps_runs() {
+        let style = Style::new().with_font_size(10).with_small_caps();
+        let upper = Style::new().with_font_size(10);
+        let lower = Style::new().with_font_size(8);
+        assert_eq!(
+            style.small_caps_runs("Straße 1"),
+            vec![
+                ("S".to_owned(), upper),
+                ("TRASSE".to_owned(), lower),
+                (" 1".to_owned(), upper),
+            ]
+        );
+    }
 }
@@ -1,5 +1,17 @@
 //! Provides syntax highlighting support for code blocks
+//!
+//! If the `code-syntax-loading` feature is enabled, syntax definitions and themes that are not
+//! included in the defaults of [`syntect`][] can be loaded from `.sublime-syntax` and `.tmTheme`
+//! files, see [`SyntaxHighlighter::from_folders`][].
+//!
+//! [`syntect`]: https://docs.rs/syntect
+//! [`SyntaxHighlighter::from_folders`]: struct.SyntaxHighlighter.html#method.from_folders
 
+#[cfg(feature = "code-syntax-loading")]
+use std::{io, path};
+
+#[cfg(feature = "code-syntax-loading")]
+use syntect::parsing::{SyntaxDefinition, SyntaxSetBuilder};
 use syntect::{
     easy::HighlightLines,
     highlighting::{FontStyle, ThemeSet},
@@ -7,13 +19,35 @@ use syntect::{
     util::LinesWithEndings,
 };
 
+use crate::error::{Error, ErrorKind};
 use crate::style::{Color, Style, StyledStr};
 
+/// The color scheme of a document, used to select a matching default theme for code blocks.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    Light,
+    /// Light text on a dark background.
+    Dark,
+}
+
+impl ColorScheme {
+    /// Returns the name of the default theme for this color scheme.
+    pub fn default_theme(self) -> &'static str {
+        match self {
+            ColorScheme::Light => "base16-ocean.light",
+            ColorScheme::Dark => "base16-ocean.dark",
+        }
+    }
+}
+
 /// The SyntaxHighlighter is used to create styled string segments from the given input text
 #[derive(Debug)]
 pub struct SyntaxHighlighter {
     syntax_set: SyntaxSet,
     theme_set: ThemeSet,
+    default_theme: Option<String>,
+    color_scheme: Option<ColorScheme>,
 }
 
 impl SyntaxHighlighter {
@@ -25,11 +59,155 @@ impl SyntaxHighlighter {
         Self {
             syntax_set,
             theme_set,
+            default_theme: None,
+            color_scheme: None,
         }
     }
 
+    /// Loads the syntax definitions and the themes from the given folders.
+    ///
+    /// The syntax definitions are loaded from all `.sublime-syntax` files in the syntax folder
+    /// and its subfolders, and the themes are loaded from all `.tmTheme` files in the theme
+    /// folder and its subfolders.  The themes are named after their file names without the
+    /// extension.  In contrast to [`load_defaults`][], the default syntaxes and themes are not
+    /// included.
+    ///
+    /// *Only available if the `code-syntax-loading` feature is enabled.*
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use genpdf::syntax_highlighting::SyntaxHighlighter;
+    /// let highlighter = SyntaxHighlighter::from_folders("./syntaxes", "./themes")
+    ///     .expect("Failed to load syntaxes and themes")
+    ///     .with_default_theme("Company");
+    /// ```
+    ///
+    /// [`load_defaults`]: #method.load_defaults
+    #[cfg(feature = "code-syntax-loading")]
+    pub fn from_folders(
+        syntax_dir: impl AsRef<path::Path>,
+        theme_dir: impl AsRef<path::Path>,
+    ) -> Result<Self, Error> {
+        let syntax_dir = syntax_dir.as_ref();
+        let theme_dir = theme_dir.as_ref();
+
+        let mut builder = SyntaxSetBuilder::new();
+        builder.add_plain_text_syntax();
+        builder.add_from_folder(syntax_dir, true).map_err(|err| {
+            Error::new(
+                format!("Failed to load syntaxes from {}", syntax_dir.display()),
+                ErrorKind::External(Box::new(err)),
+            )
+        })?;
+        let theme_set = ThemeSet::load_from_folder(theme_dir).map_err(|err| {
+            Error::new(
+                format!("Failed to load themes from {}", theme_dir.display()),
+                ErrorKind::External(Box::new(err)),
+            )
+        })?;
+
+        Ok(Self {
+            syntax_set: builder.build(),
+            theme_set,
+            default_theme: None,
+            color_scheme: None,
+        })
+    }
+
+    /// Adds the syntax definition in the `.sublime-syntax` format to this highlighter.
+    ///
+    /// The syntax can be selected for a code block by its name or by one of its file extensions.
+    /// If the definition does not contain a name, it cannot be selected by name.
+    ///
+    /// *Only available if the `code-syntax-loading` feature is enabled.*
+    #[cfg(feature = "code-syntax-loading")]
+    pub fn add_syntax_from_str(&mut self, syntax: &str) -> Result<(), Error> {
+        let syntax = SyntaxDefinition::load_from_str(syntax, true, None).map_err(|err| {
+            Error::new(
+                "Failed to parse syntax definition",
+                ErrorKind::External(Box::new(err)),
+            )
+        })?;
+        let mut builder = std::mem::take(&mut self.syntax_set).into_builder();
+        builder.add(syntax);
+        self.syntax_set = builder.build();
+        Ok(())
+    }
+
+    /// Adds the theme in the `.tmTheme` format to this highlighter using the given name.
+    ///
+    /// If there already is a theme with the given name, it is replaced.
+    ///
+    /// *Only available if the `code-syntax-loading` feature is enabled.*
+    #[cfg(feature = "code-syntax-loading")]
+    pub fn add_theme(&mut self, name: impl Into<String>, theme: &str) -> Result<(), Error> {
+        let name = name.into();
+        let theme = ThemeSet::load_from_reader(&mut io::Cursor::new(theme)).map_err(|err| {
+            Error::new(
+                format!("Failed to parse theme {}", name),
+                ErrorKind::External(Box::new(err)),
+            )
+        })?;
+        self.theme_set.themes.insert(name, theme);
+        Ok(())
+    }
+
+    /// Sets the theme that is used for code blocks without an explicit theme.
+    pub fn set_default_theme(&mut self, theme: impl Into<String>) {
+        self.default_theme = Some(theme.into());
+    }
+
+    /// Sets the theme that is used for code blocks without an explicit theme and returns the
+    /// highlighter.
+    pub fn with_default_theme(mut self, theme: impl Into<String>) -> Self {
+        self.set_default_theme(theme);
+        self
+    }
+
+    /// Sets the color scheme of the document.
+    ///
+    /// If no default theme is set, code blocks without an explicit theme are highlighted with the
+    /// default theme of this color scheme, see [`ColorScheme::default_theme`][].
+    ///
+    /// [`ColorScheme::default_theme`]: enum.ColorScheme.html#method.default_theme
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        self.color_scheme = Some(color_scheme);
+    }
+
+    /// Sets the color scheme of the document and returns the highlighter.
+    pub fn with_color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.set_color_scheme(color_scheme);
+        self
+    }
+
+    /// Returns the color scheme of the document, if set.
+    pub fn color_scheme(&self) -> Option<ColorScheme> {
+        self.color_scheme
+    }
+
+    /// Returns the theme that is used for code blocks without an explicit theme, if set.
+    ///
+    /// This is the default theme set with [`set_default_theme`][] or, if it is not set, the
+    /// default theme of the color scheme set with [`set_color_scheme`][].
+    ///
+    /// [`set_default_theme`]: #method.set_default_theme
+    /// [`set_color_scheme`]: #method.set_color_scheme
+    pub fn default_theme(&self) -> Option<&str> {
+        self.default_theme
+            .as_deref()
+            .or_else(|| self.color_scheme.map(ColorScheme::default_theme))
+    }
+
     /// Highlight the provided code with syntax for the specified language and using the provided
     /// theme. The `base_style` is used to provide the font size and font family.
+    ///
+    /// The language can be a name or a file extension.  If no syntax is found for it, the syntax
+    /// is detected from the first line of the code, for example a shebang.  If no syntax or theme
+    /// is found, `None` is returned.  If `syntect` fails to highlight the code, an error with the
+    /// [`External`][] kind is returned.
+    ///
+    /// [`External`]: ../error/enum.ErrorKind.html#variant.External
     pub fn highlight<'a>(
         &self,
         code: &'a str,
@@ -37,15 +215,27 @@ impl SyntaxHighlighter {
         theme: &str,
         base_style: Style,
         only_regular_font: bool,
-    ) -> Option<Vec<Vec<StyledStr<'a>>>> {
-        let syntax = self.syntax_set.find_syntax_by_token(&language)?;
+    ) -> Result<Option<Vec<Vec<StyledStr<'a>>>>, Error> {
+        let syntax = self.syntax_set.find_syntax_by_token(&language).or_else(|| {
+            self.syntax_set
+                .find_syntax_by_first_line(code.lines().next()?)
+        });
+        let (syntax, theme) = match (syntax, self.theme_set.themes.get(theme)) {
+            (Some(syntax), Some(theme)) => (syntax, theme),
+            _ => return Ok(None),
+        };
 
-        let mut h = HighlightLines::new(syntax, self.theme_set.themes.get(theme)?);
+        let mut h = HighlightLines::new(syntax, theme);
 
         let lines = LinesWithEndings::from(&code)
             .map(|line| {
-                h.highlight_line(line, &self.syntax_set)
-                    .unwrap()
+                let ranges = h.highlight_line(line, &self.syntax_set).map_err(|err| {
+                    Error::new(
+                        format!("Failed to highlight code as {}", syntax.name),
+                        ErrorKind::External(Box::new(err)),
+                    )
+                })?;
+                Ok(ranges
                     .into_iter()
                     .map(|(syntax_style, code_segment)| {
                         let color = Color::Rgb(
@@ -69,10 +259,10 @@ impl SyntaxHighlighter {
 
                         StyledStr::new(code_segment, style)
                     })
-                    .collect()
+                    .collect())
             })
-            .collect();
+            .collect::<Result<_, Error>>()?;
 
-        Some(lines)
+        Ok(Some(lines))
     }
 }
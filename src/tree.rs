@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2020-2021 Robin Krahl <robin.krahl@ireas.org>
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Debug representation of the element structure of a document.
+//!
+//! Documents that are built programmatically can end up with deeply nested elements that are
+//! hard to inspect.  [`Element::dump_tree`][] returns an [`ElementTree`][] describing the element
+//! and its children, with the element types and some key properties.  The tree can be printed as
+//! indented text using its [`Display`][] implementation, or exported as a [DOT][] graph that can
+//! be rendered with Graphviz using [`ElementTree::to_dot`][].
+//!
+//! # Example
+//!
+//! ```
+//! use genpdf::{elements, Element as _};
+//! let mut layout = elements::LinearLayout::vertical();
+//! layout.push(elements::Paragraph::new("Hello World!"));
+//! layout.push(elements::Break::new(1).padded(5));
+//! let tree = layout.dump_tree();
+//! assert_eq!(
+//!     tree.to_string(),
+//!     "LinearLayout (elements: 2)\n  Paragraph (text: \"Hello World!\")\n  PaddedElement (padding: 5 5 5 5)\n    Break\n"
+//! );
+//! ```
+//!
+//! [`Element::dump_tree`]: ../trait.Element.html#method.dump_tree
+//! [`ElementTree`]: struct.ElementTree.html
+//! [`ElementTree::to_dot`]: struct.ElementTree.html#method.to_dot
+//! [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+//! [DOT]: https://graphviz.org/doc/info/lang.html
+
+use std::any;
+use std::fmt;
+use std::fmt::Write as _;
+
+/// The maximum number of characters of text properties, see [`ElementTree::with_text`][].
+///
+/// [`ElementTree::with_text`]: struct.ElementTree.html#method.with_text
+const MAX_TEXT_LEN: usize = 30;
+
+/// A node of the element tree of a document, see the [module documentation](index.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementTree {
+    name: String,
+    properties: Vec<(String, String)>,
+    children: Vec<ElementTree>,
+}
+
+impl ElementTree {
+    /// Creates a new node with the given name and without properties and children.
+    pub fn new(name: impl Into<String>) -> ElementTree {
+        ElementTree {
+            name: name.into(),
+            properties: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Creates a new node that is named after the type `T` without its module path and generic
+    /// arguments.
+    pub fn for_type<T: ?Sized>() -> ElementTree {
+        ElementTree::new(short_type_name(any::type_name::<T>()))
+    }
+
+    /// Returns the name of this node, usually the type of the element.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the properties of this node.
+    pub fn properties(&self) -> &[(String, String)] {
+        &self.properties
+    }
+
+    /// Returns the children of this node.
+    pub fn children(&self) -> &[ElementTree] {
+        &self.children
+    }
+
+    /// Adds a property with the given key and value to this node.
+    pub fn push_property(&mut self, key: impl Into<String>, value: impl fmt::Display) {
+        self.properties.push((key.into(), value.to_string()));
+    }
+
+    /// Adds a property with the given key and value to this node and returns the node.
+    pub fn with_property(mut self, key: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.push_property(key, value);
+        self
+    }
+
+    /// Adds a quoted text property to this node and returns the node.
+    ///
+    /// Texts with more than 30 characters are truncated.
+    pub fn with_text(self, key: impl Into<String>, text: &str) -> Self {
+        let mut value = String::from("\"");
+        value.extend(text.chars().take(MAX_TEXT_LEN));
+        if text.chars().nth(MAX_TEXT_LEN).is_some() {
+            value.push('…');
+        }
+        value.push('"');
+        self.with_property(key, value)
+    }
+
+    /// Adds a child node to this node.
+    pub fn push_child(&mut self, child: ElementTree) {
+        self.children.push(child);
+    }
+
+    /// Adds a child node to this node and returns the node.
+    pub fn with_child(mut self, child: ElementTree) -> Self {
+        self.push_child(child);
+        self
+    }
+
+    /// Adds the given child nodes to this node and returns the node.
+    pub fn with_children(mut self, children: impl IntoIterator<Item = ElementTree>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Exports this tree as a directed graph in the [DOT][] language.
+    ///
+    /// Every node is labeled with its name and its properties.  The nodes are named `n0`, `n1`,
+    /// … in depth-first order.
+    ///
+    /// [DOT]: https://graphviz.org/doc/info/lang.html
+    pub fn to_dot(&self) -> String {
+        let mut s = String::from("digraph elements {\n  node [shape=box];\n");
+        self.write_dot(&mut s, &mut 0);
+        s.push_str("}\n");
+        s
+    }
+
+    fn write_dot(&self, s: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let mut label = self.name.clone();
+        for (key, value) in &self.properties {
+            write!(label, "\n{}: {}", key, value).unwrap();
+        }
+        write!(s, "  n{} [label=", id).unwrap();
+        push_dot_str(s, &label);
+        s.push_str("];\n");
+        for child in &self.children {
+            let child_id = child.write_dot(s, next_id);
+            writeln!(s, "  n{} -> n{};", id, child_id).unwrap();
+        }
+        id
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:1$}{2}", "", depth * 2, self.name)?;
+        for (i, (key, value)) in self.properties.iter().enumerate() {
+            let prefix = if i == 0 { " (" } else { ", " };
+            write!(f, "{}{}: {}", prefix, key, value)?;
+        }
+        if !self.properties.is_empty() {
+            f.write_str(")")?;
+        }
+        f.write_str("\n")?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ElementTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+fn short_type_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn push_dot_str(s: &mut String, value: &str) {
+    s.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ElementTree;
+
+    #[test]
+    fn test_for_type() {
+        assert_eq!(ElementTree::for_type::<Vec<String>>().name(), "Vec");
+        assert_eq!(ElementTree::for_type::<str>().name(), "str");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree = ElementTree::new("LinearLayout")
+            .with_child(ElementTree::new("Paragraph").with_text("text", "a \"quote\""))
+            .with_child(ElementTree::new("Break"));
+        assert_eq!(
+            tree.to_dot(),
+            "digraph elements {\n  node [shape=box];\n  n0 [label=\"LinearLayout\"];\n  \
+             n1 [label=\"Paragraph\\ntext: \\\"a \\\"quote\\\"\\\"\"];\n  n0 -> n1;\n  \
+             n2 [label=\"Break\"];\n  n0 -> n2;\n}\n"
+        );
+    }
+}
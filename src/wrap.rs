@@ -3,22 +3,85 @@
 
 //! Utilities for text wrapping.
 
+use std::borrow;
 use std::mem;
 
 use crate::style;
 use crate::Context;
 use crate::Mm;
 
+/// The soft hyphen (U+00AD) marks an optional break point in a word.  It is only rendered (as a
+/// hyphen) if the line is broken at this point.
+pub const SOFT_HYPHEN: char = '\u{ad}';
+
+/// Returns true if the given character is a space that must not be used as a break point:  the
+/// no-break space (U+00A0), the figure space (U+2007) or the narrow no-break space (U+202F).
+pub fn is_non_breaking_space(c: char) -> bool {
+    matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// Removes the leading whitespace of the given string, except for non-breaking spaces.
+pub fn trim_start(s: &str) -> &str {
+    s.trim_start_matches(|c: char| c.is_whitespace() && !is_non_breaking_space(c))
+}
+
+/// Removes the trailing whitespace of the given string, except for non-breaking spaces.
+pub fn trim_end(s: &str) -> &str {
+    s.trim_end_matches(|c: char| c.is_whitespace() && !is_non_breaking_space(c))
+}
+
+/// Removes all soft hyphens from the given string as they should not be rendered.
+pub fn remove_soft_hyphens(s: &str) -> borrow::Cow<'_, str> {
+    if s.contains(SOFT_HYPHEN) {
+        s.replace(SOFT_HYPHEN, "").into()
+    } else {
+        s.into()
+    }
+}
+
+/// Information about a word that could not be wrapped because it is wider than a line.
+#[derive(Clone, Debug)]
+pub struct Overflow {
+    /// The (remainder of the) word that did not fit into a line.
+    pub text: String,
+    /// The style of the word.
+    pub style: style::Style,
+    /// The width that would be required to render the word.
+    pub width: Mm,
+    /// The available line width.
+    pub available_width: Mm,
+}
+
 /// Combines a sequence of styled words into lines with a maximum width.
 ///
-/// If a word does not fit into a line, the wrapper tries to split it using the `split` function.
+/// If a word does not fit into a line, the wrapper tries to split it at a soft hyphen or, if the
+/// word does not contain soft hyphens, using the hyphenator.  If the remainder of a split word is
+/// still wider than a line, it is split again on the next line.  Words that are joined by a
+/// non-breaking space are moved to the next line together.  A word that only consists of a line
+/// break (`"\n"`) ends the current line.  It is included at the end of the returned line so that
+/// the caller can keep track of the consumed input.
+///
+/// Per default, all lines have the same maximum width.  Different widths for the first lines can
+/// be set with [`with_first_line_width`][] and [`with_line_widths`][].  If a word does not fit
+/// into one of these lines but into a line with the default width, the line is returned without
+/// words.  It then contains an empty string with the style of the word so that the caller can
+/// determine the line height.
+///
+/// [`with_first_line_width`]: #method.with_first_line_width
+/// [`with_line_widths`]: #method.with_line_widths
 pub struct Wrapper<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> {
     iter: I,
     context: &'c Context,
     width: Mm,
+    first_line_width: Option<Mm>,
+    line_widths: Vec<Mm>,
+    line: usize,
     x: Mm,
     buf: Vec<style::StyledCow<'s>>,
-    has_overflowed: bool,
+    pending: Option<style::StyledStr<'s>>,
+    overflow: Option<Overflow>,
+    #[cfg(feature = "hyphenation")]
+    language: Option<hyphenation::Language>,
 }
 
 impl<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> Wrapper<'c, 's, I> {
@@ -28,16 +91,68 @@ impl<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> Wrapper<'c, 's, I> {
             iter,
             context,
             width,
+            first_line_width: None,
+            line_widths: Vec::new(),
+            line: 0,
             x: Mm(0.0),
             buf: Vec::new(),
-            has_overflowed: false,
+            pending: None,
+            overflow: None,
+            #[cfg(feature = "hyphenation")]
+            language: None,
+        }
+    }
+
+    /// Sets the language that is used to select the hyphenator and returns the wrapper.
+    ///
+    /// If this method is not called or if the language is `None`, the default hyphenator of the
+    /// context is used, see [`Context::hyphenator_for`][].
+    ///
+    /// *Only available if the `hyphenation` feature is enabled.*
+    ///
+    /// [`Context::hyphenator_for`]: ../struct.Context.html#method.hyphenator_for
+    #[cfg(feature = "hyphenation")]
+    pub fn with_language(mut self, language: Option<hyphenation::Language>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Sets a different maximum width for the first line and returns the wrapper.
+    pub fn with_first_line_width(mut self, width: Mm) -> Self {
+        self.first_line_width = Some(width);
+        self
+    }
+
+    /// Sets the maximum widths of the first lines and returns the wrapper.
+    ///
+    /// The *i*-th line has the *i*-th width of the given vector.  The following lines have the
+    /// default width.  These widths take precedence over the width set with
+    /// [`with_first_line_width`][].
+    ///
+    /// [`with_first_line_width`]: #method.with_first_line_width
+    pub fn with_line_widths(mut self, widths: Vec<Mm>) -> Self {
+        self.line_widths = widths;
+        self
+    }
+
+    /// Returns the maximum width of the current line.
+    fn line_width(&self) -> Mm {
+        match self.line_widths.get(self.line) {
+            Some(width) => *width,
+            None if self.line == 0 => self.first_line_width.unwrap_or(self.width),
+            None => self.width,
         }
     }
 
     /// Returns true if this wrapper has overflowed, i. e. if it encountered a word that it could
     /// not split so that it would fit into a line.
     pub fn has_overflowed(&self) -> bool {
-        self.has_overflowed
+        self.overflow.is_some()
+    }
+
+    /// Returns information about the word that caused this wrapper to overflow, if any.
+    pub fn overflow(&self) -> Option<&Overflow> {
+        self.overflow.as_ref()
     }
 }
 
@@ -47,18 +162,48 @@ impl<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> Iterator for Wrapper<'c,
     type Item = (Vec<style::StyledCow<'s>>, usize);
 
     fn next(&mut self) -> Option<(Vec<style::StyledCow<'s>>, usize)> {
-        // Append words to self.buf until the maximum line length is reached
-        while let Some(s) = self.iter.next() {
+        // Append words to self.buf until the maximum line length is reached, starting with the
+        // remainder of a word that has been split for the previous line
+        while let Some(s) = self.pending.take().or_else(|| self.iter.next()) {
+            if s.s == "\n" {
+                // Forced line break, so return the current line including the line break
+                self.buf.push(s.into());
+                self.x = Mm(0.0);
+                self.line += 1;
+                return Some((mem::take(&mut self.buf), 0));
+            }
+
             // Use the trimmed width to check for overflows as trailing spaces are not renderd
             let width_trimmed = s.width_trimmed_end(&self.context.font_cache);
             let mut width = s.width(&self.context.font_cache);
 
-            if self.x + width_trimmed > self.width {
+            if self.x + width_trimmed > self.line_width() {
                 // The word does not fit into the current line (at least not completely)
 
+                // If the previous words are joined to this word by non-breaking spaces, move
+                // them to the next line too (unless they are the only words in this line)
+                let joined = self
+                    .buf
+                    .iter()
+                    .rev()
+                    .take_while(|w| w.s.ends_with(is_non_breaking_space))
+                    .count();
+                if joined > 0 && joined < self.buf.len() {
+                    let mut v = mem::take(&mut self.buf);
+                    self.buf = v.split_off(v.len() - joined);
+                    self.x = self
+                        .buf
+                        .iter()
+                        .map(|w| w.width(&self.context.font_cache))
+                        .sum();
+                    self.line += 1;
+                    self.pending = Some(s);
+                    return Some((v, 0));
+                }
+
                 let mut delta = 0;
                 // Try to split the word so that the first part fits into the current line
-                let s = if let Some((start, end)) = split(self.context, s, self.width - self.x) {
+                let s = if let Some((start, end)) = self.split(s, self.line_width() - self.x) {
                     // Calculate the number of bytes that we added to the string when splitting it
                     // (for the hyphen, if required).
                     delta = start.s.len() + end.s.len() - s.s.len();
@@ -66,21 +211,36 @@ impl<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> Iterator for Wrapper<'c,
                     width = end.width(&self.context.font_cache);
                     end
                 } else {
-                    s.into()
+                    s
                 };
 
-                if width > self.width {
+                if width > self.width && !self.buf.is_empty() {
+                    // The remainder of the word is longer than a line, but it might be possible
+                    // to split it again on the next line.
+                    self.pending = Some(s);
+                    self.x = Mm(0.0);
+                    self.line += 1;
+                    return Some((mem::take(&mut self.buf), delta));
+                } else if width > self.width {
                     // The remainder of the word is longer than the current page – we will never be
                     // able to render it completely.
-                    // TODO: handle gracefully, emit warning
-                    self.has_overflowed = true;
+                    self.overflow = Some(Overflow {
+                        text: s.s.to_owned(),
+                        style: s.style,
+                        width,
+                        available_width: self.width,
+                    });
                     return None;
                 }
 
                 // Return the current line and add the word that did not fit to the next line
-                let v = std::mem::take(&mut self.buf);
-                self.buf.push(s);
+                let mut v = std::mem::take(&mut self.buf);
+                if v.is_empty() {
+                    v.push(style::StyledCow::new("", s.style));
+                }
+                self.buf.push(s.into());
                 self.x = width;
+                self.line += 1;
                 return Some((v, delta));
             } else {
                 // The word fits in the current line, so just append it
@@ -97,64 +257,116 @@ impl<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> Iterator for Wrapper<'c,
     }
 }
 
-#[cfg(not(feature = "hyphenation"))]
-fn split<'s>(
-    _context: &Context,
-    _s: style::StyledStr<'s>,
-    _len: Mm,
-) -> Option<(style::StyledCow<'s>, style::StyledCow<'s>)> {
-    None
-}
-
-/// Tries to split the given string into two parts so that the first part is shorter than the given
-/// width.
-#[cfg(feature = "hyphenation")]
-fn split<'s>(
-    context: &Context,
-    s: style::StyledStr<'s>,
-    width: Mm,
-) -> Option<(style::StyledCow<'s>, style::StyledCow<'s>)> {
-    use hyphenation::{Hyphenator, Iter};
-
-    let hyphenator = if let Some(hyphenator) = &context.hyphenator {
-        hyphenator
-    } else {
-        return None;
-    };
-
-    let mark = "-";
-    let mark_width = s.style.str_width(&context.font_cache, mark);
-
-    let hyphenated = hyphenator.hyphenate(s.s);
-    let segments: Vec<_> = hyphenated.iter().segments().collect();
-
-    // Find the hyphenation with the longest first part so that the first part (and the hyphen) are
-    // shorter than or equals to the required width.
-    let idx = segments
-        .iter()
-        .scan(Mm(0.0), |acc, t| {
-            *acc += s.style.str_width(&context.font_cache, t);
-            Some(*acc)
-        })
-        .position(|w| w + mark_width > width)
-        .unwrap_or_default();
-    if idx > 0 {
-        let idx = hyphenated.breaks[idx - 1];
-        let mut start = s.s[..idx].to_owned();
-        if !start.ends_with(mark) {
-            start.push_str(mark);
+impl<'c, 's, I: Iterator<Item = style::StyledStr<'s>>> Wrapper<'c, 's, I> {
+    /// Tries to split the given string into two parts so that the first part is shorter than the
+    /// given width.
+    ///
+    /// If the string contains soft hyphens, it is only split at these.  Otherwise, the hyphenator
+    /// is used (if the `hyphenation` feature is enabled).
+    fn split(
+        &self,
+        s: style::StyledStr<'s>,
+        width: Mm,
+    ) -> Option<(style::StyledCow<'s>, style::StyledStr<'s>)> {
+        if s.s.contains(SOFT_HYPHEN) {
+            self.split_at_soft_hyphen(s, width)
+        } else {
+            self.hyphenate(s, width)
         }
-        let end = &s.s[idx..];
+    }
+
+    /// Splits the given string at the last soft hyphen so that the first part and the hyphen are
+    /// shorter than the given width.
+    fn split_at_soft_hyphen(
+        &self,
+        s: style::StyledStr<'s>,
+        width: Mm,
+    ) -> Option<(style::StyledCow<'s>, style::StyledStr<'s>)> {
+        let mark = "-";
+        let mark_width = s.style.str_width(&self.context.font_cache, mark);
+        let idx =
+            s.s.match_indices(SOFT_HYPHEN)
+                .map(|(idx, _)| idx)
+                .take_while(|idx| {
+                    s.style.str_width(&self.context.font_cache, &s.s[..*idx]) + mark_width <= width
+                })
+                .last()?;
+        // The soft hyphen is kept in the first part so that the rendered text still matches the
+        // input, but it is not printed.
+        let idx = idx + SOFT_HYPHEN.len_utf8();
+        let start = format!("{}{}", &s.s[..idx], mark);
         Some((
             style::StyledCow::new(start, s.style),
-            style::StyledCow::new(end, s.style),
+            style::StyledStr::new(&s.s[idx..], s.style),
         ))
-    } else {
+    }
+
+    #[cfg(not(feature = "hyphenation"))]
+    fn hyphenate(
+        &self,
+        _s: style::StyledStr<'s>,
+        _width: Mm,
+    ) -> Option<(style::StyledCow<'s>, style::StyledStr<'s>)> {
         None
     }
+
+    /// Tries to split the given string into two parts using the hyphenator so that the first
+    /// part is shorter than the given width.  A hyphen is appended to the first part unless it
+    /// already ends with one.
+    #[cfg(feature = "hyphenation")]
+    fn hyphenate(
+        &self,
+        s: style::StyledStr<'s>,
+        width: Mm,
+    ) -> Option<(style::StyledCow<'s>, style::StyledStr<'s>)> {
+        use hyphenation::{Hyphenator, Iter};
+
+        let context = self.context;
+        let hyphenator = context.hyphenator_for(self.language)?;
+
+        let mark = "-";
+        let mark_width = s.style.str_width(&context.font_cache, mark);
+
+        let hyphenated = hyphenator.hyphenate(s.s);
+        let segments: Vec<_> = hyphenated.iter().segments().collect();
+
+        // Find the hyphenation with the longest first part so that the first part (and the
+        // hyphen) are shorter than or equals to the required width.
+        let idx = segments
+            .iter()
+            .scan(Mm(0.0), |acc, t| {
+                *acc += s.style.str_width(&context.font_cache, t);
+                Some(*acc)
+            })
+            .position(|w| w + mark_width > width)
+            .unwrap_or_default();
+        if idx > 0 {
+            let idx = hyphenated.breaks[idx - 1];
+            let mut start = s.s[..idx].to_owned();
+            if !start.ends_with(mark) {
+                start.push_str(mark);
+            }
+            Some((
+                style::StyledCow::new(start, s.style),
+                style::StyledStr::new(&s.s[idx..], s.style),
+            ))
+        } else {
+            None
+        }
+    }
 }
 
 /// Splits a sequence of styled strings into words.
+///
+/// Words are split after spaces.  Line breaks (`'\n'`) are returned as separate words.
+///
+/// If the `unicode-linebreak` feature is enabled, words are split at the break opportunities
+/// defined by the [Unicode line breaking algorithm][UAX #14] instead, for example after hyphens
+/// and dashes or between CJK characters.  Soft hyphens are not used as break opportunities as
+/// they are handled by the [`Wrapper`][].
+///
+/// [UAX #14]: https://www.unicode.org/reports/tr14/
+/// [`Wrapper`]: struct.Wrapper.html
 pub struct Words<I: Iterator<Item = style::StyledString>> {
     iter: I,
     s: Option<style::StyledString>,
@@ -181,9 +393,7 @@ impl<I: Iterator<Item = style::StyledString>> Iterator for Words<I> {
         }
 
         if let Some(s) = &mut self.s {
-            // Split at the first space or use the complete string
-            let n = s.s.find(' ').map(|i| i + 1).unwrap_or_else(|| s.s.len());
-            let mut tmp = s.s.split_off(n);
+            let mut tmp = s.s.split_off(first_word_len(&s.s));
             mem::swap(&mut tmp, &mut s.s);
             Some(style::StyledString::new(tmp, s.style))
         } else {
@@ -191,3 +401,93 @@ impl<I: Iterator<Item = style::StyledString>> Iterator for Words<I> {
         }
     }
 }
+
+/// Returns the length of the first word of the given string.
+#[cfg(not(feature = "unicode-linebreak"))]
+fn first_word_len(s: &str) -> usize {
+    // Split after the first space, before or after the first line break or use the complete
+    // string
+    match s.find(&[' ', '\n'][..]) {
+        Some(0) => 1,
+        Some(i) if s[i..].starts_with('\n') => i,
+        Some(i) => i + 1,
+        None => s.len(),
+    }
+}
+
+/// Returns the length of the first word of the given string.
+#[cfg(feature = "unicode-linebreak")]
+fn first_word_len(s: &str) -> usize {
+    // Line breaks are always separate words, so we only have to search the text before the first
+    // line break for break opportunities
+    let line_len = match s.find('\n') {
+        Some(0) => return 1,
+        Some(i) => i,
+        None => s.len(),
+    };
+    let line = &s[..line_len];
+    unicode_linebreak::linebreaks(line)
+        .map(|(i, _)| i)
+        .find(|&i| i > 0 && !line[..i].ends_with(SOFT_HYPHEN))
+        .unwrap_or(line_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{remove_soft_hyphens, trim_end, trim_start, Words};
+    use crate::style::StyledString;
+
+    #[test]
+    fn test_words() {
+        let words: Vec<_> = Words::new(vec![
+            StyledString::from("Lorem ipsum\n\ndolor"),
+            StyledString::from(" sit"),
+        ])
+        .map(|s| s.s)
+        .collect();
+        assert_eq!(
+            words,
+            vec!["Lorem ", "ipsum", "\n", "\n", "dolor", " ", "sit"]
+        );
+    }
+
+    #[cfg(feature = "unicode-linebreak")]
+    #[test]
+    fn test_words_unicode_linebreak() {
+        let words: Vec<_> = Words::new(vec![
+            StyledString::from("well-known 日本語\nSil\u{ad}ben"),
+            StyledString::from("a\u{a0}b"),
+        ])
+        .map(|s| s.s)
+        .collect();
+        assert_eq!(
+            words,
+            vec![
+                "well-",
+                "known ",
+                "日",
+                "本",
+                "語",
+                "\n",
+                "Sil\u{ad}ben",
+                "a\u{a0}b"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(trim_start(" \u{a0}a "), "\u{a0}a ");
+        assert_eq!(trim_end(" a\u{202f} "), " a\u{202f}");
+        assert_eq!(trim_end("a \n"), "a");
+    }
+
+    #[test]
+    fn test_remove_soft_hyphens() {
+        assert_eq!(
+            remove_soft_hyphens("Sil\u{ad}ben\u{ad}tren\u{ad}nung"),
+            "Silbentrennung"
+        );
+        assert_eq!(remove_soft_hyphens("a-b"), "a-b");
+    }
+}